@@ -15,6 +15,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     for proto_file in &proto_files {
         let proto_path = format!("{}/{}", proto_dir, proto_file);
+        // The protos live outside the package, so cargo wouldn't notice edits
+        println!("cargo:rerun-if-changed={}", proto_path);
         let proto_content = std::fs::read_to_string(&proto_path)?;
         
         // Strip Elixir-specific options
@@ -0,0 +1,34 @@
+//! Clipboard Support
+//!
+//! Thin wrapper around the system clipboard used by the `y` (yank) key.
+//! Clipboard access is behind the `clipboard` cargo feature so headless
+//! builds can drop the dependency with `--no-default-features`; without it,
+//! copying returns an error that is shown in the status bar.
+
+use anyhow::Result;
+
+#[derive(Default)]
+pub struct Clipboard {
+    // Kept alive for the lifetime of the app: on X11 the copied text is
+    // only served while the owning clipboard handle exists.
+    #[cfg(feature = "clipboard")]
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    #[cfg(feature = "clipboard")]
+    pub fn copy(&mut self, text: &str) -> Result<()> {
+        if self.inner.is_none() {
+            self.inner = Some(arboard::Clipboard::new()?);
+        }
+        if let Some(clipboard) = self.inner.as_mut() {
+            clipboard.set_text(text.to_string())?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    pub fn copy(&mut self, _text: &str) -> Result<()> {
+        anyhow::bail!("clipboard support not compiled in (build with --features clipboard)")
+    }
+}
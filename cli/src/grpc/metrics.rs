@@ -0,0 +1,308 @@
+//! gRPC Call Metrics
+//!
+//! Per-method call counts, error counts and latency histograms for the
+//! `ScalegraphClient`. Every RPC is timed; the numbers are only surfaced when
+//! asked for, either as a summary table (`--check`) or as Prometheus text
+//! served over HTTP (`--metrics-addr`).
+//!
+//! The most recent success and failure are also kept, so the TUI can derive
+//! its connection status without every call site checking results.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tonic::Status;
+
+/// Upper bounds of the latency histogram buckets, in seconds
+const BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+#[derive(Debug, Clone, Default)]
+struct MethodStats {
+    calls: u64,
+    errors: u64,
+    total: Duration,
+    max: Duration,
+    // Non-cumulative counts per bucket; the extra slot is +Inf
+    buckets: [u64; BUCKETS.len() + 1],
+}
+
+impl MethodStats {
+    fn record(&mut self, elapsed: Duration, ok: bool) {
+        self.calls += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        let secs = elapsed.as_secs_f64();
+        let slot = BUCKETS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(BUCKETS.len());
+        self.buckets[slot] += 1;
+    }
+
+    fn mean(&self) -> Duration {
+        if self.calls == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.calls as u32
+        }
+    }
+}
+
+/// The most recent failed call
+#[derive(Debug, Clone)]
+pub struct CallError {
+    pub method: &'static str,
+    pub code: tonic::Code,
+    pub message: String,
+    pub at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct LastOutcome {
+    ok_at: Option<Instant>,
+    error: Option<CallError>,
+    /// When the most recent call finished, successful or not
+    call_at: Option<Instant>,
+}
+
+/// Shared metrics registry; clones of the client record into the same one.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    methods: Arc<Mutex<BTreeMap<&'static str, MethodStats>>>,
+    last: Arc<Mutex<LastOutcome>>,
+}
+
+impl Metrics {
+    /// Await an RPC, recording its latency and outcome under `method`.
+    /// Errors carry `method` as context so callers can tell which RPC failed.
+    pub async fn time<T>(
+        &self,
+        method: &'static str,
+        call: impl Future<Output = Result<T, Status>>,
+    ) -> anyhow::Result<T> {
+        let started = Instant::now();
+        let result = call.await;
+        if let Ok(mut methods) = self.methods.lock() {
+            methods
+                .entry(method)
+                .or_default()
+                .record(started.elapsed(), result.is_ok());
+        }
+        match result {
+            Ok(_) => {
+                if let Ok(mut last) = self.last.lock() {
+                    last.ok_at = Some(Instant::now());
+                }
+            }
+            Err(ref status) => self.record_error(method, status.code(), status.message()),
+        }
+        if let Ok(mut last) = self.last.lock() {
+            last.call_at = Some(Instant::now());
+        }
+        result.map_err(|status| anyhow::Error::new(status).context(method))
+    }
+
+    /// Record a failure that didn't go through `time`, such as a failed reconnect
+    pub fn record_error(&self, method: &'static str, code: tonic::Code, message: &str) {
+        if let Ok(mut last) = self.last.lock() {
+            last.error = Some(CallError {
+                method,
+                code,
+                message: message.to_string(),
+                at: Instant::now(),
+            });
+        }
+    }
+
+    /// The most recent failure, unless a call has succeeded since
+    pub fn unresolved_error(&self) -> Option<CallError> {
+        let last = self.last.lock().ok()?;
+        let error = last.error.clone()?;
+        match last.ok_at {
+            Some(ok_at) if ok_at > error.at => None,
+            _ => Some(error),
+        }
+    }
+
+    /// When the most recent call finished, if any has been made
+    pub fn last_call(&self) -> Option<Instant> {
+        self.last.lock().ok()?.call_at
+    }
+
+    fn snapshot(&self) -> BTreeMap<&'static str, MethodStats> {
+        self.methods.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+
+    /// Human-readable table of calls, errors and latency per method
+    pub fn summary(&self) -> String {
+        let methods = self.snapshot();
+        if methods.is_empty() {
+            return "No gRPC calls recorded.\n".to_string();
+        }
+        let mut out = format!(
+            "{:<26} {:>6} {:>6} {:>10} {:>10}\n",
+            "METHOD", "CALLS", "ERRORS", "MEAN ms", "MAX ms"
+        );
+        for (method, stats) in &methods {
+            let _ = writeln!(
+                out,
+                "{:<26} {:>6} {:>6} {:>10.1} {:>10.1}",
+                method,
+                stats.calls,
+                stats.errors,
+                stats.mean().as_secs_f64() * 1000.0,
+                stats.max.as_secs_f64() * 1000.0,
+            );
+        }
+        out
+    }
+
+    /// Prometheus text exposition format
+    pub fn prometheus(&self) -> String {
+        let methods = self.snapshot();
+        let mut out = String::new();
+        out.push_str("# HELP scalegraph_grpc_calls_total gRPC calls made by the client.\n");
+        out.push_str("# TYPE scalegraph_grpc_calls_total counter\n");
+        for (method, stats) in &methods {
+            let _ = writeln!(
+                out,
+                "scalegraph_grpc_calls_total{{method=\"{}\"}} {}",
+                method, stats.calls
+            );
+        }
+        out.push_str("# HELP scalegraph_grpc_errors_total gRPC calls that returned an error.\n");
+        out.push_str("# TYPE scalegraph_grpc_errors_total counter\n");
+        for (method, stats) in &methods {
+            let _ = writeln!(
+                out,
+                "scalegraph_grpc_errors_total{{method=\"{}\"}} {}",
+                method, stats.errors
+            );
+        }
+        out.push_str("# HELP scalegraph_grpc_latency_seconds gRPC call latency.\n");
+        out.push_str("# TYPE scalegraph_grpc_latency_seconds histogram\n");
+        for (method, stats) in &methods {
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(stats.buckets.iter()) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "scalegraph_grpc_latency_seconds_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    method, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "scalegraph_grpc_latency_seconds_bucket{{method=\"{}\",le=\"+Inf\"}} {}",
+                method, stats.calls
+            );
+            let _ = writeln!(
+                out,
+                "scalegraph_grpc_latency_seconds_sum{{method=\"{}\"}} {}",
+                method,
+                stats.total.as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "scalegraph_grpc_latency_seconds_count{{method=\"{}\"}} {}",
+                method, stats.calls
+            );
+        }
+        out
+    }
+}
+
+/// Pause before accepting again after `accept` fails
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Serve `metrics` as Prometheus text on every HTTP request to `addr`.
+///
+/// A minimal HTTP/1.1 responder is enough for scrapers and `curl`; the request
+/// path is ignored.
+pub async fn serve(addr: &str, metrics: Metrics) -> std::io::Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tokio::spawn(async move {
+        let mut failing = false;
+        loop {
+            let mut socket = match listener.accept().await {
+                Ok((socket, _)) => {
+                    failing = false;
+                    socket
+                }
+                // Errors such as EMFILE last until connections close, so wait
+                // instead of spinning, and report once per run of failures
+                Err(e) => {
+                    if !failing {
+                        eprintln!("Metrics endpoint failed to accept a connection: {}", e);
+                        failing = true;
+                    }
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            let metrics = metrics.clone();
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let body = metrics.prometheus();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_calls_show_up_in_the_summary_and_histogram() {
+        let metrics = Metrics::default();
+        {
+            let mut methods = metrics.methods.lock().unwrap();
+            let stats = methods.entry("list_participants").or_default();
+            for ms in [3, 20, 40] {
+                stats.record(Duration::from_millis(ms), true);
+            }
+            stats.record(Duration::from_secs(9), false);
+        }
+
+        let summary = metrics.summary();
+        let row = summary.lines().nth(1).unwrap();
+        assert_eq!(
+            row.split_whitespace().collect::<Vec<_>>(),
+            ["list_participants", "4", "1", "2265.8", "9000.0"]
+        );
+
+        let text = metrics.prometheus();
+        for line in [
+            "scalegraph_grpc_calls_total{method=\"list_participants\"} 4",
+            "scalegraph_grpc_errors_total{method=\"list_participants\"} 1",
+            // Cumulative: 3ms is under 5ms, 20ms under 25ms, 40ms under 50ms
+            "scalegraph_grpc_latency_seconds_bucket{method=\"list_participants\",le=\"0.005\"} 1",
+            "scalegraph_grpc_latency_seconds_bucket{method=\"list_participants\",le=\"0.01\"} 1",
+            "scalegraph_grpc_latency_seconds_bucket{method=\"list_participants\",le=\"0.025\"} 2",
+            "scalegraph_grpc_latency_seconds_bucket{method=\"list_participants\",le=\"0.05\"} 3",
+            // The 9s call only lands in +Inf
+            "scalegraph_grpc_latency_seconds_bucket{method=\"list_participants\",le=\"5\"} 3",
+            "scalegraph_grpc_latency_seconds_bucket{method=\"list_participants\",le=\"+Inf\"} 4",
+            "scalegraph_grpc_latency_seconds_sum{method=\"list_participants\"} 9.063",
+            "scalegraph_grpc_latency_seconds_count{method=\"list_participants\"} 4",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {:?} in\n{}", line, text);
+        }
+    }
+}
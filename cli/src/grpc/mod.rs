@@ -6,6 +6,7 @@
 //! - `ScalegraphClient`: Main client struct that wraps all service clients
 //! - Service-specific methods for Participant, Ledger, and Business operations
 //! - Type conversions between Rust types and protobuf messages
+//! - Per-method call and latency metrics (`metrics` submodule)
 //!
 //! The client handles connection management, request/response serialization,
 //! and error handling for all gRPC operations.
@@ -30,41 +31,123 @@ pub mod smartcontracts {
     tonic::include_proto!("scalegraph.smartcontracts");
 }
 
+mod metrics;
+
+pub use metrics::{serve as serve_metrics, CallError, Metrics};
+
 use anyhow::Result;
-use common::{Account, AccountType, Participant, ParticipantRole, Transaction, TransferEntry};
+use common::{Account, AccountType, Contact, Participant, ParticipantRole, Transaction, TransferEntry};
 use ledger::{
     ledger_service_client::LedgerServiceClient, CreditRequest, DebitRequest, GetAccountRequest,
     GetBalanceRequest, ListTransactionsRequest, TransferRequest,
 };
 use business::{
     business_service_client::BusinessServiceClient, participant_service_client::ParticipantServiceClient,
-    AccessPaymentRequest, BusinessTransactionResponse, GetParticipantAccountsRequest,
-    GetParticipantRequest, ListParticipantsRequest, PayInvoiceRequest, PurchaseInvoiceRequest,
+    AccessPaymentRequest, AddServiceRequest, BusinessTransactionResponse, CreateLoanRequest,
+    GetParticipantAccountsRequest, GetParticipantRequest, ListParticipantsRequest,
+    PayInvoiceRequest, PurchaseInvoiceRequest, RemoveServiceRequest, RepayLoanRequest,
+    UpdateParticipantContactRequest,
 };
 use smartcontracts::{
-    smart_contract_service_client::SmartContractServiceClient, ContractResponse,
-    ListContractsRequest,
+    contract_response, smart_contract_service_client::SmartContractServiceClient,
+    ContractResponse, CreateGenericContractRequest, CreateInvoiceContractRequest,
+    CreateRevenueShareContractRequest, GenericContract, GetContractRequest, InvoiceContract,
+    ListContractsRequest, RevenueShareContract, RevenueShareParty,
 };
-use tonic::transport::Channel;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use scalegraph_config::{AuthChannel, Config};
+pub use scalegraph_rules::amounts::format_balance;
+use scalegraph_rules::contact::validate_contact;
+use scalegraph_rules::escrow::{check_escrow_account, EscrowMove};
+use scalegraph_rules::shares::validate_revenue_shares;
+use scalegraph_rules::transactions::{reversal_entries, REVERSAL_PREFIX};
+
+/// Most account requests `get_accounts_of` keeps in flight at once
+const ACCOUNT_FETCH_CONCURRENCY: usize = 8;
 
 #[derive(Clone)]
 pub struct ScalegraphClient {
-    ledger: LedgerServiceClient<Channel>,
-    participant: ParticipantServiceClient<Channel>,
-    business: BusinessServiceClient<Channel>,
-    contracts: SmartContractServiceClient<Channel>,
+    ledger: LedgerServiceClient<AuthChannel>,
+    participant: ParticipantServiceClient<AuthChannel>,
+    business: BusinessServiceClient<AuthChannel>,
+    contracts: SmartContractServiceClient<AuthChannel>,
+    metrics: Metrics,
+    config: Config,
+    /// When the current channel was set up
+    opened_at: Instant,
+    /// Set by `disconnect`; the next call opens a new connection
+    disconnected_at: Option<Instant>,
 }
 
 impl ScalegraphClient {
-    pub async fn connect(addr: &str) -> Result<Self> {
-        let channel = Channel::from_shared(addr.to_string())?.connect().await?;
+    pub async fn connect(config: &Config) -> Result<Self> {
+        let channel = config.connect().await?;
+        Ok(Self::with_channel(channel, config, Metrics::default()))
+    }
 
-        Ok(Self {
+    fn with_channel(channel: AuthChannel, config: &Config, metrics: Metrics) -> Self {
+        Self {
             ledger: LedgerServiceClient::new(channel.clone()),
             participant: ParticipantServiceClient::new(channel.clone()),
             business: BusinessServiceClient::new(channel.clone()),
             contracts: SmartContractServiceClient::new(channel),
-        })
+            metrics,
+            config: config.clone(),
+            opened_at: Instant::now(),
+            disconnected_at: None,
+        }
+    }
+
+    /// Drop the connection, replacing it with a lazy channel so the next call
+    /// reconnects on its own. Used to release idle connections.
+    pub fn disconnect(&mut self) -> Result<()> {
+        let channel = self.config.connect_lazy()?;
+        *self = Self {
+            disconnected_at: Some(Instant::now()),
+            ..Self::with_channel(channel, &self.config, self.metrics.clone())
+        };
+        Ok(())
+    }
+
+    /// Whether the connection was dropped by `disconnect` and no call has
+    /// been made since
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected_at
+            .is_some_and(|at| self.metrics.last_call().is_none_or(|call| call < at))
+    }
+
+    /// Time since the last call finished, or since the channel was set up
+    pub fn idle_for(&self) -> Duration {
+        let last = self.metrics.last_call().map_or(self.opened_at, |call| call.max(self.opened_at));
+        last.elapsed()
+    }
+
+    /// Open a fresh channel with the original config, keeping the metrics
+    pub async fn reconnect(&mut self) -> Result<()> {
+        match Self::connect(&self.config).await {
+            Ok(fresh) => {
+                *self = Self {
+                    metrics: self.metrics.clone(),
+                    ..fresh
+                };
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics
+                    .record_error("connect", tonic::Code::Unavailable, &e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    pub fn grpc_url(&self) -> &str {
+        self.config.grpc_url()
+    }
+
+    /// Call and latency metrics for every RPC made through this client
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
     }
 
     // Participant operations
@@ -76,7 +159,10 @@ impl ScalegraphClient {
         let request = ListParticipantsRequest {
             role: role.map(|r| r as i32).unwrap_or(0),
         };
-        let response = self.participant.list_participants(request).await?;
+        let response = self
+            .metrics
+            .time("list_participants", self.participant.list_participants(request))
+            .await?;
         Ok(response.into_inner().participants)
     }
 
@@ -85,7 +171,10 @@ impl ScalegraphClient {
         let request = GetParticipantRequest {
             participant_id: id.to_string(),
         };
-        let response = self.participant.get_participant(request).await?;
+        let response = self
+            .metrics
+            .time("get_participant", self.participant.get_participant(request))
+            .await?;
         Ok(response.into_inner())
     }
 
@@ -93,10 +182,104 @@ impl ScalegraphClient {
         let request = GetParticipantAccountsRequest {
             participant_id: participant_id.to_string(),
         };
-        let response = self.participant.get_participant_accounts(request).await?;
+        let response = self
+            .metrics
+            .time("get_participant_accounts", self.participant.get_participant_accounts(request))
+            .await?;
         Ok(response.into_inner().accounts)
     }
 
+    /// Accounts of every participant in `participant_ids`, fetched with up to
+    /// `ACCOUNT_FETCH_CONCURRENCY` requests in flight over the shared channel.
+    /// Results come back in the order of `participant_ids`.
+    pub async fn get_accounts_of(&self, participant_ids: &[String]) -> Vec<Result<Vec<Account>>> {
+        let mut pending = participant_ids.iter().cloned().enumerate();
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut results: Vec<Option<Result<Vec<Account>>>> =
+            participant_ids.iter().map(|_| None).collect();
+        loop {
+            while tasks.len() < ACCOUNT_FETCH_CONCURRENCY {
+                let Some((index, participant_id)) = pending.next() else {
+                    break;
+                };
+                let mut client = self.clone();
+                tasks.spawn(async move { (index, client.get_participant_accounts(&participant_id).await) });
+            }
+            match tasks.join_next().await {
+                Some(Ok((index, accounts))) => results[index] = Some(accounts),
+                // A panicked request leaves its slot empty, reported below
+                Some(Err(_)) => {}
+                None => break,
+            }
+        }
+        results
+            .into_iter()
+            .map(|r| r.unwrap_or_else(|| Err(anyhow::anyhow!("account request did not complete"))))
+            .collect()
+    }
+
+    /// Tag a participant with a service; returns the updated participant
+    #[allow(dead_code)]
+    pub async fn add_service(&mut self, participant_id: &str, service_id: &str) -> Result<Participant> {
+        let request = AddServiceRequest {
+            participant_id: participant_id.to_string(),
+            service_id: service_id.to_string(),
+        };
+        let response = self
+            .metrics
+            .time("add_service", self.participant.add_service(request))
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    #[allow(dead_code)]
+    pub async fn remove_service(&mut self, participant_id: &str, service_id: &str) -> Result<Participant> {
+        let request = RemoveServiceRequest {
+            participant_id: participant_id.to_string(),
+            service_id: service_id.to_string(),
+        };
+        let response = self
+            .metrics
+            .time("remove_service", self.participant.remove_service(request))
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Replace a participant's services with `services`, one RPC per change
+    #[allow(dead_code)]
+    pub async fn set_services(&mut self, participant_id: &str, services: &[String]) -> Result<Participant> {
+        let mut participant = self.get_participant(participant_id).await?;
+        for service_id in participant.services.clone() {
+            if !services.contains(&service_id) {
+                participant = self.remove_service(participant_id, &service_id).await?;
+            }
+        }
+        for service_id in services {
+            if !participant.services.contains(service_id) {
+                participant = self.add_service(participant_id, service_id).await?;
+            }
+        }
+        Ok(participant)
+    }
+
+    /// Replace a participant's whole contact block; empty fields are cleared
+    pub async fn update_participant_contact(
+        &mut self,
+        participant_id: &str,
+        contact: Contact,
+    ) -> Result<Participant> {
+        validate_contact(&contact.email, &contact.phone, &contact.website).map_err(anyhow::Error::msg)?;
+        let request = UpdateParticipantContactRequest {
+            participant_id: participant_id.to_string(),
+            contact: Some(contact),
+        };
+        let response = self
+            .metrics
+            .time("update_participant_contact", self.participant.update_participant_contact(request))
+            .await?;
+        Ok(response.into_inner())
+    }
+
     // Ledger operations
 
     #[allow(dead_code)]
@@ -104,7 +287,10 @@ impl ScalegraphClient {
         let request = GetAccountRequest {
             account_id: account_id.to_string(),
         };
-        let response = self.ledger.get_account(request).await?;
+        let response = self
+            .metrics
+            .time("get_account", self.ledger.get_account(request))
+            .await?;
         Ok(response.into_inner())
     }
 
@@ -113,7 +299,10 @@ impl ScalegraphClient {
         let request = GetBalanceRequest {
             account_id: account_id.to_string(),
         };
-        let response = self.ledger.get_balance(request).await?;
+        let response = self
+            .metrics
+            .time("get_balance", self.ledger.get_balance(request))
+            .await?;
         Ok(response.into_inner().balance)
     }
 
@@ -129,7 +318,10 @@ impl ScalegraphClient {
             amount,
             reference: reference.to_string(),
         };
-        let response = self.ledger.credit(request).await?;
+        let response = self
+            .metrics
+            .time("credit", self.ledger.credit(request))
+            .await?;
         Ok(response.into_inner())
     }
 
@@ -145,7 +337,10 @@ impl ScalegraphClient {
             amount,
             reference: reference.to_string(),
         };
-        let response = self.ledger.debit(request).await?;
+        let response = self
+            .metrics
+            .time("debit", self.ledger.debit(request))
+            .await?;
         Ok(response.into_inner())
     }
 
@@ -161,7 +356,10 @@ impl ScalegraphClient {
                 .collect(),
             reference: reference.to_string(),
         };
-        let response = self.ledger.transfer(request).await?;
+        let response = self
+            .metrics
+            .time("transfer", self.ledger.transfer(request))
+            .await?;
         Ok(response.into_inner())
     }
 
@@ -174,10 +372,65 @@ impl ScalegraphClient {
             limit: limit.unwrap_or(50),
             account_id: account_id.unwrap_or("").to_string(),
         };
-        let response = self.ledger.list_transactions(request).await?;
+        let response = self
+            .metrics
+            .time("list_transactions", self.ledger.list_transactions(request))
+            .await?;
         Ok(response.into_inner().transactions)
     }
 
+    /// Post the inverse of a recent transaction with reference "REVERSAL of <id>"
+    pub async fn reverse_transaction(&mut self, transaction_id: &str) -> Result<Transaction> {
+        let original = self
+            .list_transactions(Some(REVERSAL_SCAN_LIMIT), None)
+            .await?
+            .into_iter()
+            .find(|tx| tx.id == transaction_id)
+            .ok_or_else(|| anyhow::anyhow!("transaction {} not found", transaction_id))?;
+        let entries = original.entries.iter().map(|e| (e.account_id.as_str(), e.amount));
+        let entries = reversal_entries(&original.id, &original.reference, entries).map_err(anyhow::Error::msg)?;
+        let reference = format!("{}{}", REVERSAL_PREFIX, original.id);
+        self.transfer(entries, &reference).await
+    }
+
+    /// Move `amount` from the payer's operating account into an escrow account
+    #[allow(dead_code)]
+    pub async fn fund_escrow(
+        &mut self,
+        payer_id: &str,
+        escrow_account_id: &str,
+        amount: i64,
+        reference: &str,
+    ) -> Result<Transaction> {
+        let escrow = self.get_account(escrow_account_id).await?;
+        let account_type = account_type_to_string(escrow.account_type);
+        check_escrow_account(&escrow.id, account_type, escrow.balance, EscrowMove::Fund, amount).map_err(anyhow::Error::msg)?;
+        let entries = vec![
+            (format!("{}:operating", payer_id), -amount),
+            (escrow_account_id.to_string(), amount),
+        ];
+        self.transfer(entries, reference).await
+    }
+
+    /// Move `amount` out of an escrow account to the beneficiary's operating account
+    #[allow(dead_code)]
+    pub async fn release_escrow(
+        &mut self,
+        escrow_account_id: &str,
+        beneficiary_id: &str,
+        amount: i64,
+        reference: &str,
+    ) -> Result<Transaction> {
+        let escrow = self.get_account(escrow_account_id).await?;
+        let account_type = account_type_to_string(escrow.account_type);
+        check_escrow_account(&escrow.id, account_type, escrow.balance, EscrowMove::Release, amount).map_err(anyhow::Error::msg)?;
+        let entries = vec![
+            (escrow_account_id.to_string(), -amount),
+            (format!("{}:operating", beneficiary_id), amount),
+        ];
+        self.transfer(entries, reference).await
+    }
+
     // Business operations
 
     #[allow(dead_code)]
@@ -194,7 +447,10 @@ impl ScalegraphClient {
             amount,
             reference: reference.to_string(),
         };
-        let response = self.business.purchase_invoice(request).await?;
+        let response = self
+            .metrics
+            .time("purchase_invoice", self.business.purchase_invoice(request))
+            .await?;
         Ok(response.into_inner())
     }
 
@@ -212,11 +468,13 @@ impl ScalegraphClient {
             amount,
             reference: reference.to_string(),
         };
-        let response = self.business.pay_invoice(request).await?;
+        let response = self
+            .metrics
+            .time("pay_invoice", self.business.pay_invoice(request))
+            .await?;
         Ok(response.into_inner())
     }
 
-    #[allow(dead_code)]
     pub async fn access_payment(
         &mut self,
         payer_id: &str,
@@ -234,7 +492,54 @@ impl ScalegraphClient {
             platform_id: platform_id.unwrap_or("").to_string(),
             platform_fee: platform_fee.unwrap_or(0),
         };
-        let response = self.business.access_payment(request).await?;
+        let response = self
+            .metrics
+            .time("access_payment", self.business.access_payment(request))
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    pub async fn create_loan(
+        &mut self,
+        lender_id: &str,
+        borrower_id: &str,
+        principal_cents: i64,
+        annual_interest_rate: f64,
+        term_months: i32,
+        reference: &str,
+    ) -> Result<BusinessTransactionResponse> {
+        let request = CreateLoanRequest {
+            lender_id: lender_id.to_string(),
+            borrower_id: borrower_id.to_string(),
+            principal_cents,
+            annual_interest_rate,
+            term_months,
+            reference: reference.to_string(),
+        };
+        let response = self
+            .metrics
+            .time("create_loan", self.business.create_loan(request))
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    pub async fn repay_loan(
+        &mut self,
+        lender_id: &str,
+        borrower_id: &str,
+        amount: i64,
+        reference: &str,
+    ) -> Result<BusinessTransactionResponse> {
+        let request = RepayLoanRequest {
+            lender_id: lender_id.to_string(),
+            borrower_id: borrower_id.to_string(),
+            amount,
+            reference: reference.to_string(),
+        };
+        let response = self
+            .metrics
+            .time("repay_loan", self.business.repay_loan(request))
+            .await?;
         Ok(response.into_inner())
     }
 
@@ -253,11 +558,100 @@ impl ScalegraphClient {
             participant_id: participant_id.unwrap_or_default(),
             limit: limit.unwrap_or(100),
         };
-        let response = self.contracts.list_contracts(request).await?;
+        let response = self
+            .metrics
+            .time("list_contracts", self.contracts.list_contracts(request))
+            .await?;
         Ok(response.into_inner().contracts)
     }
+
+    pub async fn create_invoice_contract(
+        &mut self,
+        request: CreateInvoiceContractRequest,
+    ) -> Result<InvoiceContract> {
+        let response = self
+            .metrics
+            .time(
+                "create_invoice_contract",
+                self.contracts.create_invoice_contract(request),
+            )
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Create a contract from a YAML definition, with optional `${var}` substitutions
+    #[allow(dead_code)]
+    pub async fn create_generic_contract(
+        &mut self,
+        yaml_content: &str,
+        variables: HashMap<String, String>,
+    ) -> Result<GenericContract> {
+        let request = CreateGenericContractRequest {
+            yaml_content: yaml_content.to_string(),
+            yaml_file_path: String::new(),
+            variables,
+        };
+        let response = self
+            .metrics
+            .time(
+                "create_generic_contract",
+                self.contracts.create_generic_contract(request),
+            )
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_generic_contract(&mut self, contract_id: &str) -> Result<GenericContract> {
+        let request = GetContractRequest {
+            contract_id: contract_id.to_string(),
+            contract_type: 0,
+        };
+        let response = self
+            .metrics
+            .time("get_contract", self.contracts.get_contract(request))
+            .await?;
+        match response.into_inner().contract {
+            Some(contract_response::Contract::Generic(contract)) => Ok(contract),
+            Some(_) => anyhow::bail!("contract {} is not a generic contract", contract_id),
+            None => anyhow::bail!("contract {} not found", contract_id),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn create_revenue_share_contract(
+        &mut self,
+        transaction_type: &str,
+        parties: &[(String, f64)],
+        auto_split: bool,
+    ) -> Result<RevenueShareContract> {
+        validate_revenue_shares(parties).map_err(anyhow::Error::msg)?;
+        let request = CreateRevenueShareContractRequest {
+            transaction_type: transaction_type.to_string(),
+            parties: parties
+                .iter()
+                .map(|(participant_id, share)| RevenueShareParty {
+                    participant_id: participant_id.clone(),
+                    share: *share,
+                })
+                .collect(),
+            auto_split,
+            metadata: Default::default(),
+        };
+        let response = self
+            .metrics
+            .time(
+                "create_revenue_share_contract",
+                self.contracts.create_revenue_share_contract(request),
+            )
+            .await?;
+        Ok(response.into_inner())
+    }
 }
 
+/// How far back reverse_transaction looks for the original
+const REVERSAL_SCAN_LIMIT: i32 = 1000;
+
 // Helper functions for display
 
 pub fn role_to_string(role: i32) -> &'static str {
@@ -267,6 +661,7 @@ pub fn role_to_string(role: i32) -> &'static str {
         Ok(ParticipantRole::EcosystemPartner) => "Ecosystem Partner",
         Ok(ParticipantRole::Supplier) => "Supplier",
         Ok(ParticipantRole::EquipmentProvider) => "Equipment Provider",
+        Ok(ParticipantRole::EcosystemOrchestrator) => "Ecosystem Orchestrator",
         _ => "Unknown",
     }
 }
@@ -284,12 +679,37 @@ pub fn account_type_to_string(account_type: i32) -> &'static str {
     }
 }
 
-pub fn format_balance(balance: i64) -> String {
-    let whole = balance / 100;
-    let cents = (balance % 100).abs();
-    if balance < 0 {
-        format!("-{}.{:02}", whole.abs(), cents)
-    } else {
-        format!("{}.{:02}", whole, cents)
+/// One-line description of a client error: the RPC that failed and the
+/// server's status, without tonic's metadata dump
+pub fn error_summary(e: &anyhow::Error) -> String {
+    match e.downcast_ref::<tonic::Status>() {
+        Some(status) => format!("{}: {:?}: {}", e, status.code(), status.message()),
+        None => format!("{:#}", e),
+    }
+}
+
+
+/// Parse a decimal amount such as "1500.23" or "-12.5" into cents.
+/// At most two decimal places are accepted.
+pub fn parse_amount(input: &str) -> Option<i64> {
+    let input = input.trim();
+    let (negative, digits) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    let (whole, frac) = match digits.split_once('.') {
+        Some((w, f)) => (w, f),
+        None => (digits, ""),
+    };
+    if (whole.is_empty() && frac.is_empty())
+        || frac.len() > 2
+        || !whole.chars().all(|c| c.is_ascii_digit())
+        || !frac.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
     }
+    let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+    let frac: i64 = format!("{:0<2}", frac).parse().ok()?;
+    let cents = whole.checked_mul(100)?.checked_add(frac)?;
+    Some(if negative { -cents } else { cents })
 }
@@ -9,15 +9,27 @@
 //! - Real-time balance viewing
 //! - Transaction execution
 //! - Transaction history
+//! - Copying ids to the system clipboard (optional `clipboard` feature)
+//! - gRPC latency metrics (`--check` summary, Prometheus via `--metrics-addr`)
+//! - `scalegraph watch`: a non-interactive health monitor (see `watch`)
+//! - `scalegraph verify`: ledger consistency checks (see `verify`)
 //!
 //! The CLI connects to the Scalegraph gRPC server running on localhost:50051
 //! (or a custom address specified via command-line arguments).
+//!
+//! Settings can also come from `scalegraph.toml` (see `scalegraph_config`);
+//! command-line flags override the file, which overrides environment variables.
 
+mod clipboard;
 mod grpc;
+mod paths;
+mod templates;
 mod ui;
+mod verify;
+mod watch;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
@@ -25,23 +37,113 @@ use crossterm::{
 };
 use grpc::ScalegraphClient;
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
+use std::io::{self, Write};
+use scalegraph_config::Config;
 use std::panic;
+use std::path::PathBuf;
 use std::time::Duration;
 use tokio::time::timeout;
-use ui::{run_app, App};
+use ui::{run_app, App, Keymap, ThemeName, TuiState};
 
 #[derive(Parser, Debug)]
 #[command(name = "scalegraph")]
 #[command(about = "TUI CLI for Scalegraph Ledger", long_about = None)]
 struct Args {
-    /// gRPC server address
-    #[arg(short, long, default_value = "http://localhost:50051")]
-    server: String,
+    /// gRPC server address [default: http://localhost:50051]
+    #[arg(short, long, value_parser = parse_server_url)]
+    server: Option<String>,
+
+    /// Config file [default: $SCALEGRAPH_CONFIG or ./scalegraph.toml]
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// PEM file with the CA certificate for TLS connections
+    #[arg(long)]
+    tls_ca: Option<PathBuf>,
+
+    /// Per-RPC timeout in seconds
+    #[arg(long)]
+    rpc_timeout: Option<u64>,
+
+    /// Reload data automatically every N seconds (0 disables)
+    #[arg(long)]
+    refresh_interval: Option<u64>,
+
+    /// Drop the connection after N minutes without calls; the next call reconnects (0 disables)
+    #[arg(long, value_name = "MINUTES")]
+    idle_timeout: Option<u64>,
 
     /// Check connection and list participants without starting TUI
     #[arg(long)]
     check: bool,
+
+    /// Serve gRPC call metrics as Prometheus text on this address (e.g. 127.0.0.1:9184)
+    #[arg(long)]
+    metrics_addr: Option<String>,
+
+    /// Color theme (cycle at runtime with 'T') [default: dark]
+    #[arg(long, value_enum)]
+    theme: Option<ThemeName>,
+
+    /// Draw without colors; also enabled by a non-empty NO_COLOR environment variable
+    #[arg(long)]
+    no_color: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Probe the server repeatedly and print one status line per probe
+    Watch {
+        /// Seconds between probes
+        #[arg(short, long, default_value_t = 5)]
+        interval: u64,
+
+        /// Exit with status 1 after this many consecutive failed probes
+        #[arg(long)]
+        fail_after: Option<u32>,
+    },
+    /// Check ledger invariants against the server and exit with status 1 on a failure
+    Verify {
+        /// Most recent transactions to fetch; balances are only checked against a complete history
+        #[arg(long, default_value_t = 10_000)]
+        limit: i32,
+
+        /// Treat warnings (such as unexplained opening balances) as failures
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Print a shell completion script for bash, zsh, fish, elvish or PowerShell
+    ///
+    /// For example: scalegraph completions bash > ~/.local/share/bash-completion/completions/scalegraph
+    Completions {
+        /// Shell to generate the script for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+}
+
+/// `--server` must be a full URL; catching "localhost:50051" here beats a transport error later
+fn parse_server_url(url: &str) -> Result<String, String> {
+    scalegraph_config::check_grpc_url("--server", url)
+        .map(|()| url.to_string())
+        .map_err(|e| e.to_string())
+}
+
+impl Args {
+    /// Layer command-line flags over the loaded config
+    fn apply_to(&self, config: Config) -> Config {
+        config.merge(Config {
+            grpc_url: self.server.clone(),
+            tls_ca: self.tls_ca.clone(),
+            rpc_timeout: self.rpc_timeout,
+            refresh_interval: self.refresh_interval,
+            idle_timeout: self.idle_timeout,
+            ..Default::default()
+        })
+    }
 }
 
 fn cleanup_terminal() {
@@ -52,6 +154,53 @@ fn cleanup_terminal() {
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+    // Needs neither a config nor a server
+    if let Some(Command::Completions { shell }) = args.command {
+        // Buffered so a closed pipe (`| head`) is an error, not a panic
+        let mut script = Vec::new();
+        clap_complete::generate(shell, &mut Args::command(), "scalegraph", &mut script);
+        io::stdout().write_all(&script)?;
+        return Ok(());
+    }
+    let config = match Config::load(args.config.as_deref()) {
+        Ok(config) => args.apply_to(config),
+        Err(e) => {
+            eprintln!("{:#}", e);
+            std::process::exit(1);
+        }
+    };
+    let saved_state = TuiState::load();
+    let theme = match (args.theme, config.theme.as_deref()) {
+        (Some(theme), _) => theme,
+        (None, Some(name)) => match ThemeName::from_str(name, true) {
+            Ok(theme) => theme,
+            Err(_) => {
+                eprintln!("Unknown theme in config: {}", name);
+                std::process::exit(1);
+            }
+        },
+        // Neither flag nor config: the theme last used, if any
+        (None, None) => saved_state.theme.unwrap_or(ThemeName::Dark),
+    };
+    let keymap = match Keymap::from_config(config.keys.as_ref()) {
+        Ok(keymap) => keymap,
+        Err(e) => {
+            eprintln!("Invalid key bindings in config: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Watch mode handles its own (re)connects and never starts the TUI
+    if let Some(Command::Watch { interval, fail_after }) = args.command {
+        return watch::run(&config, Duration::from_secs(interval.max(1)), fail_after).await;
+    }
+    if let Some(Command::Verify { limit, strict }) = args.command {
+        if let Err(e) = verify::run(&config, limit.max(1), strict).await {
+            eprintln!("Verification could not run: {}", grpc::error_summary(&e));
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     // Set up panic hook to restore terminal
     let original_hook = panic::take_hook();
@@ -61,8 +210,8 @@ async fn main() -> Result<()> {
     }));
 
     // Connect to gRPC server with timeout
-    println!("Connecting to {}...", args.server);
-    let connect_future = ScalegraphClient::connect(&args.server);
+    println!("Connecting to {}...", config.grpc_url());
+    let connect_future = ScalegraphClient::connect(&config);
     let mut client = match timeout(Duration::from_secs(5), connect_future).await {
         Ok(Ok(c)) => {
             println!("Connected!");
@@ -78,7 +227,7 @@ async fn main() -> Result<()> {
             eprintln!("Connection timed out after 5 seconds.");
             eprintln!(
                 "Make sure the Scalegraph Elixir server is running on {}",
-                args.server
+                config.grpc_url()
             );
             std::process::exit(1);
         }
@@ -94,9 +243,11 @@ async fn main() -> Result<()> {
                 for p in participants {
                     println!("  - {} ({})", p.name, p.id);
                 }
+                println!();
+                print!("{}", client.metrics().summary());
             }
             Ok(Err(e)) => {
-                eprintln!("❌ gRPC call failed: {}", e);
+                eprintln!("❌ gRPC call failed: {}", grpc::error_summary(&e));
                 std::process::exit(1);
             }
             Err(_) => {
@@ -107,6 +258,16 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    if let Some(ref addr) = args.metrics_addr {
+        if let Err(e) = grpc::serve_metrics(addr, client.metrics().clone()).await {
+            eprintln!("Failed to serve metrics on {}: {}", addr, e);
+            std::process::exit(1);
+        }
+    }
+
+    // https://no-color.org: any non-empty value disables color
+    let no_color = args.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -115,7 +276,12 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and run
-    let app = App::new(client);
+    let app = App::new(client, theme)
+        .with_refresh_interval(config.refresh_interval())
+        .with_idle_timeout(config.idle_timeout())
+        .with_no_color(no_color)
+        .with_keymap(keymap)
+        .with_saved_state(saved_state);
     let res = run_app(&mut terminal, app).await;
 
     // Restore terminal
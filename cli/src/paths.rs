@@ -0,0 +1,15 @@
+//! Per-User Files
+//!
+//! Files the TUI keeps between runs (transfer templates, the last view) live
+//! in `$XDG_CONFIG_HOME/scalegraph`, falling back to `~/.config/scalegraph`.
+
+use std::path::PathBuf;
+
+/// `<config dir>/scalegraph/<file>`, or None when neither variable is set
+pub fn config_file(file: &str) -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("scalegraph").join(file))
+}
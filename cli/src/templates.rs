@@ -0,0 +1,111 @@
+//! Transfer Templates
+//!
+//! Named transfers (rent, fees, ...) saved from the Transfer form so the
+//! account ids don't have to be retyped. They are kept as JSON in
+//! `transfer_templates.json` under the user's config directory (see `paths`).
+
+use crate::paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const TEMPLATES_FILE: &str = "transfer_templates.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransferTemplate {
+    pub name: String,
+    pub from_account: String,
+    pub to_account: String,
+    /// Amount as typed in the form (e.g. "1250.00"), possibly empty
+    pub amount: String,
+    pub reference: String,
+}
+
+#[derive(Debug, Default)]
+pub struct TemplateStore {
+    /// None when no config directory could be determined; saving then fails
+    path: Option<PathBuf>,
+    /// Sorted by name
+    pub templates: Vec<TransferTemplate>,
+}
+
+impl TemplateStore {
+    /// Templates from the default location. A missing file is an empty store;
+    /// an unreadable one is an error, with the store still pointing at it.
+    pub fn load_default() -> (Self, Option<anyhow::Error>) {
+        let path = paths::config_file(TEMPLATES_FILE);
+        match Self::load(path.clone()) {
+            Ok(store) => (store, None),
+            Err(e) => (Self { path, templates: Vec::new() }, Some(e)),
+        }
+    }
+
+    pub fn load(path: Option<PathBuf>) -> Result<Self> {
+        let templates = match path {
+            Some(ref p) if p.exists() => {
+                let content = std::fs::read_to_string(p)
+                    .with_context(|| format!("cannot read {}", p.display()))?;
+                serde_json::from_str(&content)
+                    .with_context(|| format!("invalid templates file {}", p.display()))?
+            }
+            _ => Vec::new(),
+        };
+        Ok(Self { path, templates })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = self
+            .path
+            .as_ref()
+            .context("no config directory (set HOME or XDG_CONFIG_HOME)")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("cannot create {}", dir.display()))?;
+        }
+        let content = serde_json::to_string_pretty(&self.templates)?;
+        std::fs::write(path, content).with_context(|| format!("cannot write {}", path.display()))
+    }
+
+    /// Add `template`, replacing an existing one with the same name
+    pub fn upsert(&mut self, template: TransferTemplate) {
+        self.templates.retain(|t| t.name != template.name);
+        self.templates.push(template);
+        self.templates.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<TransferTemplate> {
+        (index < self.templates.len()).then(|| self.templates.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(name: &str, amount: &str) -> TransferTemplate {
+        TransferTemplate {
+            name: name.to_string(),
+            from_account: "salon_glamour:operating".to_string(),
+            to_account: "beauty_hosting:fees".to_string(),
+            amount: amount.to_string(),
+            reference: format!("{} payment", name),
+        }
+    }
+
+    #[test]
+    fn templates_round_trip_through_the_file() {
+        let dir = std::env::temp_dir().join(format!("scalegraph-templates-{}", std::process::id()));
+        let path = dir.join(TEMPLATES_FILE);
+
+        let mut store = TemplateStore::load(Some(path.clone())).unwrap();
+        assert!(store.templates.is_empty());
+        store.upsert(template("rent", "1000.00"));
+        store.upsert(template("fees", "50.00"));
+        store.upsert(template("rent", "1100.00"));
+        store.save().unwrap();
+
+        let loaded = TemplateStore::load(Some(path)).unwrap();
+        assert_eq!(loaded.templates, vec![template("fees", "50.00"), template("rent", "1100.00")]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -6,27 +6,84 @@
 //! - Application state (participants, accounts, transactions)
 //! - User input handling and navigation
 //! - Data loading from the gRPC server
-//! - Transfer form state and validation
+//! - Transfer form state and validation, and saved transfer templates
 //! - View management and transitions
 //!
 //! The `App` struct is the central state container, and `run_app` is the
 //! main event loop that processes user input and updates the UI.
 
-use crate::grpc::{self, ScalegraphClient};
+use crate::clipboard::Clipboard;
+use crate::grpc::{self, CallError, ScalegraphClient};
+use crate::templates::{TemplateStore, TransferTemplate};
+use super::export;
+use super::keymap::{fixed_commands, Action, Command, Keymap};
+use super::nav::{self, Motion};
+use super::timeline::{self, DAY_MS};
+use super::state::TuiState;
+use super::theme::{Theme, ThemeName};
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
-use ratatui::{backend::CrosstermBackend, widgets::ListState, Terminal};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Position, Rect},
+    widgets::ListState,
+    Terminal,
+};
+use scalegraph_rules::loans::{monthly_payment, MAX_TERM_MONTHS};
+use scalegraph_rules::transactions::{transaction_type, TRANSACTION_TYPES};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::io::Stdout;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 pub type AppResult<T> = Result<T>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Rows per page for Ctrl+F/Ctrl+B when the view didn't record its list area
+const DEFAULT_PAGE_ROWS: usize = 10;
+
+/// Participants and accounts fetched less than this long ago are reused when
+/// switching views; 'r' always refetches
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Contracts fetched for the Future and Overview views
+pub const CONTRACT_LIST_LIMIT: i32 = 100;
+
+/// How long a transient status message (e.g. "copied <id>") stays visible
+const STATUS_MESSAGE_TTL: Duration = Duration::from_secs(3);
+
+/// Errors stay in the status bar longer than ordinary messages
+const ERROR_MESSAGE_TTL: Duration = Duration::from_secs(10);
+
+/// Lines scrolled per PageUp/PageDown in the participant detail About/Contact panels
+const DETAIL_SCROLL_PAGE: u16 = 5;
+
+/// Participants shown per page; the whole list stays loaded, only drawing is paged
+pub const PARTICIPANT_PAGE_SIZE: usize = 50;
+
+/// Recent transactions replayed per account for the Participant Details trend
+const TREND_TRANSACTIONS: i32 = 30;
+
+/// How long a balance that moved in a reload stays highlighted with its delta
+const BALANCE_CHANGE_TTL: Duration = Duration::from_secs(3);
+
+/// Two clicks on the same row within this interval count as a double-click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum View {
+    Overview,
     Participants,
     ParticipantDetail,
     Transfer,
     History,
     Future,
+    Loan,
+    Accounts,
+    AccessPayment,
 }
 
 impl View {
@@ -34,25 +91,33 @@ impl View {
         // Only include flat navigation views (tabs), not hierarchical views
         // ParticipantDetail is accessed by drilling down from Participants, not via tabs
         vec![
+            View::Overview,
             View::Participants,
             View::Transfer,
             View::History,
             View::Future,
+            View::Loan,
+            View::Accounts,
+            View::AccessPayment,
         ]
     }
 
     pub fn title(&self) -> &'static str {
         match self {
+            View::Overview => "Overview",
             View::Participants => "Participants",
             View::ParticipantDetail => "Participant Details",
             View::Transfer => "Transfer",
             View::History => "History",
             View::Future => "Future",
+            View::Loan => "Loan",
+            View::Accounts => "Accounts",
+            View::AccessPayment => "Access Pay",
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ContactInfo {
     pub email: String,
     pub phone: String,
@@ -73,14 +138,39 @@ pub struct ParticipantInfo {
     pub metadata: std::collections::HashMap<String, String>,
     pub about: String,
     pub contact: ContactInfo,
+    /// Sum of the participant's operating accounts, if it has any
+    pub operating_balance: Option<i64>,
+}
+
+impl ParticipantInfo {
+    /// Amount by which the operating account is below zero, if overdrawn
+    pub fn overdrawn_by(&self) -> Option<i64> {
+        self.operating_balance.filter(|b| *b < 0).map(|b| -b)
+    }
+}
+
+/// Whether data fetched at `loaded_at` is recent enough to reuse
+fn is_fresh(loaded_at: Option<Instant>) -> bool {
+    loaded_at.is_some_and(|at| at.elapsed() < CACHE_TTL)
+}
+
+/// Combined balance of the operating accounts in `accounts`, if there are any
+fn operating_balance<'a>(accounts: impl IntoIterator<Item = (&'a str, i64)>) -> Option<i64> {
+    accounts
+        .into_iter()
+        .filter(|(account_type, _)| *account_type == "Operating")
+        .map(|(_, balance)| balance)
+        .reduce(|a, b| a + b)
 }
 
 #[derive(Debug, Clone)]
 pub struct ContractInfo {
+    #[allow(dead_code)]
     pub id: String,
     pub contract_type: String,
     pub description: String,
     pub participants: Vec<String>, // Other participants in the contract
+    #[allow(dead_code)]
     pub next_execution: Option<i64>, // Next execution time in milliseconds
 }
 
@@ -90,17 +180,184 @@ pub struct ParticipantDetail {
     pub accounts: Vec<AccountInfo>,
     pub total_balance: i64,
     pub contracts: Vec<ContractInfo>,
+    /// Balance after each of the account's recent transactions, oldest first,
+    /// keyed by account id
+    pub trends: HashMap<String, Vec<i64>>,
+}
+
+/// Account types in display order for per-type subtotals
+pub const SUBTOTAL_ACCOUNT_TYPES: [&str; 7] = [
+    "Operating",
+    "Receivables",
+    "Payables",
+    "Escrow",
+    "Fees",
+    "Usage",
+    "Standalone",
+];
+
+/// Liability/revenue accounts whose normal balance is a credit (stored negative)
+pub fn is_credit_normal(account_type: &str) -> bool {
+    matches!(account_type, "Payables" | "Fees")
+}
+
+fn balances_by_id(accounts: &[AccountInfo]) -> HashMap<String, i64> {
+    accounts.iter().map(|a| (a.id.clone(), a.balance)).collect()
+}
+
+/// How much each account in `after` moved since `before`. Accounts that are
+/// new, or unchanged, are left out.
+pub fn balance_changes(before: &HashMap<String, i64>, after: &[AccountInfo]) -> HashMap<String, i64> {
+    after
+        .iter()
+        .filter_map(|a| {
+            let delta = a.balance - before.get(&a.id)?;
+            (delta != 0).then(|| (a.id.clone(), delta))
+        })
+        .collect()
+}
+
+/// Balances of `account_id` over `transactions` (newest first, as the ledger
+/// lists them), worked backwards from its current `balance`. Oldest first,
+/// starting with the balance before the oldest transaction.
+pub fn balance_trend(account_id: &str, balance: i64, transactions: &[grpc::common::Transaction]) -> Vec<i64> {
+    let mut trend = vec![balance];
+    let mut after = balance;
+    for tx in transactions {
+        let change: i64 = tx
+            .entries
+            .iter()
+            .filter(|e| e.account_id == account_id)
+            .map(|e| e.amount)
+            .sum();
+        after -= change;
+        trend.push(after);
+    }
+    trend.reverse();
+    trend
+}
+
+impl ParticipantDetail {
+    /// Balance per account type, in `SUBTOTAL_ACCOUNT_TYPES` order, for types the participant has
+    pub fn subtotals(&self) -> Vec<(&'static str, i64)> {
+        SUBTOTAL_ACCOUNT_TYPES
+            .iter()
+            .filter_map(|t| {
+                let mut accounts = self.accounts.iter().filter(|a| a.account_type == *t).peekable();
+                accounts.peek()?;
+                Some((*t, accounts.map(|a| a.balance).sum()))
+            })
+            .collect()
+    }
+
+    /// Receivables plus payables (payables are negative): what the participant is owed, net
+    pub fn net_receivables(&self) -> Option<i64> {
+        let subtotals = self.subtotals();
+        let find = |t: &str| subtotals.iter().find(|(ty, _)| *ty == t).map(|(_, b)| *b);
+        match (find("Receivables"), find("Payables")) {
+            (None, None) => None,
+            (r, p) => Some(r.unwrap_or(0) + p.unwrap_or(0)),
+        }
+    }
+}
+
+/// Ecosystem composition shown in the Overview view
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OverviewStats {
+    /// Participant count per role, largest first
+    pub roles: Vec<(String, usize)>,
+    pub participants: usize,
+    pub accounts: usize,
+    pub receivables: i64,
+    /// What participants owe, as a positive amount (payables are stored negative)
+    pub payables: i64,
+}
+
+impl OverviewStats {
+    pub fn compute(participants: &[ParticipantInfo], accounts: &[AccountInfo]) -> Self {
+        let mut roles: Vec<(String, usize)> = Vec::new();
+        for participant in participants {
+            match roles.iter_mut().find(|(role, _)| *role == participant.role) {
+                Some((_, count)) => *count += 1,
+                None => roles.push((participant.role.clone(), 1)),
+            }
+        }
+        roles.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let sum = |account_type: &str| -> i64 {
+            accounts
+                .iter()
+                .filter(|a| a.account_type == account_type)
+                .map(|a| a.balance)
+                .sum()
+        };
+        Self {
+            roles,
+            participants: participants.len(),
+            accounts: accounts.len(),
+            receivables: sum("Receivables"),
+            payables: -sum("Payables"),
+        }
+    }
+}
+
+/// Type label of `contract` if it is still in force: pending or overdue
+/// invoices, pending conditional payments, and active subscriptions, revenue
+/// shares and generic contracts
+fn active_contract_type(contract: &grpc::smartcontracts::ContractResponse) -> Option<String> {
+    use crate::grpc::smartcontracts::contract_response::Contract;
+    use crate::grpc::smartcontracts::ContractStatus;
+
+    let label = match contract.contract.as_ref()? {
+        Contract::Invoice(inv) if matches!(inv.status.as_str(), "pending" | "overdue") => "Invoice".to_string(),
+        Contract::Subscription(sub) if sub.status == "active" => "Subscription".to_string(),
+        Contract::ConditionalPayment(cp) if matches!(cp.status.as_str(), "pending" | "conditions_met") => {
+            "Conditional Payment".to_string()
+        }
+        Contract::RevenueShare(rs) if rs.status == "active" => "Revenue Share".to_string(),
+        Contract::Generic(gen) if gen.status == ContractStatus::Active as i32 => {
+            App::contract_type_to_string(gen.contract_type)
+        }
+        _ => return None,
+    };
+    Some(label)
+}
+
+/// Active contracts per type label, largest first
+fn active_contract_counts(contracts: &[grpc::smartcontracts::ContractResponse]) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for label in contracts.iter().filter_map(active_contract_type) {
+        match counts.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((label, 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
 }
 
 #[derive(Debug, Clone)]
 pub struct AccountInfo {
     pub id: String,
-    #[allow(dead_code)]
     pub participant_id: String,
     pub account_type: String,
     pub balance: i64,
 }
 
+/// A line of the all-accounts view: a participant group header, or one of
+/// that participant's accounts
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountRow {
+    Group {
+        participant_id: String,
+        name: String,
+        count: usize,
+        subtotal: i64,
+        collapsed: bool,
+    },
+    /// Index into `App::accounts`
+    Account(usize),
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct TransferForm {
     pub from_account: String,
@@ -114,6 +371,494 @@ pub struct TransferForm {
     pub show_suggestions: bool,
 }
 
+/// Modal popup over the Transfer form for saving or loading a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePopup {
+    /// Naming the current form before saving it
+    Save { name: String },
+    /// Choosing a saved template; `selected` indexes `TemplateStore::templates`
+    Pick { selected: usize },
+}
+
+/// Labels of the contact form fields, in `ContactForm::values` order
+pub const CONTACT_FIELDS: [&str; 7] = ["Email", "Phone", "Website", "Address", "Postal code", "City", "Country"];
+
+/// Popup editing the contact block of the participant in Participant Details
+#[derive(Debug, Clone, Default)]
+pub struct ContactForm {
+    pub participant_id: String,
+    pub values: [String; 7],
+    pub selected: usize,
+    pub error: Option<String>,
+}
+
+impl ContactForm {
+    fn new(participant_id: &str, contact: &ContactInfo) -> Self {
+        Self {
+            participant_id: participant_id.to_string(),
+            values: [
+                contact.email.clone(),
+                contact.phone.clone(),
+                contact.website.clone(),
+                contact.address.clone(),
+                contact.postal_code.clone(),
+                contact.city.clone(),
+                contact.country.clone(),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn to_contact(&self) -> grpc::common::Contact {
+        let [email, phone, website, address, postal_code, city, country] =
+            self.values.clone().map(|v| v.trim().to_string());
+        grpc::common::Contact {
+            email,
+            phone,
+            website,
+            address,
+            postal_code,
+            city,
+            country,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceField {
+    Supplier,
+    Buyer,
+    Amount,
+    IssueDate,
+    DueDate,
+    PaymentTerms,
+    AutoDebit,
+    LateFee,
+    Reference,
+}
+
+/// Invoice form fields in display order
+pub const INVOICE_FIELDS: [InvoiceField; 9] = [
+    InvoiceField::Supplier,
+    InvoiceField::Buyer,
+    InvoiceField::Amount,
+    InvoiceField::IssueDate,
+    InvoiceField::DueDate,
+    InvoiceField::PaymentTerms,
+    InvoiceField::AutoDebit,
+    InvoiceField::LateFee,
+    InvoiceField::Reference,
+];
+
+impl InvoiceField {
+    pub fn label(self) -> &'static str {
+        match self {
+            InvoiceField::Supplier => "Supplier",
+            InvoiceField::Buyer => "Buyer",
+            InvoiceField::Amount => "Amount",
+            InvoiceField::IssueDate => "Issue date",
+            InvoiceField::DueDate => "Due date",
+            InvoiceField::PaymentTerms => "Terms",
+            InvoiceField::AutoDebit => "Auto-debit",
+            InvoiceField::LateFee => "Late fee",
+            InvoiceField::Reference => "Reference",
+        }
+    }
+}
+
+/// Days from issue to due date that a new invoice form starts with
+const INVOICE_DEFAULT_TERM_DAYS: u64 = 30;
+
+/// Popup creating an invoice contract, opened from Participant Details
+#[derive(Debug, Clone, Default)]
+pub struct InvoiceForm {
+    pub supplier_id: String,
+    pub buyer_id: String,
+    pub amount: String,     // Decimal, e.g. "1500.00"
+    pub issue_date: String, // YYYY-MM-DD
+    pub due_date: String,   // YYYY-MM-DD
+    pub payment_terms: String,
+    pub auto_debit: bool,
+    pub late_fee: String, // Decimal, empty for no fee
+    pub reference: String,
+    pub selected: usize,
+    pub error: Option<String>,
+}
+
+impl InvoiceForm {
+    /// A Net 30 invoice from `supplier_id` issued today
+    fn new(supplier_id: &str, today: chrono::NaiveDate) -> Self {
+        let due = today + chrono::Days::new(INVOICE_DEFAULT_TERM_DAYS);
+        Self {
+            supplier_id: supplier_id.to_string(),
+            issue_date: today.format("%Y-%m-%d").to_string(),
+            due_date: due.format("%Y-%m-%d").to_string(),
+            payment_terms: format!("Net {}", INVOICE_DEFAULT_TERM_DAYS),
+            ..Default::default()
+        }
+    }
+
+    pub fn current_field(&self) -> InvoiceField {
+        INVOICE_FIELDS[self.selected.min(INVOICE_FIELDS.len() - 1)]
+    }
+
+    pub fn value(&self, field: InvoiceField) -> &str {
+        match field {
+            InvoiceField::Supplier => &self.supplier_id,
+            InvoiceField::Buyer => &self.buyer_id,
+            InvoiceField::Amount => &self.amount,
+            InvoiceField::IssueDate => &self.issue_date,
+            InvoiceField::DueDate => &self.due_date,
+            InvoiceField::PaymentTerms => &self.payment_terms,
+            InvoiceField::AutoDebit => if self.auto_debit { "yes" } else { "no" },
+            InvoiceField::LateFee => &self.late_fee,
+            InvoiceField::Reference => &self.reference,
+        }
+    }
+
+    fn value_mut(&mut self, field: InvoiceField) -> Option<&mut String> {
+        match field {
+            InvoiceField::Supplier => Some(&mut self.supplier_id),
+            InvoiceField::Buyer => Some(&mut self.buyer_id),
+            InvoiceField::Amount => Some(&mut self.amount),
+            InvoiceField::IssueDate => Some(&mut self.issue_date),
+            InvoiceField::DueDate => Some(&mut self.due_date),
+            InvoiceField::PaymentTerms => Some(&mut self.payment_terms),
+            InvoiceField::AutoDebit => None,
+            InvoiceField::LateFee => Some(&mut self.late_fee),
+            InvoiceField::Reference => Some(&mut self.reference),
+        }
+    }
+
+    /// The request for the entered invoice, or what is wrong with it
+    fn to_request(&self) -> Result<grpc::smartcontracts::CreateInvoiceContractRequest> {
+        let supplier_id = self.supplier_id.trim();
+        let buyer_id = self.buyer_id.trim();
+        if supplier_id.is_empty() || buyer_id.is_empty() {
+            anyhow::bail!("Supplier and buyer are required");
+        }
+        if supplier_id == buyer_id {
+            anyhow::bail!("Supplier and buyer must be different participants");
+        }
+        let amount_cents = grpc::parse_amount(&self.amount)
+            .filter(|a| *a > 0)
+            .ok_or_else(|| anyhow::anyhow!("Amount must be a positive number, e.g. 1500.00"))?;
+        let issue_date = parse_date_millis("Issue date", &self.issue_date)?;
+        let due_date = parse_date_millis("Due date", &self.due_date)?;
+        if due_date < issue_date {
+            anyhow::bail!("Due date must not be before the issue date");
+        }
+        let late_fee_cents = if self.late_fee.trim().is_empty() {
+            0
+        } else {
+            grpc::parse_amount(&self.late_fee)
+                .filter(|fee| *fee >= 0)
+                .ok_or_else(|| anyhow::anyhow!("Late fee must be an amount, e.g. 25.00, or empty"))?
+        };
+        Ok(grpc::smartcontracts::CreateInvoiceContractRequest {
+            supplier_id: supplier_id.to_string(),
+            buyer_id: buyer_id.to_string(),
+            amount_cents,
+            issue_date,
+            due_date,
+            payment_terms: self.payment_terms.trim().to_string(),
+            auto_debit: self.auto_debit,
+            late_fee_cents,
+            reference: self.reference.trim().to_string(),
+            metadata: HashMap::new(),
+        })
+    }
+}
+
+/// Midnight UTC of a YYYY-MM-DD date, in Unix milliseconds
+fn parse_date_millis(label: &str, input: &str) -> Result<i64> {
+    chrono::NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|at| at.and_utc().timestamp_millis())
+        .ok_or_else(|| anyhow::anyhow!("{} must be a date like 2025-03-31", label))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoanAction {
+    #[default]
+    Create,
+    Repay,
+}
+
+impl LoanAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LoanAction::Create => "Create loan",
+            LoanAction::Repay => "Repay loan",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoanField {
+    Action,
+    Lender,
+    Borrower,
+    Amount,
+    InterestRate,
+    TermMonths,
+    Reference,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LoanForm {
+    pub action: LoanAction,
+    pub lender_id: String,
+    pub borrower_id: String,
+    pub amount: String,        // Decimal, e.g. "1500.23"
+    pub interest_rate: String, // Annual rate in percent, e.g. "5" or "4.5"
+    pub term_months: String,
+    pub reference: String,
+    pub selected_field: usize,
+    pub error: Option<String>,
+    pub success: Option<String>,
+}
+
+impl LoanForm {
+    /// Fields shown for the current action; interest terms only apply when creating
+    pub fn fields(&self) -> Vec<LoanField> {
+        match self.action {
+            LoanAction::Create => vec![
+                LoanField::Action,
+                LoanField::Lender,
+                LoanField::Borrower,
+                LoanField::Amount,
+                LoanField::InterestRate,
+                LoanField::TermMonths,
+                LoanField::Reference,
+            ],
+            LoanAction::Repay => vec![
+                LoanField::Action,
+                LoanField::Lender,
+                LoanField::Borrower,
+                LoanField::Amount,
+                LoanField::Reference,
+            ],
+        }
+    }
+
+    pub fn current_field(&self) -> LoanField {
+        let fields = self.fields();
+        fields[self.selected_field.min(fields.len() - 1)]
+    }
+
+    pub fn value(&self, field: LoanField) -> &str {
+        match field {
+            LoanField::Action => self.action.label(),
+            LoanField::Lender => &self.lender_id,
+            LoanField::Borrower => &self.borrower_id,
+            LoanField::Amount => &self.amount,
+            LoanField::InterestRate => &self.interest_rate,
+            LoanField::TermMonths => &self.term_months,
+            LoanField::Reference => &self.reference,
+        }
+    }
+
+    fn value_mut(&mut self, field: LoanField) -> Option<&mut String> {
+        match field {
+            LoanField::Action => None,
+            LoanField::Lender => Some(&mut self.lender_id),
+            LoanField::Borrower => Some(&mut self.borrower_id),
+            LoanField::Amount => Some(&mut self.amount),
+            LoanField::InterestRate => Some(&mut self.interest_rate),
+            LoanField::TermMonths => Some(&mut self.term_months),
+            LoanField::Reference => Some(&mut self.reference),
+        }
+    }
+
+    pub fn toggle_action(&mut self) {
+        self.action = match self.action {
+            LoanAction::Create => LoanAction::Repay,
+            LoanAction::Repay => LoanAction::Create,
+        };
+        self.selected_field = 0;
+        self.error = None;
+    }
+
+    /// Annual interest rate as a decimal (5 → 0.05)
+    pub fn parsed_rate(&self) -> Option<f64> {
+        let percent: f64 = self.interest_rate.trim().parse().ok()?;
+        if percent.is_finite() && percent >= 0.0 {
+            Some(percent / 100.0)
+        } else {
+            None
+        }
+    }
+
+    pub fn parsed_term(&self) -> Option<i32> {
+        self.term_months.trim().parse().ok().filter(|t| (1..=MAX_TERM_MONTHS).contains(t))
+    }
+
+    /// Estimated monthly payment for the entered terms, if they parse
+    pub fn monthly_payment_estimate(&self) -> Option<i64> {
+        let principal = grpc::parse_amount(&self.amount)?;
+        monthly_payment(principal, self.parsed_rate()?, self.parsed_term()?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessPaymentField {
+    Payer,
+    Provider,
+    Amount,
+    Reference,
+    Platform,
+    PlatformFee,
+}
+
+/// Access payment form fields in display order
+pub const ACCESS_PAYMENT_FIELDS: [AccessPaymentField; 6] = [
+    AccessPaymentField::Payer,
+    AccessPaymentField::Provider,
+    AccessPaymentField::Amount,
+    AccessPaymentField::Reference,
+    AccessPaymentField::Platform,
+    AccessPaymentField::PlatformFee,
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct AccessPaymentForm {
+    pub payer_id: String,
+    pub access_provider_id: String,
+    pub amount: String, // Decimal, e.g. "2.50"
+    pub reference: String,
+    pub platform_id: String,
+    pub platform_fee: String, // Decimal, empty for no fee
+    pub selected_field: usize,
+    pub error: Option<String>,
+    pub success: Option<String>,
+}
+
+impl AccessPaymentForm {
+    pub fn current_field(&self) -> AccessPaymentField {
+        ACCESS_PAYMENT_FIELDS[self.selected_field.min(ACCESS_PAYMENT_FIELDS.len() - 1)]
+    }
+
+    pub fn value(&self, field: AccessPaymentField) -> &str {
+        match field {
+            AccessPaymentField::Payer => &self.payer_id,
+            AccessPaymentField::Provider => &self.access_provider_id,
+            AccessPaymentField::Amount => &self.amount,
+            AccessPaymentField::Reference => &self.reference,
+            AccessPaymentField::Platform => &self.platform_id,
+            AccessPaymentField::PlatformFee => &self.platform_fee,
+        }
+    }
+
+    fn value_mut(&mut self, field: AccessPaymentField) -> &mut String {
+        match field {
+            AccessPaymentField::Payer => &mut self.payer_id,
+            AccessPaymentField::Provider => &mut self.access_provider_id,
+            AccessPaymentField::Amount => &mut self.amount,
+            AccessPaymentField::Reference => &mut self.reference,
+            AccessPaymentField::Platform => &mut self.platform_id,
+            AccessPaymentField::PlatformFee => &mut self.platform_fee,
+        }
+    }
+
+    /// Platform fee in cents; an empty field means no fee
+    pub fn parsed_fee(&self) -> Option<i64> {
+        if self.platform_fee.trim().is_empty() {
+            Some(0)
+        } else {
+            grpc::parse_amount(&self.platform_fee).filter(|fee| *fee >= 0)
+        }
+    }
+
+    /// (provider share, platform fee) in cents, if the amount and fee are valid
+    pub fn split(&self) -> Option<(i64, i64)> {
+        let amount = grpc::parse_amount(&self.amount).filter(|a| *a > 0)?;
+        let fee = self.parsed_fee().filter(|fee| *fee <= amount)?;
+        Some((amount - fee, fee))
+    }
+}
+
+/// Case-insensitive fuzzy match of `query` against `candidate`, higher is better.
+///
+/// Prefix matches rank above substring matches, which rank above scattered
+/// subsequence matches ("salop" → "salon_glamour:operating"). Subsequence
+/// matches score better when characters land on word starts (after `_`, `:`
+/// or `-`) and when there are fewer gaps. `None` if `query` isn't a subsequence.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query = query.to_lowercase();
+    let candidate = candidate.to_lowercase();
+    if candidate.starts_with(&query) {
+        return Some(3000 - candidate.len() as i64);
+    }
+    if let Some(pos) = candidate.find(&query) {
+        return Some(2000 - pos as i64);
+    }
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut score = 1000;
+    let mut next = 0;
+    let mut last_match: Option<usize> = None;
+    for q in query.chars() {
+        let offset = chars[next..].iter().position(|c| *c == q)?;
+        let idx = next + offset;
+        let word_start = idx == 0 || matches!(chars[idx - 1], '_' | ':' | '-');
+        if word_start {
+            score += 10;
+        }
+        if let Some(last) = last_match {
+            score -= (idx - last - 1) as i64;
+        }
+        last_match = Some(idx);
+        next = idx + 1;
+    }
+    Some(score)
+}
+
+#[derive(Debug, Clone)]
+pub struct TransactionInfo {
+    pub id: String,
+    pub tx_type: String,
+    pub entries: Vec<(String, i64)>, // (account_id, amount)
+    pub reference: String,
+    pub timestamp: i64, // Unix timestamp in milliseconds
+}
+
+impl TransactionInfo {
+    /// One-line summary used in the History list
+    pub fn summary(&self) -> String {
+        let entries_str: Vec<String> = self
+            .entries
+            .iter()
+            .map(|(account_id, amount)| format!("{}: {}", account_id, grpc::format_balance(*amount)))
+            .collect();
+        let short_id: String = self.id.chars().take(8).collect();
+        format!(
+            "[{}] {} | {} | {}",
+            short_id,
+            self.tx_type,
+            entries_str.join(", "),
+            self.reference
+        )
+    }
+}
+
+impl From<grpc::common::Transaction> for TransactionInfo {
+    fn from(tx: grpc::common::Transaction) -> Self {
+        Self {
+            id: tx.id,
+            tx_type: transaction_type(&tx.r#type, &tx.reference).to_string(),
+            entries: tx
+                .entries
+                .into_iter()
+                .map(|e| (e.account_id, e.amount))
+                .collect(),
+            reference: tx.reference,
+            timestamp: tx.timestamp,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FutureEvent {
     pub contract_id: String,
@@ -122,10 +867,249 @@ pub struct FutureEvent {
     pub execution_time: i64, // Unix timestamp in milliseconds
 }
 
+impl FutureEvent {
+    /// The next scheduled execution of `contract`, if it is still live and has one.
+    /// Conditional payments and revenue share have no scheduled execution.
+    fn from_contract(contract: grpc::smartcontracts::ContractResponse) -> Option<Self> {
+        use crate::grpc::smartcontracts::contract_response::Contract;
+        use crate::grpc::smartcontracts::ContractStatus;
+
+        let event = match contract.contract? {
+            Contract::Invoice(inv) if Self::is_live(&inv.status) => FutureEvent {
+                contract_id: inv.id,
+                contract_type: "Invoice".to_string(),
+                description: format!("Invoice payment: {} from {} to {}",
+                    grpc::format_balance(inv.amount_cents),
+                    inv.supplier_id,
+                    inv.buyer_id),
+                execution_time: inv.due_date,
+            },
+            Contract::Subscription(sub) if Self::is_live(&sub.status) => FutureEvent {
+                contract_id: sub.id,
+                contract_type: "Subscription".to_string(),
+                description: format!("Subscription billing: {} from {} to {}",
+                    grpc::format_balance(sub.monthly_fee_cents),
+                    sub.provider_id,
+                    sub.subscriber_id),
+                execution_time: sub.next_billing_date,
+            },
+            // Generic (YAML-based) contracts carry an enum status; compare it by name
+            // so the same gating applies as for the typed contracts
+            Contract::Generic(gen) => {
+                let status = ContractStatus::try_from(gen.status)
+                    .map(|s| s.as_str_name().to_lowercase())
+                    .unwrap_or_default();
+                if !Self::is_live(&status) {
+                    return None;
+                }
+                let participants = App::extract_participants_from_metadata(&gen.metadata, "");
+                let mut description = format!("{}: {}", gen.name, gen.description);
+                if !participants.is_empty() {
+                    description.push_str(&format!(" (participants: {})", participants.join(", ")));
+                }
+                FutureEvent {
+                    contract_id: gen.id,
+                    contract_type: format!("Generic ({})", App::contract_type_to_string(gen.contract_type)),
+                    description,
+                    execution_time: gen.next_execution_at,
+                }
+            }
+            _ => return None,
+        };
+        (event.execution_time > 0).then_some(event)
+    }
+
+    /// Due at or before `now` while the contract is still pending/active
+    pub fn is_overdue(&self, now: i64) -> bool {
+        self.execution_time <= now
+    }
+
+    /// Contracts that still have executions ahead of them; invoices are live while pending
+    fn is_live(status: &str) -> bool {
+        matches!(status, "active" | "pending")
+    }
+}
+
+/// Most billings of one subscription projected ahead, whatever the window
+const MAX_SUBSCRIPTION_OCCURRENCES: u32 = 12;
+
+/// `event`, the next execution of `contract`, followed for a subscription
+/// by the monthly billings after it up to `until` (or the subscription's
+/// end date, if sooner)
+fn occurrences(
+    event: FutureEvent,
+    contract: &grpc::smartcontracts::ContractResponse,
+    until: i64,
+) -> Vec<FutureEvent> {
+    use crate::grpc::smartcontracts::contract_response::Contract;
+
+    let end = match &contract.contract {
+        Some(Contract::Subscription(sub)) if sub.end_date > 0 => sub.end_date.min(until),
+        Some(Contract::Subscription(_)) => until,
+        // Everything else executes once
+        _ => return vec![event],
+    };
+    let mut events = Vec::new();
+    if let Some(first) = chrono::DateTime::from_timestamp_millis(event.execution_time) {
+        for months in 1..MAX_SUBSCRIPTION_OCCURRENCES {
+            let Some(at) = first.checked_add_months(chrono::Months::new(months)) else {
+                break;
+            };
+            let at = at.timestamp_millis();
+            if at > end {
+                break;
+            }
+            events.push(FutureEvent {
+                execution_time: at,
+                ..event.clone()
+            });
+        }
+    }
+    events.insert(0, event);
+    events
+}
+
+/// Executions within `timeline::HORIZON_DAYS` after `now`: each live
+/// contract's next one, plus the monthly billings that follow it for
+/// subscriptions (up to their end date). Sorted by time.
+fn project_timeline(contracts: &[grpc::smartcontracts::ContractResponse], now: i64) -> Vec<FutureEvent> {
+    let until = now + timeline::HORIZON_DAYS * DAY_MS;
+    let mut events: Vec<FutureEvent> = contracts
+        .iter()
+        .filter_map(|contract| Some(occurrences(FutureEvent::from_contract(contract.clone())?, contract, until)))
+        .flatten()
+        .filter(|e| e.execution_time > now && e.execution_time <= until)
+        .collect();
+    events.sort_by_key(|e| e.execution_time);
+    events
+}
+
+/// The Future list: (overdue, upcoming), both sorted by time. A contract
+/// whose next execution has passed is overdue once; its later billings, like
+/// every execution after `now`, are upcoming. Subscriptions repeat monthly
+/// within `timeline::HORIZON_DAYS`; other contracts are listed once however
+/// far off.
+fn schedule(
+    contracts: &[grpc::smartcontracts::ContractResponse],
+    now: i64,
+) -> (Vec<FutureEvent>, Vec<FutureEvent>) {
+    let until = now + timeline::HORIZON_DAYS * DAY_MS;
+    let (mut overdue, mut upcoming) = (Vec::new(), Vec::new());
+    for contract in contracts {
+        let Some(event) = FutureEvent::from_contract(contract.clone()) else {
+            continue;
+        };
+        for (i, occurrence) in occurrences(event, contract, until).into_iter().enumerate() {
+            if !occurrence.is_overdue(now) {
+                upcoming.push(occurrence);
+            } else if i == 0 {
+                overdue.push(occurrence);
+            }
+        }
+    }
+    overdue.sort_by_key(|e| e.execution_time);
+    upcoming.sort_by_key(|e| e.execution_time);
+    (overdue, upcoming)
+}
+
+/// Where a view's data stands, so an empty list can say whether the server
+/// has nothing, hasn't answered yet, or failed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LoadState {
+    #[default]
+    Loading,
+    Loaded,
+    Failed(String),
+}
+
+impl LoadState {
+    /// State after a load finished with `result`
+    pub fn after(result: &Result<()>) -> Self {
+        match result {
+            Ok(()) => LoadState::Loaded,
+            Err(e) => LoadState::Failed(grpc::error_summary(e)),
+        }
+    }
+}
+
+/// Health of the gRPC connection, derived from the most recent call outcomes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    Connected,
+    Degraded,
+    Disconnected,
+    /// Dropped after the idle timeout; the next call reconnects
+    Idle,
+}
+
+impl ConnectionStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnectionStatus::Connected => "Connected",
+            ConnectionStatus::Degraded => "Degraded",
+            ConnectionStatus::Disconnected => "Disconnected",
+            ConnectionStatus::Idle => "Idle",
+        }
+    }
+
+    /// Classify the latest failure that no successful call has followed.
+    /// Request-level errors (not found, invalid argument, ...) say nothing
+    /// about the connection itself.
+    fn from_error(error: Option<&CallError>) -> Self {
+        use tonic::Code;
+        match error.map(|e| e.code) {
+            Some(Code::Unavailable) => ConnectionStatus::Disconnected,
+            Some(
+                Code::DeadlineExceeded
+                | Code::Internal
+                | Code::Unknown
+                | Code::Cancelled
+                | Code::ResourceExhausted
+                | Code::DataLoss
+                | Code::Unauthenticated,
+            ) => ConnectionStatus::Degraded,
+            _ => ConnectionStatus::Connected,
+        }
+    }
+}
+
+/// Screen regions recorded while drawing, used to map mouse clicks back to
+/// tabs and list rows
+#[derive(Debug, Clone, Default)]
+pub struct ClickMap {
+    /// Tab header areas, in `View::all()` order
+    pub tabs: Vec<Rect>,
+    /// Breadcrumb segment labels, in breadcrumb order
+    pub breadcrumbs: Vec<Rect>,
+    /// Area holding the current view's selectable rows, one item per line
+    pub rows: Option<Rect>,
+    /// Index of the item drawn on the first line of `rows`
+    pub row_offset: usize,
+}
+
+impl ClickMap {
+    fn tab_at(&self, position: Position) -> Option<usize> {
+        self.tabs.iter().position(|area| area.contains(position))
+    }
+
+    fn breadcrumb_at(&self, position: Position) -> Option<usize> {
+        self.breadcrumbs.iter().position(|area| area.contains(position))
+    }
+
+    fn row_at(&self, position: Position) -> Option<usize> {
+        let rows = self.rows?;
+        if !rows.contains(position) {
+            return None;
+        }
+        Some(self.row_offset + (position.y - rows.y) as usize)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BreadcrumbSegment {
     pub label: String,
     pub view: View,
+    #[allow(dead_code)]
     pub context: Option<String>, // e.g., participant_id when viewing participant's accounts
 }
 
@@ -143,60 +1127,314 @@ pub struct App {
 
     // Participant detail view
     pub participant_detail: Option<ParticipantDetail>,
+    pub detail_account_state: ListState,
+    // Scroll offsets (in lines) of the About/Contact panels and the Accounts panel
+    pub detail_info_scroll: u16,
+    pub detail_accounts_scroll: u16,
 
     // Accounts (for transfer form)
     pub accounts: Vec<AccountInfo>,
+    #[allow(dead_code)]
     pub account_state: ListState,
 
+    // All-accounts view: participant groups that are folded, and the account type shown
+    pub accounts_browse_state: ListState,
+    pub collapsed_groups: HashSet<String>,
+    pub account_type_filter: Option<&'static str>,
+
     // Transfer view
     pub transfer_form: TransferForm,
+    pub templates: TemplateStore,
+    pub template_popup: Option<TemplatePopup>,
+
+    // Path being typed for a History export; the prompt is open while Some
+    pub export_prompt: Option<String>,
+
+    // Contact editor for Participant Details; open while Some
+    pub contact_form: Option<ContactForm>,
+    pub invoice_form: Option<InvoiceForm>,
 
-    // History
-    pub history: Vec<String>,
+    // Loan view
+    pub loan_form: LoanForm,
+
+    // Access payment view
+    pub access_payment_form: AccessPaymentForm,
+
+    // History (oldest first; displayed newest first)
+    pub history: Vec<TransactionInfo>,
+    pub history_state: ListState,
+    /// Only transactions of this type are listed (None: all)
+    pub history_type_filter: Option<&'static str>,
+    /// Transaction armed for reversal by a first 'u'; a second 'u' posts it
+    pub pending_reversal: Option<String>,
 
     // Future (scheduled events)
     pub future_events: Vec<FutureEvent>,
+    pub future_state: ListState,
+    /// Live contracts whose due/billing date has already passed
+    pub overdue_events: Vec<FutureEvent>,
+    /// Executions over the next `timeline::HORIZON_DAYS`, subscriptions projected monthly
+    pub timeline_events: Vec<FutureEvent>,
+    /// Future view draws the timeline instead of the list ('v')
+    pub show_timeline: bool,
+
+    // Overview: active contracts per type, from the same fetch as Future
+    pub active_contracts: Vec<(String, usize)>,
+    /// The contract fetch hit `CONTRACT_LIST_LIMIT`, so counts may be low
+    pub contracts_truncated: bool,
 
     // Status
-    #[allow(dead_code)]
     pub status_message: Option<String>,
+    pub status_set_at: Option<Instant>,
+    pub status_is_error: bool,
     pub loading: bool,
+
+    // System clipboard for yanking ids
+    pub clipboard: Clipboard,
+
+    // Help overlay
+    pub show_help: bool,
+
+    // Color palette used by all views
+    pub theme: Theme,
+
+    // Keys for the remappable actions
+    pub keymap: Keymap,
+
+    // Auto-refresh (disabled when None)
+    pub refresh_interval: Option<Duration>,
+    pub last_refresh: Instant,
+
+    // Balance deltas of the accounts that moved in the last reload, by account id
+    pub balance_changes: HashMap<String, i64>,
+    pub balance_changes_at: Option<Instant>,
+
+    // Drop the connection after this long without calls (disabled when None)
+    pub idle_timeout: Option<Duration>,
+
+    // When participants and accounts were last fetched (None: never, or stale)
+    pub participants_loaded_at: Option<Instant>,
+    pub accounts_loaded_at: Option<Instant>,
+
+    // Outcome of the latest load behind each list, for its empty state
+    pub participants_load: LoadState,
+    pub accounts_load: LoadState,
+    pub history_load: LoadState,
+    pub future_load: LoadState,
+    pub detail_load: LoadState,
+
+    // Connection health, and the error behind it when not connected
+    pub connection_status: ConnectionStatus,
+    pub last_error: Option<String>,
+
+    // First 'g' of a 'gg' jump to the top
+    pending_g: bool,
+
+    // Mouse support
+    pub click_map: ClickMap,
+    last_click: Option<(Instant, View, usize)>,
+
+    // Where the previous session left off, applied after the initial load
+    saved_state: Option<TuiState>,
 }
 
 impl App {
-    pub fn new(client: ScalegraphClient) -> Self {
+    pub fn new(client: ScalegraphClient, theme: ThemeName) -> Self {
         let mut participant_state = ListState::default();
         participant_state.select(Some(0));
 
         let mut account_state = ListState::default();
         account_state.select(Some(0));
 
+        let mut detail_account_state = ListState::default();
+        detail_account_state.select(Some(0));
+
+        let mut history_state = ListState::default();
+        history_state.select(Some(0));
+
+        let mut accounts_browse_state = ListState::default();
+        accounts_browse_state.select(Some(0));
+
+        let mut future_state = ListState::default();
+        future_state.select(Some(0));
+
+        let (templates, templates_error) = TemplateStore::load_default();
+
         let mut app = Self {
             client,
-            current_view: View::Participants,
+            current_view: View::Overview,
             running: true,
             breadcrumb: Vec::new(),
             participants: Vec::new(),
             participant_state,
             participant_detail: None,
+            detail_account_state,
+            detail_info_scroll: 0,
+            detail_accounts_scroll: 0,
             accounts: Vec::new(),
             account_state,
+            accounts_browse_state,
+            collapsed_groups: HashSet::new(),
+            account_type_filter: None,
             transfer_form: TransferForm::default(),
+            templates,
+            template_popup: None,
+            export_prompt: None,
+            contact_form: None,
+            invoice_form: None,
+            loan_form: LoanForm::default(),
+            access_payment_form: AccessPaymentForm::default(),
             history: Vec::new(),
+            history_state,
+            history_type_filter: None,
+            pending_reversal: None,
             future_events: Vec::new(),
+            future_state,
+            overdue_events: Vec::new(),
+            timeline_events: Vec::new(),
+            show_timeline: false,
+            active_contracts: Vec::new(),
+            contracts_truncated: false,
             status_message: None,
+            status_set_at: None,
+            status_is_error: false,
             loading: false,
+            clipboard: Clipboard::default(),
+            show_help: false,
+            theme: Theme::new(theme),
+            keymap: Keymap::default(),
+            refresh_interval: None,
+            last_refresh: Instant::now(),
+            balance_changes: HashMap::new(),
+            balance_changes_at: None,
+            idle_timeout: None,
+            participants_loaded_at: None,
+            accounts_loaded_at: None,
+            participants_load: LoadState::default(),
+            accounts_load: LoadState::default(),
+            history_load: LoadState::default(),
+            future_load: LoadState::default(),
+            detail_load: LoadState::default(),
+            connection_status: ConnectionStatus::Connected,
+            last_error: None,
+            pending_g: false,
+            click_map: ClickMap::default(),
+            last_click: None,
+            saved_state: None,
         };
         app.update_breadcrumb();
+        if let Some(e) = templates_error {
+            app.set_error(format!("Transfer templates not loaded: {:#}", e));
+        }
         app
     }
 
+    pub fn with_refresh_interval(mut self, interval: Option<Duration>) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Draw without colors (`--no-color` or `NO_COLOR`)
+    pub fn with_no_color(mut self, no_color: bool) -> Self {
+        if no_color {
+            self.theme = self.theme.no_color();
+        }
+        self
+    }
+
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    pub fn with_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Reopen the view and participant of a previous session once data is loaded
+    pub fn with_saved_state(mut self, state: TuiState) -> Self {
+        self.saved_state = Some(state);
+        self
+    }
+
+    /// Select the saved participant and enter the saved view. The participant
+    /// is found by id, falling back to its index clamped to the current list;
+    /// Participant Details is only reopened if the same participant still exists.
+    async fn restore_saved_state(&mut self) {
+        let Some(state) = self.saved_state.take() else {
+            return;
+        };
+        let by_id = state
+            .participant_id
+            .as_ref()
+            .and_then(|id| self.participants.iter().position(|p| &p.id == id));
+        if !self.participants.is_empty() {
+            let index = by_id
+                .or(state.participant_index)
+                .map(|i| i.min(self.participants.len() - 1));
+            self.participant_state.select(Some(index.unwrap_or(0)));
+        }
+        match state.view {
+            Some(View::ParticipantDetail) if by_id.is_some() => {
+                self.open_selected_participant().await;
+            }
+            Some(view) if View::all().contains(&view) => {
+                self.current_view = view;
+                self.update_breadcrumb();
+                self.load_view_data().await;
+            }
+            _ => {}
+        }
+    }
+
+    /// What to restore next time; Participant Details remembers the participant shown
+    fn current_state(&self) -> TuiState {
+        let participant_id = match (self.current_view, &self.participant_detail) {
+            (View::ParticipantDetail, Some(detail)) => Some(detail.info.id.clone()),
+            _ => self
+                .participant_state
+                .selected()
+                .and_then(|i| self.participants.get(i))
+                .map(|p| p.id.clone()),
+        };
+        TuiState {
+            view: Some(self.current_view),
+            participant_index: self.participant_state.selected(),
+            participant_id,
+            theme: Some(self.theme.name),
+        }
+    }
+
     pub async fn load_participants(&mut self) -> Result<()> {
+        self.participants_load = LoadState::Loading;
+        let result = self.fetch_participants().await;
+        self.participants_load = LoadState::after(&result);
+        result
+    }
+
+    async fn fetch_participants(&mut self) -> Result<()> {
         self.loading = true;
         let participants = self.client.list_participants(None).await?;
+        let ids: Vec<String> = participants.iter().map(|p| p.id.clone()).collect();
+        let operating_balances: Vec<_> = self
+            .client
+            .get_accounts_of(&ids)
+            .await
+            .into_iter()
+            .map(|accounts| {
+                operating_balance(
+                    accounts
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|a| (grpc::account_type_to_string(a.account_type), a.balance)),
+                )
+            })
+            .collect();
         self.participants = participants
             .into_iter()
-            .map(|p| {
+            .zip(operating_balances)
+            .map(|(p, operating_balance)| {
                 let contact = p.contact.as_ref().map(|c| ContactInfo {
                     email: c.email.clone(),
                     phone: c.phone.clone(),
@@ -224,15 +1462,31 @@ impl App {
                     metadata: p.metadata,
                     about: p.about,
                     contact,
+                    operating_balance,
                 }
             })
             .collect();
+        self.participants_loaded_at = Some(Instant::now());
         self.loading = false;
         Ok(())
     }
 
     pub async fn load_participant_detail(&mut self, participant_id: &str) -> Result<()> {
+        self.detail_load = LoadState::Loading;
+        let result = self.fetch_participant_detail(participant_id).await;
+        self.detail_load = LoadState::after(&result);
+        result
+    }
+
+    async fn fetch_participant_detail(&mut self, participant_id: &str) -> Result<()> {
         self.loading = true;
+        // Only a reload of the same participant has balances to compare
+        let before = self
+            .participant_detail
+            .as_ref()
+            .filter(|d| d.info.id == participant_id)
+            .map(|d| balances_by_id(&d.accounts))
+            .unwrap_or_default();
         
         // Load full participant details
         let participant = self.client.get_participant(participant_id).await?;
@@ -252,6 +1506,15 @@ impl App {
         
         // Calculate total balance
         let total_balance: i64 = account_infos.iter().map(|a| a.balance).sum();
+
+        // Replay recent transactions into a balance trend per account; an
+        // account whose history fails to load just has no trend
+        let mut trends = HashMap::new();
+        for account in &account_infos {
+            if let Ok(transactions) = self.client.list_transactions(Some(TREND_TRANSACTIONS), Some(&account.id)).await {
+                trends.insert(account.id.clone(), balance_trend(&account.id, account.balance, &transactions));
+            }
+        }
         
         // Load contracts for this participant
         let contracts = self.client.list_contracts(None, None, Some(participant_id.to_string()), Some(100)).await.unwrap_or_default();
@@ -367,97 +1630,130 @@ impl App {
             metadata: participant.metadata,
             about: participant.about,
             contact,
+            operating_balance: operating_balance(
+                account_infos
+                    .iter()
+                    .map(|a| (a.account_type.as_str(), a.balance)),
+            ),
         };
         
+        self.note_balance_changes(balance_changes(&before, &account_infos));
         self.participant_detail = Some(ParticipantDetail {
             info,
             accounts: account_infos,
             total_balance,
             contracts: contract_infos,
+            trends,
         });
         
         self.loading = false;
         Ok(())
     }
 
-    pub async fn load_accounts(&mut self) -> Result<()> {
-        self.loading = true;
-        self.accounts.clear();
+    pub async fn load_accounts(&mut self) -> Result<()> {
+        self.accounts_load = LoadState::Loading;
+        let result = self.fetch_accounts().await;
+        self.accounts_load = LoadState::after(&result);
+        result
+    }
+
+    async fn fetch_accounts(&mut self) -> Result<()> {
+        self.loading = true;
+        let before = balances_by_id(&self.accounts);
+        self.accounts.clear();
+
+        let ids: Vec<String> = self.participants.iter().map(|p| p.id.clone()).collect();
+        // Participants whose accounts fail to load are left out
+        for accounts in self.client.get_accounts_of(&ids).await.into_iter().flatten() {
+            for acc in accounts {
+                self.accounts.push(AccountInfo {
+                    id: acc.id,
+                    participant_id: acc.participant_id,
+                    account_type: grpc::account_type_to_string(acc.account_type).to_string(),
+                    balance: acc.balance,
+                });
+            }
+        }
+
+        self.note_balance_changes(balance_changes(&before, &self.accounts));
+        self.accounts_loaded_at = Some(Instant::now());
+        self.loading = false;
+        Ok(())
+    }
+
+    /// Highlight accounts whose balance moved; a reload where nothing moved
+    /// leaves the previous highlight to run out
+    fn note_balance_changes(&mut self, changes: HashMap<String, i64>) {
+        if !changes.is_empty() {
+            self.balance_changes = changes;
+            self.balance_changes_at = Some(Instant::now());
+        }
+    }
+
+    /// Delta shown next to `account_id`'s balance, while its highlight lasts
+    pub fn balance_change(&self, account_id: &str) -> Option<i64> {
+        self.balance_changes.get(account_id).copied()
+    }
+
+    pub fn expire_balance_changes(&mut self) {
+        if self.balance_changes_at.is_some_and(|at| at.elapsed() >= BALANCE_CHANGE_TTL) {
+            self.balance_changes.clear();
+            self.balance_changes_at = None;
+        }
+    }
+
+    /// `load_participants`, unless they were fetched within `CACHE_TTL`
+    pub async fn load_participants_cached(&mut self) -> Result<()> {
+        if is_fresh(self.participants_loaded_at) {
+            return Ok(());
+        }
+        self.load_participants().await
+    }
 
-        for participant in &self.participants {
-            if let Ok(accounts) = self.client.get_participant_accounts(&participant.id).await {
-                for acc in accounts {
-                    self.accounts.push(AccountInfo {
-                        id: acc.id,
-                        participant_id: acc.participant_id,
-                        account_type: grpc::account_type_to_string(acc.account_type).to_string(),
-                        balance: acc.balance,
-                    });
-                }
-            }
+    /// `load_accounts`, unless they were fetched within `CACHE_TTL`
+    pub async fn load_accounts_cached(&mut self) -> Result<()> {
+        if is_fresh(self.accounts_loaded_at) {
+            return Ok(());
         }
+        self.load_accounts().await
+    }
 
-        self.loading = false;
-        Ok(())
+    /// Make the next view switch refetch; called after anything that moves money
+    pub fn invalidate_cache(&mut self) {
+        self.participants_loaded_at = None;
+        self.accounts_loaded_at = None;
     }
 
     pub async fn load_future_events(&mut self) -> Result<()> {
+        self.future_load = LoadState::Loading;
+        let result = self.fetch_future_events().await;
+        self.future_load = LoadState::after(&result);
+        result
+    }
+
+    async fn fetch_future_events(&mut self) -> Result<()> {
         self.loading = true;
         self.future_events.clear();
+        self.overdue_events.clear();
+
+        // Load all contracts; status gating is done per contract type below, since
+        // a server-side "active" filter would drop pending invoices
+        let contracts = self
+            .client
+            .list_contracts(None, None, None, Some(CONTRACT_LIST_LIMIT))
+            .await?;
+        self.active_contracts = active_contract_counts(&contracts);
+        self.contracts_truncated = contracts.len() >= CONTRACT_LIST_LIMIT as usize;
 
-        // Load all contracts
-        let contracts = self.client.list_contracts(None, Some("active".to_string()), None, Some(100)).await.unwrap_or_default();
-        
-        let mut events: Vec<FutureEvent> = Vec::new();
         let now = chrono::Utc::now().timestamp_millis();
-        
-        use crate::grpc::smartcontracts::contract_response::Contract;
-        for contract_resp in contracts {
-            match contract_resp.contract {
-                Some(Contract::Invoice(inv)) => {
-                    if inv.due_date > now && inv.status == "pending" {
-                        events.push(FutureEvent {
-                            contract_id: inv.id,
-                            contract_type: "Invoice".to_string(),
-                            description: format!("Invoice payment: {} from {} to {}", 
-                                grpc::format_balance(inv.amount_cents),
-                                inv.supplier_id,
-                                inv.buyer_id),
-                            execution_time: inv.due_date,
-                        });
-                    }
-                }
-                Some(Contract::Subscription(sub)) => {
-                    if sub.next_billing_date > now && sub.status == "active" {
-                        events.push(FutureEvent {
-                            contract_id: sub.id,
-                            contract_type: "Subscription".to_string(),
-                            description: format!("Subscription billing: {} from {} to {}", 
-                                grpc::format_balance(sub.monthly_fee_cents),
-                                sub.provider_id,
-                                sub.subscriber_id),
-                            execution_time: sub.next_billing_date,
-                        });
-                    }
-                }
-                Some(Contract::Generic(gen)) => {
-                    // Handle generic contracts (YAML-based)
-                    if gen.next_execution_at > now && gen.status == 1 {  // 1 = ACTIVE
-                        events.push(FutureEvent {
-                            contract_id: gen.id.clone(),
-                            contract_type: format!("Generic ({})", Self::contract_type_to_string(gen.contract_type)),
-                            description: format!("{}: {}", gen.name, gen.description),
-                            execution_time: gen.next_execution_at,
-                        });
-                    }
-                }
-                _ => {} // Conditional payments and revenue share don't have scheduled execution
-            }
-        }
-        
-        // Sort by execution time and take top 5
-        events.sort_by_key(|e| e.execution_time);
-        self.future_events = events.into_iter().take(5).collect();
+        self.timeline_events = project_timeline(&contracts, now);
+
+        // Everything overdue is shown, oldest first, followed by the next 5
+        // upcoming executions, repeat billings of subscriptions included
+        let (overdue, upcoming) = schedule(&contracts, now);
+        self.overdue_events = overdue;
+        self.future_events = upcoming.into_iter().take(5).collect();
+        self.future_state.select(Some(0));
         
         self.loading = false;
         Ok(())
@@ -497,34 +1793,284 @@ impl App {
         participants
     }
 
+    /// Edit the contact block of the participant shown in Participant Details
+    pub fn open_contact_form(&mut self) {
+        if let Some(detail) = &self.participant_detail {
+            self.contact_form = Some(ContactForm::new(&detail.info.id, &detail.info.contact));
+        }
+    }
+
+    /// Keys while the contact form is open; it is modal like the template popup
+    pub async fn handle_contact_key(&mut self, key: KeyEvent) {
+        let Some(mut form) = self.contact_form.take() else {
+            return;
+        };
+        let count = CONTACT_FIELDS.len();
+        match key.code {
+            KeyCode::Esc => return,
+            KeyCode::Enter => {
+                match self.client.update_participant_contact(&form.participant_id, form.to_contact()).await {
+                    Ok(_) => {
+                        self.invalidate_cache();
+                        self.reload_participant_detail().await;
+                        self.set_status(format!("Contact updated for {}", form.participant_id));
+                        return;
+                    }
+                    Err(e) => form.error = Some(grpc::error_summary(&e)),
+                }
+            }
+            KeyCode::Tab | KeyCode::Down => form.selected = (form.selected + 1) % count,
+            KeyCode::BackTab | KeyCode::Up => form.selected = (form.selected + count - 1) % count,
+            KeyCode::Backspace => {
+                form.values[form.selected].pop();
+            }
+            KeyCode::Char(c) => form.values[form.selected].push(c),
+            _ => {}
+        }
+        self.contact_form = Some(form);
+    }
+
+    /// Start an invoice from the participant shown in Participant Details
+    pub fn open_invoice_form(&mut self) {
+        if let Some(detail) = &self.participant_detail {
+            let today = chrono::Utc::now().date_naive();
+            self.invoice_form = Some(InvoiceForm::new(&detail.info.id, today));
+        }
+    }
+
+    /// Keys while the invoice form is open; modal like the contact form
+    pub async fn handle_invoice_key(&mut self, key: KeyEvent) {
+        let Some(mut form) = self.invoice_form.take() else {
+            return;
+        };
+        let count = INVOICE_FIELDS.len();
+        let field = form.current_field();
+        match key.code {
+            KeyCode::Esc => return,
+            KeyCode::Enter => match form.to_request() {
+                Ok(request) => match self.client.create_invoice_contract(request).await {
+                    Ok(invoice) => {
+                        self.invalidate_cache();
+                        self.reload_participant_detail().await;
+                        self.set_status(format!(
+                            "Invoice {} created: {} owes {} {}",
+                            invoice.id,
+                            invoice.buyer_id,
+                            invoice.supplier_id,
+                            grpc::format_balance(invoice.amount_cents)
+                        ));
+                        return;
+                    }
+                    Err(e) => form.error = Some(grpc::error_summary(&e)),
+                },
+                Err(e) => form.error = Some(e.to_string()),
+            },
+            KeyCode::Tab | KeyCode::Down => form.selected = (form.selected + 1) % count,
+            KeyCode::BackTab | KeyCode::Up => form.selected = (form.selected + count - 1) % count,
+            KeyCode::Char(' ') if field == InvoiceField::AutoDebit => form.auto_debit = !form.auto_debit,
+            KeyCode::Backspace => {
+                if let Some(value) = form.value_mut(field) {
+                    value.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(value) = form.value_mut(field) {
+                    value.push(c);
+                }
+            }
+            _ => {}
+        }
+        self.invoice_form = Some(form);
+    }
+
+    /// Ask where to export the loaded History, suggesting a timestamped CSV
+    pub fn open_export_prompt(&mut self) {
+        if self.history_rows().is_empty() {
+            self.set_error("No transactions to export".to_string());
+            return;
+        }
+        self.export_prompt = Some(export::default_path());
+    }
+
+    /// Keys while the export prompt is open; it is modal like the template popup
+    pub fn handle_export_key(&mut self, key: KeyEvent) {
+        let Some(mut path) = self.export_prompt.take() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {}
+            KeyCode::Enter => self.export_history(path.trim()),
+            KeyCode::Backspace => {
+                path.pop();
+                self.export_prompt = Some(path);
+            }
+            KeyCode::Char(c) => {
+                path.push(c);
+                self.export_prompt = Some(path);
+            }
+            _ => self.export_prompt = Some(path),
+        }
+    }
+
+    fn export_history(&mut self, path: &str) {
+        if path.is_empty() {
+            self.set_error("Export path cannot be empty".to_string());
+            return;
+        }
+        // Exactly what History lists: filtered, oldest first
+        let mut rows = self.history_rows();
+        rows.reverse();
+        let transactions: Vec<TransactionInfo> = rows.into_iter().map(|i| self.history[i].clone()).collect();
+        match export::write_transactions(Path::new(path), &transactions) {
+            Ok(()) => self.set_status(format!("Exported {} transactions to {}", transactions.len(), path)),
+            Err(e) => self.set_error(format!("Export failed: {:#}", e)),
+        }
+    }
+
     pub async fn load_transactions(&mut self) -> Result<()> {
+        self.history_load = LoadState::Loading;
+        let result = self.fetch_transactions().await;
+        self.history_load = LoadState::after(&result);
+        result
+    }
+
+    async fn fetch_transactions(&mut self) -> Result<()> {
         self.loading = true;
         self.history.clear();
 
-        if let Ok(transactions) = self.client.list_transactions(Some(50), None).await {
-            for tx in transactions {
-                // Format each transaction as a string for display
-                let entries_str: Vec<String> = tx
-                    .entries
-                    .iter()
-                    .map(|e| format!("{}: {}", e.account_id, grpc::format_balance(e.amount)))
-                    .collect();
-
-                let msg = format!(
-                    "[{}] {} | {} | {}",
-                    &tx.id[..8],
-                    tx.r#type,
-                    entries_str.join(", "),
-                    tx.reference
-                );
-                self.history.push(msg);
-            }
-        }
+        let transactions = self.client.list_transactions(Some(50), None).await?;
+        self.history = transactions.into_iter().map(TransactionInfo::from).collect();
+        self.history_state.select(Some(0));
 
         self.loading = false;
         Ok(())
     }
 
+    /// Ask for a name to save the Transfer form under, suggesting its reference
+    pub fn open_save_template(&mut self) {
+        let form = &self.transfer_form;
+        if form.from_account.is_empty() || form.to_account.is_empty() {
+            self.set_error("Fill in From and To before saving a template".to_string());
+            return;
+        }
+        self.template_popup = Some(TemplatePopup::Save {
+            name: form.reference.clone(),
+        });
+    }
+
+    pub fn open_template_picker(&mut self) {
+        if self.templates.templates.is_empty() {
+            self.set_status("No saved templates; Ctrl+S saves the current transfer".to_string());
+            return;
+        }
+        self.template_popup = Some(TemplatePopup::Pick { selected: 0 });
+    }
+
+    /// Keys while the template popup is open; it is modal like the help overlay
+    pub fn handle_template_key(&mut self, key: KeyEvent) {
+        let Some(popup) = self.template_popup.take() else {
+            return;
+        };
+        match popup {
+            TemplatePopup::Save { mut name } => match key.code {
+                KeyCode::Esc => {}
+                KeyCode::Enter => self.save_template(name.trim().to_string()),
+                KeyCode::Backspace => {
+                    name.pop();
+                    self.template_popup = Some(TemplatePopup::Save { name });
+                }
+                KeyCode::Char(c) => {
+                    name.push(c);
+                    self.template_popup = Some(TemplatePopup::Save { name });
+                }
+                _ => self.template_popup = Some(TemplatePopup::Save { name }),
+            },
+            TemplatePopup::Pick { selected } => {
+                let len = self.templates.templates.len();
+                match key.code {
+                    KeyCode::Esc => {}
+                    KeyCode::Enter => self.load_template(selected),
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        self.template_popup = Some(TemplatePopup::Pick {
+                            selected: (selected + 1).min(len.saturating_sub(1)),
+                        });
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        self.template_popup = Some(TemplatePopup::Pick {
+                            selected: selected.saturating_sub(1),
+                        });
+                    }
+                    KeyCode::Char('d') | KeyCode::Delete => self.delete_template(selected),
+                    _ => self.template_popup = Some(TemplatePopup::Pick { selected }),
+                }
+            }
+        }
+    }
+
+    fn save_template(&mut self, name: String) {
+        if name.is_empty() {
+            self.set_error("Template name cannot be empty".to_string());
+            return;
+        }
+        let form = &self.transfer_form;
+        self.templates.upsert(TransferTemplate {
+            name: name.clone(),
+            from_account: form.from_account.clone(),
+            to_account: form.to_account.clone(),
+            amount: form.amount.clone(),
+            reference: form.reference.clone(),
+        });
+        match self.templates.save() {
+            Ok(()) => self.set_status(format!("Saved template '{}'", name)),
+            Err(e) => self.set_error(format!("Template not saved: {:#}", e)),
+        }
+    }
+
+    /// Fill the Transfer form from a template, refusing ones whose accounts are gone
+    fn load_template(&mut self, index: usize) {
+        let Some(template) = self.templates.templates.get(index).cloned() else {
+            return;
+        };
+        let missing: Vec<&str> = [&template.from_account, &template.to_account]
+            .into_iter()
+            .filter(|id| !self.accounts.iter().any(|acc| &acc.id == *id))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            self.set_error(format!(
+                "Template '{}' not loaded: unknown account {}",
+                template.name,
+                missing.join(", ")
+            ));
+            return;
+        }
+        self.transfer_form = TransferForm {
+            from_account: template.from_account,
+            to_account: template.to_account,
+            amount: template.amount,
+            reference: template.reference,
+            // Land on the amount, ready to review and press Enter
+            selected_field: 2,
+            ..TransferForm::default()
+        };
+        self.set_status(format!("Loaded template '{}'", template.name));
+    }
+
+    fn delete_template(&mut self, index: usize) {
+        let Some(removed) = self.templates.remove(index) else {
+            return;
+        };
+        match self.templates.save() {
+            Ok(()) => self.set_status(format!("Deleted template '{}'", removed.name)),
+            Err(e) => self.set_error(format!("Template not deleted: {:#}", e)),
+        }
+        if !self.templates.templates.is_empty() {
+            self.template_popup = Some(TemplatePopup::Pick {
+                selected: index.min(self.templates.templates.len() - 1),
+            });
+        }
+    }
+
     pub async fn execute_transfer(&mut self) -> Result<()> {
         self.transfer_form.error = None;
         self.transfer_form.success = None;
@@ -553,29 +2099,370 @@ impl App {
             .await
         {
             Ok(tx) => {
-                let msg = format!(
-                    "Transfer {} from {} to {} (ref: {}, tx: {})",
-                    grpc::format_balance(amount),
-                    self.transfer_form.from_account,
-                    self.transfer_form.to_account,
-                    self.transfer_form.reference,
-                    tx.id
-                );
-                self.history.push(msg.clone());
+                self.invalidate_cache();
                 self.transfer_form.success = Some(format!("Success! TX: {}", tx.id));
+                self.history.push(TransactionInfo::from(tx));
                 self.transfer_form = TransferForm {
                     success: self.transfer_form.success.clone(),
                     ..Default::default()
                 };
             }
             Err(e) => {
-                self.transfer_form.error = Some(format!("Failed: {}", e));
+                self.transfer_form.error = Some(format!("Failed: {}", grpc::error_summary(&e)));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn execute_loan(&mut self) -> Result<()> {
+        let form = &mut self.loan_form;
+        form.error = None;
+        form.success = None;
+
+        if form.lender_id.is_empty() || form.borrower_id.is_empty() {
+            form.error = Some("Lender and borrower required".to_string());
+            return Ok(());
+        }
+        let amount = match grpc::parse_amount(&form.amount) {
+            Some(a) if a > 0 => a,
+            _ => {
+                form.error = Some("Invalid amount (e.g. 1500.00)".to_string());
+                return Ok(());
+            }
+        };
+
+        let result = match form.action {
+            LoanAction::Create => {
+                let Some(rate) = form.parsed_rate() else {
+                    form.error = Some("Invalid interest rate (percent, e.g. 5)".to_string());
+                    return Ok(());
+                };
+                let Some(term) = form.parsed_term() else {
+                    form.error = Some(format!("Invalid term (whole months, 1 to {})", MAX_TERM_MONTHS));
+                    return Ok(());
+                };
+                self.client
+                    .create_loan(
+                        &form.lender_id,
+                        &form.borrower_id,
+                        amount,
+                        rate,
+                        term,
+                        &form.reference,
+                    )
+                    .await
+            }
+            LoanAction::Repay => {
+                self.client
+                    .repay_loan(&form.lender_id, &form.borrower_id, amount, &form.reference)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(resp) => {
+                self.invalidate_cache();
+                // The server's message states the terms it recorded
+                let success = format!("{} TX: {}", resp.message, resp.transaction_id);
+                self.loan_form = LoanForm {
+                    action: self.loan_form.action,
+                    success: Some(success),
+                    ..Default::default()
+                };
+            }
+            Err(e) => {
+                self.loan_form.error = Some(format!("Failed: {}", grpc::error_summary(&e)));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn execute_access_payment(&mut self) -> Result<()> {
+        let form = &mut self.access_payment_form;
+        form.error = None;
+        form.success = None;
+
+        if form.payer_id.is_empty() || form.access_provider_id.is_empty() {
+            form.error = Some("Payer and access provider required".to_string());
+            return Ok(());
+        }
+        let Some(amount) = grpc::parse_amount(&form.amount).filter(|a| *a > 0) else {
+            form.error = Some("Invalid amount (e.g. 2.50)".to_string());
+            return Ok(());
+        };
+        let Some((_, fee)) = form.split() else {
+            form.error = Some("Invalid platform fee (0 up to the amount, e.g. 0.25)".to_string());
+            return Ok(());
+        };
+        if fee > 0 && form.platform_id.is_empty() {
+            form.error = Some("Platform id required when charging a platform fee".to_string());
+            return Ok(());
+        }
+
+        let platform_id = Some(form.platform_id.as_str()).filter(|id| !id.is_empty());
+        let result = self
+            .client
+            .access_payment(
+                &form.payer_id,
+                &form.access_provider_id,
+                amount,
+                &form.reference,
+                platform_id,
+                Some(fee).filter(|fee| *fee > 0),
+            )
+            .await;
+
+        match result {
+            Ok(resp) => {
+                self.invalidate_cache();
+                let success = format!("Success! TX: {}", resp.transaction_id);
+                // Keep the parties so repeat payments only need a new amount
+                let form = &self.access_payment_form;
+                self.access_payment_form = AccessPaymentForm {
+                    payer_id: form.payer_id.clone(),
+                    access_provider_id: form.access_provider_id.clone(),
+                    platform_id: form.platform_id.clone(),
+                    platform_fee: form.platform_fee.clone(),
+                    selected_field: 2,
+                    success: Some(success),
+                    ..Default::default()
+                };
+            }
+            Err(e) => {
+                self.access_payment_form.error =
+                    Some(format!("Failed: {}", grpc::error_summary(&e)));
             }
         }
 
         Ok(())
     }
 
+    /// Reload everything, including the open participant detail
+    pub async fn refresh_all(&mut self) {
+        let result = self.load_participants().await;
+        self.report(result);
+        let result = self.load_accounts().await;
+        self.report(result);
+        let result = self.load_transactions().await;
+        self.report(result);
+        let result = self.load_future_events().await;
+        self.report(result);
+        if self.current_view == View::ParticipantDetail {
+            self.reload_participant_detail().await;
+        }
+        self.last_refresh = Instant::now();
+    }
+
+    /// Re-derive the connection status from the client's recorded call outcomes
+    pub fn update_connection_status(&mut self) {
+        // Any error predates the disconnect, since no call has been made since
+        if self.client.is_disconnected() {
+            self.connection_status = ConnectionStatus::Idle;
+            self.last_error = None;
+            return;
+        }
+        let error = self.client.metrics().unresolved_error();
+        self.connection_status = ConnectionStatus::from_error(error.as_ref());
+        self.last_error = match self.connection_status {
+            ConnectionStatus::Connected => None,
+            _ => error.map(|e| format!("{}: {}", e.method, e.message)),
+        };
+    }
+
+    /// Release the connection once no call has been made for `idle_timeout`.
+    /// The client reconnects by itself on the next call.
+    pub fn disconnect_if_idle(&mut self) {
+        let Some(timeout) = self.idle_timeout else {
+            return;
+        };
+        if self.client.is_disconnected() || self.client.idle_for() < timeout {
+            return;
+        }
+        if let Err(e) = self.client.disconnect() {
+            self.set_error(format!("Idle disconnect failed: {}", e));
+        }
+    }
+
+    /// Open a new connection to the server and reload everything on success
+    pub async fn reconnect(&mut self) {
+        match self.client.reconnect().await {
+            Ok(()) => {
+                self.refresh_all().await;
+                let msg = format!("Reconnected to {}", self.client.grpc_url());
+                self.set_status(msg);
+            }
+            Err(e) => self.set_error(format!("Reconnect failed: {}", e)),
+        }
+        self.update_connection_status();
+    }
+
+    /// Whether the current view is a text-entry form, where printable keys
+    /// are typed into fields instead of triggering shortcuts
+    pub fn is_form_view(&self) -> bool {
+        matches!(
+            self.current_view,
+            View::Transfer | View::Loan | View::AccessPayment
+        )
+    }
+
+    /// Whether Tab and Shift+Tab edit the focused field rather than switch
+    /// tabs: the Transfer account fields cycle suggestions and the Loan
+    /// action field toggles Create/Repay
+    fn tab_edits_form(&self) -> bool {
+        (self.current_view == View::Transfer && self.transfer_form.selected_field <= 1)
+            || (self.current_view == View::Loan && self.loan_form.current_field() == LoanField::Action)
+    }
+
+    /// Run a remappable action. Returns false when it doesn't apply in the
+    /// current view, so the key keeps its fixed meaning (e.g. typing 'r' into
+    /// a form field).
+    pub async fn perform(&mut self, action: Action) -> bool {
+        match action {
+            Action::Quit => self.running = false,
+            Action::NextView | Action::PrevView => {
+                if action == Action::NextView {
+                    self.next_view();
+                } else {
+                    self.prev_view();
+                }
+                // Reload data when entering views
+                self.load_view_data().await;
+            }
+            Action::Select => match self.current_view {
+                View::Participants => self.open_selected_participant().await,
+                View::Accounts => self.toggle_selected_group(),
+                _ => return false,
+            },
+            Action::Execute => match self.current_view {
+                // In an account field (0 or 1), accept the suggestion and move on
+                View::Transfer if self.transfer_form.selected_field <= 1 => self.accept_suggestion(),
+                View::Transfer => {
+                    let _ = self.execute_transfer().await;
+                }
+                View::Loan if self.loan_form.current_field() == LoanField::Action => {
+                    self.loan_form.toggle_action();
+                }
+                View::Loan => {
+                    let _ = self.execute_loan().await;
+                }
+                View::AccessPayment => {
+                    let _ = self.execute_access_payment().await;
+                }
+                _ => return false,
+            },
+            Action::Refresh if !self.is_form_view() => self.refresh_all().await,
+            // Back moves up the hierarchical dimension (breadcrumb), unlike
+            // next/previous view which move in the flat dimension
+            Action::Back if self.breadcrumb.len() > 1 => {
+                let target_index = self.breadcrumb.len() - 2;
+                self.jump_to_breadcrumb(target_index).await;
+            }
+            Action::Refresh | Action::Back => return false,
+        }
+        true
+    }
+
+    /// Run the fixed binding `command` for `key`. Returns false when it
+    /// doesn't apply in the current view, so the next row for the key (or
+    /// typing it into a form field) gets its turn. `g_pending` is whether
+    /// the previous key was a lone 'g'.
+    pub async fn run_command(&mut self, command: Command, key: &KeyEvent, g_pending: bool) -> bool {
+        let digit = match key.code {
+            KeyCode::Char(c @ '1'..='9') => Some(c as usize - '1' as usize),
+            _ => None,
+        };
+        let form = self.is_form_view();
+        match command {
+            Command::ForceQuit => self.running = false,
+            // '?' is typed text in the forms, so F1 works everywhere
+            Command::ToggleHelp if key.code == KeyCode::Char('?') && form => return false,
+            Command::ToggleHelp => self.show_help = true,
+            Command::Escape => match self.current_view {
+                View::Transfer => self.transfer_form = TransferForm::default(),
+                View::Loan => self.loan_form = LoanForm::default(),
+                View::AccessPayment => self.access_payment_form = AccessPaymentForm::default(),
+                _ => self.running = false,
+            },
+            // In a drilled-down view, number keys first jump to that breadcrumb
+            // level ('1' is the root); other numbers still switch tabs
+            Command::JumpToBreadcrumb => match digit {
+                Some(index) if !form && self.breadcrumb.len() > 1 && index < self.breadcrumb.len() => {
+                    self.jump_to_breadcrumb(index).await;
+                }
+                _ => return false,
+            },
+            Command::SwitchTab => match digit {
+                Some(index) if !form => self.switch_view(index).await,
+                _ => return false,
+            },
+            // Reconnect after a backend outage
+            Command::Reconnect if !form => self.reconnect().await,
+            Command::CopyId if !form => self.yank_selected(),
+            Command::CycleTheme if !form => {
+                self.theme.cycle();
+                let msg = if self.theme.monochrome {
+                    format!("Theme: {} (colors are off)", self.theme.name.label())
+                } else {
+                    format!("Theme: {}", self.theme.name.label())
+                };
+                self.set_status(msg);
+            }
+            Command::EditFormField if self.tab_edits_form() => {
+                if self.current_view == View::Loan {
+                    self.loan_form.toggle_action();
+                } else if key.code == KeyCode::Tab {
+                    self.next_suggestion();
+                } else {
+                    self.prev_suggestion();
+                }
+            }
+            Command::SaveTemplate if self.current_view == View::Transfer => self.open_save_template(),
+            Command::LoadTemplate if self.current_view == View::Transfer => self.open_template_picker(),
+            Command::DeleteChar => self.handle_backspace(),
+            Command::Down => self.select_next(),
+            Command::Up => self.select_prev(),
+            Command::PendingTop if self.has_list() => {
+                if g_pending {
+                    self.navigate(Motion::Top);
+                } else {
+                    self.pending_g = true;
+                }
+            }
+            Command::Navigate(motion) if self.has_list() => self.navigate(motion),
+            Command::PageParticipants if self.current_view == View::Participants => {
+                self.page_participants(key.code == KeyCode::PageDown);
+            }
+            // j/k keep moving the account selection
+            Command::ScrollDetail if self.current_view == View::ParticipantDetail => {
+                self.scroll_detail_info(key.code == KeyCode::PageDown);
+            }
+            Command::TransferWithAccount if self.current_view == View::ParticipantDetail => {
+                self.transfer_with_selected_account(key.code == KeyCode::Char('f')).await;
+            }
+            Command::EditContact if self.current_view == View::ParticipantDetail => self.open_contact_form(),
+            Command::NewInvoice if self.current_view == View::ParticipantDetail => self.open_invoice_form(),
+            Command::ToggleGroup if self.current_view == View::Accounts => self.toggle_selected_group(),
+            Command::CycleAccountType if self.current_view == View::Accounts => {
+                self.cycle_account_type_filter();
+            }
+            // Undo the selected transaction by posting its inverse
+            Command::ReverseTransaction if self.current_view == View::History => {
+                self.reverse_selected().await;
+            }
+            Command::Export if self.current_view == View::History => self.open_export_prompt(),
+            Command::CycleHistoryType if self.current_view == View::History => {
+                self.cycle_history_type_filter();
+            }
+            Command::ToggleTimeline if self.current_view == View::Future => {
+                self.show_timeline = !self.show_timeline;
+            }
+            _ => return false,
+        }
+        true
+    }
+
     /// Update breadcrumb based on current view and context.
     /// 
     /// Breadcrumbs represent the hierarchical navigation dimension (drilling down into data),
@@ -588,6 +2475,13 @@ impl App {
         self.breadcrumb.clear();
 
         match self.current_view {
+            View::Overview => {
+                self.breadcrumb.push(BreadcrumbSegment {
+                    label: "Overview".to_string(),
+                    view: View::Overview,
+                    context: None,
+                });
+            }
             View::Participants => {
                 self.breadcrumb.push(BreadcrumbSegment {
                     label: "Participants".to_string(),
@@ -617,17 +2511,38 @@ impl App {
                     context: None,
                 });
             }
-            View::History => {
+            View::History => {
+                self.breadcrumb.push(BreadcrumbSegment {
+                    label: "History".to_string(),
+                    view: View::History,
+                    context: None,
+                });
+            }
+            View::Future => {
+                self.breadcrumb.push(BreadcrumbSegment {
+                    label: "Future".to_string(),
+                    view: View::Future,
+                    context: None,
+                });
+            }
+            View::Loan => {
+                self.breadcrumb.push(BreadcrumbSegment {
+                    label: "Loan".to_string(),
+                    view: View::Loan,
+                    context: None,
+                });
+            }
+            View::Accounts => {
                 self.breadcrumb.push(BreadcrumbSegment {
-                    label: "History".to_string(),
-                    view: View::History,
+                    label: "Accounts".to_string(),
+                    view: View::Accounts,
                     context: None,
                 });
             }
-            View::Future => {
+            View::AccessPayment => {
                 self.breadcrumb.push(BreadcrumbSegment {
-                    label: "Future".to_string(),
-                    view: View::Future,
+                    label: "Access Payment".to_string(),
+                    view: View::AccessPayment,
                     context: None,
                 });
             }
@@ -648,6 +2563,22 @@ impl App {
         }
     }
 
+    /// Jump up the hierarchy to breadcrumb `index` and reload that level's data
+    pub async fn jump_to_breadcrumb(&mut self, index: usize) {
+        if index + 1 >= self.breadcrumb.len() {
+            return;
+        }
+        self.navigate_to_breadcrumb(index);
+        if self.current_view == View::ParticipantDetail {
+            self.reload_participant_detail().await;
+        } else if self.current_view == View::Participants {
+            let result = self.load_participants_cached().await;
+            self.report(result);
+        } else {
+            self.load_view_data().await;
+        }
+    }
+
     /// Navigate to next view in the flat navigation dimension.
     /// This switches between parallel views (Participants, ParticipantDetail, Transfer, History, Future),
     /// not hierarchical drill-down. Use breadcrumb/back for hierarchical navigation.
@@ -683,6 +2614,191 @@ impl App {
         }
     }
 
+    /// Jump to a view by index, loading the data it needs on entry
+    pub async fn switch_view(&mut self, index: usize) {
+        self.goto_view(index);
+        self.load_view_data().await;
+    }
+
+    /// Load the data the current view needs when it is entered
+    pub async fn load_view_data(&mut self) {
+        let result = match self.current_view {
+            View::Transfer | View::Accounts => self.load_accounts_cached().await,
+            View::Future => self.load_future_events().await,
+            View::Overview => match self.load_accounts_cached().await {
+                Ok(()) => self.load_future_events().await,
+                Err(e) => Err(e),
+            },
+            _ => Ok(()),
+        };
+        self.report(result);
+    }
+
+    /// Reload the participant currently shown in Participant Details
+    pub async fn reload_participant_detail(&mut self) {
+        let participant_id = self.participant_detail.as_ref().map(|d| d.info.id.clone());
+        if let Some(pid) = participant_id {
+            let result = self.load_participant_detail(&pid).await;
+            self.report(result);
+        }
+    }
+
+    /// Drill into the participant selected in the Participants view
+    pub async fn open_selected_participant(&mut self) {
+        let participant_id = self
+            .participant_state
+            .selected()
+            .and_then(|idx| self.participants.get(idx))
+            .map(|p| p.id.clone());
+        if let Some(pid) = participant_id {
+            let result = self.load_participant_detail(&pid).await;
+            self.report(result);
+            self.detail_account_state.select(Some(0));
+            self.detail_info_scroll = 0;
+            self.detail_accounts_scroll = 0;
+            self.current_view = View::ParticipantDetail;
+            self.update_breadcrumb();
+        }
+    }
+
+    /// Zero-based page of the selected participant, and the number of pages
+    pub fn participant_page(&self) -> (usize, usize) {
+        let selected = self.participant_state.selected().unwrap_or(0);
+        let pages = self.participants.len().div_ceil(PARTICIPANT_PAGE_SIZE).max(1);
+        ((selected / PARTICIPANT_PAGE_SIZE).min(pages - 1), pages)
+    }
+
+    /// Select the first participant of the next or previous page
+    pub fn page_participants(&mut self, forward: bool) {
+        let (page, pages) = self.participant_page();
+        let target = if forward {
+            (page + 1).min(pages - 1)
+        } else {
+            page.saturating_sub(1)
+        };
+        if !self.participants.is_empty() {
+            self.participant_state.select(Some(target * PARTICIPANT_PAGE_SIZE));
+        }
+    }
+
+    /// Scroll the participant detail About/Contact panels by a page.
+    /// The draw pass clamps the offset to the content.
+    pub fn scroll_detail_info(&mut self, down: bool) {
+        self.detail_info_scroll = if down {
+            self.detail_info_scroll.saturating_add(DETAIL_SCROLL_PAGE)
+        } else {
+            self.detail_info_scroll.saturating_sub(DETAIL_SCROLL_PAGE)
+        };
+    }
+
+    /// Number of selectable rows in the current view
+    fn row_count(&self) -> usize {
+        match self.current_view {
+            View::Participants => self.participants.len(),
+            View::ParticipantDetail => self
+                .participant_detail
+                .as_ref()
+                .map(|d| d.accounts.len())
+                .unwrap_or(0),
+            View::History => self.history_rows().len(),
+            View::Accounts => self.account_rows().len(),
+            View::Future => self.future_events.len(),
+            _ => 0,
+        }
+    }
+
+    /// Whether the current view is a list that `navigate` can move through
+    pub fn has_list(&self) -> bool {
+        matches!(
+            self.current_view,
+            View::Participants
+                | View::ParticipantDetail
+                | View::History
+                | View::Accounts
+                | View::Future
+        )
+    }
+
+    /// Jump to the top or bottom of the current view's list, or by a (half)
+    /// page of the rows that were visible in the last draw
+    pub fn navigate(&mut self, motion: Motion) {
+        let len = self.row_count();
+        let page = self
+            .click_map
+            .rows
+            .map_or(DEFAULT_PAGE_ROWS, |rows| rows.height as usize);
+        let state = match self.current_view {
+            View::Participants => &mut self.participant_state,
+            View::ParticipantDetail => &mut self.detail_account_state,
+            View::History => &mut self.history_state,
+            View::Accounts => &mut self.accounts_browse_state,
+            View::Future => &mut self.future_state,
+            _ => return,
+        };
+        nav::apply(state, len, motion, page);
+    }
+
+    fn select_row(&mut self, index: usize) {
+        match self.current_view {
+            View::Participants => self.participant_state.select(Some(index)),
+            View::ParticipantDetail => self.detail_account_state.select(Some(index)),
+            View::History => self.history_state.select(Some(index)),
+            View::Accounts => self.accounts_browse_state.select(Some(index)),
+            View::Future => self.future_state.select(Some(index)),
+            _ => {}
+        }
+    }
+
+    /// Handle a mouse event: clicking a tab switches views, clicking a row
+    /// selects it (double-clicking a participant opens it), and the scroll
+    /// wheel moves the selection.
+    pub async fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(index) = self.click_map.tab_at(position) {
+                    self.last_click = None;
+                    self.switch_view(index).await;
+                    return;
+                }
+                if let Some(index) = self.click_map.breadcrumb_at(position) {
+                    self.last_click = None;
+                    self.jump_to_breadcrumb(index).await;
+                    return;
+                }
+                let Some(row) = self.click_map.row_at(position) else {
+                    return;
+                };
+                if row >= self.row_count() {
+                    return;
+                }
+                self.select_row(row);
+
+                let now = Instant::now();
+                let double_click = matches!(
+                    self.last_click,
+                    Some((at, view, last_row))
+                        if view == self.current_view
+                            && last_row == row
+                            && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+                );
+                if double_click {
+                    self.last_click = None;
+                    if self.current_view == View::Participants {
+                        self.open_selected_participant().await;
+                    } else if self.current_view == View::Accounts {
+                        self.toggle_selected_group();
+                    }
+                } else {
+                    self.last_click = Some((now, self.current_view, row));
+                }
+            }
+            MouseEventKind::ScrollDown if !self.is_form_view() => self.select_next(),
+            MouseEventKind::ScrollUp if !self.is_form_view() => self.select_prev(),
+            _ => {}
+        }
+    }
+
     pub fn select_next(&mut self) {
         match self.current_view {
             View::Participants => {
@@ -691,10 +2807,46 @@ impl App {
                     self.participant_state.select(Some(i + 1));
                 }
             }
+            View::ParticipantDetail => {
+                let len = self.participant_detail.as_ref().map(|d| d.accounts.len()).unwrap_or(0);
+                let i = self.detail_account_state.selected().unwrap_or(0);
+                if i < len.saturating_sub(1) {
+                    self.detail_account_state.select(Some(i + 1));
+                }
+            }
             View::Transfer => {
                 self.transfer_form.selected_field = (self.transfer_form.selected_field + 1) % 4;
             }
-            _ => {}
+            View::History => {
+                let i = self.history_state.selected().unwrap_or(0);
+                if i < self.history_rows().len().saturating_sub(1) {
+                    self.history_state.select(Some(i + 1));
+                }
+            }
+            View::Loan => {
+                let count = self.loan_form.fields().len();
+                self.loan_form.selected_field = (self.loan_form.selected_field + 1) % count;
+            }
+            View::AccessPayment => {
+                let count = ACCESS_PAYMENT_FIELDS.len();
+                let form = &mut self.access_payment_form;
+                form.selected_field = (form.selected_field + 1) % count;
+            }
+            View::Accounts => {
+                let len = self.account_rows().len();
+                let i = self.accounts_browse_state.selected().unwrap_or(0);
+                if i < len.saturating_sub(1) {
+                    self.accounts_browse_state.select(Some(i + 1));
+                }
+            }
+            View::Future => {
+                let i = self.future_state.selected().unwrap_or(0);
+                if i < self.future_events.len().saturating_sub(1) {
+                    self.future_state.select(Some(i + 1));
+                }
+            }
+            // Nothing to select
+            View::Overview => {}
         }
     }
 
@@ -706,11 +2858,308 @@ impl App {
                     self.participant_state.select(Some(i - 1));
                 }
             }
+            View::ParticipantDetail => {
+                let i = self.detail_account_state.selected().unwrap_or(0);
+                if i > 0 {
+                    self.detail_account_state.select(Some(i - 1));
+                }
+            }
             View::Transfer => {
                 self.transfer_form.selected_field = (self.transfer_form.selected_field + 3) % 4;
             }
-            _ => {}
+            View::History => {
+                let i = self.history_state.selected().unwrap_or(0);
+                if i > 0 {
+                    self.history_state.select(Some(i - 1));
+                }
+            }
+            View::Loan => {
+                let count = self.loan_form.fields().len();
+                self.loan_form.selected_field = (self.loan_form.selected_field + count - 1) % count;
+            }
+            View::AccessPayment => {
+                let count = ACCESS_PAYMENT_FIELDS.len();
+                let form = &mut self.access_payment_form;
+                form.selected_field = (form.selected_field + count - 1) % count;
+            }
+            View::Accounts => {
+                let i = self.accounts_browse_state.selected().unwrap_or(0);
+                if i > 0 {
+                    self.accounts_browse_state.select(Some(i - 1));
+                }
+            }
+            View::Future => {
+                let i = self.future_state.selected().unwrap_or(0);
+                if i > 0 {
+                    self.future_state.select(Some(i - 1));
+                }
+            }
+            View::Overview => {}
+        }
+    }
+
+    /// Accounts passing the type filter, grouped by participant in load order.
+    /// Collapsed groups contribute only their header row.
+    pub fn account_rows(&self) -> Vec<AccountRow> {
+        let mut rows = Vec::new();
+        let mut seen = HashSet::new();
+        for account in &self.accounts {
+            let participant_id = &account.participant_id;
+            if !seen.insert(participant_id.as_str()) {
+                continue;
+            }
+            let members: Vec<usize> = self
+                .accounts
+                .iter()
+                .enumerate()
+                .filter(|(_, a)| &a.participant_id == participant_id && self.passes_type_filter(a))
+                .map(|(i, _)| i)
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            let name = self
+                .participants
+                .iter()
+                .find(|p| &p.id == participant_id)
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| participant_id.clone());
+            let collapsed = self.collapsed_groups.contains(participant_id);
+            rows.push(AccountRow::Group {
+                participant_id: participant_id.clone(),
+                name,
+                count: members.len(),
+                subtotal: members.iter().map(|i| self.accounts[*i].balance).sum(),
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend(members.into_iter().map(AccountRow::Account));
+            }
+        }
+        rows
+    }
+
+    /// Grand total of the accounts passing the type filter
+    pub fn filtered_accounts_total(&self) -> i64 {
+        self.accounts
+            .iter()
+            .filter(|a| self.passes_type_filter(a))
+            .map(|a| a.balance)
+            .sum()
+    }
+
+    fn passes_type_filter(&self, account: &AccountInfo) -> bool {
+        self.account_type_filter
+            .is_none_or(|t| account.account_type == t)
+    }
+
+    /// Step the account type filter through All and each account type
+    pub fn cycle_account_type_filter(&mut self) {
+        let next = match self.account_type_filter {
+            None => 0,
+            Some(current) => {
+                SUBTOTAL_ACCOUNT_TYPES
+                    .iter()
+                    .position(|t| *t == current)
+                    .map_or(0, |i| i + 1)
+            }
+        };
+        self.account_type_filter = SUBTOTAL_ACCOUNT_TYPES.get(next).copied();
+        self.clamp_accounts_selection();
+        let label = self.account_type_filter.unwrap_or("All");
+        self.set_status(format!("Account type: {}", label));
+    }
+
+    /// Fold or unfold the participant group under the selection
+    pub fn toggle_selected_group(&mut self) {
+        let Some(i) = self.accounts_browse_state.selected() else {
+            return;
+        };
+        if let Some(AccountRow::Group { participant_id, .. }) = self.account_rows().into_iter().nth(i) {
+            if !self.collapsed_groups.remove(&participant_id) {
+                self.collapsed_groups.insert(participant_id);
+            }
+        }
+        self.clamp_accounts_selection();
+    }
+
+    /// Indices into `history` of the listed transactions, newest first,
+    /// narrowed to `history_type_filter`
+    pub fn history_rows(&self) -> Vec<usize> {
+        self.history
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, tx)| self.history_type_filter.is_none_or(|t| tx.tx_type == t))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Step the History type filter: All, then each of `TRANSACTION_TYPES`
+    pub fn cycle_history_type_filter(&mut self) {
+        let next = match self.history_type_filter {
+            None => 0,
+            Some(current) => TRANSACTION_TYPES
+                .iter()
+                .position(|t| *t == current)
+                .map_or(0, |i| i + 1),
+        };
+        self.history_type_filter = TRANSACTION_TYPES.get(next).copied();
+        let len = self.history_rows().len();
+        let i = self.history_state.selected().unwrap_or(0);
+        self.history_state.select(Some(i.min(len.saturating_sub(1))));
+        let label = self.history_type_filter.unwrap_or("All");
+        self.set_status(format!("Transaction type: {} ({} shown)", label, len));
+    }
+
+    fn clamp_accounts_selection(&mut self) {
+        let len = self.account_rows().len();
+        let i = self.accounts_browse_state.selected().unwrap_or(0);
+        self.accounts_browse_state
+            .select(Some(i.min(len.saturating_sub(1))));
+    }
+
+    /// Show a transient message in the status bar
+    pub fn set_status(&mut self, message: String) {
+        self.status_message = Some(message);
+        self.status_set_at = Some(Instant::now());
+        self.status_is_error = false;
+    }
+
+    /// Show an error in the status bar; it stays visible longer than a message
+    pub fn set_error(&mut self, message: String) {
+        self.set_status(message);
+        self.status_is_error = true;
+    }
+
+    /// Surface a failed load in the status bar instead of silently keeping
+    /// stale data on screen
+    pub fn report(&mut self, result: Result<()>) {
+        if let Err(e) = result {
+            self.loading = false;
+            self.set_error(format!("Load failed: {}", grpc::error_summary(&e)));
+        }
+    }
+
+    /// Clear the status message once it has been visible long enough
+    pub fn expire_status(&mut self) {
+        let ttl = if self.status_is_error {
+            ERROR_MESSAGE_TTL
+        } else {
+            STATUS_MESSAGE_TTL
+        };
+        if let Some(set_at) = self.status_set_at {
+            if set_at.elapsed() >= ttl {
+                self.status_message = None;
+                self.status_set_at = None;
+                self.status_is_error = false;
+            }
+        }
+    }
+
+    /// Id of the currently selected item in the current view, if any:
+    /// participant id in Participants, account id in Participant Details,
+    /// transaction id in History.
+    pub fn selected_id(&self) -> Option<String> {
+        match self.current_view {
+            View::Participants => self
+                .participant_state
+                .selected()
+                .and_then(|i| self.participants.get(i))
+                .map(|p| p.id.clone()),
+            View::ParticipantDetail => {
+                let detail = self.participant_detail.as_ref()?;
+                self.detail_account_state
+                    .selected()
+                    .and_then(|i| detail.accounts.get(i))
+                    .map(|a| a.id.clone())
+            }
+            View::History => {
+                let i = self.history_state.selected()?;
+                let idx = *self.history_rows().get(i)?;
+                self.history.get(idx).map(|tx| tx.id.clone())
+            }
+            View::Accounts => {
+                let i = self.accounts_browse_state.selected()?;
+                match self.account_rows().into_iter().nth(i)? {
+                    AccountRow::Group { participant_id, .. } => Some(participant_id),
+                    AccountRow::Account(idx) => self.accounts.get(idx).map(|a| a.id.clone()),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Reverse the selected History transaction. The first press only arms the
+    /// reversal; pressing again on the same transaction posts it.
+    pub async fn reverse_selected(&mut self) {
+        let Some(id) = self.selected_id() else {
+            self.set_status("No transaction selected".to_string());
+            return;
+        };
+        let short_id: String = id.chars().take(8).collect();
+        if self.pending_reversal.as_deref() != Some(id.as_str()) {
+            self.set_status(format!("Press u again to reverse [{}]", short_id));
+            self.pending_reversal = Some(id);
+            return;
+        }
+        self.pending_reversal = None;
+        match self.client.reverse_transaction(&id).await {
+            Ok(tx) => {
+                self.invalidate_cache();
+                let new_id: String = tx.id.chars().take(8).collect();
+                let result = self.load_transactions().await;
+                self.report(result);
+                self.set_status(format!("Reversed [{}] as [{}]", short_id, new_id));
+            }
+            Err(e) => self.set_error(format!("Reversal failed: {}", grpc::error_summary(&e))),
+        }
+    }
+
+    /// Copy the selected item's id to the system clipboard
+    pub fn yank_selected(&mut self) {
+        let Some(id) = self.selected_id() else {
+            self.set_status("Nothing selected to copy".to_string());
+            return;
+        };
+        match self.clipboard.copy(&id) {
+            Ok(()) => self.set_status(format!("copied {}", id)),
+            Err(e) => self.set_error(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Put the account selected in Participant Details into the Transfer form's
+    /// From (`as_source`) or To field and switch to Transfer. The rest of the
+    /// form is kept, so 'f' on one account and 't' on another fills both.
+    pub async fn transfer_with_selected_account(&mut self, as_source: bool) {
+        let Some(account_id) = self.selected_id() else {
+            self.set_status("No account selected".to_string());
+            return;
+        };
+        let form = &mut self.transfer_form;
+        if as_source {
+            form.from_account = account_id.clone();
+        } else {
+            form.to_account = account_id.clone();
+        }
+        form.show_suggestions = false;
+        form.suggestion_index = None;
+        form.error = None;
+        form.success = None;
+        // Continue with whichever account is still missing, else the amount
+        form.selected_field = if form.from_account.is_empty() {
+            0
+        } else if form.to_account.is_empty() {
+            1
+        } else {
+            2
+        };
+
+        if let Some(index) = View::all().iter().position(|v| *v == View::Transfer) {
+            self.switch_view(index).await;
         }
+        let field = if as_source { "From" } else { "To" };
+        self.set_status(format!("{}: {}", field, account_id));
     }
 
     pub fn handle_char(&mut self, c: char) {
@@ -726,6 +3175,18 @@ impl App {
             // Show suggestions when typing in account fields
             self.transfer_form.suggestion_index = None;
             self.transfer_form.show_suggestions = self.transfer_form.selected_field <= 1;
+        } else if self.current_view == View::Loan {
+            let field = self.loan_form.current_field();
+            if field == LoanField::Action {
+                if c == ' ' {
+                    self.loan_form.toggle_action();
+                }
+            } else if let Some(value) = self.loan_form.value_mut(field) {
+                value.push(c);
+            }
+        } else if self.current_view == View::AccessPayment {
+            let field = self.access_payment_form.current_field();
+            self.access_payment_form.value_mut(field).push(c);
         }
     }
 
@@ -742,6 +3203,14 @@ impl App {
             // Reset suggestions when typing
             self.transfer_form.suggestion_index = None;
             self.transfer_form.show_suggestions = self.transfer_form.selected_field <= 1;
+        } else if self.current_view == View::Loan {
+            let field = self.loan_form.current_field();
+            if let Some(value) = self.loan_form.value_mut(field) {
+                value.pop();
+            }
+        } else if self.current_view == View::AccessPayment {
+            let field = self.access_payment_form.current_field();
+            self.access_payment_form.value_mut(field).pop();
         }
     }
 
@@ -753,15 +3222,21 @@ impl App {
             _ => return vec![],
         };
 
-        let filter_lower = filter.to_lowercase();
-        self.accounts
+        if filter.is_empty() {
+            return self.accounts.iter().collect();
+        }
+        let mut scored: Vec<(i64, &AccountInfo)> = self
+            .accounts
             .iter()
-            .filter(|acc| {
-                filter.is_empty()
-                    || acc.id.to_lowercase().contains(&filter_lower)
-                    || acc.account_type.to_lowercase().contains(&filter_lower)
+            .filter_map(|acc| {
+                let score = fuzzy_score(filter, &acc.id)
+                    .max(fuzzy_score(filter, &acc.account_type))?;
+                Some((score, acc))
             })
-            .collect()
+            .collect();
+        // Stable sort keeps load order among equally good matches
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, acc)| acc).collect()
     }
 
     /// Cycle to next account suggestion
@@ -851,202 +3326,404 @@ pub async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     mut app: App,
 ) -> AppResult<()> {
-    // Initial data load - ignore errors to show UI even if server has issues
-    let _ = app.load_participants().await;
-    let _ = app.load_accounts().await;
-    let _ = app.load_transactions().await;
-    let _ = app.load_future_events().await;
+    // Initial data load - failures are reported in the status bar so the UI still comes up
+    app.refresh_all().await;
+    app.restore_saved_state().await;
 
     loop {
+        app.expire_status();
+        app.expire_balance_changes();
+        // Auto-refresh, but never while the user is typing into a form
+        if let Some(interval) = app.refresh_interval {
+            if !app.is_form_view() && app.last_refresh.elapsed() >= interval {
+                app.refresh_all().await;
+            }
+        }
+        app.disconnect_if_idle();
+        app.update_connection_status();
         terminal.draw(|f| super::views::draw(f, &mut app))?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    // Handle Ctrl+C
-                    if key.modifiers.contains(KeyModifiers::CONTROL)
-                        && key.code == KeyCode::Char('c')
-                    {
+            match event::read()? {
+                // The help overlay and template popup are modal, so mouse input is ignored while open
+                Event::Mouse(mouse)
+                    if !app.show_help
+                        && app.template_popup.is_none()
+                        && app.export_prompt.is_none()
+                        && app.contact_form.is_none()
+                        && app.invoice_form.is_none() =>
+                {
+                    app.handle_mouse(mouse).await;
+                }
+                // Clear so the next draw repaints everything at the new size
+                Event::Resize(_, _) => {
+                    terminal.clear()?;
+                }
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    let commands = fixed_commands(&key);
+                    if commands.contains(&Command::ForceQuit) {
                         app.running = false;
                         continue;
                     }
 
-                    match key.code {
-                        KeyCode::Char('q') => {
-                            // 'q' always quits, even in Transfer view
+                    // Help overlay is modal: only close/quit keys are handled while it is open
+                    if app.show_help {
+                        if commands.iter().any(|c| matches!(c, Command::ToggleHelp | Command::Escape)) {
+                            app.show_help = false;
+                        } else if app.keymap.is(&key, Action::Quit) {
                             app.running = false;
                         }
-                        KeyCode::Esc => {
-                            // Esc clears form in Transfer view, quits elsewhere
-                            if app.current_view == View::Transfer {
-                                app.transfer_form = TransferForm::default();
-                            } else {
-                                app.running = false;
-                            }
-                        }
-                        // Tab navigation - in Transfer form, Tab cycles through account suggestions
-                        KeyCode::Tab => {
-                            if app.current_view == View::Transfer
-                                && app.transfer_form.selected_field <= 1
-                            {
-                                app.next_suggestion();
-                            } else {
-                                let was_transfer = app.current_view == View::Transfer;
-                                app.next_view();
-                                // Reload data when entering views
-                                if !was_transfer && app.current_view == View::Transfer {
-                                    let _ = app.load_accounts().await;
-                                } else if app.current_view == View::Future {
-                                    let _ = app.load_future_events().await;
-                                }
-                            }
-                        }
-                        KeyCode::BackTab => {
-                            if app.current_view == View::Transfer
-                                && app.transfer_form.selected_field <= 1
-                            {
-                                app.prev_suggestion();
-                            } else {
-                                let was_transfer = app.current_view == View::Transfer;
-                                app.prev_view();
-                                // Reload data when entering views
-                                if !was_transfer && app.current_view == View::Transfer {
-                                    let _ = app.load_accounts().await;
-                                } else if app.current_view == View::Future {
-                                    let _ = app.load_future_events().await;
-                                }
-                            }
-                        }
-                        KeyCode::Right => {
-                            // Right arrow always switches to next tab
-                            let was_transfer = app.current_view == View::Transfer;
-                            app.next_view();
-                            // Reload data when entering views
-                            if !was_transfer && app.current_view == View::Transfer {
-                                let _ = app.load_accounts().await;
-                            } else if app.current_view == View::Future {
-                                let _ = app.load_future_events().await;
-                            }
-                        }
-                        KeyCode::Left => {
-                            // Left arrow always switches to previous tab
-                            let was_transfer = app.current_view == View::Transfer;
-                            app.prev_view();
-                            // Reload data when entering views
-                            if !was_transfer && app.current_view == View::Transfer {
-                                let _ = app.load_accounts().await;
-                            } else if app.current_view == View::Future {
-                                let _ = app.load_future_events().await;
-                            }
-                        }
-                        // Number keys for direct tab access (not in Transfer view)
-                        KeyCode::Char('1') if app.current_view != View::Transfer => {
-                            app.goto_view(0);
-                        }
-                        KeyCode::Char('2') if app.current_view != View::Transfer => {
-                            app.goto_view(1);
-                        }
-                        KeyCode::Char('3') if app.current_view != View::Transfer => {
-                            // Entering Transfer view - load all accounts
-                            app.goto_view(2);
-                            let _ = app.load_accounts().await;
-                        }
-                        KeyCode::Char('4') if app.current_view != View::Transfer => {
-                            app.goto_view(3);
-                            let _ = app.load_future_events().await;
-                        }
-                        // List navigation
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            app.select_next();
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            app.select_prev();
-                        }
-                        // Home/End for list navigation
-                        KeyCode::Home => match app.current_view {
-                            View::Participants => app.participant_state.select(Some(0)),
-                            _ => {}
-                        },
-                        KeyCode::End => match app.current_view {
-                            View::Participants => {
-                                let len = app.participants.len();
-                                if len > 0 {
-                                    app.participant_state.select(Some(len - 1));
-                                }
-                            }
-                            _ => {}
-                        },
-                        // Enter actions
-                        KeyCode::Enter => {
-                            if app.current_view == View::Transfer {
-                                // If in account field (0 or 1), accept and move to next field
-                                if app.transfer_form.selected_field <= 1 {
-                                    app.accept_suggestion();
-                                } else {
-                                    // In amount or reference field, execute transfer
-                                    let _ = app.execute_transfer().await;
-                                }
-                            } else if app.current_view == View::Participants {
-                                if let Some(idx) = app.participant_state.selected() {
-                                    let participant_id =
-                                        app.participants.get(idx).map(|p| p.id.clone());
-                                    if let Some(pid) = participant_id {
-                                        let _ = app.load_participant_detail(&pid).await;
-                                        app.current_view = View::ParticipantDetail;
-                                        app.update_breadcrumb();
-                                    }
-                                }
-                            }
+                        continue;
+                    }
+
+                    if app.template_popup.is_some() {
+                        app.handle_template_key(key);
+                        continue;
+                    }
+                    if app.export_prompt.is_some() {
+                        app.handle_export_key(key);
+                        continue;
+                    }
+                    if app.contact_form.is_some() {
+                        app.handle_contact_key(key).await;
+                        continue;
+                    }
+                    if app.invoice_form.is_some() {
+                        app.handle_invoice_key(key).await;
+                        continue;
+                    }
+
+                    // Any other key (Ctrl+U included) disarms a pending reversal
+                    if key.code != KeyCode::Char('u') || key.modifiers.contains(KeyModifiers::CONTROL) {
+                        app.pending_reversal = None;
+                    }
+                    // 'g' waits for a second 'g'; any other key cancels it
+                    let g_pending = std::mem::take(&mut app.pending_g);
+
+                    // Tab editing a form field comes first, then the remappable
+                    // actions ('q' quits even in the forms), then the other fixed
+                    // bindings. A key none of them applies to is typed into the form.
+                    let (early, late): (Vec<_>, Vec<_>) =
+                        commands.into_iter().partition(|c| c.precedes_actions());
+                    let mut handled = false;
+                    for command in early {
+                        if app.run_command(command, &key, g_pending).await {
+                            handled = true;
+                            break;
                         }
-                        // Refresh
-                        KeyCode::Char('r') if app.current_view != View::Transfer => {
-                            let _ = app.load_participants().await;
-                            let _ = app.load_accounts().await;
-                            let _ = app.load_transactions().await;
-                            let _ = app.load_future_events().await;
-                            // Reload participant detail if viewing it
-                            if app.current_view == View::ParticipantDetail {
-                                let participant_id = app.participant_detail.as_ref().map(|d| d.info.id.clone());
-                                if let Some(pid) = participant_id {
-                                    let _ = app.load_participant_detail(&pid).await;
-                                }
+                    }
+                    if !handled {
+                        for action in app.keymap.actions(&key) {
+                            if app.perform(action).await {
+                                handled = true;
+                                break;
                             }
                         }
-                        // Back navigation - move up the hierarchical dimension (breadcrumb)
-                        // This is different from Tab/arrows which move in the flat dimension
-                        KeyCode::Char('b') if app.breadcrumb.len() > 1 => {
-                            // Navigate back to previous segment
-                            let target_index = app.breadcrumb.len() - 2;
-                            app.navigate_to_breadcrumb(target_index);
-                            
-                            // Reload data based on new view
-                            if app.current_view == View::ParticipantDetail {
-                                // Reload participant detail if we're going back to it
-                                let participant_id = app.participant_detail.as_ref().map(|d| d.info.id.clone());
-                                if let Some(pid) = participant_id {
-                                    let _ = app.load_participant_detail(&pid).await;
-                                }
-                            } else if app.current_view == View::Participants {
-                                let _ = app.load_participants().await;
-                            } else if app.current_view == View::Future {
-                                let _ = app.load_future_events().await;
+                    }
+                    if !handled {
+                        for command in late {
+                            if app.run_command(command, &key, g_pending).await {
+                                handled = true;
+                                break;
                             }
                         }
-                        // Text input for Transfer form
-                        KeyCode::Char(c) => {
-                            app.handle_char(c);
-                        }
-                        KeyCode::Backspace => {
-                            app.handle_backspace();
-                        }
-                        _ => {}
+                    }
+                    if let (false, KeyCode::Char(c)) = (handled, key.code) {
+                        app.handle_char(c);
                     }
                 }
+                _ => {}
             }
         }
 
         if !app.running {
+            // Best effort: failing to remember the view shouldn't fail the exit
+            let _ = app.current_state().save();
             return Ok(());
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::smartcontracts::{contract_response::Contract, ContractResponse, ContractStatus, GenericContract};
+
+    fn generic(status: ContractStatus, next_execution_at: i64) -> ContractResponse {
+        ContractResponse {
+            contract: Some(Contract::Generic(GenericContract {
+                id: "gen-1".to_string(),
+                name: "Ecosystem fees".to_string(),
+                description: "Monthly fee split".to_string(),
+                contract_type: 7,
+                status: status as i32,
+                next_execution_at,
+                metadata: [
+                    ("orchestrator_id", "beauty_hosting"),
+                    ("first_provider_id", "salon_glamour"),
+                    ("note", "ignored"),
+                ]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+                ..Default::default()
+            })),
+        }
+    }
+
+    #[test]
+    fn generic_contract_future_event_lists_metadata_participants() {
+        let event = FutureEvent::from_contract(generic(ContractStatus::Active, 2_000))
+            .expect("active generic contract with a scheduled execution");
+        assert_eq!(event.contract_id, "gen-1");
+        assert_eq!(event.contract_type, "Generic (Ecosystem Partner Membership)");
+        assert_eq!(event.execution_time, 2_000);
+        assert_eq!(
+            event.description,
+            "Ecosystem fees: Monthly fee split (participants: beauty_hosting, salon_glamour)"
+        );
+    }
+
+    #[test]
+    fn generic_contract_future_event_respects_status_and_schedule() {
+        assert!(FutureEvent::from_contract(generic(ContractStatus::Paused, 2_000)).is_none());
+        assert!(FutureEvent::from_contract(generic(ContractStatus::Active, 0)).is_none());
+
+        let overdue = FutureEvent::from_contract(generic(ContractStatus::Active, 500))
+            .expect("past-due contracts are kept");
+        assert!(overdue.is_overdue(1_000));
+        let upcoming = FutureEvent::from_contract(generic(ContractStatus::Active, 2_000)).unwrap();
+        assert!(!upcoming.is_overdue(1_000));
+    }
+
+    #[test]
+    fn overview_counts_roles_and_totals_receivables_and_payables() {
+        let participant = |id: &str, role: &str| ParticipantInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            role: role.to_string(),
+            services: Vec::new(),
+            created_at: None,
+            metadata: Default::default(),
+            about: String::new(),
+            contact: ContactInfo::default(),
+            operating_balance: None,
+        };
+        let account = |participant_id: &str, account_type: &str, balance: i64| AccountInfo {
+            id: format!("{}:{}", participant_id, account_type.to_lowercase()),
+            participant_id: participant_id.to_string(),
+            account_type: account_type.to_string(),
+            balance,
+        };
+        let participants = [
+            participant("schampo_etc", "Supplier"),
+            participant("seb", "Banking Partner"),
+            participant("clipper_oy", "Supplier"),
+        ];
+        let accounts = [
+            account("schampo_etc", "Receivables", 30_000),
+            account("seb", "Receivables", 5_000),
+            account("salon_glamour", "Payables", -30_000),
+            account("seb", "Operating", 1_000_000),
+        ];
+        let stats = OverviewStats::compute(&participants, &accounts);
+        assert_eq!(
+            stats.roles,
+            [("Supplier".to_string(), 2), ("Banking Partner".to_string(), 1)]
+        );
+        assert_eq!((stats.participants, stats.accounts), (3, 4));
+        assert_eq!((stats.receivables, stats.payables), (35_000, 30_000));
+    }
+
+    #[test]
+    fn overview_counts_only_contracts_still_in_force() {
+        use crate::grpc::smartcontracts::{contract_response::Contract, InvoiceContract, SubscriptionContract};
+
+        let invoice = |status: &str| ContractResponse {
+            contract: Some(Contract::Invoice(InvoiceContract {
+                status: status.to_string(),
+                ..Default::default()
+            })),
+        };
+        let subscription = |status: &str| ContractResponse {
+            contract: Some(Contract::Subscription(SubscriptionContract {
+                status: status.to_string(),
+                ..Default::default()
+            })),
+        };
+        let contracts = [
+            invoice("pending"),
+            invoice("overdue"),
+            invoice("paid"),
+            subscription("active"),
+            subscription("cancelled"),
+            generic(ContractStatus::Active, 0),
+            generic(ContractStatus::Completed, 0),
+        ];
+        assert_eq!(
+            active_contract_counts(&contracts),
+            [
+                ("Invoice".to_string(), 2),
+                ("Ecosystem Partner Membership".to_string(), 1),
+                ("Subscription".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn timeline_projects_subscriptions_monthly_within_the_horizon() {
+        use crate::grpc::smartcontracts::{contract_response::Contract, InvoiceContract, SubscriptionContract};
+
+        let now = 1_700_000_000_000;
+        let subscription = |id: &str, end_date: i64| ContractResponse {
+            contract: Some(Contract::Subscription(SubscriptionContract {
+                id: id.to_string(),
+                status: "active".to_string(),
+                next_billing_date: now + 10 * DAY_MS,
+                end_date,
+                ..Default::default()
+            })),
+        };
+        let invoice = |id: &str, due_date: i64| ContractResponse {
+            contract: Some(Contract::Invoice(InvoiceContract {
+                id: id.to_string(),
+                status: "pending".to_string(),
+                due_date,
+                ..Default::default()
+            })),
+        };
+        let contracts = [
+            subscription("sub-open", 0),
+            subscription("sub-ending", now + 50 * DAY_MS),
+            invoice("inv-soon", now + 5 * DAY_MS),
+            invoice("inv-late", now + 100 * DAY_MS),
+            invoice("inv-overdue", now - DAY_MS),
+        ];
+        let events = project_timeline(&contracts, now);
+        let count = |id: &str| events.iter().filter(|e| e.contract_id == id).count();
+        assert_eq!(count("sub-open"), 3);
+        assert_eq!(count("sub-ending"), 2);
+        assert_eq!(count("inv-soon"), 1);
+        assert_eq!(count("inv-late") + count("inv-overdue"), 0);
+        assert!(events.windows(2).all(|w| w[0].execution_time <= w[1].execution_time));
+        assert_eq!(events[0].contract_id, "inv-soon");
+
+        let (overdue, upcoming) = schedule(&contracts, now);
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].contract_id, "inv-overdue");
+        let ids: Vec<&str> = upcoming.iter().map(|e| e.contract_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["inv-soon", "sub-open", "sub-ending", "sub-open", "sub-ending", "sub-open", "inv-late"]
+        );
+
+        // A subscription that missed its billing is overdue once; the
+        // billings after it are still upcoming
+        let lapsed = ContractResponse {
+            contract: Some(Contract::Subscription(SubscriptionContract {
+                id: "sub-lapsed".to_string(),
+                status: "active".to_string(),
+                next_billing_date: now - 40 * DAY_MS,
+                ..Default::default()
+            })),
+        };
+        let (overdue, upcoming) = schedule(&[lapsed], now);
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].execution_time, now - 40 * DAY_MS);
+        assert_eq!(upcoming.len(), 3);
+        assert!(upcoming.iter().all(|e| e.execution_time > now));
+    }
+
+    #[test]
+    fn balance_trend_replays_transactions_back_from_the_current_balance() {
+        use crate::grpc::common::{Transaction, TransferEntry};
+
+        let tx = |entries: &[(&str, i64)]| Transaction {
+            entries: entries
+                .iter()
+                .map(|(account_id, amount)| TransferEntry {
+                    account_id: account_id.to_string(),
+                    amount: *amount,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        // Newest first: +500 in, then 200 out, and a transfer touching the account twice
+        let transactions = [
+            tx(&[("bank:operating", -500), ("salon:operating", 500)]),
+            tx(&[("salon:operating", -200), ("supplier:operating", 200)]),
+            tx(&[("salon:operating", 100), ("salon:operating", 50), ("bank:operating", -150)]),
+        ];
+        assert_eq!(
+            balance_trend("salon:operating", 1_000, &transactions),
+            [550, 700, 500, 1_000]
+        );
+        assert_eq!(balance_trend("salon:operating", 1_000, &[]), [1_000]);
+    }
+
+    #[test]
+    fn balance_changes_report_moved_accounts_only() {
+        let account = |id: &str, balance: i64| AccountInfo {
+            id: id.to_string(),
+            participant_id: String::new(),
+            account_type: "Operating".to_string(),
+            balance,
+        };
+        let before = balances_by_id(&[account("a:operating", 1_000), account("b:operating", 500), account("c:fees", 0)]);
+        let after = [
+            account("a:operating", 1_250),
+            account("b:operating", 500),
+            account("c:fees", -75),
+            account("d:operating", 9_999),
+        ];
+        let changes = balance_changes(&before, &after);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes["a:operating"], 250);
+        assert_eq!(changes["c:fees"], -75);
+        assert!(balance_changes(&HashMap::new(), &after).is_empty());
+    }
+
+    #[test]
+    fn invoice_form_checks_dates_and_amounts() {
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        let mut form = InvoiceForm::new("salon_glamour", today);
+        assert_eq!(form.due_date, "2025-02-14");
+        assert!(form.to_request().unwrap_err().to_string().contains("buyer"));
+
+        form.buyer_id = "seb".to_string();
+        form.amount = "1500.50".to_string();
+        form.late_fee = "25".to_string();
+        let request = form.to_request().unwrap();
+        assert_eq!(request.amount_cents, 150_050);
+        assert_eq!(request.late_fee_cents, 2_500);
+        assert_eq!(request.issue_date, 1_736_899_200_000);
+        assert_eq!(request.due_date - request.issue_date, 30 * DAY_MS);
+
+        form.due_date = "2025-01-14".to_string();
+        assert!(form.to_request().unwrap_err().to_string().contains("before the issue date"));
+        form.due_date = "2025-01-15".to_string();
+        assert!(form.to_request().is_ok());
+        form.issue_date = "15/01/2025".to_string();
+        assert!(form.to_request().unwrap_err().to_string().contains("Issue date"));
+    }
+
+    #[test]
+    fn fuzzy_score_ranks_prefix_then_substring_then_subsequence() {
+        // Exact and prefix matches: shorter candidates first, case ignored
+        assert_eq!(fuzzy_score("Salon", "salon"), Some(2995));
+        assert_eq!(fuzzy_score("salon", "salon_glamour"), Some(2987));
+        // Substring matches rank below any prefix, earlier positions first
+        assert_eq!(fuzzy_score("glam", "salon_glamour"), Some(1994));
+        assert!(fuzzy_score("glam", "glamour_salon") > fuzzy_score("glam", "salon_glamour"));
+        assert!(fuzzy_score("glam", "salon_glamour") > fuzzy_score("glam", "x_salon_glamour"));
+        // Subsequences rank below substrings; word starts and fewer gaps score higher
+        assert_eq!(fuzzy_score("salop", "salon_glamour:operating"), Some(999));
+        assert!(fuzzy_score("sg", "salon_glamour") > fuzzy_score("sg", "sag"));
+        assert!(fuzzy_score("sg", "salon_glamour") > fuzzy_score("sg", "salon_xglamour"));
+        // Characters must appear in order
+        assert_eq!(fuzzy_score("xyz", "salon_glamour"), None);
+        assert_eq!(fuzzy_score("gs", "sg"), None);
+        // Equal-length prefixes tie
+        assert_eq!(fuzzy_score("sa", "salon_a"), fuzzy_score("sa", "salon_b"));
+    }
+}
@@ -0,0 +1,113 @@
+//! History Export
+//!
+//! Writes the transactions loaded in the History view to a local file: JSON
+//! when the path ends in `.json`, CSV otherwise. CSV has one row per entry,
+//! so a four-entry settlement becomes four rows sharing a transaction id.
+
+use super::app::TransactionInfo;
+use crate::grpc;
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::path::Path;
+
+/// Default file name, e.g. `transactions-20260314-093000.csv`
+pub fn default_path() -> String {
+    format!("./transactions-{}.csv", chrono::Local::now().format("%Y%m%d-%H%M%S"))
+}
+
+/// Write `transactions` to `path`, picking the format from the extension
+pub fn write_transactions(path: &Path, transactions: &[TransactionInfo]) -> Result<()> {
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let content = if is_json {
+        to_json(transactions)?
+    } else {
+        to_csv(transactions)
+    };
+    std::fs::write(path, content).with_context(|| format!("cannot write {}", path.display()))
+}
+
+fn to_json(transactions: &[TransactionInfo]) -> Result<String> {
+    let rows: Vec<_> = transactions
+        .iter()
+        .map(|tx| {
+            json!({
+                "transaction_id": tx.id,
+                "timestamp": rfc3339(tx.timestamp),
+                "type": tx.tx_type,
+                "reference": tx.reference,
+                "entries": tx.entries.iter().map(|(account_id, amount)| json!({
+                    "account_id": account_id,
+                    "amount": grpc::format_balance(*amount),
+                    "amount_cents": amount,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&rows)?)
+}
+
+fn to_csv(transactions: &[TransactionInfo]) -> String {
+    let mut out = String::from("transaction_id,timestamp,type,reference,account_id,amount_cents\n");
+    for tx in transactions {
+        for (account_id, amount) in &tx.entries {
+            let fields = [
+                csv_field(&tx.id),
+                csv_field(&rfc3339(tx.timestamp)),
+                csv_field(&tx.tx_type),
+                csv_field(&tx.reference),
+                csv_field(account_id),
+                amount.to_string(),
+            ];
+            out.push_str(&fields.join(","));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Quote a field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Timestamp in milliseconds as RFC 3339, empty when unset
+fn rfc3339(millis: i64) -> String {
+    match chrono::DateTime::from_timestamp_millis(millis) {
+        Some(datetime) if millis > 0 => datetime.to_rfc3339(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_has_one_row_per_entry_with_quoting() {
+        let tx = TransactionInfo {
+            id: "tx1".to_string(),
+            tx_type: "transfer".to_string(),
+            entries: vec![
+                ("salon_glamour:operating".to_string(), -12_500),
+                ("schampo_etc:operating".to_string(), 12_500),
+            ],
+            reference: "Invoice 42, \"rush\"".to_string(),
+            timestamp: 0,
+        };
+
+        let csv = to_csv(&[tx]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "tx1,,transfer,\"Invoice 42, \"\"rush\"\"\",salon_glamour:operating,-12500"
+        );
+        assert!(lines[2].ends_with(",schampo_etc:operating,12500"));
+    }
+}
@@ -0,0 +1,73 @@
+//! Keybinding Reference
+//!
+//! This module lists the keyboard shortcuts shown in the help overlay (`?`).
+//! The remappable actions come first, read from the active `Keymap` so the
+//! overlay shows the keys configured in `[keys]`. The fixed keys follow,
+//! grouped into sections straight from `FIXED_BINDINGS`, the table `run_app`
+//! dispatches them from, so a key can't be handled without being listed.
+
+use super::keymap::{keys_label, Action, Keymap, FIXED_BINDINGS};
+
+/// Keys and description of each remappable action, in `Action::ALL` order
+pub fn action_bindings(keymap: &Keymap) -> Vec<(String, &'static str)> {
+    Action::ALL
+        .iter()
+        .map(|&action| (keymap.labels(action), action.description()))
+        .collect()
+}
+
+pub struct HelpSection {
+    pub title: &'static str,
+    pub bindings: Vec<(String, &'static str)>,
+}
+
+/// Mouse input isn't a key binding, so it is listed here by hand
+const MOUSE_BINDINGS: &[(&str, &str)] = &[
+    ("Click tab", "Switch to that tab"),
+    ("Click breadcrumb", "Go up to that level"),
+    ("Click row", "Select participant, account or transaction"),
+    ("Double-click", "Open participant details"),
+    ("Wheel", "Move selection (not in forms)"),
+];
+
+/// The fixed bindings by section, in the order the sections first appear in
+/// `FIXED_BINDINGS`, followed by the mouse
+pub fn help_sections() -> Vec<HelpSection> {
+    let mut sections: Vec<HelpSection> = Vec::new();
+    for binding in FIXED_BINDINGS {
+        let row = (keys_label(binding.keys), binding.description);
+        match sections.iter_mut().find(|s| s.title == binding.section) {
+            Some(section) => section.bindings.push(row),
+            None => sections.push(HelpSection {
+                title: binding.section,
+                bindings: vec![row],
+            }),
+        }
+    }
+    sections.push(HelpSection {
+        title: "Mouse",
+        bindings: MOUSE_BINDINGS
+            .iter()
+            .map(|&(keys, action)| (keys.to_string(), action))
+            .collect(),
+    });
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn action_rows_follow_the_configured_keys() {
+        let rows = action_bindings(&Keymap::default());
+        assert_eq!(rows.len(), Action::ALL.len());
+        assert_eq!(rows[0], ("q".to_string(), Action::Quit.description()));
+        assert_eq!(rows[1].0, "Tab/→");
+
+        let keys = BTreeMap::from([("quit".to_string(), "ctrl+q F10".to_string())]);
+        let rows = action_bindings(&Keymap::from_config(Some(&keys)).unwrap());
+        assert_eq!(rows[0].0, "^Q/F10");
+    }
+}
@@ -0,0 +1,479 @@
+//! Key Bindings
+//!
+//! The keys for the main actions (quit, switching tabs, selecting, running a
+//! form, refreshing and going back) can be changed in the `[keys]` table of
+//! `scalegraph.toml`. Each entry names an action and lists its keys separated
+//! by spaces; an action left out keeps its default keys:
+//!
+//! ```toml
+//! [keys]
+//! quit = "ctrl+q"          # 'q' no longer quits
+//! next_view = "tab l"
+//! prev_view = "shift+tab h"
+//! ```
+//!
+//! Other keys (list navigation, form editing, view-specific letters) are
+//! fixed. They are listed once, in `FIXED_BINDINGS`: `run_app` runs the
+//! `Command` of the first row for a pressed key that applies in the current
+//! view, and the help overlay is drawn from the same rows.
+
+use super::nav::Motion;
+use anyhow::{bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    NextView,
+    PrevView,
+    /// Open the selected participant, fold the selected account group
+    Select,
+    /// Submit the Transfer, Loan or Access Payment form
+    Execute,
+    Refresh,
+    /// Up one breadcrumb level
+    Back,
+}
+
+impl Action {
+    pub const ALL: [Action; 7] = [
+        Action::Quit,
+        Action::NextView,
+        Action::PrevView,
+        Action::Select,
+        Action::Execute,
+        Action::Refresh,
+        Action::Back,
+    ];
+
+    /// Name used in the `[keys]` table
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::NextView => "next_view",
+            Action::PrevView => "prev_view",
+            Action::Select => "select",
+            Action::Execute => "execute",
+            Action::Refresh => "refresh",
+            Action::Back => "back",
+        }
+    }
+
+    /// What the action does, for the help overlay
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit (also in forms; Ctrl+C always quits)",
+            Action::NextView => "Next tab (Tab cycles suggestions in account fields)",
+            Action::PrevView => "Previous tab",
+            Action::Select => "Open participant details, collapse / expand account group",
+            Action::Execute => "Submit the Transfer, Loan or Access Payment form, or accept a suggestion",
+            Action::Refresh => "Refresh data (not in forms)",
+            Action::Back => "Up one breadcrumb level",
+        }
+    }
+
+    fn default_keys(self) -> &'static str {
+        match self {
+            Action::Quit => "q",
+            Action::NextView => "tab right",
+            Action::PrevView => "shift+tab left",
+            Action::Select | Action::Execute => "enter",
+            Action::Refresh => "r",
+            Action::Back => "b",
+        }
+    }
+}
+
+/// What a fixed key does; see `FIXED_BINDINGS`. Commands are only built
+/// from that table, so one left out of it is reported as never constructed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Quit from anywhere, popups included
+    ForceQuit,
+    ToggleHelp,
+    /// Clear the form in form views, quit elsewhere
+    Escape,
+    /// Number key: jump to that breadcrumb level in a drilled-down view
+    JumpToBreadcrumb,
+    /// Number key: switch to that tab
+    SwitchTab,
+    Reconnect,
+    CopyId,
+    CycleTheme,
+    /// Tab in a Transfer account field or on the Loan action field
+    EditFormField,
+    SaveTemplate,
+    LoadTemplate,
+    DeleteChar,
+    /// Down a row, or to the next form field
+    Down,
+    /// Up a row, or to the previous form field
+    Up,
+    /// The first 'g' of 'gg'
+    PendingTop,
+    Navigate(Motion),
+    PageParticipants,
+    ScrollDetail,
+    /// Start a transfer from ('f') or to ('t') the selected account
+    TransferWithAccount,
+    EditContact,
+    NewInvoice,
+    ToggleGroup,
+    CycleAccountType,
+    ReverseTransaction,
+    Export,
+    CycleHistoryType,
+    ToggleTimeline,
+}
+
+impl Command {
+    /// Whether the command is tried before the remappable actions, so Tab
+    /// edits a form field instead of switching tabs
+    pub fn precedes_actions(self) -> bool {
+        self == Command::EditFormField
+    }
+}
+
+/// A row of `FIXED_BINDINGS`
+pub struct FixedBinding {
+    /// Help overlay section the row is listed under
+    pub section: &'static str,
+    /// Space-separated keys, written as in `[keys]`
+    pub keys: &'static str,
+    pub command: Command,
+    pub description: &'static str,
+}
+
+const fn fixed(
+    section: &'static str,
+    keys: &'static str,
+    command: Command,
+    description: &'static str,
+) -> FixedBinding {
+    FixedBinding {
+        section,
+        keys,
+        command,
+        description,
+    }
+}
+
+/// Every key handled outside the popups besides the remappable actions and
+/// typing into form fields. Rows sharing a key apply in different views;
+/// the first that applies wins.
+pub const FIXED_BINDINGS: &[FixedBinding] = &[
+    fixed("Global", "ctrl+c", Command::ForceQuit, "Quit"),
+    fixed("Global", "? f1", Command::ToggleHelp, "Toggle this help (F1 also works in forms)"),
+    fixed("Global", "esc", Command::Escape, "Close help, clear forms, quit elsewhere"),
+    fixed(
+        "Global",
+        "1 2 3 4 5 6 7 8 9",
+        Command::JumpToBreadcrumb,
+        "Jump to breadcrumb level in a drilled-down view (1 is the root)",
+    ),
+    fixed("Global", "1 2 3 4 5 6 7 8", Command::SwitchTab, "Jump to tab (not in forms)"),
+    fixed("Global", "R", Command::Reconnect, "Reconnect to the server (not in forms)"),
+    fixed("Global", "y", Command::CopyId, "Copy selected id to clipboard (not in forms)"),
+    fixed("Global", "T", Command::CycleTheme, "Cycle color theme (not in forms)"),
+    fixed("Lists", "down j", Command::Down, "Move selection (next field in forms)"),
+    fixed("Lists", "up k", Command::Up, "Move selection (previous field in forms)"),
+    fixed("Lists", "g", Command::PendingTop, "First row (press twice)"),
+    fixed("Lists", "home", Command::Navigate(Motion::Top), "First row"),
+    fixed("Lists", "G end", Command::Navigate(Motion::Bottom), "Last row"),
+    fixed("Lists", "ctrl+d", Command::Navigate(Motion::HalfPageDown), "Half a page down"),
+    fixed("Lists", "ctrl+u", Command::Navigate(Motion::HalfPageUp), "Half a page up"),
+    fixed("Lists", "ctrl+f", Command::Navigate(Motion::PageDown), "A page down"),
+    fixed("Lists", "ctrl+b", Command::Navigate(Motion::PageUp), "A page up"),
+    fixed("Participants", "pageup pagedown", Command::PageParticipants, "Previous / next page of 50"),
+    fixed("Participant Details", "pageup pagedown", Command::ScrollDetail, "Scroll About / Contact"),
+    fixed("Participant Details", "f t", Command::TransferWithAccount, "Transfer from / to selected account"),
+    fixed("Participant Details", "c", Command::EditContact, "Edit contact information"),
+    fixed("Participant Details", "i", Command::NewInvoice, "Create an invoice from this participant"),
+    fixed(
+        "Forms",
+        "tab backtab",
+        Command::EditFormField,
+        "Cycle account suggestions (Transfer), switch Create / Repay (Loan Action field, Space too)",
+    ),
+    fixed("Forms", "backspace", Command::DeleteChar, "Delete last character"),
+    fixed("Forms", "ctrl+s", Command::SaveTemplate, "Save the Transfer form as a named template"),
+    fixed("Forms", "ctrl+o", Command::LoadTemplate, "Load a saved Transfer template (d deletes)"),
+    fixed("History", "u", Command::ReverseTransaction, "Reverse selected transaction (press twice)"),
+    fixed("History", "t", Command::CycleHistoryType, "Cycle transaction type filter (All, transfer, invoice, ...)"),
+    fixed("History", "e", Command::Export, "Export listed transactions to CSV or JSON"),
+    fixed("Future", "v", Command::ToggleTimeline, "Switch between the list and a 90-day timeline"),
+    fixed("Accounts", "space", Command::ToggleGroup, "Collapse / expand participant group"),
+    fixed("Accounts", "t", Command::CycleAccountType, "Cycle account type filter"),
+];
+
+/// Commands of the fixed bindings for `key`, in table order
+pub fn fixed_commands(key: &KeyEvent) -> Vec<Command> {
+    FIXED_BINDINGS
+        .iter()
+        .filter(|row| {
+            row.keys
+                .split_whitespace()
+                .any(|spec| KeyBinding::parse(spec).is_ok_and(|binding| binding.matches(key)))
+        })
+        .map(|row| row.command)
+        .collect()
+}
+
+/// `keys` (as in `FixedBinding::keys`) for the help overlay, with runs of
+/// number keys shortened to a range: `1-8`, `↓/j`
+pub fn keys_label(keys: &str) -> String {
+    let labels: Vec<String> = keys
+        .split_whitespace()
+        .filter_map(|spec| KeyBinding::parse(spec).ok())
+        .map(|binding| binding.to_string())
+        .collect();
+    let digits: Vec<u32> = labels.iter().filter_map(|l| l.parse().ok().filter(|d| *d < 10)).collect();
+    if digits.len() > 2 && digits.len() == labels.len() && digits.windows(2).all(|w| w[1] == w[0] + 1) {
+        return format!("{}-{}", digits[0], digits[digits.len() - 1]);
+    }
+    labels.join("/")
+}
+
+/// A key plus the Ctrl/Alt modifiers that must be held. Shift is part of
+/// the key itself (`Q`, `shift+tab`), since terminals report it unevenly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+const MATCHED_MODIFIERS: KeyModifiers = KeyModifiers::CONTROL.union(KeyModifiers::ALT);
+
+impl KeyBinding {
+    /// Parse `q`, `Q`, `ctrl+q`, `alt+x`, `tab`, `shift+tab`, `enter`, `f5`, ...
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut shift = false;
+        let mut rest = spec;
+        while let Some((modifier, key)) = rest.split_once('+').filter(|(_, key)| !key.is_empty()) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" => modifiers |= KeyModifiers::ALT,
+                "shift" => shift = true,
+                _ => bail!("unknown modifier '{}' in key '{}'", modifier, spec),
+            }
+            rest = key;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "tab" if shift => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => bail!("unknown key '{}'", spec),
+                },
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        self.code == key.code && self.modifiers == key.modifiers & MATCHED_MODIFIERS
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("^")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("M-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::BackTab => f.write_str("S-Tab"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(None).expect("default key bindings parse")
+    }
+}
+
+impl Keymap {
+    /// Defaults with the actions named in `keys` rebound
+    pub fn from_config(keys: Option<&BTreeMap<String, String>>) -> Result<Self> {
+        if let Some(keys) = keys {
+            if let Some(unknown) = keys.keys().find(|name| !Action::ALL.iter().any(|a| a.name() == *name)) {
+                let names: Vec<&str> = Action::ALL.iter().map(|a| a.name()).collect();
+                bail!("unknown action '{}' in [keys]; expected one of: {}", unknown, names.join(", "));
+            }
+        }
+        let mut bindings = Vec::new();
+        for action in Action::ALL {
+            let specs = keys
+                .and_then(|keys| keys.get(action.name()))
+                .map(String::as_str)
+                .unwrap_or(action.default_keys());
+            if specs.split_whitespace().next().is_none() {
+                bail!("no keys given for '{}' in [keys]", action.name());
+            }
+            for spec in specs.split_whitespace() {
+                bindings.push((KeyBinding::parse(spec)?, action));
+            }
+        }
+        Ok(Self { bindings })
+    }
+
+    /// Actions bound to `key`, in `Action::ALL` order. One key can serve
+    /// several actions (Enter both selects and executes); the caller runs the
+    /// first that applies in the current view.
+    pub fn actions(&self, key: &KeyEvent) -> Vec<Action> {
+        self.bindings
+            .iter()
+            .filter(|(binding, _)| binding.matches(key))
+            .map(|(_, action)| *action)
+            .collect()
+    }
+
+    pub fn is(&self, key: &KeyEvent, action: Action) -> bool {
+        self.bindings
+            .iter()
+            .any(|(binding, bound)| *bound == action && binding.matches(key))
+    }
+
+    /// First key bound to `action`, for the status bar hints
+    pub fn label(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| *bound == action)
+            .map(|(binding, _)| binding.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Every key bound to `action`, for the help overlay
+    pub fn labels(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(binding, _)| binding.to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn defaults_match_the_built_in_keys() {
+        let keymap = Keymap::default();
+        let none = KeyModifiers::NONE;
+        assert_eq!(keymap.actions(&key(KeyCode::Char('q'), none)), [Action::Quit]);
+        assert_eq!(keymap.actions(&key(KeyCode::Right, none)), [Action::NextView]);
+        assert_eq!(keymap.actions(&key(KeyCode::BackTab, KeyModifiers::SHIFT)), [Action::PrevView]);
+        assert_eq!(keymap.actions(&key(KeyCode::Enter, none)), [Action::Select, Action::Execute]);
+        // Ctrl+B pages up; only a plain 'b' goes back
+        assert!(keymap.actions(&key(KeyCode::Char('b'), KeyModifiers::CONTROL)).is_empty());
+        assert_eq!(keymap.label(Action::Quit), "q");
+    }
+
+    #[test]
+    fn configured_actions_replace_their_defaults_only() {
+        let keys: BTreeMap<String, String> = [("quit", "ctrl+q"), ("next_view", "tab L")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let keymap = Keymap::from_config(Some(&keys)).unwrap();
+        assert!(keymap.actions(&key(KeyCode::Char('q'), KeyModifiers::NONE)).is_empty());
+        assert!(keymap.is(&key(KeyCode::Char('q'), KeyModifiers::CONTROL), Action::Quit));
+        assert!(keymap.is(&key(KeyCode::Char('L'), KeyModifiers::SHIFT), Action::NextView));
+        assert!(!keymap.is(&key(KeyCode::Right, KeyModifiers::NONE), Action::NextView));
+        assert!(keymap.is(&key(KeyCode::Char('r'), KeyModifiers::NONE), Action::Refresh));
+        assert_eq!(keymap.label(Action::Quit), "^Q");
+    }
+
+    #[test]
+    fn fixed_keys_find_their_commands() {
+        let none = KeyModifiers::NONE;
+        assert_eq!(fixed_commands(&key(KeyCode::Char('j'), none)), [Command::Down]);
+        assert_eq!(
+            fixed_commands(&key(KeyCode::Char('t'), none)),
+            [Command::TransferWithAccount, Command::CycleHistoryType, Command::CycleAccountType]
+        );
+        assert_eq!(
+            fixed_commands(&key(KeyCode::Char('2'), none)),
+            [Command::JumpToBreadcrumb, Command::SwitchTab]
+        );
+        assert_eq!(fixed_commands(&key(KeyCode::Char('9'), none)), [Command::JumpToBreadcrumb]);
+        // Shift is part of the key, so 'G' matches however the terminal reports it
+        assert_eq!(
+            fixed_commands(&key(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            [Command::Navigate(Motion::Bottom)]
+        );
+        assert_eq!(
+            fixed_commands(&key(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            [Command::Navigate(Motion::HalfPageDown)]
+        );
+        assert!(fixed_commands(&key(KeyCode::Char('d'), none)).is_empty());
+    }
+
+    #[test]
+    fn fixed_rows_parse_and_label_their_keys() {
+        for row in FIXED_BINDINGS {
+            for spec in row.keys.split_whitespace() {
+                assert!(KeyBinding::parse(spec).is_ok(), "{:?} in {:?}", spec, row.command);
+            }
+        }
+        assert_eq!(keys_label("1 2 3 4 5 6 7 8"), "1-8");
+        assert_eq!(keys_label("down j"), "↓/j");
+        assert_eq!(keys_label("G end"), "G/End");
+        assert_eq!(keys_label("ctrl+d"), "^D");
+    }
+
+    #[test]
+    fn bad_entries_are_rejected() {
+        for (action, keys) in [("quti", "q"), ("quit", "ctrl+"), ("quit", "hyper+q"), ("quit", "f13"), ("back", " ")] {
+            let config = BTreeMap::from([(action.to_string(), keys.to_string())]);
+            assert!(Keymap::from_config(Some(&config)).is_err(), "{} = {:?}", action, keys);
+        }
+        assert_eq!(KeyBinding::parse("+").unwrap().code, KeyCode::Char('+'));
+        assert_eq!(KeyBinding::parse("shift+q").unwrap().code, KeyCode::Char('Q'));
+    }
+}
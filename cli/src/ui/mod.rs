@@ -3,12 +3,29 @@
 //! This module exports the UI components for the Scalegraph CLI:
 //!
 //! - `app`: Application state and event loop
+//! - `export`: Writing the History view's transactions to CSV or JSON
+//! - `help`: Help overlay sections built from the key bindings
+//! - `keymap`: Remappable keys for the main actions (`[keys]` in scalegraph.toml) and the fixed key table
+//! - `nav`: Top/bottom and page jumps shared by the list views
+//! - `state`: View, participant and theme remembered between runs
+//! - `theme`: Color palettes (dark, light, high-contrast)
+//! - `timeline`: Axis layout for the Future view's 90-day timeline
 //! - `views`: Rendering functions for all UI views
 //!
 //! The UI module provides a complete terminal interface for interacting
 //! with the Scalegraph ledger system.
 
 mod app;
+mod export;
+mod help;
+mod keymap;
+mod nav;
+mod state;
+mod theme;
+mod timeline;
 mod views;
 
 pub use app::{run_app, App};
+pub use keymap::Keymap;
+pub use state::TuiState;
+pub use theme::ThemeName;
@@ -0,0 +1,71 @@
+//! List Navigation
+//!
+//! Vim-style jumps shared by every list view: top and bottom (`gg` / `G`,
+//! Home / End), half pages (Ctrl+D / Ctrl+U) and full pages (Ctrl+F / Ctrl+B).
+//! Each view only supplies its `ListState` and row count.
+
+use ratatui::widgets::ListState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Top,
+    Bottom,
+    HalfPageDown,
+    HalfPageUp,
+    PageDown,
+    PageUp,
+}
+
+/// Move the selection of a `len`-row list by `motion`, where a page is
+/// `page` rows. The selection stays within the list; an empty list is left
+/// alone.
+pub fn apply(state: &mut ListState, len: usize, motion: Motion, page: usize) {
+    if len == 0 {
+        return;
+    }
+    let last = len - 1;
+    let current = state.selected().unwrap_or(0).min(last);
+    let half = (page / 2).max(1);
+    let page = page.max(1);
+    let target = match motion {
+        Motion::Top => 0,
+        Motion::Bottom => last,
+        Motion::HalfPageDown => current.saturating_add(half).min(last),
+        Motion::HalfPageUp => current.saturating_sub(half),
+        Motion::PageDown => current.saturating_add(page).min(last),
+        Motion::PageUp => current.saturating_sub(page),
+    };
+    state.select(Some(target));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn motions_stay_within_the_list() {
+        let mut state = ListState::default();
+        state.select(Some(3));
+
+        apply(&mut state, 30, Motion::HalfPageDown, 10);
+        assert_eq!(state.selected(), Some(8));
+        apply(&mut state, 30, Motion::PageDown, 10);
+        assert_eq!(state.selected(), Some(18));
+        apply(&mut state, 30, Motion::PageDown, 20);
+        assert_eq!(state.selected(), Some(29));
+        apply(&mut state, 30, Motion::HalfPageUp, 1);
+        assert_eq!(state.selected(), Some(28));
+        apply(&mut state, 30, Motion::Top, 10);
+        assert_eq!(state.selected(), Some(0));
+        apply(&mut state, 30, Motion::PageUp, 10);
+        assert_eq!(state.selected(), Some(0));
+        apply(&mut state, 30, Motion::Bottom, 10);
+        assert_eq!(state.selected(), Some(29));
+
+        // A stale selection past a shrunken list is clamped first
+        apply(&mut state, 5, Motion::HalfPageUp, 4);
+        assert_eq!(state.selected(), Some(2));
+        apply(&mut state, 0, Motion::Bottom, 10);
+        assert_eq!(state.selected(), Some(2));
+    }
+}
@@ -0,0 +1,43 @@
+//! Persisted TUI State
+//!
+//! The view, selected participant and theme are written to `tui_state.json`
+//! (under the user's config directory, see `paths`) when the TUI quits, and
+//! restored on the next launch. The file is a convenience: a missing or
+//! unreadable one just means starting from the defaults.
+
+use super::app::View;
+use super::theme::ThemeName;
+use crate::paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const STATE_FILE: &str = "tui_state.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TuiState {
+    pub view: Option<View>,
+    pub participant_index: Option<usize>,
+    /// Preferred over the index, which shifts when participants are added
+    pub participant_id: Option<String>,
+    pub theme: Option<ThemeName>,
+}
+
+impl TuiState {
+    pub fn load() -> Self {
+        paths::config_file(STATE_FILE)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = paths::config_file(STATE_FILE)
+            .context("no config directory (set HOME or XDG_CONFIG_HOME)")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("cannot write {}", path.display()))
+    }
+}
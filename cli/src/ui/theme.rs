@@ -0,0 +1,168 @@
+//! Color Themes
+//!
+//! This module defines the color palettes used by the TUI. Views never use
+//! literal colors; they look up the semantic role (accent, dim, positive, ...)
+//! on the active `Theme`, so switching palettes restyles the whole UI.
+//!
+//! Available themes:
+//! - `dark`: the default palette, tuned for dark terminal backgrounds
+//! - `light`: darker foreground colors for light terminal backgrounds
+//! - `high-contrast`: bright, strongly separated colors for low-vision users
+//!
+//! With `--no-color` or `NO_COLOR` set, any theme is drawn without colors:
+//! the selection is shown reversed and emphasis uses bold and underline.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use ratatui::style::{Color, Modifier, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemeName {
+    pub fn next(self) -> ThemeName {
+        match self {
+            ThemeName::Dark => ThemeName::Light,
+            ThemeName::Light => ThemeName::HighContrast,
+            ThemeName::HighContrast => ThemeName::Dark,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeName::Dark => "dark",
+            ThemeName::Light => "light",
+            ThemeName::HighContrast => "high-contrast",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub name: ThemeName,
+    /// Titles, headers, borders of focused panels, the active tab
+    pub accent: Color,
+    /// Regular body text
+    pub text: Color,
+    /// Hints, separators, secondary information
+    pub dim: Color,
+    /// Inactive tabs and unfocused input text
+    pub inactive: Color,
+    /// Roles, account types, key names
+    pub info: Color,
+    /// Positive balances, services, success messages
+    pub positive: Color,
+    /// Negative balances, errors
+    pub negative: Color,
+    /// Secondary panel borders (e.g. smart contracts)
+    pub highlight: Color,
+    /// Background of the selected row
+    pub selection_bg: Color,
+    /// Text on the selected row
+    pub selection_fg: Color,
+    /// Every color is the terminal default (see `no_color`)
+    pub monochrome: bool,
+}
+
+impl Theme {
+    pub fn new(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Self {
+                name,
+                accent: Color::Yellow,
+                text: Color::White,
+                dim: Color::DarkGray,
+                inactive: Color::Gray,
+                info: Color::Cyan,
+                positive: Color::Green,
+                negative: Color::Red,
+                highlight: Color::Magenta,
+                selection_bg: Color::Blue,
+                selection_fg: Color::White,
+                monochrome: false,
+            },
+            ThemeName::Light => Self {
+                name,
+                accent: Color::Blue,
+                text: Color::Black,
+                dim: Color::DarkGray,
+                inactive: Color::DarkGray,
+                info: Color::Magenta,
+                positive: Color::Green,
+                negative: Color::Red,
+                highlight: Color::Cyan,
+                selection_bg: Color::LightBlue,
+                selection_fg: Color::Black,
+                monochrome: false,
+            },
+            ThemeName::HighContrast => Self {
+                name,
+                accent: Color::LightYellow,
+                text: Color::White,
+                dim: Color::Gray,
+                inactive: Color::White,
+                info: Color::LightCyan,
+                positive: Color::LightGreen,
+                negative: Color::LightRed,
+                highlight: Color::LightMagenta,
+                selection_bg: Color::White,
+                selection_fg: Color::Black,
+                monochrome: false,
+            },
+        }
+    }
+
+    /// The same theme with every color replaced by the terminal default
+    pub fn no_color(self) -> Self {
+        Self {
+            name: self.name,
+            accent: Color::Reset,
+            text: Color::Reset,
+            dim: Color::Reset,
+            inactive: Color::Reset,
+            info: Color::Reset,
+            positive: Color::Reset,
+            negative: Color::Reset,
+            highlight: Color::Reset,
+            selection_bg: Color::Reset,
+            selection_fg: Color::Reset,
+            monochrome: true,
+        }
+    }
+
+    /// Style of the selected row; reversed when there are no colors to show it
+    pub fn selected(&self) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().bg(self.selection_bg).fg(self.selection_fg)
+        }
+    }
+
+    /// Modifier for text that must stand out, such as the active tab or an
+    /// error; underlined too when color can't carry the difference
+    pub fn emphasis(&self) -> Modifier {
+        if self.monochrome {
+            Modifier::BOLD | Modifier::UNDERLINED
+        } else {
+            Modifier::BOLD
+        }
+    }
+
+    /// Switch to the next theme in the cycle
+    pub fn cycle(&mut self) {
+        let next = Theme::new(self.name.next());
+        *self = if self.monochrome { next.no_color() } else { next };
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new(ThemeName::Dark)
+    }
+}
@@ -0,0 +1,123 @@
+//! Contract Timeline
+//!
+//! Layout for the Future view's timeline mode ('v'): upcoming executions are
+//! placed on a horizontal axis covering the next `HORIZON_DAYS`, one lane per
+//! contract type, so clusters of obligations stand out. The axis is scaled to
+//! whatever width the terminal gives it.
+
+use super::app::FutureEvent;
+
+/// Days covered by the timeline, starting today
+pub const HORIZON_DAYS: i64 = 90;
+
+pub const DAY_MS: i64 = 86_400_000;
+
+/// Executions of one contract type, counted per axis column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lane {
+    pub label: String,
+    pub cells: Vec<usize>,
+}
+
+/// Column of `at` on a `width`-column axis from `now` to the horizon, or
+/// None if it falls outside
+pub fn column(at: i64, now: i64, width: usize) -> Option<usize> {
+    let span = HORIZON_DAYS * DAY_MS;
+    if width == 0 || at < now || at > now + span {
+        return None;
+    }
+    let last = width as i64 - 1;
+    Some(((at - now) * last / span) as usize)
+}
+
+/// One lane per contract type, in order of each type's first execution
+pub fn lanes(events: &[FutureEvent], now: i64, width: usize) -> Vec<Lane> {
+    let mut lanes: Vec<Lane> = Vec::new();
+    for event in events {
+        let Some(col) = column(event.execution_time, now, width) else {
+            continue;
+        };
+        let index = match lanes.iter().position(|l| l.label == event.contract_type) {
+            Some(index) => index,
+            None => {
+                lanes.push(Lane {
+                    label: event.contract_type.clone(),
+                    cells: vec![0; width],
+                });
+                lanes.len() - 1
+            }
+        };
+        lanes[index].cells[col] += 1;
+    }
+    lanes
+}
+
+/// The 7-day window, starting at an execution, that holds the most
+/// executions: its start and count. Earliest wins a tie.
+pub fn busiest_week(events: &[FutureEvent]) -> Option<(i64, usize)> {
+    let mut times: Vec<i64> = events.iter().map(|e| e.execution_time).collect();
+    times.sort_unstable();
+    let mut best: Option<(i64, usize)> = None;
+    for (i, start) in times.iter().enumerate() {
+        let count = times[i..].iter().take_while(|t| **t < start + 7 * DAY_MS).count();
+        if best.is_none_or(|(_, most)| count > most) {
+            best = Some((*start, count));
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(contract_type: &str, execution_time: i64) -> FutureEvent {
+        FutureEvent {
+            contract_id: format!("{}-{}", contract_type, execution_time),
+            contract_type: contract_type.to_string(),
+            description: String::new(),
+            execution_time,
+        }
+    }
+
+    #[test]
+    fn the_axis_spans_today_to_the_horizon() {
+        let now = 1_000;
+        assert_eq!(column(now, now, 91), Some(0));
+        assert_eq!(column(now + 45 * DAY_MS, now, 91), Some(45));
+        assert_eq!(column(now + HORIZON_DAYS * DAY_MS, now, 91), Some(90));
+        assert_eq!(column(now - 1, now, 91), None);
+        assert_eq!(column(now + HORIZON_DAYS * DAY_MS + 1, now, 91), None);
+        assert_eq!(column(now, now, 0), None);
+    }
+
+    #[test]
+    fn lanes_count_executions_per_type_and_column() {
+        let now = 0;
+        let events = [
+            event("Invoice", DAY_MS),
+            event("Subscription", 2 * DAY_MS),
+            event("Invoice", DAY_MS + 1),
+            event("Invoice", 100 * DAY_MS),
+        ];
+        let lanes = lanes(&events, now, 91);
+        assert_eq!(lanes.len(), 2);
+        assert_eq!(lanes[0].label, "Invoice");
+        assert_eq!(lanes[0].cells[1], 2);
+        assert_eq!(lanes[0].cells.iter().sum::<usize>(), 2);
+        assert_eq!(lanes[1].cells[2], 1);
+    }
+
+    #[test]
+    fn busiest_week_finds_the_densest_cluster() {
+        let events = [
+            event("Invoice", 0),
+            event("Invoice", 20 * DAY_MS),
+            event("Subscription", 22 * DAY_MS),
+            event("Invoice", 26 * DAY_MS),
+            event("Invoice", 30 * DAY_MS),
+        ];
+        assert_eq!(busiest_week(&events), Some((20 * DAY_MS, 3)));
+        assert_eq!(busiest_week(&[]), None);
+    }
+}
@@ -5,25 +5,62 @@
 //!
 //! - Participants list with services
 //! - Accounts table with balances
-//! - Transfer form with account suggestions
+//! - Transfer form with account suggestions and saved templates
+//! - Loan form for creating and repaying loans with interest terms
 //! - Transaction history
 //! - Status bar and navigation tabs
+//! - Help overlay listing all keybindings
 //!
 //! All rendering functions use the `ratatui` library to create the terminal UI.
 //! The functions are organized by view type and handle layout, styling, and
 //! user interaction feedback.
 
-use super::app::{App, View};
+use super::app::{
+    is_credit_normal, AccessPaymentField, AccountRow, App, ClickMap, ConnectionStatus, ContactForm, FutureEvent,
+    InvoiceField, InvoiceForm, LoadState, LoanAction, LoanField, OverviewStats, TemplatePopup, View, ACCESS_PAYMENT_FIELDS,
+    CONTACT_FIELDS, CONTRACT_LIST_LIMIT, INVOICE_FIELDS, PARTICIPANT_PAGE_SIZE,
+};
+use super::help::{action_bindings, help_sections};
+use super::keymap::{Action, Keymap};
+use super::timeline;
+use super::theme::Theme;
 use crate::grpc;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Table, Tabs, Wrap},
+    widgets::{
+        Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Sparkline, Table, TableState, Tabs,
+        Wrap,
+    },
     Frame,
 };
 
+/// Smallest terminal the normal layout renders sensibly in
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 10;
+
 pub fn draw(f: &mut Frame, app: &mut App) {
+    // Views that have clickable rows record them while drawing
+    app.click_map = ClickMap::default();
+
+    let area = f.area();
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        draw_too_small(f, app.theme, area);
+    } else {
+        draw_screen(f, app, area);
+    }
+
+    // Catches colors not taken from the theme, such as widget defaults
+    if app.theme.monochrome {
+        for cell in f.buffer_mut().content.iter_mut() {
+            cell.set_fg(Color::Reset).set_bg(Color::Reset);
+        }
+    }
+}
+
+fn draw_screen(f: &mut Frame, app: &mut App, area: Rect) {
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -32,15 +69,58 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             Constraint::Min(0),    // Main content
             Constraint::Length(3), // Status bar
         ])
-        .split(f.area());
+        .split(area);
 
     draw_tabs(f, app, chunks[0]);
     draw_breadcrumb(f, app, chunks[1]);
     draw_main(f, app, chunks[2]);
     draw_status_bar(f, app, chunks[3]);
+
+    if let Some(ref popup) = app.template_popup {
+        draw_template_popup(f, app, popup, area);
+    }
+    if let Some(ref path) = app.export_prompt {
+        draw_export_prompt(f, app.theme, path, area);
+    }
+    if let Some(ref form) = app.contact_form {
+        draw_contact_form(f, app.theme, form, area);
+    }
+    if let Some(ref form) = app.invoice_form {
+        draw_invoice_form(f, app.theme, form, area);
+    }
+    if app.show_help {
+        draw_help_overlay(f, app.theme, &app.keymap, area);
+    }
+}
+
+/// Shown instead of the normal layout when the terminal is below the minimum size
+fn draw_too_small(f: &mut Frame, theme: Theme, area: Rect) {
+    let lines = vec![
+        Line::from(Span::styled(
+            "Terminal too small",
+            Style::default()
+                .fg(theme.negative)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("{}x{}, need {}x{}", area.width, area.height, MIN_WIDTH, MIN_HEIGHT),
+            Style::default().fg(theme.dim),
+        )),
+    ];
+    let top = area.height.saturating_sub(lines.len() as u16) / 2;
+    let centered = Rect {
+        y: area.y + top,
+        height: area.height.saturating_sub(top),
+        ..area
+    };
+    let message = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(message, centered);
 }
 
-fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
+fn draw_tabs(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
     let titles: Vec<Line> = View::all()
         .iter()
         .enumerate()
@@ -48,25 +128,38 @@ fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
             let num = format!("[{}] ", i + 1);
             let style = if *v == app.current_view {
                 Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
+                    .fg(theme.accent)
+                    .add_modifier(theme.emphasis())
             } else {
-                Style::default().fg(Color::Gray)
+                Style::default().fg(theme.inactive)
             };
             Line::from(vec![
-                Span::styled(num, Style::default().fg(Color::DarkGray)),
+                Span::styled(num, Style::default().fg(theme.dim)),
                 Span::styled(v.title(), style),
             ])
         })
         .collect();
 
+    // Mirror the Tabs layout (one space of padding each side, one-column
+    // divider) so clicks can be mapped back to a tab
+    let mut x = area.x + 1;
+    app.click_map.tabs = titles
+        .iter()
+        .map(|title| {
+            let width = title.width() as u16 + 2;
+            let tab = Rect::new(x, area.y, width, area.height).intersection(area);
+            x = x.saturating_add(width + 1);
+            tab
+        })
+        .collect();
+
     let tabs = Tabs::new(titles)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" Scalegraph Ledger  [←/→ or 1-4 to switch tabs] "),
+                .title(" Scalegraph Ledger  [←/→ or 1-8 to switch tabs] "),
         )
-        .highlight_style(Style::default().fg(Color::Yellow))
+        .highlight_style(Style::default().fg(theme.accent))
         .select(
             View::all()
                 .iter()
@@ -77,36 +170,45 @@ fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(tabs, area);
 }
 
-fn draw_breadcrumb(f: &mut Frame, app: &App, area: Rect) {
+fn draw_breadcrumb(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
     if app.breadcrumb.is_empty() {
         return;
     }
 
     let mut spans = Vec::new();
+    // Record each segment's position so it can be clicked
+    let mut x = area.x;
 
     for (i, segment) in app.breadcrumb.iter().enumerate() {
         if i > 0 {
-            spans.push(Span::styled(" > ", Style::default().fg(Color::DarkGray)));
+            let separator = Span::styled(" > ", Style::default().fg(theme.dim));
+            x = x.saturating_add(separator.width() as u16);
+            spans.push(separator);
         }
 
         let style = if i == app.breadcrumb.len() - 1 {
             // Current segment - highlighted
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD)
         } else {
             // Previous segments - navigable
-            Style::default().fg(Color::Cyan)
+            Style::default().fg(theme.info)
         };
 
-        spans.push(Span::styled(segment.label.clone(), style));
+        let label = Span::styled(segment.label.clone(), style);
+        let width = (label.width() as u16).min(area.right().saturating_sub(x));
+        app.click_map.breadcrumbs.push(Rect::new(x, area.y, width, 1));
+        x = x.saturating_add(label.width() as u16);
+        spans.push(label);
     }
 
     let line = Line::from(spans);
     let paragraph = Paragraph::new(line).block(
         Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::DarkGray)),
+            .border_style(Style::default().fg(theme.dim)),
     );
 
     f.render_widget(paragraph, area);
@@ -114,53 +216,173 @@ fn draw_breadcrumb(f: &mut Frame, app: &App, area: Rect) {
 
 fn draw_main(f: &mut Frame, app: &mut App, area: Rect) {
     match app.current_view {
+        View::Overview => draw_overview(f, app, area),
         View::Participants => draw_participants(f, app, area),
         View::ParticipantDetail => draw_participant_detail(f, app, area),
         View::Transfer => draw_transfer(f, app, area),
         View::History => draw_history(f, app, area),
         View::Future => draw_future(f, app, area),
+        View::Loan => draw_loan(f, app, area),
+        View::Accounts => draw_accounts(f, app, area),
+        View::AccessPayment => draw_access_payment(f, app, area),
+    }
+}
+
+/// One bar of a count chart: the label, a bar scaled against `max` to fit
+/// `width` cells, and the count
+fn count_bar(theme: Theme, label: &str, count: usize, max: usize, width: u16) -> Line<'static> {
+    const LABEL_WIDTH: usize = 30;
+    let room = (width as usize).saturating_sub(LABEL_WIDTH + 8).max(1);
+    let cells = if max == 0 { 0 } else { (count * room).div_ceil(max) };
+    Line::from(vec![
+        Span::styled(format!("  {:<width$}", label, width = LABEL_WIDTH - 2), Style::default().fg(theme.info)),
+        Span::styled("█".repeat(cells), Style::default().fg(theme.accent)),
+        Span::styled(format!(" {}", count), Style::default().fg(theme.text)),
+    ])
+}
+
+/// Landing view: participants by role, ledger totals and active contracts
+fn draw_overview(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let stats = OverviewStats::compute(&app.participants, &app.accounts);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(stats.roles.len().max(1) as u16 + 2), Constraint::Length(7), Constraint::Min(0)])
+        .split(area);
+    let panel = |title: String| {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(title)
+    };
+
+    let max_role = stats.roles.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    let role_lines: Vec<Line> = if stats.roles.is_empty() {
+        vec![Line::from(Span::styled("  No participants loaded. Press r to refresh.", Style::default().fg(theme.dim)))]
+    } else {
+        stats
+            .roles
+            .iter()
+            .map(|(role, count)| count_bar(theme, role, *count, max_role, chunks[0].width))
+            .collect()
+    };
+    f.render_widget(
+        Paragraph::new(role_lines).block(panel(format!(" Participants by Role ({}) ", stats.participants))),
+        chunks[0],
+    );
+
+    let net = stats.receivables - stats.payables;
+    let total = |label: &str, value: String, color| {
+        Line::from(vec![
+            Span::styled(format!("  {:<28}", label), Style::default().fg(theme.accent)),
+            Span::styled(value, Style::default().fg(color)),
+        ])
+    };
+    let ledger_lines = vec![
+        total("Accounts", stats.accounts.to_string(), theme.text),
+        total("Receivables (owed to)", grpc::format_balance(stats.receivables), theme.positive),
+        total("Payables (owed by)", grpc::format_balance(stats.payables), theme.highlight),
+        total(
+            "Net A/R − A/P",
+            grpc::format_balance(net),
+            if net >= 0 { theme.positive } else { theme.negative },
+        ),
+    ];
+    f.render_widget(Paragraph::new(ledger_lines).block(panel(" Ledger ".to_string())), chunks[1]);
+
+    let active: usize = app.active_contracts.iter().map(|(_, n)| n).sum();
+    let max_contracts = app.active_contracts.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    let mut contract_lines: Vec<Line> = app
+        .active_contracts
+        .iter()
+        .map(|(contract_type, count)| count_bar(theme, contract_type, *count, max_contracts, chunks[2].width))
+        .collect();
+    if contract_lines.is_empty() {
+        contract_lines.push(Line::from(Span::styled("  No active contracts.", Style::default().fg(theme.dim))));
     }
+    if app.contracts_truncated {
+        contract_lines.push(Line::raw(""));
+        contract_lines.push(Line::from(Span::styled(
+            format!("  Counted from the first {} contracts only", CONTRACT_LIST_LIMIT),
+            Style::default().fg(theme.dim),
+        )));
+    }
+    f.render_widget(
+        Paragraph::new(contract_lines).block(panel(format!(" Active Contracts by Type ({}) ", active))),
+        chunks[2],
+    );
 }
 
 fn draw_participants(f: &mut Frame, app: &mut App, area: Rect) {
+    if app.participants.is_empty() {
+        let empty = "No participants found. Is the server seeded?";
+        draw_empty_state(f, app, &app.participants_load, empty, " Participants ", area);
+        return;
+    }
+    let theme = app.theme;
     let selected_idx = app.participant_state.selected().unwrap_or(0);
     let total = app.participants.len();
 
-    let title = format!(" Participants ({}/{}) ", selected_idx + 1, total);
+    let overdrawn = app
+        .participants
+        .iter()
+        .filter(|p| p.overdrawn_by().is_some())
+        .count();
+    let (page, pages) = app.participant_page();
+    let page_label = if pages > 1 {
+        format!(" page {} of {} ", page + 1, pages)
+    } else {
+        " ".to_string()
+    };
+    let title = if overdrawn > 0 {
+        format!(
+            " Participants ({}/{}){}⚠ {} overdrawn ",
+            selected_idx + 1,
+            total,
+            page_label,
+            overdrawn
+        )
+    } else {
+        format!(" Participants ({}/{}){}", selected_idx + 1, total, page_label)
+    };
 
     let header = Row::new(vec![
         Cell::from("Name").style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Cell::from("Role").style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Cell::from("ID").style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Cell::from("Services").style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
     ])
     .height(1)
     .bottom_margin(1);
 
+    // Only the selected participant's page is drawn
+    let page_start = page * PARTICIPANT_PAGE_SIZE;
     let rows: Vec<Row> = app
         .participants
         .iter()
         .enumerate()
+        .skip(page_start)
+        .take(PARTICIPANT_PAGE_SIZE)
         .map(|(i, p)| {
             let selected = app.participant_state.selected() == Some(i);
             let row_style = if selected {
-                Style::default().bg(Color::Blue).fg(Color::White)
+                theme.selected()
             } else {
                 Style::default()
             };
@@ -188,31 +410,36 @@ fn draw_participants(f: &mut Frame, app: &mut App, area: Rect) {
             };
 
             let prefix = if selected { "▶ " } else { "  " };
+            let warning = if p.overdrawn_by().is_some() {
+                Span::styled("⚠ ", Style::default().fg(theme.negative))
+            } else {
+                Span::raw("")
+            };
             
             Row::new(vec![
-                Cell::from(format!("{}{}", prefix, name_display))
-                    .style(if selected {
-                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
-                    } else {
-                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
-                    }),
+                Cell::from(Line::from(vec![
+                    Span::raw(prefix),
+                    warning,
+                    Span::raw(name_display),
+                ]))
+                .style(Style::default().fg(theme.text).add_modifier(Modifier::BOLD)),
                 Cell::from(p.role.clone())
                     .style(if selected {
-                        Style::default().fg(Color::White)
+                        Style::default().fg(theme.selection_fg)
                     } else {
-                        Style::default().fg(Color::Cyan)
+                        Style::default().fg(theme.info)
                     }),
                 Cell::from(id_display)
                     .style(if selected {
-                        Style::default().fg(Color::White)
+                        Style::default().fg(theme.selection_fg)
                     } else {
-                        Style::default().fg(Color::DarkGray)
+                        Style::default().fg(theme.dim)
                     }),
                 Cell::from(services_display)
                     .style(if selected {
-                        Style::default().fg(Color::White)
+                        Style::default().fg(theme.selection_fg)
                     } else {
-                        Style::default().fg(Color::Green)
+                        Style::default().fg(theme.positive)
                     }),
             ])
             .style(row_style)
@@ -231,29 +458,63 @@ fn draw_participants(f: &mut Frame, app: &mut App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Yellow))
+                .border_style(Style::default().fg(theme.accent))
                 .title(title),
         );
 
-    f.render_widget(table, area);
-    
-    // Handle selection navigation manually since Table doesn't support stateful rendering
-    // The selection highlighting is already applied in the row styles above
+    // The selection highlighting is already applied in the row styles above; the
+    // table state only scrolls the page so the selected row stays visible
+    let mut state = TableState::default().with_selected(Some(selected_idx.saturating_sub(page_start)));
+    f.render_stateful_widget(table, area, &mut state);
+
+    // Rows start below the top border and the header (plus its bottom margin)
+    app.click_map.rows = Some(Rect {
+        x: area.x + 1,
+        y: area.y + 3,
+        width: area.width.saturating_sub(2),
+        height: area.height.saturating_sub(4),
+    });
+    app.click_map.row_offset = page_start + state.offset();
 }
 
-fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
+fn draw_participant_detail(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
     let detail = match &app.participant_detail {
         Some(d) => d,
         None => {
-            let msg = Paragraph::new(Line::from(Span::styled(
-                "No participant selected",
-                Style::default().fg(Color::DarkGray),
-            )));
-            f.render_widget(msg, area);
+            let empty = "No participant selected. Open one from the Participants tab.";
+            draw_empty_state(f, app, &app.detail_load, empty, " Participant ", area);
             return;
         }
     };
 
+    // Overdrawn operating account gets a banner above everything else
+    let area = if let Some(amount) = detail.info.overdrawn_by() {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+        let banner = Paragraph::new(Line::from(vec![
+            Span::styled(
+                "⚠ Operating account overdrawn by ",
+                Style::default().fg(theme.negative).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("${}", grpc::format_balance(amount)),
+                Style::default().fg(theme.negative).add_modifier(Modifier::BOLD),
+            ),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.negative)),
+        );
+        f.render_widget(banner, rows[0]);
+        rows[1]
+    } else {
+        area
+    };
+
     // Split into left (About/Contact) and right (Accounts summary) columns
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -283,30 +544,30 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
     
     let mut about_lines = vec![
         Line::from(vec![
-            Span::styled("Name: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(&detail.info.name, Style::default().fg(Color::White)),
+            Span::styled("Name: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&detail.info.name, Style::default().fg(theme.text)),
         ]),
         Line::from(vec![
-            Span::styled("ID: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(&detail.info.id, Style::default().fg(Color::DarkGray)),
+            Span::styled("ID: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&detail.info.id, Style::default().fg(theme.dim)),
         ]),
         Line::from(vec![
-            Span::styled("Role: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(&detail.info.role, Style::default().fg(Color::Cyan)),
+            Span::styled("Role: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&detail.info.role, Style::default().fg(theme.info)),
         ]),
         Line::from(vec![
-            Span::styled("Created: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(&created_at_str, Style::default().fg(Color::DarkGray)),
+            Span::styled("Created: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&created_at_str, Style::default().fg(theme.dim)),
         ]),
         Line::from(vec![
-            Span::styled("Services: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled("Services: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
             Span::styled(
                 if detail.info.services.is_empty() {
                     "None".to_string()
                 } else {
                     detail.info.services.join(", ")
                 },
-                Style::default().fg(Color::Green),
+                Style::default().fg(theme.positive),
             ),
         ]),
     ];
@@ -316,39 +577,27 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
         about_lines.push(Line::raw(""));
         about_lines.push(Line::from(Span::styled(
             "About:",
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        )));
+        // Wrapped by the paragraph to the panel's actual width
+        about_lines.push(Line::from(Span::styled(
+            detail.info.about.as_str(),
+            Style::default().fg(theme.text),
         )));
-        // Split about text into multiple lines (simple word wrap at 40 chars)
-        let about_text = &detail.info.about;
-        let words: Vec<&str> = about_text.split_whitespace().collect();
-        let mut current_line = String::new();
-        for word in words {
-            if current_line.len() + word.len() + 1 > 40 && !current_line.is_empty() {
-                about_lines.push(Line::from(Span::styled(
-                    current_line.clone(),
-                    Style::default().fg(Color::White),
-                )));
-                current_line = word.to_string();
-            } else {
-                if !current_line.is_empty() {
-                    current_line.push(' ');
-                }
-                current_line.push_str(word);
-            }
-        }
-        if !current_line.is_empty() {
-            about_lines.push(Line::from(Span::styled(
-                current_line,
-                Style::default().fg(Color::White),
-            )));
-        }
     }
 
-    let about = Paragraph::new(about_lines)
+    // About and Contact share one scroll offset, each clamped to its own overflow
+    let about = Paragraph::new(about_lines).wrap(Wrap { trim: true });
+    let about_overflow = overflow(
+        about.line_count(left_chunks[0].width.saturating_sub(2)),
+        left_chunks[0],
+    );
+    let about = about
+        .scroll((app.detail_info_scroll.min(about_overflow), 0))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Yellow))
+                .border_style(Style::default().fg(theme.accent))
                 .title(" About "),
         );
     f.render_widget(about, left_chunks[0]);
@@ -360,29 +609,29 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
     // Display contact fields that have values
     if !contact.email.is_empty() {
         contact_lines.push(Line::from(vec![
-            Span::styled("Email: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(&contact.email, Style::default().fg(Color::White)),
+            Span::styled("Email: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&contact.email, Style::default().fg(theme.text)),
         ]));
     }
     
     if !contact.phone.is_empty() {
         contact_lines.push(Line::from(vec![
-            Span::styled("Phone: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(&contact.phone, Style::default().fg(Color::White)),
+            Span::styled("Phone: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&contact.phone, Style::default().fg(theme.text)),
         ]));
     }
     
     if !contact.website.is_empty() {
         contact_lines.push(Line::from(vec![
-            Span::styled("Website: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(&contact.website, Style::default().fg(Color::White)),
+            Span::styled("Website: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&contact.website, Style::default().fg(theme.text)),
         ]));
     }
     
     if !contact.address.is_empty() {
         contact_lines.push(Line::from(vec![
-            Span::styled("Address: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(&contact.address, Style::default().fg(Color::White)),
+            Span::styled("Address: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&contact.address, Style::default().fg(theme.text)),
         ]));
     }
     
@@ -400,8 +649,8 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
     
     if !location_parts.is_empty() {
         contact_lines.push(Line::from(vec![
-            Span::styled("Location: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(location_parts.join(", "), Style::default().fg(Color::White)),
+            Span::styled("Location: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(location_parts.join(", "), Style::default().fg(theme.text)),
         ]));
     }
     
@@ -415,9 +664,9 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
                 contact_lines.push(Line::from(vec![
                     Span::styled(
                         format!("{}: ", label),
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                        Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
                     ),
-                    Span::styled(value, Style::default().fg(Color::White)),
+                    Span::styled(value, Style::default().fg(theme.text)),
                 ]));
             }
         }
@@ -430,9 +679,9 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
             contact_lines.push(Line::from(vec![
                 Span::styled(
                     format!("{}: ", key),
-                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(value, Style::default().fg(Color::White)),
+                Span::styled(value, Style::default().fg(theme.text)),
             ]));
         }
     }
@@ -440,102 +689,193 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
     if contact_lines.is_empty() {
         contact_lines.push(Line::from(Span::styled(
             "No contact information available",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.dim),
         )));
     }
 
+    let contact_overflow = overflow(contact_lines.len(), left_chunks[1]);
     let contact = Paragraph::new(contact_lines)
+        .scroll((app.detail_info_scroll.min(contact_overflow), 0))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(Style::default().fg(theme.info))
                 .title(" Contact "),
         );
     f.render_widget(contact, left_chunks[1]);
 
     // Right side: Accounts and Contracts
-    // Calculate dynamic height for accounts section: header (4 lines) + account list
-    let accounts_header_height = 4; // Total Balance, Account Count, blank, "Accounts:" header
+    let subtotals = detail.subtotals();
+    let net_receivables = detail.net_receivables();
+
+    // Calculate dynamic height for accounts section: header + subtotals + account list
+    // Total Balance, Account Count, blank, "By Type:", subtotals, net line, blank, "Accounts:"
+    let accounts_header_height =
+        6 + subtotals.len() + usize::from(net_receivables.is_some());
     let calculated_height = accounts_header_height + detail.accounts.len() + 1; // +1 for border
     // Cap at 80% of available height to leave room for contracts
     let max_height = (area.height as usize * 4 / 5).max(8); // At least 8 lines
     let accounts_display_height = calculated_height.min(max_height) as u16;
     
+    // Trend of the selected account, when it has moved at all recently
+    let trend = app
+        .detail_account_state
+        .selected()
+        .and_then(|i| detail.accounts.get(i))
+        .and_then(|account| Some((account, detail.trends.get(&account.id)?)))
+        .filter(|(_, trend)| trend.len() > 1);
+
     let right_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(accounts_display_height), // Accounts summary (dynamic)
+            Constraint::Length(if trend.is_some() { 5 } else { 0 }), // Balance trend
             Constraint::Min(0),   // Contracts
         ])
         .split(chunks[1]);
 
     // Accounts Summary
-    let account_summary_lines = vec![
+    let mut account_summary_lines = vec![
         Line::from(vec![
-            Span::styled("Total Balance: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled("Total Balance: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
             Span::styled(
                 grpc::format_balance(detail.total_balance),
                 Style::default().fg(if detail.total_balance >= 0 {
-                    Color::Green
+                    theme.positive
                 } else {
-                    Color::Red
+                    theme.negative
                 }),
             ),
         ]),
         Line::from(vec![
-            Span::styled("Account Count: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled("Account Count: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
             Span::styled(
                 detail.accounts.len().to_string(),
-                Style::default().fg(Color::White),
+                Style::default().fg(theme.text),
             ),
         ]),
         Line::raw(""),
         Line::from(Span::styled(
-            "Accounts:",
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            "By Type:",
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
         )),
     ];
 
+    // Debit-normal (asset) types are expected to be positive, credit-normal
+    // (liability/revenue) types negative; only the unexpected sign is flagged.
+    for (account_type, subtotal) in &subtotals {
+        let credit_normal = is_credit_normal(account_type);
+        let unexpected = if credit_normal { *subtotal > 0 } else { *subtotal < 0 };
+        account_summary_lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<12}", account_type),
+                Style::default().fg(if credit_normal { theme.highlight } else { theme.info }),
+            ),
+            Span::styled(
+                if credit_normal { "Cr " } else { "Dr " },
+                Style::default().fg(theme.dim),
+            ),
+            Span::styled(
+                grpc::format_balance(*subtotal),
+                Style::default().fg(if unexpected { theme.negative } else { theme.positive }),
+            ),
+        ]));
+    }
+    if let Some(net) = net_receivables {
+        account_summary_lines.push(Line::from(vec![
+            Span::styled("  Net A/R − A/P  ", Style::default().fg(theme.accent)),
+            Span::styled(
+                grpc::format_balance(net),
+                Style::default().fg(if net >= 0 { theme.positive } else { theme.negative }),
+            ),
+        ]));
+    }
+    account_summary_lines.push(Line::raw(""));
+    account_summary_lines.push(Line::from(Span::styled(
+        "Accounts:",
+        Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+    )));
+
+    if detail.accounts.is_empty() {
+        account_summary_lines.push(Line::from(Span::styled(
+            "  No accounts yet",
+            Style::default().fg(theme.dim),
+        )));
+    }
+
     // Show all accounts (no limit)
+    let selected_account = app.detail_account_state.selected();
     let account_list_lines: Vec<Line> = detail
         .accounts
         .iter()
-        .map(|acc| {
-            Line::from(vec![
-                Span::styled("  • ", Style::default().fg(Color::DarkGray)),
-                Span::styled(&acc.account_type, Style::default().fg(Color::Cyan)),
+        .enumerate()
+        .map(|(i, acc)| {
+            let marker = if selected_account == Some(i) {
+                Span::styled("  ▶ ", Style::default().fg(theme.accent))
+            } else {
+                Span::styled("  • ", Style::default().fg(theme.dim))
+            };
+            let mut spans = vec![
+                marker,
+                Span::styled(&acc.account_type, Style::default().fg(theme.info)),
                 Span::raw(": "),
                 Span::styled(
                     grpc::format_balance(acc.balance),
-                    Style::default().fg(if acc.balance >= 0 {
-                        Color::Green
-                    } else {
-                        Color::Red
-                    }),
+                    balance_style(theme, acc.balance, app.balance_change(&acc.id)),
                 ),
-            ])
+            ];
+            spans.extend(balance_change_span(theme, app.balance_change(&acc.id)));
+            Line::from(spans)
         })
         .collect();
 
     let mut all_account_lines = account_summary_lines;
     all_account_lines.extend(account_list_lines);
 
+    // Scroll just enough to keep the selected account visible; selecting the
+    // first account brings the summary back into view
+    let header_lines = accounts_header_height as u16;
+    let visible_lines = right_chunks[0].height.saturating_sub(2);
+    let selected_line = header_lines + selected_account.unwrap_or(0) as u16;
+    let mut accounts_scroll = app.detail_accounts_scroll;
+    if selected_account.unwrap_or(0) == 0 {
+        accounts_scroll = 0;
+    } else if selected_line < accounts_scroll {
+        accounts_scroll = selected_line;
+    } else if selected_line >= accounts_scroll + visible_lines {
+        accounts_scroll = selected_line + 1 - visible_lines.max(1);
+    }
+    accounts_scroll = accounts_scroll.min(overflow(all_account_lines.len(), right_chunks[0]));
+
     let accounts_summary = Paragraph::new(all_account_lines)
+        .scroll((accounts_scroll, 0))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Green))
+                .border_style(Style::default().fg(theme.positive))
                 .title(" Accounts "),
         );
     f.render_widget(accounts_summary, right_chunks[0]);
+    if let Some((account, trend)) = trend {
+        draw_balance_trend(f, theme, &account.account_type, trend, right_chunks[1]);
+    }
+    // Account rows visible below whatever part of the summary is still on screen
+    let accounts_inner = right_chunks[0].inner(Margin::new(1, 1));
+    let first_row_line = header_lines.max(accounts_scroll);
+    let rows_top = (first_row_line - accounts_scroll).min(accounts_inner.height);
+    let account_rows = Rect {
+        y: accounts_inner.y + rows_top,
+        height: accounts_inner.height - rows_top,
+        ..accounts_inner
+    };
 
     // Smart Contracts
     let mut contract_lines = vec![
         Line::from(vec![
-            Span::styled("Contracts: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled("Contracts: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
             Span::styled(
                 detail.contracts.len().to_string(),
-                Style::default().fg(Color::White),
+                Style::default().fg(theme.text),
             ),
         ]),
         Line::raw(""),
@@ -544,13 +884,13 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
     if detail.contracts.is_empty() {
         contract_lines.push(Line::from(Span::styled(
             "  No contracts",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.dim),
         )));
     } else {
         for contract in detail.contracts.iter().take(10) {
             contract_lines.push(Line::from(vec![
-                Span::styled("  • ", Style::default().fg(Color::DarkGray)),
-                Span::styled(&contract.contract_type, Style::default().fg(Color::Cyan)),
+                Span::styled("  • ", Style::default().fg(theme.dim)),
+                Span::styled(&contract.contract_type, Style::default().fg(theme.info)),
                 Span::raw(": "),
             ]));
             
@@ -562,7 +902,7 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
             };
             contract_lines.push(Line::from(vec![
                 Span::raw("    "),
-                Span::styled(desc, Style::default().fg(Color::White)),
+                Span::styled(desc, Style::default().fg(theme.text)),
             ]));
             
             // Show other participants
@@ -575,8 +915,8 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
                 };
                 contract_lines.push(Line::from(vec![
                     Span::raw("    "),
-                    Span::styled("With: ", Style::default().fg(Color::DarkGray)),
-                    Span::styled(participants_display, Style::default().fg(Color::Green)),
+                    Span::styled("With: ", Style::default().fg(theme.dim)),
+                    Span::styled(participants_display, Style::default().fg(theme.positive)),
                 ]));
             }
             contract_lines.push(Line::raw(""));
@@ -585,7 +925,7 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
         if detail.contracts.len() > 10 {
             contract_lines.push(Line::from(Span::styled(
                 format!("  ... and {} more", detail.contracts.len() - 10),
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.dim),
             )));
         }
     }
@@ -594,66 +934,272 @@ fn draw_participant_detail(f: &mut Frame, app: &App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Magenta))
+                .border_style(Style::default().fg(theme.highlight))
                 .title(" Smart Contracts "),
         );
-    f.render_widget(contracts_widget, right_chunks[1]);
+    f.render_widget(contracts_widget, right_chunks[2]);
+
+    app.click_map.rows = Some(account_rows);
+    app.click_map.row_offset = (first_row_line - header_lines) as usize;
+    app.detail_accounts_scroll = accounts_scroll;
+    // Clamp so PageUp responds immediately after scrolling past the end
+    app.detail_info_scroll = app.detail_info_scroll.min(about_overflow.max(contact_overflow));
 }
 
-fn draw_future(f: &mut Frame, app: &App, area: Rect) {
-    let total = app.future_events.len();
+/// Sparkline of an account's balance over its last `TREND_TRANSACTIONS`
+/// transactions, the newest on the right. Bars are measured from the lowest
+/// balance in the window, so overdrafts and credit-normal accounts still show
+/// their shape.
+fn draw_balance_trend(f: &mut Frame, theme: Theme, account_type: &str, trend: &[i64], area: Rect) {
+    let low = trend.iter().copied().min().unwrap_or(0);
+    let high = trend.iter().copied().max().unwrap_or(0);
+    // Keep the newest balances when the panel is narrower than the trend
+    let width = area.width.saturating_sub(2) as usize;
+    let data: Vec<u64> = trend[trend.len().saturating_sub(width)..]
+        .iter()
+        .map(|balance| (balance - low) as u64 + 1)
+        .collect();
+    let title = format!(
+        " {} trend, last {} tx: {} – {} ",
+        account_type,
+        trend.len() - 1,
+        grpc::format_balance(low),
+        grpc::format_balance(high)
+    );
+    let sparkline = Sparkline::default()
+        .data(&data)
+        .style(Style::default().fg(theme.accent))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.positive))
+                .title(title),
+        );
+    f.render_widget(sparkline, area);
+}
+
+/// Lines of `line_count` that don't fit inside the bordered `area`
+fn overflow(line_count: usize, area: Rect) -> u16 {
+    (line_count as u16).saturating_sub(area.height.saturating_sub(2))
+}
 
+fn draw_future(f: &mut Frame, app: &mut App, area: Rect) {
+    if app.show_timeline {
+        draw_timeline(f, app, area);
+        return;
+    }
+    if app.overdue_events.is_empty() && app.future_events.is_empty() {
+        let empty = "No scheduled events. Contracts with upcoming execution dates will appear here.";
+        draw_empty_state(f, app, &app.future_load, empty, " Scheduled Events ", area);
+        return;
+    }
+    let theme = app.theme;
+
+    // Overdue obligations are the actionable ones, so they get their own block on top
+    let area = if app.overdue_events.is_empty() {
+        area
+    } else {
+        let wanted = app.overdue_events.len() as u16 * 3 + 2;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(wanted.min(area.height / 2)), Constraint::Min(0)])
+            .split(area);
+
+        let items: Vec<ListItem> = app
+            .overdue_events
+            .iter()
+            .enumerate()
+            .map(|(i, event)| future_event_item(theme, i, event, theme.negative))
+            .collect();
+        let title = format!(" Overdue ({}) ", app.overdue_events.len());
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.negative))
+                .title(Span::styled(
+                    title,
+                    Style::default().fg(theme.negative).add_modifier(Modifier::BOLD),
+                )),
+        );
+        f.render_widget(list, chunks[0]);
+        chunks[1]
+    };
+
+    let total = app.future_events.len();
     let items: Vec<ListItem> = if app.future_events.is_empty() {
         vec![ListItem::new(Line::from(Span::styled(
-            "  No scheduled events. Contracts with upcoming execution dates will appear here.",
-            Style::default().fg(Color::DarkGray),
+            "  Nothing upcoming beyond the overdue contracts above.",
+            Style::default().fg(theme.dim),
         )))]
     } else {
         app.future_events
             .iter()
             .enumerate()
-            .map(|(i, event)| {
-                // Format execution time
-                let execution_time_str = if let Some(datetime) = chrono::DateTime::from_timestamp_millis(event.execution_time) {
-                    datetime.format("%Y-%m-%d %H:%M:%S").to_string()
-                } else {
-                    format!("{}", event.execution_time)
-                };
-                
-                let num = format!("{:>2}. ", i + 1);
-                ListItem::new(vec![
-                    Line::from(vec![
-                        Span::styled(num, Style::default().fg(Color::DarkGray)),
-                        Span::styled(execution_time_str.clone(), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                        Span::raw(" - "),
-                        Span::styled(event.contract_type.clone(), Style::default().fg(Color::Cyan)),
-                    ]),
-                    Line::from(vec![
-                        Span::raw("    "),
-                        Span::styled(event.description.clone(), Style::default().fg(Color::White)),
-                    ]),
-                    Line::from(vec![
-                        Span::raw("    "),
-                        Span::styled("Contract: ", Style::default().fg(Color::DarkGray)),
-                        Span::styled(event.contract_id.clone(), Style::default().fg(Color::Green)),
-                    ]),
-                ])
-            })
+            .map(|(i, event)| future_event_item(theme, i, event, theme.accent))
             .collect()
     };
 
     let title = format!(" Scheduled Events ({} upcoming) ", total);
-    let list = List::new(items).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow))
-            .title(title),
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .title(title),
+        )
+        .highlight_style(theme.selected());
+
+    if total == 0 {
+        f.render_widget(list, area);
+    } else {
+        f.render_stateful_widget(list, area, &mut app.future_state);
+    }
+}
+
+/// Three-line entry for a scheduled or overdue event, with its time in `time_color`
+/// Future view in timeline mode: one lane per contract type on a 90-day axis
+fn draw_timeline(f: &mut Frame, app: &App, area: Rect) {
+    const LABEL_WIDTH: usize = 24;
+    let theme = app.theme;
+    let now = chrono::Utc::now().timestamp_millis();
+    let width = (area.width as usize).saturating_sub(LABEL_WIDTH + 3).max(10);
+    let date = |at: i64| {
+        chrono::DateTime::from_timestamp_millis(at)
+            .map(|d| d.format("%b %d").to_string())
+            .unwrap_or_default()
+    };
+
+    // Date labels every 30 days, and the axis with a tick under each
+    let ticks: Vec<(usize, i64)> = (0..=timeline::HORIZON_DAYS)
+        .step_by(30)
+        .filter_map(|day| {
+            let at = now + day * timeline::DAY_MS;
+            timeline::column(at, now, width).map(|col| (col, at))
+        })
+        .collect();
+    let mut labels = vec![' '; width];
+    let mut free_from = 0;
+    for (col, at) in &ticks {
+        let text: Vec<char> = date(*at).chars().collect();
+        let start = (*col).min(width.saturating_sub(text.len()));
+        if start < free_from {
+            continue;
+        }
+        labels[start..start + text.len()].copy_from_slice(&text);
+        free_from = start + text.len() + 1;
+    }
+    let axis: String = (0..width)
+        .map(|col| match col {
+            0 => '├',
+            c if c + 1 == width => '┤',
+            c if ticks.iter().any(|(t, _)| *t == c) => '┼',
+            _ => '─',
+        })
+        .collect();
+    let gutter = " ".repeat(LABEL_WIDTH);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw(gutter.clone()),
+            Span::styled("▼ today", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(vec![
+            Span::raw(gutter.clone()),
+            Span::styled(labels.into_iter().collect::<String>(), Style::default().fg(theme.dim)),
+        ]),
+        Line::from(vec![Span::raw(gutter), Span::styled(axis, Style::default().fg(theme.dim))]),
+    ];
+
+    let lanes = timeline::lanes(&app.timeline_events, now, width);
+    let palette = [theme.highlight, theme.info, theme.positive, theme.accent];
+    for (i, lane) in lanes.iter().enumerate() {
+        let color = palette[i % palette.len()];
+        let mut label: String = lane.label.chars().take(LABEL_WIDTH - 3).collect();
+        label = format!(" {:<width$}", label, width = LABEL_WIDTH - 1);
+        let track: String = lane
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(col, count)| match count {
+                0 if col % 7 == 0 => '·',
+                0 => ' ',
+                1 => '●',
+                n @ 2..=9 => char::from_digit(*n as u32, 10).unwrap_or('+'),
+                _ => '+',
+            })
+            .collect();
+        lines.push(Line::from(vec![
+            Span::styled(label, Style::default().fg(color)),
+            Span::styled(track, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        ]));
+    }
+    lines.push(Line::raw(""));
+    if lanes.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No executions scheduled in the next 90 days.",
+            Style::default().fg(theme.dim),
+        )));
+    } else if let Some((start, count)) = timeline::busiest_week(&app.timeline_events) {
+        lines.push(Line::from(vec![
+            Span::styled("  Busiest week: ", Style::default().fg(theme.accent)),
+            Span::styled(
+                format!("{} – {}, {} executions", date(start), date(start + 6 * timeline::DAY_MS), count),
+                Style::default().fg(theme.text),
+            ),
+        ]));
+    }
+    if !app.overdue_events.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {} overdue, shown in the list view", app.overdue_events.len()),
+            Style::default().fg(theme.negative),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "  ● one execution  2-9 several on one day  + more  · week",
+        Style::default().fg(theme.dim),
+    )));
+
+    let title = format!(
+        " Timeline: next {} days ({} executions) ",
+        timeline::HORIZON_DAYS,
+        app.timeline_events.len()
     );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(title);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn future_event_item(theme: Theme, i: usize, event: &FutureEvent, time_color: Color) -> ListItem<'static> {
+    // Format execution time
+    let execution_time_str = if let Some(datetime) = chrono::DateTime::from_timestamp_millis(event.execution_time) {
+        datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+    } else {
+        format!("{}", event.execution_time)
+    };
 
-    f.render_widget(list, area);
+    let num = format!("{:>2}. ", i + 1);
+    ListItem::new(vec![
+        Line::from(vec![
+            Span::styled(num, Style::default().fg(theme.dim)),
+            Span::styled(execution_time_str, Style::default().fg(time_color).add_modifier(Modifier::BOLD)),
+            Span::raw(" - "),
+            Span::styled(event.contract_type.clone(), Style::default().fg(theme.info)),
+        ]),
+        Line::from(vec![
+            Span::raw("    "),
+            Span::styled(event.description.clone(), Style::default().fg(theme.text)),
+        ]),
+        Line::from(vec![
+            Span::raw("    "),
+            Span::styled("Contract: ", Style::default().fg(theme.dim)),
+            Span::styled(event.contract_id.clone(), Style::default().fg(theme.positive)),
+        ]),
+    ])
 }
 
 fn draw_transfer(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
     // Check if we should show suggestions
     let show_suggestions =
         app.transfer_form.show_suggestions && app.transfer_form.selected_field <= 1;
@@ -687,7 +1233,7 @@ fn draw_transfer(f: &mut Frame, app: &App, area: Rect) {
     };
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow))
+        .border_style(Style::default().fg(theme.accent))
         .title(title);
     f.render_widget(block, area);
 
@@ -720,18 +1266,18 @@ fn draw_transfer(f: &mut Frame, app: &App, area: Rect) {
         let (label_style, input_style, border_color) = if is_selected {
             (
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD),
                 Style::default()
-                    .fg(Color::White)
+                    .fg(theme.text)
                     .add_modifier(Modifier::BOLD),
-                Color::Yellow,
+                theme.accent,
             )
         } else {
             (
-                Style::default().fg(Color::DarkGray),
-                Style::default().fg(Color::Gray),
-                Color::DarkGray,
+                Style::default().fg(theme.dim),
+                Style::default().fg(theme.inactive),
+                theme.dim,
             )
         };
 
@@ -760,23 +1306,27 @@ fn draw_transfer(f: &mut Frame, app: &App, area: Rect) {
     // Submit hint with context-sensitive key info
     let hint_text = if app.transfer_form.selected_field <= 1 {
         Line::from(vec![
-            Span::styled("Tab", Style::default().fg(Color::Cyan)),
-            Span::styled("/", Style::default().fg(Color::DarkGray)),
-            Span::styled("Shift+Tab", Style::default().fg(Color::Cyan)),
-            Span::styled(" Cycle accounts  ", Style::default().fg(Color::DarkGray)),
-            Span::styled("Enter", Style::default().fg(Color::Green)),
-            Span::styled(" Accept & next  ", Style::default().fg(Color::DarkGray)),
-            Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
-            Span::styled(" Fields", Style::default().fg(Color::DarkGray)),
+            Span::styled("Tab", Style::default().fg(theme.info)),
+            Span::styled("/", Style::default().fg(theme.dim)),
+            Span::styled("Shift+Tab", Style::default().fg(theme.info)),
+            Span::styled(" Cycle accounts  ", Style::default().fg(theme.dim)),
+            Span::styled("Enter", Style::default().fg(theme.positive)),
+            Span::styled(" Accept & next  ", Style::default().fg(theme.dim)),
+            Span::styled("↑/↓", Style::default().fg(theme.info)),
+            Span::styled(" Fields", Style::default().fg(theme.dim)),
         ])
     } else {
         Line::from(vec![
-            Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
-            Span::styled(" Navigate  ", Style::default().fg(Color::DarkGray)),
-            Span::styled("Enter", Style::default().fg(Color::Green)),
-            Span::styled(" Execute Transfer  ", Style::default().fg(Color::DarkGray)),
-            Span::styled("Esc", Style::default().fg(Color::Red)),
-            Span::styled(" Clear", Style::default().fg(Color::DarkGray)),
+            Span::styled("↑/↓", Style::default().fg(theme.info)),
+            Span::styled(" Navigate  ", Style::default().fg(theme.dim)),
+            Span::styled("Enter", Style::default().fg(theme.positive)),
+            Span::styled(" Execute Transfer  ", Style::default().fg(theme.dim)),
+            Span::styled("Esc", Style::default().fg(theme.negative)),
+            Span::styled(" Clear  ", Style::default().fg(theme.dim)),
+            Span::styled("Ctrl+S", Style::default().fg(theme.info)),
+            Span::styled("/", Style::default().fg(theme.dim)),
+            Span::styled("Ctrl+O", Style::default().fg(theme.info)),
+            Span::styled(" Save/Load template", Style::default().fg(theme.dim)),
         ])
     };
     let hint = Paragraph::new(hint_text);
@@ -784,7 +1334,13 @@ fn draw_transfer(f: &mut Frame, app: &App, area: Rect) {
 
     // Show suggestions or error/success messages in bottom area
     if show_suggestions && !suggestions.is_empty() {
-        // Show account suggestions
+        // Show account suggestions; when picking the source account, flag the
+        // ones that can't cover the amount already entered
+        let required = if app.transfer_form.selected_field == 0 {
+            grpc::parse_amount(&app.transfer_form.amount).filter(|amount| *amount > 0)
+        } else {
+            None
+        };
         let suggestion_items: Vec<Line> = suggestions
             .iter()
             .enumerate()
@@ -792,31 +1348,43 @@ fn draw_transfer(f: &mut Frame, app: &App, area: Rect) {
             .map(|(i, acc)| {
                 let is_current = app.transfer_form.suggestion_index == Some(i);
                 let prefix = if is_current { "▶ " } else { "  " };
+                let insufficient = required.is_some_and(|amount| acc.balance < amount);
                 let style = if is_current {
                     Style::default()
-                        .fg(Color::Yellow)
+                        .fg(if insufficient { theme.negative } else { theme.accent })
                         .add_modifier(Modifier::BOLD)
+                } else if insufficient {
+                    Style::default().fg(theme.negative)
                 } else {
-                    Style::default().fg(Color::White)
+                    Style::default().fg(theme.text)
                 };
-                Line::from(vec![
+                let mut spans = vec![
                     Span::styled(prefix, style),
                     Span::styled(&acc.id, style),
                     Span::styled(" ", Style::default()),
                     Span::styled(
                         format!("[{}]", acc.account_type),
-                        Style::default().fg(Color::Cyan),
+                        Style::default().fg(theme.info),
                     ),
                     Span::styled(" ", Style::default()),
                     Span::styled(
                         grpc::format_balance(acc.balance),
-                        Style::default().fg(if acc.balance >= 0 {
-                            Color::Green
+                        Style::default().fg(if acc.balance >= 0 && !insufficient {
+                            theme.positive
                         } else {
-                            Color::Red
+                            theme.negative
                         }),
                     ),
-                ])
+                ];
+                if insufficient {
+                    spans.push(Span::styled(
+                        " insufficient",
+                        Style::default()
+                            .fg(theme.negative)
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                }
+                Line::from(spans)
             })
             .collect();
 
@@ -830,81 +1398,514 @@ fn draw_transfer(f: &mut Frame, app: &App, area: Rect) {
         let suggestion_widget = Paragraph::new(suggestion_items).block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(Style::default().fg(theme.info))
                 .title(title),
         );
         f.render_widget(suggestion_widget, chunks[5]);
     } else if let Some(ref err) = app.transfer_form.error {
         let msg = Paragraph::new(Line::from(vec![
-            Span::styled("✗ ", Style::default().fg(Color::Red)),
-            Span::styled(err.as_str(), Style::default().fg(Color::Red)),
+            Span::styled("✗ ", Style::default().fg(theme.negative)),
+            Span::styled(err.as_str(), Style::default().fg(theme.negative)),
         ]))
         .wrap(Wrap { trim: true });
         f.render_widget(msg, chunks[5]);
     } else if let Some(ref success) = app.transfer_form.success {
         let msg = Paragraph::new(Line::from(vec![
-            Span::styled("✓ ", Style::default().fg(Color::Green)),
-            Span::styled(success.as_str(), Style::default().fg(Color::Green)),
+            Span::styled("✓ ", Style::default().fg(theme.positive)),
+            Span::styled(success.as_str(), Style::default().fg(theme.positive)),
         ]))
         .wrap(Wrap { trim: true });
         f.render_widget(msg, chunks[5]);
     }
 }
 
-fn draw_history(f: &mut Frame, app: &App, area: Rect) {
-    let total = app.history.len();
+fn draw_loan(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let form = &app.loan_form;
+    let fields = form.fields();
 
-    let items: Vec<ListItem> = if app.history.is_empty() {
-        vec![ListItem::new(Line::from(Span::styled(
-            "  No transactions yet. Use the Transfer tab to create one.",
-            Style::default().fg(Color::DarkGray),
-        )))]
-    } else {
-        app.history
-            .iter()
-            .rev()
-            .enumerate()
-            .map(|(i, h)| {
-                let num = format!("{:>3}. ", total - i);
-                ListItem::new(Line::from(vec![
-                    Span::styled(num, Style::default().fg(Color::DarkGray)),
-                    Span::styled(h.clone(), Style::default().fg(Color::White)),
-                ]))
-            })
-            .collect()
-    };
+    let mut constraints: Vec<Constraint> = fields.iter().map(|_| Constraint::Length(3)).collect();
+    constraints.push(Constraint::Length(2)); // Submit hint
+    constraints.push(Constraint::Length(3)); // Repayment estimate
+    constraints.push(Constraint::Min(0)); // Messages
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .margin(1)
+        .split(area);
 
-    let title = format!(" Transaction History ({} total) ", total);
-    let list = List::new(items).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow))
-            .title(title),
+    let title = format!(
+        " Loan (Field {}/{}) ",
+        form.selected_field + 1,
+        fields.len()
     );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(title);
+    f.render_widget(block, area);
 
-    f.render_widget(list, area);
-}
+    for (i, field) in fields.iter().enumerate() {
+        let is_selected = form.selected_field == i;
+        let (label, hint) = match field {
+            LoanField::Action => ("Action", "Tab/Space to switch"),
+            LoanField::Lender => ("Lender", "Participant id"),
+            LoanField::Borrower => ("Borrower", "Participant id"),
+            LoanField::Amount => match form.action {
+                LoanAction::Create => ("Principal", "Amount, e.g. 1500.00"),
+                LoanAction::Repay => ("Repayment", "Amount, e.g. 250.00"),
+            },
+            LoanField::InterestRate => ("Interest Rate %", "Annual rate, e.g. 5"),
+            LoanField::TermMonths => ("Term (months)", "e.g. 60"),
+            LoanField::Reference => ("Reference", "Optional reference text"),
+        };
+        let value = form.value(*field);
 
-fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
-    let help_text = if app.loading {
-        "Loading...".to_string()
-    } else {
-        match app.current_view {
-            View::Participants => {
-                " ↑/↓:Select  Enter:View Details  r:Refresh  q:Quit ".to_string()
-            }
+        let (label_style, input_style, border_color) = if is_selected {
+            (
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(theme.text)
+                    .add_modifier(Modifier::BOLD),
+                theme.accent,
+            )
+        } else {
+            (
+                Style::default().fg(theme.dim),
+                Style::default().fg(theme.inactive),
+                theme.dim,
+            )
+        };
+
+        let display_value = if *field == LoanField::Action {
+            format!("◀ {} ▶", value)
+        } else if value.is_empty() && !is_selected {
+            format!("({})", hint)
+        } else if is_selected {
+            format!("{}█", value)
+        } else {
+            value.to_string()
+        };
+
+        let indicator = if is_selected { "▶ " } else { "  " };
+        let text = Line::from(vec![
+            Span::styled(indicator, label_style),
+            Span::styled(format!("{}: ", label), label_style),
+            Span::styled(display_value, input_style),
+        ]);
+
+        let paragraph = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::BOTTOM)
+                .border_style(Style::default().fg(border_color)),
+        );
+        f.render_widget(paragraph, chunks[i]);
+    }
+
+    let hint_text = Line::from(vec![
+        Span::styled("↑/↓", Style::default().fg(theme.info)),
+        Span::styled(" Navigate  ", Style::default().fg(theme.dim)),
+        Span::styled("Tab", Style::default().fg(theme.info)),
+        Span::styled(" Create/Repay (on Action)  ", Style::default().fg(theme.dim)),
+        Span::styled("Enter", Style::default().fg(theme.positive)),
+        Span::styled(" Submit  ", Style::default().fg(theme.dim)),
+        Span::styled("Esc", Style::default().fg(theme.negative)),
+        Span::styled(" Clear", Style::default().fg(theme.dim)),
+    ]);
+    f.render_widget(Paragraph::new(hint_text), chunks[fields.len()]);
+
+    // Repayment estimate, so the borrower sees the cost before submitting
+    let estimate = match form.action {
+        LoanAction::Create => match form.monthly_payment_estimate() {
+            Some(monthly) => {
+                let total = monthly * form.parsed_term().unwrap_or(0) as i64;
+                Line::from(vec![
+                    Span::styled("Monthly payment: ", Style::default().fg(theme.dim)),
+                    Span::styled(
+                        grpc::format_balance(monthly),
+                        Style::default().fg(theme.accent),
+                    ),
+                    Span::styled("  Total repayment: ", Style::default().fg(theme.dim)),
+                    Span::styled(
+                        grpc::format_balance(total),
+                        Style::default().fg(theme.accent),
+                    ),
+                ])
+            }
+            None => Line::from(Span::styled(
+                "Enter principal, rate and term to see the repayment estimate",
+                Style::default().fg(theme.dim),
+            )),
+        },
+        LoanAction::Repay => Line::from(Span::styled(
+            "Repayment moves funds from the borrower back to the lender",
+            Style::default().fg(theme.dim),
+        )),
+    };
+    let estimate_widget = Paragraph::new(estimate).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.info))
+            .title(" Estimate "),
+    );
+    f.render_widget(estimate_widget, chunks[fields.len() + 1]);
+
+    let message_area = chunks[fields.len() + 2];
+    if let Some(ref err) = form.error {
+        let msg = Paragraph::new(Line::from(vec![
+            Span::styled("✗ ", Style::default().fg(theme.negative)),
+            Span::styled(err.as_str(), Style::default().fg(theme.negative)),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(msg, message_area);
+    } else if let Some(ref success) = form.success {
+        let msg = Paragraph::new(Line::from(vec![
+            Span::styled("✓ ", Style::default().fg(theme.positive)),
+            Span::styled(success.as_str(), Style::default().fg(theme.positive)),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(msg, message_area);
+    }
+}
+
+fn draw_access_payment(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let form = &app.access_payment_form;
+
+    let mut constraints: Vec<Constraint> = ACCESS_PAYMENT_FIELDS
+        .iter()
+        .map(|_| Constraint::Length(3))
+        .collect();
+    constraints.push(Constraint::Length(2)); // Submit hint
+    constraints.push(Constraint::Length(3)); // Split preview
+    constraints.push(Constraint::Min(0)); // Messages
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .margin(1)
+        .split(area);
+
+    let title = format!(
+        " Access Payment (Field {}/{}) ",
+        form.selected_field + 1,
+        ACCESS_PAYMENT_FIELDS.len()
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(title);
+    f.render_widget(block, area);
+
+    for (i, field) in ACCESS_PAYMENT_FIELDS.iter().enumerate() {
+        let is_selected = form.selected_field == i;
+        let (label, hint) = match field {
+            AccessPaymentField::Payer => ("Payer", "Participant id"),
+            AccessPaymentField::Provider => ("Access Provider", "Participant id"),
+            AccessPaymentField::Amount => ("Amount", "e.g. 2.50"),
+            AccessPaymentField::Reference => ("Reference", "Optional, e.g. door unlock"),
+            AccessPaymentField::Platform => ("Platform", "Optional participant id"),
+            AccessPaymentField::PlatformFee => ("Platform Fee", "Optional, e.g. 0.25"),
+        };
+        let value = form.value(*field);
+
+        let (label_style, input_style, border_color) = if is_selected {
+            (
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(theme.text)
+                    .add_modifier(Modifier::BOLD),
+                theme.accent,
+            )
+        } else {
+            (
+                Style::default().fg(theme.dim),
+                Style::default().fg(theme.inactive),
+                theme.dim,
+            )
+        };
+
+        let display_value = if value.is_empty() && !is_selected {
+            format!("({})", hint)
+        } else if is_selected {
+            format!("{}█", value)
+        } else {
+            value.to_string()
+        };
+
+        let indicator = if is_selected { "▶ " } else { "  " };
+        let text = Line::from(vec![
+            Span::styled(indicator, label_style),
+            Span::styled(format!("{}: ", label), label_style),
+            Span::styled(display_value, input_style),
+        ]);
+
+        let paragraph = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::BOTTOM)
+                .border_style(Style::default().fg(border_color)),
+        );
+        f.render_widget(paragraph, chunks[i]);
+    }
+
+    let fields_len = ACCESS_PAYMENT_FIELDS.len();
+    let hint_text = Line::from(vec![
+        Span::styled("↑/↓", Style::default().fg(theme.info)),
+        Span::styled(" Navigate  ", Style::default().fg(theme.dim)),
+        Span::styled("Enter", Style::default().fg(theme.positive)),
+        Span::styled(" Pay  ", Style::default().fg(theme.dim)),
+        Span::styled("Esc", Style::default().fg(theme.negative)),
+        Span::styled(" Clear", Style::default().fg(theme.dim)),
+    ]);
+    f.render_widget(Paragraph::new(hint_text), chunks[fields_len]);
+
+    // Split preview, so the operator sees who receives what before paying
+    let split = match form.split() {
+        Some((provider, fee)) => {
+            let provider_name = if form.access_provider_id.is_empty() {
+                "provider"
+            } else {
+                form.access_provider_id.as_str()
+            };
+            let mut spans = vec![
+                Span::styled(format!("{} gets ", provider_name), Style::default().fg(theme.dim)),
+                Span::styled(grpc::format_balance(provider), Style::default().fg(theme.accent)),
+            ];
+            if fee > 0 {
+                let platform_name = if form.platform_id.is_empty() {
+                    "platform"
+                } else {
+                    form.platform_id.as_str()
+                };
+                spans.push(Span::styled(
+                    format!("  {} gets ", platform_name),
+                    Style::default().fg(theme.dim),
+                ));
+                spans.push(Span::styled(
+                    grpc::format_balance(fee),
+                    Style::default().fg(theme.accent),
+                ));
+            }
+            Line::from(spans)
+        }
+        None => Line::from(Span::styled(
+            "Enter an amount (and optional fee up to the amount) to see the split",
+            Style::default().fg(theme.dim),
+        )),
+    };
+    let split_widget = Paragraph::new(split).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.info))
+            .title(" Split "),
+    );
+    f.render_widget(split_widget, chunks[fields_len + 1]);
+
+    let message_area = chunks[fields_len + 2];
+    if let Some(ref err) = form.error {
+        let msg = Paragraph::new(Line::from(vec![
+            Span::styled("✗ ", Style::default().fg(theme.negative)),
+            Span::styled(err.as_str(), Style::default().fg(theme.negative)),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(msg, message_area);
+    } else if let Some(ref success) = form.success {
+        let msg = Paragraph::new(Line::from(vec![
+            Span::styled("✓ ", Style::default().fg(theme.positive)),
+            Span::styled(success.as_str(), Style::default().fg(theme.positive)),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(msg, message_area);
+    }
+}
+
+fn draw_history(f: &mut Frame, app: &mut App, area: Rect) {
+    if app.history.is_empty() {
+        let empty = "No transactions yet. Use the Transfer tab to create one.";
+        draw_empty_state(f, app, &app.history_load, empty, " Transaction History ", area);
+        return;
+    }
+    let theme = app.theme;
+    let total = app.history.len();
+    let rows = app.history_rows();
+
+    let items: Vec<ListItem> = if rows.is_empty() {
+        vec![ListItem::new(Line::from(Span::styled(
+            "  No transactions of this type. Press t to change the filter.",
+            Style::default().fg(theme.dim),
+        )))]
+    } else {
+        rows.iter()
+            .map(|&i| {
+                let tx = &app.history[i];
+                let num = format!("{:>3}. ", i + 1);
+                ListItem::new(Line::from(vec![
+                    Span::styled(num, Style::default().fg(theme.dim)),
+                    Span::styled(tx.summary(), Style::default().fg(theme.text)),
+                ]))
+            })
+            .collect()
+    };
+
+    let title = match app.history_type_filter {
+        Some(tx_type) => format!(" Transaction History ({} of {}) │ Type: {} ", rows.len(), total, tx_type),
+        None => format!(" Transaction History ({} total) │ Type: All ", total),
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .title(title),
+        )
+        .highlight_style(theme.selected())
+        .highlight_symbol("▶ ");
+
+    f.render_stateful_widget(list, area, &mut app.history_state);
+
+    app.click_map.rows = Some(area.inner(Margin::new(1, 1)));
+    app.click_map.row_offset = app.history_state.offset();
+}
+
+fn draw_accounts(f: &mut Frame, app: &mut App, area: Rect) {
+    if app.accounts.is_empty() {
+        let empty = "No accounts found. Participants' accounts will appear here once opened.";
+        draw_empty_state(f, app, &app.accounts_load, empty, " All Accounts ", area);
+        return;
+    }
+    let theme = app.theme;
+    let rows = app.account_rows();
+
+    let items: Vec<ListItem> = if rows.is_empty() {
+        let msg = format!(
+            "  No {} accounts. Press t to change the type filter.",
+            app.account_type_filter.unwrap_or("matching")
+        );
+        vec![ListItem::new(Line::from(Span::styled(
+            msg,
+            Style::default().fg(theme.dim),
+        )))]
+    } else {
+        rows.iter()
+            .map(|row| match row {
+                AccountRow::Group {
+                    participant_id,
+                    name,
+                    count,
+                    subtotal,
+                    collapsed,
+                } => ListItem::new(Line::from(vec![
+                    Span::styled(
+                        if *collapsed { "▸ " } else { "▾ " },
+                        Style::default().fg(theme.dim),
+                    ),
+                    Span::styled(
+                        format!("{:<28}", name),
+                        Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("{:<22}", participant_id),
+                        Style::default().fg(theme.dim),
+                    ),
+                    Span::styled(
+                        format!("{:>3} accts  ", count),
+                        Style::default().fg(theme.info),
+                    ),
+                    Span::styled(
+                        format!("{:>14}", grpc::format_balance(*subtotal)),
+                        Style::default()
+                            .fg(if *subtotal >= 0 { theme.positive } else { theme.negative })
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ])),
+                AccountRow::Account(idx) => {
+                    let acc = &app.accounts[*idx];
+                    let change = app.balance_change(&acc.id);
+                    let mut spans = vec![
+                        Span::raw("    "),
+                        Span::styled(
+                            format!("{:<12}", acc.account_type),
+                            Style::default().fg(theme.info),
+                        ),
+                        Span::styled(format!("{:<38}", acc.id), Style::default().fg(theme.dim)),
+                        Span::styled(
+                            format!("{:>14}", grpc::format_balance(acc.balance)),
+                            balance_style(theme, acc.balance, change),
+                        ),
+                    ];
+                    spans.extend(balance_change_span(theme, change));
+                    ListItem::new(Line::from(spans))
+                }
+            })
+            .collect()
+    };
+
+    let shown = rows
+        .iter()
+        .filter_map(|row| match row {
+            AccountRow::Group { count, .. } => Some(count),
+            AccountRow::Account(_) => None,
+        })
+        .sum::<usize>();
+    let title = format!(
+        " All Accounts ({}) │ Type: {} │ Total: {} ",
+        shown,
+        app.account_type_filter.unwrap_or("All"),
+        grpc::format_balance(app.filtered_accounts_total())
+    );
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .title(title),
+        )
+        .highlight_style(theme.selected())
+        .highlight_symbol("▶ ");
+
+    f.render_stateful_widget(list, area, &mut app.accounts_browse_state);
+
+    app.click_map.rows = Some(area.inner(Margin::new(1, 1)));
+    app.click_map.row_offset = app.accounts_browse_state.offset();
+}
+
+fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let [quit, select, execute, refresh, back] =
+        [Action::Quit, Action::Select, Action::Execute, Action::Refresh, Action::Back].map(|a| app.keymap.label(a));
+    let help_text = if app.loading {
+        "Loading...".to_string()
+    } else {
+        match app.current_view {
+            View::Overview => format!(" 2-8:Open a tab  {refresh}:Refresh  ?:Help  {quit}:Quit "),
+            View::Participants => {
+                format!(" ↑/↓:Select  {select}:View Details  {refresh}:Refresh  ?:Help  {quit}:Quit ")
+            }
             View::ParticipantDetail => {
-                " b:Back  r:Refresh  q:Quit ".to_string()
+                format!(" f/t:Transfer From/To  c:Contact  i:Invoice  {back}:Back  {refresh}:Refresh  ?:Help  {quit}:Quit ")
             }
             View::Transfer => {
                 if app.transfer_form.selected_field <= 1 {
-                    " Tab:Cycle Accounts  Enter:Accept  ↑/↓:Fields  ←/→:Tabs  q:Quit ".to_string()
+                    format!(" Tab:Cycle Accounts  {execute}:Accept  ↑/↓:Fields  ←/→:Tabs  {quit}:Quit ")
                 } else {
-                    " ↑/↓:Fields  Enter:Execute  Esc:Clear  ←/→:Tabs  q:Quit ".to_string()
+                    format!(" ↑/↓:Fields  {execute}:Execute  Esc:Clear  ^S/^O:Templates  F1:Help  {quit}:Quit ")
                 }
             }
-            View::History => " r:Refresh  q:Quit ".to_string(),
-            View::Future => " r:Refresh  q:Quit ".to_string(),
+            View::History => format!(" u:Reverse  t:Type  e:Export  {refresh}:Refresh  ?:Help  {quit}:Quit "),
+            View::Future if app.show_timeline => format!(" v:List  {refresh}:Refresh  ?:Help  {quit}:Quit "),
+            View::Future => format!(" v:Timeline  {refresh}:Refresh  ?:Help  {quit}:Quit "),
+            View::Loan => {
+                format!(" ↑/↓:Fields  Tab:Create/Repay  {execute}:Submit  Esc:Clear  F1:Help  {quit}:Quit ")
+            }
+            View::Accounts => {
+                format!(" ↑/↓:Select  {select}:Fold  t:Type  {refresh}:Refresh  ?:Help  {quit}:Quit ")
+            }
+            View::AccessPayment => {
+                format!(" ↑/↓:Fields  {execute}:Pay  Esc:Clear  ←/→:Tabs  F1:Help  {quit}:Quit ")
+            }
         }
     };
 
@@ -915,46 +1916,390 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
 
     // Left: context-sensitive help
     let help_style = if app.loading {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.accent)
     } else {
-        Style::default().fg(Color::Cyan)
+        Style::default().fg(theme.info)
     };
     let help = Paragraph::new(Line::from(Span::styled(help_text, help_style)))
         .block(Block::default().borders(Borders::ALL).title(" Keys "));
 
-    // Right: context-aware info
-    let info = match app.current_view {
-        View::Participants => {
-            format!(" {} participants ", app.participants.len())
-        }
-        View::ParticipantDetail => {
-            if let Some(ref detail) = app.participant_detail {
-                format!(" {} accounts | {} contracts | {} total balance ", 
-                    detail.accounts.len(), 
-                    detail.contracts.len(),
-                    grpc::format_balance(detail.total_balance))
-            } else {
-                " Loading... ".to_string()
+    // Right: transient status message, the connection error, or context-aware info
+    let info = if let Some(ref msg) = app.status_message {
+        format!(" {} ", msg)
+    } else if let Some(ref error) = app.last_error {
+        format!(" {} (R to reconnect) ", error)
+    } else {
+        match app.current_view {
+            View::Overview => {
+                let active: usize = app.active_contracts.iter().map(|(_, n)| n).sum();
+                format!(" {} participants | {} active contracts ", app.participants.len(), active)
             }
-        }
-        View::Transfer => {
-            // Show available accounts for transfer
-            format!(" {} accounts available ", app.accounts.len())
-        }
-        View::History => {
-            format!(" {} transactions ", app.history.len())
-        }
-        View::Future => {
-            format!(" {} scheduled events ", app.future_events.len())
+            View::Participants => {
+                format!(" {} participants ", app.participants.len())
+            }
+            View::ParticipantDetail => {
+                if let Some(ref detail) = app.participant_detail {
+                    format!(" {} accounts | {} contracts | {} total balance ", 
+                        detail.accounts.len(), 
+                        detail.contracts.len(),
+                        grpc::format_balance(detail.total_balance))
+                } else {
+                    " Loading... ".to_string()
+                }
+            }
+            View::Transfer => {
+                // Show available accounts for transfer
+                format!(" {} accounts available ", app.accounts.len())
+            }
+            View::History => {
+                format!(" {} transactions ", app.history_rows().len())
+            }
+            View::Future => {
+                if app.overdue_events.is_empty() {
+                    format!(" {} scheduled events ", app.future_events.len())
+                } else {
+                    format!(
+                        " {} scheduled events | {} overdue ",
+                        app.future_events.len(),
+                        app.overdue_events.len()
+                    )
+                }
+            }
+            View::Loan => format!(" {} ", app.loan_form.action.label()),
+            View::Accounts => {
+                format!(
+                    " {} participants | {} accounts ",
+                    app.participants.len(),
+                    app.accounts.len()
+                )
+            }
+            View::AccessPayment => " Access payment ".to_string(),
         }
     };
+    let info_style = if app.status_is_error {
+        Style::default().fg(theme.negative).add_modifier(theme.emphasis())
+    } else if app.status_message.is_some() {
+        Style::default().fg(theme.positive)
+    } else if app.last_error.is_some() {
+        Style::default().fg(theme.negative)
+    } else {
+        Style::default().fg(theme.dim)
+    };
+
+    let dot_color = match app.connection_status {
+        ConnectionStatus::Connected => theme.positive,
+        ConnectionStatus::Degraded => theme.highlight,
+        ConnectionStatus::Disconnected => theme.negative,
+        ConnectionStatus::Idle => theme.dim,
+    };
+    let info_title = Line::from(vec![
+        Span::raw(" "),
+        Span::styled("●", Style::default().fg(dot_color)),
+        Span::raw(format!(" {} ", app.connection_status.label())),
+    ]);
     
-    let info_widget = Paragraph::new(Line::from(Span::styled(
-        info,
-        Style::default().fg(Color::DarkGray),
-    )))
-    .block(Block::default().borders(Borders::ALL).title(" Info "));
+    let info_widget = Paragraph::new(Line::from(Span::styled(info, info_style)))
+    .block(Block::default().borders(Borders::ALL).title(info_title));
 
     f.render_widget(help, chunks[0]);
     f.render_widget(info_widget, chunks[1]);
 }
+
+/// Widest a popup gets, whatever the terminal size
+const POPUP_MAX_WIDTH: u16 = 72;
+
+/// A `percent_x` by `percent_y` share of `area`, centered in it
+pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let width = (area.width as u32 * percent_x.min(100) as u32 / 100) as u16;
+    let height = (area.height as u32 * percent_y.min(100) as u32 / 100) as u16;
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// What an empty list says: still loading, why the load failed and how to
+/// retry, or `empty` once the server has answered with nothing
+fn empty_state_lines(load: &LoadState, empty: &str, retry_key: &str) -> Vec<String> {
+    match load {
+        LoadState::Loading => vec!["Loading…".to_string()],
+        LoadState::Failed(error) => vec![
+            format!("Could not load: {}", error),
+            format!("Press {} to retry.", retry_key),
+        ],
+        LoadState::Loaded => vec![empty.to_string()],
+    }
+}
+
+/// A view's bordered block with its empty-state message centered inside
+fn draw_empty_state(f: &mut Frame, app: &App, load: &LoadState, empty: &str, title: &str, area: Rect) {
+    let theme = app.theme;
+    let color = match load {
+        LoadState::Failed(_) => theme.negative,
+        _ => theme.dim,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(title.to_string());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let lines: Vec<Line> = empty_state_lines(load, empty, &app.keymap.label(Action::Refresh))
+        .into_iter()
+        .map(|line| Line::from(Span::styled(line, Style::default().fg(color))))
+        .collect();
+    let top = inner.height.saturating_sub(lines.len() as u16) / 2;
+    let text_area = Rect {
+        y: inner.y + top,
+        height: inner.height - top,
+        ..inner
+    };
+    let message = Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: true });
+    f.render_widget(message, text_area);
+}
+
+/// Bordered popup sized to `lines`, centered over `area` with the background
+/// cleared. It shrinks to fit small terminals, leaving a margin of the view
+/// underneath visible, and clips lines that don't fit.
+fn render_popup(f: &mut Frame, theme: Theme, title: &str, lines: Vec<Line>, area: Rect) {
+    let bounds = centered_rect(96, 96, area);
+    let width = POPUP_MAX_WIDTH.min(bounds.width);
+    let height = (lines.len() as u16 + 2).min(bounds.height);
+    let rect = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(title.to_string()),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(popup, rect);
+}
+
+/// Save-name prompt or saved-template picker, centered over the Transfer form
+fn draw_template_popup(f: &mut Frame, app: &App, popup: &TemplatePopup, area: Rect) {
+    let theme = app.theme;
+    let (title, mut lines, hint) = match popup {
+        TemplatePopup::Save { name } => (
+            " Save Transfer Template ",
+            vec![Line::from(vec![
+                Span::styled("Name: ", Style::default().fg(theme.dim)),
+                Span::styled(name.clone(), Style::default().fg(theme.text)),
+                Span::styled("█", Style::default().fg(theme.accent)),
+            ])],
+            "Enter save  Esc cancel",
+        ),
+        TemplatePopup::Pick { selected } => (
+            " Transfer Templates ",
+            app.templates
+                .templates
+                .iter()
+                .enumerate()
+                .map(|(i, t)| {
+                    let is_selected = i == *selected;
+                    let name_style = if is_selected {
+                        Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(theme.text)
+                    };
+                    let amount = if t.amount.is_empty() { "-" } else { t.amount.as_str() };
+                    Line::from(vec![
+                        Span::styled(if is_selected { "▶ " } else { "  " }, name_style),
+                        Span::styled(format!("{:<16}", t.name), name_style),
+                        Span::styled(
+                            format!(" {} → {}", t.from_account, t.to_account),
+                            Style::default().fg(theme.info),
+                        ),
+                        Span::styled(format!("  {}", amount), Style::default().fg(theme.positive)),
+                    ])
+                })
+                .collect(),
+            "Enter load  d delete  ↑/↓ select  Esc close",
+        ),
+    };
+    lines.push(Line::raw(""));
+    lines.push(Line::from(Span::styled(hint, Style::default().fg(theme.dim))));
+
+    render_popup(f, theme, title, lines, area);
+}
+
+fn draw_help_overlay(f: &mut Frame, theme: Theme, keymap: &Keymap, area: Rect) {
+    let title_style = Style::default().fg(theme.accent).add_modifier(Modifier::BOLD);
+    let row = |keys: &str, action: &'static str| {
+        Line::from(vec![
+            Span::styled(format!("  {:<16}", keys), Style::default().fg(theme.info)),
+            Span::styled(action, Style::default().fg(theme.text)),
+        ])
+    };
+    // Remappable actions first, with the keys configured in [keys]
+    let mut lines = vec![Line::from(Span::styled("Actions", title_style))];
+    for (keys, action) in action_bindings(keymap) {
+        lines.push(row(&keys, action));
+    }
+    for section in help_sections() {
+        lines.push(Line::raw(""));
+        lines.push(Line::from(Span::styled(section.title, title_style)));
+        for (keys, action) in section.bindings {
+            lines.push(row(&keys, action));
+        }
+    }
+    lines.push(Line::raw(""));
+    lines.push(Line::from(Span::styled(
+        "Press ? or Esc to close",
+        Style::default().fg(theme.dim),
+    )));
+
+    render_popup(f, theme, " Keyboard Shortcuts ", lines, area);
+}
+
+/// Contact editor opened with 'c' in Participant Details
+fn draw_contact_form(f: &mut Frame, theme: Theme, form: &ContactForm, area: Rect) {
+    let mut lines: Vec<Line> = CONTACT_FIELDS
+        .iter()
+        .zip(&form.values)
+        .enumerate()
+        .map(|(i, (label, value))| {
+            let is_selected = i == form.selected;
+            let label_style = if is_selected {
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.dim)
+            };
+            let mut spans = vec![
+                Span::styled(if is_selected { "▶ " } else { "  " }, label_style),
+                Span::styled(format!("{:<12}", label), label_style),
+                Span::styled(value.clone(), Style::default().fg(theme.text)),
+            ];
+            if is_selected {
+                spans.push(Span::styled("█", Style::default().fg(theme.accent)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    lines.push(Line::raw(""));
+    if let Some(ref error) = form.error {
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(theme.negative))));
+    }
+    lines.push(Line::from(Span::styled(
+        "Enter save  ↑/↓ or Tab field  Esc cancel",
+        Style::default().fg(theme.dim),
+    )));
+
+    let title = format!(" Contact: {} ", form.participant_id);
+    render_popup(f, theme, &title, lines, area);
+}
+
+/// Invoice contract form opened with 'i' in Participant Details
+fn draw_invoice_form(f: &mut Frame, theme: Theme, form: &InvoiceForm, area: Rect) {
+    let mut lines: Vec<Line> = INVOICE_FIELDS
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let is_selected = i == form.selected;
+            let label_style = if is_selected {
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.dim)
+            };
+            let mut spans = vec![
+                Span::styled(if is_selected { "▶ " } else { "  " }, label_style),
+                Span::styled(format!("{:<12}", field.label()), label_style),
+                Span::styled(form.value(*field).to_string(), Style::default().fg(theme.text)),
+            ];
+            if is_selected && *field != InvoiceField::AutoDebit {
+                spans.push(Span::styled("█", Style::default().fg(theme.accent)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    lines.push(Line::raw(""));
+    if let Some(ref error) = form.error {
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(theme.negative))));
+    }
+    let hint = if form.current_field() == InvoiceField::AutoDebit {
+        "Space toggle  Enter create  ↑/↓ or Tab field  Esc cancel"
+    } else {
+        "Enter create  ↑/↓ or Tab field  Esc cancel  Dates as YYYY-MM-DD"
+    };
+    lines.push(Line::from(Span::styled(hint, Style::default().fg(theme.dim))));
+
+    render_popup(f, theme, " New Invoice ", lines, area);
+}
+
+/// Path prompt for exporting the History view
+fn draw_export_prompt(f: &mut Frame, theme: Theme, path: &str, area: Rect) {
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Path: ", Style::default().fg(theme.dim)),
+            Span::styled(path.to_string(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.accent)),
+        ]),
+        Line::raw(""),
+        Line::from(Span::styled(
+            "Enter export (.json for JSON, otherwise CSV)  Esc cancel",
+            Style::default().fg(theme.dim),
+        )),
+    ];
+
+    render_popup(f, theme, " Export Transactions ", lines, area);
+}
+
+/// A balance colored by its sign, or, right after a reload moved it, by the
+/// direction it moved in
+fn balance_style(theme: Theme, balance: i64, change: Option<i64>) -> Style {
+    match change {
+        Some(delta) => Style::default()
+            .fg(if delta > 0 { theme.positive } else { theme.negative })
+            .add_modifier(Modifier::BOLD),
+        None => Style::default().fg(if balance >= 0 { theme.positive } else { theme.negative }),
+    }
+}
+
+/// " +12.50" or " -3.00" after a balance the last reload moved
+fn balance_change_span(theme: Theme, change: Option<i64>) -> Option<Span<'static>> {
+    change.map(|delta| {
+        let (sign, color) = if delta > 0 { ('+', theme.positive) } else { ('-', theme.negative) };
+        Span::styled(
+            format!(" {}{}", sign, grpc::format_balance(delta.abs())),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centered_rect_takes_a_share_of_the_area_around_its_middle() {
+        let area = Rect::new(10, 5, 100, 40);
+        assert_eq!(centered_rect(50, 50, area), Rect::new(35, 15, 50, 20));
+        assert_eq!(centered_rect(100, 100, area), area);
+        assert_eq!(centered_rect(150, 0, area), Rect::new(10, 25, 100, 0));
+        // Odd leftovers go to the right and bottom
+        assert_eq!(centered_rect(33, 33, Rect::new(0, 0, 10, 10)), Rect::new(3, 3, 3, 3));
+    }
+
+    #[test]
+    fn empty_lists_tell_loading_and_failure_apart_from_no_data() {
+        let empty = "No participants found. Is the server seeded?";
+        assert_eq!(empty_state_lines(&LoadState::Loading, empty, "r"), ["Loading…"]);
+        assert_eq!(empty_state_lines(&LoadState::Loaded, empty, "r"), [empty]);
+        let failed = LoadState::Failed("list_participants: Unavailable: connection refused".to_string());
+        assert_eq!(
+            empty_state_lines(&failed, empty, "F5"),
+            [
+                "Could not load: list_participants: Unavailable: connection refused",
+                "Press F5 to retry."
+            ]
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Ledger Verification
+//!
+//! `scalegraph verify` fetches every participant's accounts and the most
+//! recent transactions from the live server and checks the invariants the
+//! ledger is supposed to keep:
+//!
+//! - every transaction's entries sum to zero
+//! - ecosystem receivables equal payables
+//! - no operating account is negative
+//! - every account's balance equals the sum of its entries
+//!
+//! Each check prints PASS, WARN or FAIL with the offending ids, and the
+//! command exits with status 1 if any check failed. Accounts can be opened
+//! with an initial balance that is not posted as a transaction, so a balance
+//! that differs from its history is only a warning unless `--strict` is given.
+
+use crate::grpc::common::{Account, AccountType, Transaction};
+use crate::grpc::{self, ScalegraphClient};
+use anyhow::{anyhow, Result};
+use scalegraph_config::Config;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::timeout;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Offending ids listed per check before the rest are counted
+const MAX_DETAILS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    /// Suspicious, but explained by something the ledger allows
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub outcome: Outcome,
+    pub summary: String,
+    pub details: Vec<String>,
+}
+
+impl Check {
+    fn new(name: &'static str, outcome: Outcome, summary: String, details: Vec<String>) -> Self {
+        Self {
+            name,
+            outcome,
+            summary,
+            details,
+        }
+    }
+}
+
+/// Run every check against `config`'s server, scanning up to `limit`
+/// transactions, and print the report. Exits with status 1 on a failed
+/// check, or on a warning with `strict`.
+pub async fn run(config: &Config, limit: i32, strict: bool) -> Result<()> {
+    let mut client = match timeout(CONNECT_TIMEOUT, ScalegraphClient::connect(config)).await {
+        Ok(Ok(client)) => client,
+        Ok(Err(e)) => return Err(e.context(format!("connecting to {}", config.grpc_url()))),
+        Err(_) => return Err(anyhow!("connecting to {} timed out", config.grpc_url())),
+    };
+
+    let participants = client.list_participants(None).await?;
+    let ids: Vec<String> = participants.into_iter().map(|p| p.id).collect();
+    let mut accounts = Vec::new();
+    for (id, result) in ids.iter().zip(client.get_accounts_of(&ids).await) {
+        accounts.extend(result.map_err(|e| e.context(format!("accounts of {}", id)))?);
+    }
+    let transactions = client.list_transactions(Some(limit), None).await?;
+    // Fewer than asked for means nothing older was left out
+    let complete = transactions.len() < limit as usize;
+
+    println!(
+        "Verifying {}: {} accounts, {} transactions{}",
+        config.grpc_url(),
+        accounts.len(),
+        transactions.len(),
+        if complete { "" } else { " (most recent only)" }
+    );
+    println!();
+
+    let checks = [
+        transactions_balance(&transactions),
+        receivables_match_payables(&accounts),
+        operating_not_negative(&accounts),
+        balances_match_entries(&accounts, &transactions, complete),
+    ];
+    for check in &checks {
+        let label = match check.outcome {
+            Outcome::Pass => "PASS",
+            Outcome::Warn => "WARN",
+            Outcome::Fail => "FAIL",
+        };
+        println!("{}  {}: {}", label, check.name, check.summary);
+        for detail in check.details.iter().take(MAX_DETAILS) {
+            println!("      {}", detail);
+        }
+        if check.details.len() > MAX_DETAILS {
+            println!("      ... and {} more", check.details.len() - MAX_DETAILS);
+        }
+    }
+
+    let failed = checks.iter().filter(|c| c.outcome == Outcome::Fail).count();
+    let warned = checks.iter().filter(|c| c.outcome == Outcome::Warn).count();
+    println!();
+    println!("{} checks, {} failed, {} warnings", checks.len(), failed, warned);
+    if failed > 0 || (strict && warned > 0) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Every transaction's entries sum to zero
+pub fn transactions_balance(transactions: &[Transaction]) -> Check {
+    let details: Vec<String> = transactions
+        .iter()
+        .filter_map(|tx| {
+            let sum: i64 = tx.entries.iter().map(|e| e.amount).sum();
+            (sum != 0).then(|| format!("{} entries sum to {}", tx.id, grpc::format_balance(sum)))
+        })
+        .collect();
+    let name = "transactions balance";
+    if details.is_empty() {
+        Check::new(name, Outcome::Pass, format!("all {} sum to zero", transactions.len()), details)
+    } else {
+        Check::new(name, Outcome::Fail, format!("{} do not sum to zero", details.len()), details)
+    }
+}
+
+/// Receivables across the ecosystem equal payables; payables are held as
+/// negative balances, so the two sum to zero
+pub fn receivables_match_payables(accounts: &[Account]) -> Check {
+    let total = |kind: AccountType| -> i64 {
+        accounts
+            .iter()
+            .filter(|a| a.account_type == kind as i32)
+            .map(|a| a.balance)
+            .sum()
+    };
+    let receivables = total(AccountType::Receivables);
+    let payables = -total(AccountType::Payables);
+    let summary = format!(
+        "receivables {}, payables {}",
+        grpc::format_balance(receivables),
+        grpc::format_balance(payables)
+    );
+    let name = "receivables match payables";
+    if receivables == payables {
+        Check::new(name, Outcome::Pass, summary, Vec::new())
+    } else {
+        let gap = format!("off by {}", grpc::format_balance(receivables - payables));
+        Check::new(name, Outcome::Fail, summary, vec![gap])
+    }
+}
+
+/// No operating account has gone below zero
+pub fn operating_not_negative(accounts: &[Account]) -> Check {
+    let operating: Vec<&Account> = accounts
+        .iter()
+        .filter(|a| a.account_type == AccountType::Operating as i32)
+        .collect();
+    let details: Vec<String> = operating
+        .iter()
+        .filter(|a| a.balance < 0)
+        .map(|a| format!("{} is at {}", a.id, grpc::format_balance(a.balance)))
+        .collect();
+    let name = "operating accounts not negative";
+    if details.is_empty() {
+        Check::new(name, Outcome::Pass, format!("all {} at or above zero", operating.len()), details)
+    } else {
+        Check::new(name, Outcome::Fail, format!("{} below zero", details.len()), details)
+    }
+}
+
+/// Every account's balance equals the sum of its entries. Only meaningful
+/// when `complete` (the whole history was fetched); a difference is a
+/// warning, since it may be an initial balance set when the account opened.
+pub fn balances_match_entries(accounts: &[Account], transactions: &[Transaction], complete: bool) -> Check {
+    let name = "balances match entries";
+    if !complete {
+        return Check::new(
+            name,
+            Outcome::Warn,
+            "skipped: history is incomplete, raise --limit".to_string(),
+            Vec::new(),
+        );
+    }
+    let mut sums: HashMap<&str, i64> = HashMap::new();
+    for entry in transactions.iter().flat_map(|tx| &tx.entries) {
+        *sums.entry(entry.account_id.as_str()).or_insert(0) += entry.amount;
+    }
+    let details: Vec<String> = accounts
+        .iter()
+        .filter_map(|a| {
+            let sum = sums.get(a.id.as_str()).copied().unwrap_or(0);
+            (a.balance != sum).then(|| {
+                format!(
+                    "{} balance {} but entries sum to {} (off by {})",
+                    a.id,
+                    grpc::format_balance(a.balance),
+                    grpc::format_balance(sum),
+                    grpc::format_balance(a.balance - sum)
+                )
+            })
+        })
+        .collect();
+    if details.is_empty() {
+        Check::new(name, Outcome::Pass, format!("all {} agree with their entries", accounts.len()), details)
+    } else {
+        Check::new(
+            name,
+            Outcome::Warn,
+            format!("{} differ; initial balances are not posted as transactions", details.len()),
+            details,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::common::TransferEntry;
+
+    fn account(id: &str, kind: AccountType, balance: i64) -> Account {
+        Account {
+            id: id.to_string(),
+            participant_id: id.split(':').next().unwrap_or_default().to_string(),
+            account_type: kind as i32,
+            balance,
+            ..Default::default()
+        }
+    }
+
+    fn tx(id: &str, entries: &[(&str, i64)]) -> Transaction {
+        Transaction {
+            id: id.to_string(),
+            entries: entries
+                .iter()
+                .map(|(account_id, amount)| TransferEntry {
+                    account_id: account_id.to_string(),
+                    amount: *amount,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_consistent_ledger_passes_every_check() {
+        let accounts = [
+            account("a:operating", AccountType::Operating, 700),
+            account("b:operating", AccountType::Operating, 300),
+            account("a:receivables", AccountType::Receivables, 300),
+            account("b:payables", AccountType::Payables, -300),
+            account("bank:standalone", AccountType::Standalone, -1000),
+        ];
+        let transactions = [
+            tx("tx1", &[("bank:standalone", -1000), ("a:operating", 1000)]),
+            tx("tx2", &[("a:operating", -300), ("b:operating", 300)]),
+            tx("tx3", &[("a:receivables", 300), ("b:payables", -300)]),
+        ];
+        let checks = [
+            transactions_balance(&transactions),
+            receivables_match_payables(&accounts),
+            operating_not_negative(&accounts),
+            balances_match_entries(&accounts, &transactions, true),
+        ];
+        for check in &checks {
+            assert_eq!(check.outcome, Outcome::Pass, "{:?}", check);
+        }
+    }
+
+    #[test]
+    fn broken_invariants_name_the_offenders() {
+        let unbalanced = transactions_balance(&[tx("tx1", &[("a", 100), ("b", -90)])]);
+        assert_eq!(unbalanced.outcome, Outcome::Fail);
+        assert!(unbalanced.details[0].starts_with("tx1 "));
+
+        let accounts = [
+            account("a:operating", AccountType::Operating, -5),
+            account("a:receivables", AccountType::Receivables, 300),
+            account("b:payables", AccountType::Payables, -200),
+        ];
+        assert_eq!(receivables_match_payables(&accounts).outcome, Outcome::Fail);
+        let negative = operating_not_negative(&accounts);
+        assert_eq!(negative.outcome, Outcome::Fail);
+        assert_eq!(negative.details.len(), 1);
+
+        // An opening balance with no transaction behind it is only a warning
+        let opened = balances_match_entries(&accounts[..1], &[], true);
+        assert_eq!(opened.outcome, Outcome::Warn);
+        assert!(opened.details[0].contains("off by"));
+        let partial = balances_match_entries(&accounts, &[], false);
+        assert_eq!(partial.outcome, Outcome::Warn);
+        assert!(partial.summary.starts_with("skipped"));
+    }
+}
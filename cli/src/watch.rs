@@ -0,0 +1,98 @@
+//! Health Watch Mode
+//!
+//! `scalegraph watch` is a terminal-friendly uptime checker: it probes the
+//! server with `list_participants` every few seconds and prints one
+//! timestamped line per probe, without starting the TUI. A failed connection
+//! counts as a failed probe and is retried on the next tick, so the watch
+//! keeps running through server restarts.
+
+use crate::grpc::{self, ScalegraphClient};
+use anyhow::Result;
+use scalegraph_config::Config;
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+/// How long a single probe (connect + call) may take before it counts as failed
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of one probe: latency and participant count, or why it failed
+type Probe = std::result::Result<(Duration, usize), String>;
+
+/// Probe `config`'s server every `interval` until Ctrl+C. With `fail_after`,
+/// exits with status 1 once that many consecutive probes have failed.
+pub async fn run(config: &Config, interval: Duration, fail_after: Option<u32>) -> Result<()> {
+    println!("Watching {} every {}s (Ctrl+C to stop)", config.grpc_url(), interval.as_secs());
+    println!("{:<19}  {:<6}  {:>10}  {:>12}  DETAIL", "TIME", "STATUS", "LATENCY ms", "PARTICIPANTS");
+
+    let mut client: Option<ScalegraphClient> = None;
+    let mut ticker = tokio::time::interval(interval);
+    let (mut probes, mut failures, mut consecutive) = (0u32, 0u32, 0u32);
+    // One listener for the whole watch, so Ctrl+C also stops a probe that is
+    // still waiting on a slow server
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = &mut ctrl_c => break,
+        }
+
+        let result = tokio::select! {
+            result = probe(config, &mut client) => result,
+            _ = &mut ctrl_c => break,
+        };
+        probes += 1;
+        let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+        match result {
+            Ok((latency, participants)) => {
+                consecutive = 0;
+                println!(
+                    "{:<19}  {:<6}  {:>10.1}  {:>12}",
+                    time,
+                    "UP",
+                    latency.as_secs_f64() * 1000.0,
+                    participants
+                );
+            }
+            Err(detail) => {
+                failures += 1;
+                consecutive += 1;
+                // Drop the channel so the next probe reconnects from scratch
+                client = None;
+                println!("{:<19}  {:<6}  {:>10}  {:>12}  {}", time, "DOWN", "-", "-", detail);
+                if fail_after.is_some_and(|k| consecutive >= k) {
+                    eprintln!(
+                        "ALERT: {} consecutive failed probes of {}",
+                        consecutive,
+                        config.grpc_url()
+                    );
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{} probes, {} failed", probes, failures);
+    Ok(())
+}
+
+async fn probe(config: &Config, client: &mut Option<ScalegraphClient>) -> Probe {
+    if client.is_none() {
+        match timeout(PROBE_TIMEOUT, ScalegraphClient::connect(config)).await {
+            Ok(Ok(connected)) => *client = Some(connected),
+            Ok(Err(e)) => return Err(format!("connect: {}", e)),
+            Err(_) => return Err("connect: timed out".to_string()),
+        }
+    }
+    let Some(client) = client.as_mut() else {
+        return Err("not connected".to_string());
+    };
+    let started = Instant::now();
+    match timeout(PROBE_TIMEOUT, client.list_participants(None)).await {
+        Ok(Ok(participants)) => Ok((started.elapsed(), participants.len())),
+        Ok(Err(e)) => Err(grpc::error_summary(&e)),
+        Err(_) => Err("list_participants: timed out".to_string()),
+    }
+}
@@ -120,13 +120,17 @@ defmodule Scalegraph.Business.Server do
 
   @doc """
   Create a loan - lender provides funds and records obligation.
+
+  The interest rate and term are stored on the loan contract.
   """
   def create_loan(request, _stream) do
     case Transactions.create_loan(
            request.lender_id,
            request.borrower_id,
-           request.amount,
-           request.reference
+           request.principal_cents,
+           request.reference,
+           annual_interest_rate: request.annual_interest_rate,
+           term_months: request.term_months
          ) do
       {:ok, result} ->
         %BusinessProto.BusinessTransactionResponse{
@@ -135,9 +139,12 @@ defmodule Scalegraph.Business.Server do
           amount: result.amount,
           platform_fee: 0,
           status: "completed",
-          message: "Loan created: #{format_amount(result.amount)}"
+          message: "Loan created: #{format_amount(result.amount)}#{loan_terms(result)}"
         }
 
+      {:error, {:invalid_terms, message}} ->
+        business_error(:invalid_argument, message)
+
       {:error, {:not_found, account_id}} ->
         business_error(:not_found, "Account not found: #{account_id}")
 
@@ -234,6 +241,14 @@ defmodule Scalegraph.Business.Server do
     raise GRPC.RPCError, status: status, message: message
   end
 
+  # " at 5.00% over 60 months, 28.31 a month"; nothing for a loan without a term
+  defp loan_terms(%{term_months: term, monthly_payment: payment} = result) when term > 0 do
+    rate = :erlang.float_to_binary(result.annual_interest_rate * 100, decimals: 2)
+    " at #{rate}% over #{term} months, #{format_amount(payment)} a month"
+  end
+
+  defp loan_terms(_result), do: ""
+
   defp format_amount(cents) do
     whole = div(cents, 100)
     frac = rem(abs(cents), 100)
@@ -308,6 +308,12 @@ defmodule Scalegraph.Business.Transactions do
   - `borrower_id` - The borrower's participant ID (e.g., "salon_glamour")
   - `amount` - Loan amount in cents
   - `reference` - Loan reference (e.g., "LOAN-2024-001")
+  - `opts` - Options:
+    - `:annual_interest_rate` - Annual rate as a decimal, e.g. 0.05 (default: 0.0)
+    - `:term_months` - Repayment term in months; 0 means no fixed term (default: 0)
+
+  The rate and term are stored on the loan contract, along with the
+  monthly payment of a fully amortizing loan when a term is given.
 
   ## What happens (all atomic)
   1. Money moves: lender's operating → borrower's operating
@@ -315,11 +321,48 @@ defmodule Scalegraph.Business.Transactions do
   3. Record debt: borrower's payables decreases (negative = owes)
 
   ## Example
-      iex> create_loan("seb", "salon_glamour", 150023, "LOAN-2024-001")
-      {:ok, %{transaction_id: "...", loan_ref: "LOAN-2024-001"}}
+      iex> create_loan("seb", "salon_glamour", 150023, "LOAN-2024-001",
+      ...>   annual_interest_rate: 0.05, term_months: 60)
+      {:ok, %{transaction_id: "...", loan_ref: "LOAN-2024-001", monthly_payment: 2831}}
   """
-  def create_loan(lender_id, borrower_id, amount, reference)
+  def create_loan(lender_id, borrower_id, amount, reference, opts \\ [])
+
+  def create_loan(lender_id, borrower_id, amount, reference, opts)
       when is_binary(lender_id) and is_binary(borrower_id) and is_integer(amount) and amount > 0 do
+    rate = Keyword.get(opts, :annual_interest_rate, 0.0)
+    term_months = Keyword.get(opts, :term_months, 0)
+
+    cond do
+      not (is_number(rate) and rate >= 0) ->
+        {:error, {:invalid_terms, "annual interest rate must be zero or more, got #{inspect(rate)}"}}
+
+      not (is_integer(term_months) and term_months >= 0) ->
+        {:error, {:invalid_terms, "term must be a whole number of months, got #{inspect(term_months)}"}}
+
+      true ->
+        disburse_loan(lender_id, borrower_id, amount, reference, rate / 1, term_months)
+    end
+  end
+
+  def create_loan(_lender_id, _borrower_id, amount, _reference, _opts) do
+    {:error, {:invalid_terms, "loan amount must be a positive number of cents, got #{inspect(amount)}"}}
+  end
+
+  @doc """
+  Monthly payment, in cents, of a fully amortizing loan of `principal`
+  cents at `annual_rate` over `term_months`. Returns nil without a term.
+  """
+  def monthly_payment(_principal, _annual_rate, term_months) when term_months <= 0, do: nil
+
+  def monthly_payment(principal, annual_rate, term_months) when annual_rate == 0,
+    do: round(principal / term_months)
+
+  def monthly_payment(principal, annual_rate, term_months) do
+    r = annual_rate / 12
+    round(principal * r / (1 - :math.pow(1 + r, -term_months)))
+  end
+
+  defp disburse_loan(lender_id, borrower_id, amount, reference, rate, term_months) do
     alias Scalegraph.Ledger.Core, as: Ledger
     alias Scalegraph.Participant.Core, as: Participant
 
@@ -346,7 +389,17 @@ defmodule Scalegraph.Business.Transactions do
       case Ledger.transfer(entries, "LOAN: #{reference}") do
         {:ok, tx} ->
           # Create loan contract after ledger transaction succeeds
-          case Contracts.create_loan(lender_id, borrower_id, amount, tx.id, reference) do
+          payment = monthly_payment(amount, rate, term_months)
+
+          schedule =
+            if term_months > 0,
+              do: %{"term_months" => term_months, "monthly_payment_cents" => payment},
+              else: %{}
+
+          case Contracts.create_loan(lender_id, borrower_id, amount, tx.id, reference,
+                 interest_rate: rate,
+                 repayment_schedule: schedule
+               ) do
             {:ok, loan} ->
               Logger.info("Loan created: #{reference} for #{format_amount(amount)}")
 
@@ -357,7 +410,10 @@ defmodule Scalegraph.Business.Transactions do
                  loan_ref: reference,
                  lender: lender_id,
                  borrower: borrower_id,
-                 amount: amount
+                 amount: amount,
+                 annual_interest_rate: rate,
+                 term_months: term_months,
+                 monthly_payment: payment
                }}
 
             {:error, contract_reason} ->
@@ -373,6 +429,9 @@ defmodule Scalegraph.Business.Transactions do
                  lender: lender_id,
                  borrower: borrower_id,
                  amount: amount,
+                 annual_interest_rate: rate,
+                 term_months: term_months,
+                 monthly_payment: payment,
                  warning: "Contract creation failed"
                }}
           end
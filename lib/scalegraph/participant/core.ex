@@ -577,6 +577,151 @@ defmodule Scalegraph.Participant.Core do
     {:error, reason}
   end
 
+  @doc """
+  Replace a participant's contact information.
+
+  `contact` is a map with the Contact fields (:email, :phone, :website,
+  :address, :postal_code, :city, :country). The whole block is replaced, so
+  an empty string clears a field. Records in an older format are upgraded.
+
+  Returns `{:error, {:invalid_contact, message}}` without writing anything
+  when a field fails `validate_contact/1`.
+  """
+  def update_contact(participant_id, contact)
+      when is_binary(participant_id) and is_map(contact) do
+    case validate_contact(contact) do
+      :ok -> write_contact_transaction(participant_id, contact)
+      {:error, message} -> {:error, {:invalid_contact, message}}
+    end
+  end
+
+  defp write_contact_transaction(participant_id, contact) do
+    result =
+      :mnesia.transaction(fn ->
+        case :mnesia.read(Schema.participants_table(), participant_id) do
+          [] ->
+            :mnesia.abort({:error, :not_found})
+
+          # Handle old format (5 fields)
+          [{_table, id, name, role, created_at, metadata}] ->
+            write_contact(id, name, role, created_at, metadata, [], "", contact)
+
+          # Handle format (6 fields - services added)
+          [{_table, id, name, role, created_at, metadata, services}] ->
+            write_contact(id, name, role, created_at, metadata, services || [], "", contact)
+
+          # Handle current format (8 fields - with about and contact)
+          [{_table, id, name, role, created_at, metadata, services, about, _contact}] ->
+            write_contact(id, name, role, created_at, metadata, services || [], about || "", contact)
+        end
+      end)
+
+    handle_service_operation_result(result)
+  end
+
+  @doc """
+  Check the fields of a contact map that have a recognisable shape: an
+  email like name@example.com, 5-15 phone digits with an optional leading +
+  (spaces, dashes, dots and parentheses allowed), and a website with a
+  dotted host and at most an http(s) scheme. Empty or missing fields pass.
+
+  The CLI and MCP server apply the same rules before sending a contact
+  (`scalegraph_config::contact::validate_contact`).
+  """
+  def validate_contact(contact) when is_map(contact) do
+    with :ok <- check_email(Map.get(contact, :email) || ""),
+         :ok <- check_phone(Map.get(contact, :phone) || "") do
+      check_website(Map.get(contact, :website) || "")
+    end
+  end
+
+  defp check_email(""), do: :ok
+
+  defp check_email(email) do
+    valid =
+      case String.split(email, "@", parts: 2) do
+        [local, domain] ->
+          local != "" and not String.contains?(domain, "@") and String.contains?(domain, ".") and
+            not String.starts_with?(domain, ".") and not String.ends_with?(domain, ".")
+
+        _ ->
+          false
+      end
+
+    if valid and not String.match?(email, ~r/\s/u) do
+      :ok
+    else
+      {:error, "email '#{email}' is not an address like name@example.com"}
+    end
+  end
+
+  defp check_phone(""), do: :ok
+
+  defp check_phone(phone) do
+    chars = String.codepoints(phone)
+
+    allowed =
+      chars
+      |> Enum.with_index()
+      |> Enum.all?(fn {c, i} ->
+        ascii_digit?(c) or c in [" ", "-", "(", ")", "."] or (c == "+" and i == 0)
+      end)
+
+    digits = Enum.count(chars, &ascii_digit?/1)
+
+    if allowed and digits in 5..15 do
+      :ok
+    else
+      {:error,
+       "phone '#{phone}' must be 5-15 digits, optionally with a leading + and spaces, dashes, dots or parentheses"}
+    end
+  end
+
+  defp check_website(""), do: :ok
+
+  defp check_website(website) do
+    rest =
+      cond do
+        String.starts_with?(website, "https://") -> String.replace_prefix(website, "https://", "")
+        String.starts_with?(website, "http://") -> String.replace_prefix(website, "http://", "")
+        true -> website
+      end
+
+    [host | _] = String.split(rest, ["/", "?", "#"], parts: 2)
+
+    valid =
+      not String.match?(website, ~r/\s/u) and not String.contains?(rest, "://") and
+        String.contains?(host, ".") and not String.starts_with?(host, ".") and
+        not String.ends_with?(host, ".")
+
+    if valid do
+      :ok
+    else
+      {:error, "website '#{website}' is not a URL like https://example.com"}
+    end
+  end
+
+  defp ascii_digit?(c), do: c >= "0" and c <= "9"
+
+  defp write_contact(id, name, role, created_at, metadata, services, about, contact) do
+    record =
+      {Schema.participants_table(), id, name, role, created_at, metadata, services, about, contact}
+
+    :mnesia.write(record)
+
+    {:ok,
+     %{
+       id: id,
+       name: name,
+       role: role,
+       created_at: created_at,
+       metadata: metadata,
+       services: services,
+       about: about,
+       contact: contact
+     }}
+  end
+
   @doc """
   List all services provided by a participant.
   """
@@ -130,7 +130,19 @@ defmodule Scalegraph.Participant.Server do
   Create an account for a participant.
   """
   def create_participant_account(request, _stream) do
-    account_type = Map.get(@account_type_mapping, request.account_type, :operating)
+    # An unset or unknown type must not silently become an operating account
+    account_type =
+      case Map.fetch(@account_type_mapping, request.account_type) do
+        {:ok, account_type} ->
+          account_type
+
+        :error ->
+          business_error(
+            :invalid_argument,
+            "Invalid account type #{inspect(request.account_type)}. Valid: #{inspect(Map.values(@account_type_mapping))}"
+          )
+      end
+
     metadata = Map.new(request.metadata || [])
 
     case Core.create_participant_account(
@@ -258,6 +270,46 @@ defmodule Scalegraph.Participant.Server do
     end
   end
 
+  @doc """
+  Replace a participant's contact information.
+  """
+  def update_participant_contact(request, _stream) do
+    contact = request.contact || %Common.Contact{}
+
+    contact_map = %{
+      email: contact.email || "",
+      phone: contact.phone || "",
+      website: contact.website || "",
+      address: contact.address || "",
+      postal_code: contact.postal_code || "",
+      city: contact.city || "",
+      country: contact.country || ""
+    }
+
+    case Core.update_contact(request.participant_id, contact_map) do
+      {:ok, participant} ->
+        Logger.info("Contact updated for participant #{request.participant_id}")
+        participant_to_proto(participant)
+
+      {:error, :not_found} ->
+        business_error(:not_found, "Participant not found: #{request.participant_id}")
+
+      {:error, {:invalid_contact, message}} ->
+        business_error(:invalid_argument, message)
+
+      {:error, {:schema_mismatch, message}} ->
+        Logger.error("Schema mismatch error: #{message}")
+
+        business_error(
+          :failed_precondition,
+          "Database schema mismatch. The participants table may have been created with an older schema. Try recreating the table or clearing the database."
+        )
+
+      {:error, reason} ->
+        system_error("Failed to update contact", reason)
+    end
+  end
+
   # Business errors - expected conditions, logged at info level
   defp business_error(status, message) do
     Logger.info("Business error [#{status}]: #{message}")
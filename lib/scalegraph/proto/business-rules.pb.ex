@@ -128,6 +128,15 @@ defmodule Scalegraph.Business.ListServicesResponse do
   field :services, 1, repeated: true, type: :string
 end
 
+defmodule Scalegraph.Business.UpdateParticipantContactRequest do
+  @moduledoc false
+
+  use Protobuf, protoc_gen_elixir_version: "0.15.0", syntax: :proto3
+
+  field :participant_id, 1, type: :string, json_name: "participantId"
+  field :contact, 2, type: Scalegraph.Common.Contact
+end
+
 defmodule Scalegraph.Business.BusinessTransactionResponse do
   @moduledoc false
 
@@ -401,6 +410,10 @@ defmodule Scalegraph.Business.ParticipantService.Service do
   rpc :ListServices,
       Scalegraph.Business.ListServicesRequest,
       Scalegraph.Business.ListServicesResponse
+
+  rpc :UpdateParticipantContact,
+      Scalegraph.Business.UpdateParticipantContactRequest,
+      Scalegraph.Common.Participant
 end
 
 defmodule Scalegraph.Business.ParticipantService.Stub do
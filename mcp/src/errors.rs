@@ -0,0 +1,108 @@
+//! JSON-RPC error codes, and how gRPC failures from the ledger map onto them.
+//!
+//! Codes from -32000 to -32099 are reserved by JSON-RPC for server-defined
+//! errors; the ledger's gRPC status codes that have no standard JSON-RPC
+//! equivalent get one of those.
+
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct JsonRpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+/// JSON-RPC error code for a message that is not a valid request
+pub const INVALID_REQUEST: i32 = -32600;
+
+/// JSON-RPC error code for invalid method parameters
+pub const INVALID_PARAMS: i32 = -32602;
+
+/// JSON-RPC error code for a failure inside the server, such as a panicking tool
+pub const INTERNAL_ERROR: i32 = -32603;
+
+/// The caller may not perform the operation (gRPC PermissionDenied or Unauthenticated)
+pub const PERMISSION_DENIED: i32 = -32001;
+
+/// The ledger did not answer within the deadline
+pub const DEADLINE_EXCEEDED: i32 = -32002;
+
+/// The ledger could not be reached; retrying later may succeed
+pub const UNAVAILABLE: i32 = -32003;
+
+/// A participant, account or contract named in the call does not exist
+pub const NOT_FOUND: i32 = -32004;
+
+/// The ledger refused the operation in its current state, e.g. insufficient funds
+pub const FAILED_PRECONDITION: i32 = -32005;
+
+/// The participant, account or contract being created already exists
+pub const ALREADY_EXISTS: i32 = -32006;
+
+/// JSON-RPC error for a gRPC status returned by the ledger. The status message
+/// is kept as is, since it names the participant or account at fault.
+pub fn map_status_to_jsonrpc(status: &tonic::Status) -> JsonRpcError {
+    use tonic::Code;
+
+    let code = match status.code() {
+        Code::InvalidArgument | Code::OutOfRange => INVALID_PARAMS,
+        Code::NotFound => NOT_FOUND,
+        Code::AlreadyExists => ALREADY_EXISTS,
+        Code::FailedPrecondition | Code::Aborted => FAILED_PRECONDITION,
+        Code::PermissionDenied | Code::Unauthenticated => PERMISSION_DENIED,
+        Code::Unavailable | Code::ResourceExhausted => UNAVAILABLE,
+        Code::DeadlineExceeded | Code::Cancelled => DEADLINE_EXCEEDED,
+        Code::Ok
+        | Code::Unknown
+        | Code::Unimplemented
+        | Code::Internal
+        | Code::DataLoss => INTERNAL_ERROR,
+    };
+    let message = if status.message().is_empty() {
+        status.code().description().to_string()
+    } else {
+        status.message().to_string()
+    };
+    JsonRpcError { code, message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::{Code, Status};
+
+    #[test]
+    fn statuses_map_to_stable_codes_and_keep_their_message() {
+        let cases = [
+            (Code::NotFound, NOT_FOUND),
+            (Code::InvalidArgument, INVALID_PARAMS),
+            (Code::Unavailable, UNAVAILABLE),
+            (Code::DeadlineExceeded, DEADLINE_EXCEEDED),
+            (Code::PermissionDenied, PERMISSION_DENIED),
+            (Code::Unauthenticated, PERMISSION_DENIED),
+            (Code::FailedPrecondition, FAILED_PRECONDITION),
+            (Code::AlreadyExists, ALREADY_EXISTS),
+            (Code::Internal, INTERNAL_ERROR),
+            (Code::Unknown, INTERNAL_ERROR),
+        ];
+        for (code, expected) in cases {
+            let message = format!("account salon_glamour:operating failed with {:?}", code);
+            let error = map_status_to_jsonrpc(&Status::new(code, message.clone()));
+            assert_eq!(error, JsonRpcError { code: expected, message }, "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn server_defined_codes_stay_in_the_reserved_range() {
+        for code in [PERMISSION_DENIED, DEADLINE_EXCEEDED, UNAVAILABLE, NOT_FOUND, FAILED_PRECONDITION, ALREADY_EXISTS] {
+            assert!((-32099..=-32000).contains(&code), "{}", code);
+        }
+    }
+
+    #[test]
+    fn an_empty_status_message_falls_back_to_the_code_description() {
+        let error = map_status_to_jsonrpc(&Status::new(Code::Unavailable, ""));
+        assert_eq!(error.code, UNAVAILABLE);
+        assert_eq!(error.message, Code::Unavailable.description());
+    }
+}
@@ -3,23 +3,37 @@
 //! Exposes ledger and business transaction operations via Model Context Protocol (MCP)
 //! for integration with LLMs like Claude Desktop.
 //!
-//! Run: ./target/release/scalegraph-mcp
+//! Run: ./target/release/scalegraph-mcp [--config scalegraph.toml]
+//!
+//! Connection settings (grpc_url, auth_token, tls_ca, rpc_timeout) can be read
+//! from a scalegraph.toml given by --config or SCALEGRAPH_CONFIG; file values
+//! override the environment.
 //!
 //! Environment variables:
+//!   SCALEGRAPH_CONFIG - Path to scalegraph.toml
 //!   SCALEGRAPH_GRPC_URL - gRPC server URL (default: http://localhost:50051)
+//!   SCALEGRAPH_AUTH_TOKEN - Bearer token sent with every gRPC call
 //!   SCALEGRAPH_DEBUG - Enable debug output to stderr
+//!   SCALEGRAPH_LOG_FILE - Append JSON-lines logs of every request to this file
+//!   SCALEGRAPH_COMPACT_JSON - Single-line tool result text instead of pretty JSON
 //!
 //! Tools exposed:
 //! - list_participants: List all participants in the ecosystem
 //! - get_participant_accounts: Get all accounts for a participant
+//! - update_participant_services: Set, add or remove a participant's service tags
 //! - get_account_balance: Get balance for a specific account
+//! - get_ledger_summary: Ecosystem-wide totals by account type and role
 //! - transfer: Execute atomic multi-party transfer
+//! - simulate_transfer: Check a transfer against current balances without posting it
 //! - purchase_invoice: Create B2B purchase invoice (receivables/payables)
 //! - pay_invoice: Pay/settle a B2B invoice
 //! - access_payment: Real-time micro-payment for access control
 //!
 //! Configure in Claude Desktop's settings as a stdio MCP server.
 
+// The tool catalogue in get_tools_list is one large json! literal
+#![recursion_limit = "256"]
+
 #[allow(dead_code)]
 pub mod common {
     tonic::include_proto!("scalegraph.common");
@@ -40,31 +54,44 @@ pub mod smartcontracts {
     tonic::include_proto!("scalegraph.smartcontracts");
 }
 
+mod errors;
+mod references;
+
 use anyhow::Result;
-use common::TransferEntry;
+use errors::{map_status_to_jsonrpc, JsonRpcError, INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST};
+use common::{Account, AccountType, Contact, Participant, ParticipantRole, Transaction, TransferEntry};
 use ledger::{
-    ledger_service_client::LedgerServiceClient, GetBalanceRequest, ListTransactionsRequest,
-    TransferRequest,
+    ledger_service_client::LedgerServiceClient, GetAccountRequest, GetBalanceRequest,
+    ListTransactionsRequest, TransferRequest,
 };
 use business::{
     business_service_client::BusinessServiceClient, participant_service_client::ParticipantServiceClient,
     AccessPaymentRequest, CreateLoanRequest, CreateParticipantAccountRequest,
     CreateParticipantRequest, GetOutstandingLoansRequest, GetParticipantAccountsRequest,
-    GetTotalDebtRequest, ListParticipantsRequest, PayInvoiceRequest, PurchaseInvoiceRequest,
-    RepayLoanRequest,
+    GetParticipantRequest, GetTotalDebtRequest, ListParticipantsRequest, PayInvoiceRequest, PurchaseInvoiceRequest,
+    RepayLoanRequest, AddServiceRequest, RemoveServiceRequest, UpdateParticipantContactRequest,
 };
 use smartcontracts::{
     smart_contract_service_client::SmartContractServiceClient,
     ContractType, ContractStatus,
     CreateInvoiceContractRequest, CreateSubscriptionContractRequest,
     CreateConditionalPaymentRequest, CreateRevenueShareContractRequest,
-    GetContractRequest, ListContractsRequest, ExecuteContractRequest,
+    CreateGenericContractRequest, GetContractRequest, ListContractsRequest, ExecuteContractRequest,
     UpdateContractStatusRequest, RevenueShareParty,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
 use std::io::{self, BufRead, Write};
-use tonic::transport::Channel;
+use std::time::Instant;
+use references::{split_memos, CheckedReference, ReferenceFormat, ReferenceKind};
+use scalegraph_config::{AuthChannel, Config};
+use scalegraph_rules::amounts::format_balance;
+use scalegraph_rules::contact::validate_contact;
+use scalegraph_rules::escrow::{check_escrow_account, EscrowMove};
+use scalegraph_rules::loans::{self, MAX_TERM_MONTHS};
+use scalegraph_rules::shares::validate_revenue_shares;
+use scalegraph_rules::transactions::{reversal_entries, transaction_type, TRANSACTION_TYPES};
 
 // ============================================================================
 // MCP Protocol Types
@@ -89,37 +116,203 @@ struct JsonRpcResponse {
     error: Option<JsonRpcError>,
 }
 
-#[derive(Debug, Serialize)]
-struct JsonRpcError {
-    code: i32,
-    message: String,
+/// Tool argument error reported as a JSON-RPC InvalidParams error rather than
+/// as a failed tool result
+#[derive(Debug)]
+struct InvalidParams(String);
+
+impl std::fmt::Display for InvalidParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvalidParams {}
+
+/// Emits `notifications/progress` messages for a tool call in flight.
+///
+/// Progress is only reported when the client asked for it by sending a
+/// `progressToken` in `params._meta`; otherwise `report` is a no-op. The
+/// writer must be the protocol channel (stdout), since notifications are
+/// interleaved with the eventual response.
+struct Progress {
+    token: Option<Value>,
+    out: Box<dyn Write>,
+}
+
+impl Progress {
+    fn new(token: Option<Value>, out: Box<dyn Write>) -> Self {
+        Self { token, out }
+    }
+
+    fn report(&mut self, progress: usize, total: usize, message: &str) -> io::Result<()> {
+        let Some(token) = &self.token else {
+            return Ok(());
+        };
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": {
+                "progressToken": token,
+                "progress": progress,
+                "total": total,
+                "message": message,
+            }
+        });
+        writeln!(self.out, "{}", notification)?;
+        self.out.flush()
+    }
+}
+
+/// Run `f` over each item in order, reporting progress after each one completes.
+async fn run_with_progress<T, F, Fut>(
+    items: Vec<T>,
+    progress: &mut Progress,
+    describe: impl Fn(&T) -> String,
+    mut f: F,
+) -> Result<Vec<Value>>
+where
+    F: FnMut(T) -> Fut,
+    Fut: std::future::Future<Output = Value>,
+{
+    let total = items.len();
+    let mut results = Vec::with_capacity(total);
+    for (i, item) in items.into_iter().enumerate() {
+        let label = describe(&item);
+        results.push(f(item).await);
+        progress.report(i + 1, total, &label)?;
+    }
+    Ok(results)
 }
 
 // ============================================================================
 // gRPC Client
 // ============================================================================
 
+/// How far back get_transaction looks for an id, and find_transactions_by_reference by default
+const GET_TRANSACTION_SCAN_LIMIT: i32 = 1000;
+
+/// How many contracts list_contracts fetches when it has to filter by participant itself
+const CONTRACT_SCAN_LIMIT: i32 = 1000;
+
+/// Metadata keys that name a party of a generic (YAML) contract
+const PARTICIPANT_METADATA_FIELDS: [&str; 8] = [
+    "supplier_id",
+    "buyer_id",
+    "provider_id",
+    "subscriber_id",
+    "payer_id",
+    "receiver_id",
+    "orchestrator_id",
+    "first_provider_id",
+];
+
 struct ScalegraphClient {
-    ledger: LedgerServiceClient<Channel>,
-    participant: ParticipantServiceClient<Channel>,
-    business: BusinessServiceClient<Channel>,
-    contracts: SmartContractServiceClient<Channel>,
+    ledger: LedgerServiceClient<AuthChannel>,
+    participant: ParticipantServiceClient<AuthChannel>,
+    business: BusinessServiceClient<AuthChannel>,
+    contracts: SmartContractServiceClient<AuthChannel>,
+    /// One connection each (see `SCALEGRAPH_CHANNELS`); the service clients
+    /// above use `channels[next_channel]` until `use_next_channel`
+    channels: Vec<AuthChannel>,
+    next_channel: usize,
+    /// Kept so the channel can be rebuilt after the server restarts
+    config: Config,
+    references: ReferenceFormat,
 }
 
 impl ScalegraphClient {
-    async fn connect(addr: &str) -> Result<Self> {
-        let channel = Channel::from_shared(addr.to_string())?.connect().await?;
+    async fn connect(config: &Config, references: ReferenceFormat, channel_count: usize) -> Result<Self> {
+        let mut channels = Vec::with_capacity(channel_count);
+        for _ in 0..channel_count.max(1) {
+            channels.push(config.connect().await?);
+        }
+        let channel = channels[0].clone();
 
         Ok(Self {
             ledger: LedgerServiceClient::new(channel.clone()),
             participant: ParticipantServiceClient::new(channel.clone()),
             business: BusinessServiceClient::new(channel.clone()),
             contracts: SmartContractServiceClient::new(channel),
+            channels,
+            next_channel: 0,
+            config: config.clone(),
+            references,
         })
     }
 
-    async fn list_participants(&mut self) -> Result<Value> {
-        let request = ListParticipantsRequest { role: 0 };
+    /// Replace the channels with fresh connections to the same server
+    async fn reconnect(&mut self) -> Result<()> {
+        *self = Self::connect(&self.config, self.references.clone(), self.channels.len()).await?;
+        Ok(())
+    }
+
+    /// Point the service clients at the next channel in the pool, round-robin.
+    /// With a single channel (the default) nothing changes.
+    fn use_next_channel(&mut self) {
+        if self.channels.len() < 2 {
+            return;
+        }
+        self.next_channel = (self.next_channel + 1) % self.channels.len();
+        let channel = &self.channels[self.next_channel];
+        self.ledger = LedgerServiceClient::new(channel.clone());
+        self.participant = ParticipantServiceClient::new(channel.clone());
+        self.business = BusinessServiceClient::new(channel.clone());
+        self.contracts = SmartContractServiceClient::new(channel.clone());
+    }
+
+    /// Check that each `(argument, participant_id)` names an existing participant,
+    /// so a typo comes back as "unknown participant" with the closest known id
+    /// instead of an opaque error from the business RPC.
+    async fn ensure_participants_exist(&mut self, ids: &[(&str, &str)]) -> Result<()> {
+        let mut known: Option<Vec<String>> = None;
+        for (arg, participant_id) in ids {
+            if participant_id.is_empty() {
+                anyhow::bail!("{} is required", arg);
+            }
+            let request = GetParticipantRequest {
+                participant_id: participant_id.to_string(),
+            };
+            match self.participant.get_participant(request).await {
+                Ok(_) => {}
+                Err(status) if status.code() == tonic::Code::NotFound => {
+                    if known.is_none() {
+                        let response = self
+                            .participant
+                            .list_participants(ListParticipantsRequest { role: 0 })
+                            .await?;
+                        known = Some(
+                            response
+                                .into_inner()
+                                .participants
+                                .into_iter()
+                                .map(|p| p.id)
+                                .collect(),
+                        );
+                    }
+                    let candidates = known.as_deref().unwrap_or_default();
+                    match closest_match(participant_id, candidates) {
+                        Some(suggestion) => anyhow::bail!(
+                            "unknown participant '{}' for {} (did you mean '{}'?)",
+                            participant_id,
+                            arg,
+                            suggestion
+                        ),
+                        None => anyhow::bail!(
+                            "unknown participant '{}' for {}",
+                            participant_id,
+                            arg
+                        ),
+                    }
+                }
+                Err(status) => return Err(status.into()),
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_participants(&mut self, role: i32) -> Result<Value> {
+        let request = ListParticipantsRequest { role };
         let response = self.participant.list_participants(request).await?;
         let participants: Vec<Value> = response
             .into_inner()
@@ -136,6 +329,55 @@ impl ScalegraphClient {
         Ok(json!({ "participants": participants }))
     }
 
+    /// Find participants by name or id fragment, see `resolve_participant_report`
+    async fn resolve_participant(&mut self, query: &str, role: i32) -> Result<Value> {
+        if normalize_name(query).is_empty() {
+            return Err(InvalidParams("query must contain letters or digits".to_string()).into());
+        }
+        let request = ListParticipantsRequest { role };
+        let participants = self.participant.list_participants(request).await?.into_inner().participants;
+        Ok(resolve_participant_report(query, &participants))
+    }
+
+    /// Groups of participants whose names look like the same company, with
+    /// each one's accounts so an operator can pick which to keep. Read-only.
+    async fn find_duplicate_participants(&mut self, role: i32) -> Result<Value> {
+        let request = ListParticipantsRequest { role };
+        let participants = self.participant.list_participants(request).await?.into_inner().participants;
+        let groups = duplicate_groups(&participants);
+
+        let mut groups_json = Vec::with_capacity(groups.len());
+        for group in &groups {
+            let mut members = Vec::with_capacity(group.len());
+            for &index in group {
+                let p = &participants[index];
+                let request = GetParticipantAccountsRequest {
+                    participant_id: p.id.clone(),
+                };
+                let accounts = self.participant.get_participant_accounts(request).await?.into_inner().accounts;
+                members.push(json!({
+                    "id": p.id,
+                    "name": p.name,
+                    "role": role_to_string(p.role),
+                    "account_count": accounts.len(),
+                    "total_balance_cents": accounts.iter().map(|a| a.balance).sum::<i64>(),
+                }));
+            }
+            let names: Vec<String> = group.iter().map(|&i| normalize_name(&participants[i].name)).collect();
+            let same_name = names.iter().all(|n| *n == names[0]);
+            groups_json.push(json!({
+                "match": if same_name { "same_name" } else { "similar_name" },
+                "participants": members,
+            }));
+        }
+        Ok(json!({
+            "participants_checked": participants.len(),
+            "group_count": groups_json.len(),
+            "groups": groups_json,
+            "note": "Nothing was changed. To consolidate, move balances with transfer and stop using the extra ids; participants cannot be merged or deleted through these tools.",
+        }))
+    }
+
     async fn create_participant(
         &mut self,
         id: &str,
@@ -166,6 +408,14 @@ impl ScalegraphClient {
         account_type: i32,
         initial_balance: i64,
     ) -> Result<Value> {
+        // The ledger would otherwise create an operating account for an unset type
+        if account_type == AccountType::Unspecified as i32 {
+            return Err(InvalidParams(format!(
+                "account_type must be one of: {}",
+                PARTICIPANT_ACCOUNT_TYPES.join(", ")
+            ))
+            .into());
+        }
         let request = CreateParticipantAccountRequest {
             participant_id: participant_id.to_string(),
             account_type,
@@ -184,14 +434,156 @@ impl ScalegraphClient {
         }))
     }
 
-    async fn get_participant_accounts(&mut self, participant_id: &str) -> Result<Value> {
+    /// Create the conventional accounts for a participant's role, skipping
+    /// types it already has. Without `role`, the participant's own role is used.
+    async fn setup_participant_accounts(
+        &mut self,
+        participant_id: &str,
+        role: Option<i32>,
+    ) -> Result<Value> {
+        let role = match role {
+            Some(role) => role,
+            None => {
+                let request = GetParticipantRequest {
+                    participant_id: participant_id.to_string(),
+                };
+                self.participant.get_participant(request).await?.into_inner().role
+            }
+        };
+        let account_types = default_account_types(role);
+        if account_types.is_empty() {
+            anyhow::bail!("no default accounts for role {}", role_to_string(role));
+        }
+
         let request = GetParticipantAccountsRequest {
             participant_id: participant_id.to_string(),
         };
-        let response = self.participant.get_participant_accounts(request).await?;
-        let accounts: Vec<Value> = response
+        let existing: Vec<i32> = self
+            .participant
+            .get_participant_accounts(request)
+            .await?
+            .into_inner()
+            .accounts
+            .iter()
+            .map(|a| a.account_type)
+            .collect();
+
+        let mut created = Vec::new();
+        let mut skipped = Vec::new();
+        for &account_type in account_types {
+            let account_type = account_type_string_to_int(account_type);
+            if existing.contains(&account_type) {
+                skipped.push(account_type_to_string(account_type));
+                continue;
+            }
+            let request = CreateParticipantAccountRequest {
+                participant_id: participant_id.to_string(),
+                account_type,
+                initial_balance: 0,
+                metadata: std::collections::HashMap::new(),
+            };
+            let a = self.participant.create_participant_account(request).await?.into_inner();
+            created.push(json!({
+                "id": a.id,
+                "type": account_type_to_string(a.account_type),
+                "balance": format_balance(a.balance),
+                "balance_cents": a.balance,
+            }));
+        }
+        Ok(json!({
+            "participant_id": participant_id,
+            "role": role_to_string(role),
+            "created": created,
+            "skipped_existing": skipped,
+        }))
+    }
+
+    /// Create `accounts` for a participant one after another. A type the
+    /// participant already has (or that appears earlier in the list) is
+    /// skipped when `skip_existing`, otherwise reported as failed; a failure
+    /// doesn't stop the remaining accounts.
+    async fn create_participant_accounts(
+        &mut self,
+        participant_id: &str,
+        accounts: Vec<(i32, i64)>,
+        skip_existing: bool,
+        progress: &mut Progress,
+    ) -> Result<Value> {
+        let request = GetParticipantAccountsRequest {
+            participant_id: participant_id.to_string(),
+        };
+        let mut existing: Vec<i32> = self
+            .participant
+            .get_participant_accounts(request)
+            .await?
             .into_inner()
             .accounts
+            .iter()
+            .map(|a| a.account_type)
+            .collect();
+
+        let total = accounts.len();
+        let mut results = Vec::with_capacity(total);
+        for (index, (account_type, initial_balance)) in accounts.into_iter().enumerate() {
+            let type_name = account_type_to_string(account_type);
+            if existing.contains(&account_type) {
+                let status = if skip_existing { "skipped" } else { "failed" };
+                results.push(json!({
+                    "index": index,
+                    "account_type": type_name,
+                    "status": status,
+                    "error": format!("{} already has a {} account", participant_id, type_name),
+                }));
+            } else {
+                let request = CreateParticipantAccountRequest {
+                    participant_id: participant_id.to_string(),
+                    account_type,
+                    initial_balance,
+                    metadata: std::collections::HashMap::new(),
+                };
+                match self.participant.create_participant_account(request).await {
+                    Ok(response) => {
+                        let a = response.into_inner();
+                        existing.push(account_type);
+                        results.push(json!({
+                            "index": index,
+                            "account_type": type_name,
+                            "status": "created",
+                            "id": a.id,
+                            "balance": format_balance(a.balance),
+                            "balance_cents": a.balance,
+                        }));
+                    }
+                    Err(status) => results.push(json!({
+                        "index": index,
+                        "account_type": type_name,
+                        "status": "failed",
+                        "error": status.message(),
+                    })),
+                }
+            }
+            progress.report(index + 1, total, &format!("Account {} ({})", index, type_name))?;
+        }
+
+        let count = |status: &str| results.iter().filter(|r| r["status"] == status).count();
+        Ok(json!({
+            "participant_id": participant_id,
+            "total": total,
+            "created": count("created"),
+            "skipped": count("skipped"),
+            "failed": count("failed"),
+            "results": results,
+        }))
+    }
+
+    async fn get_participant_accounts(&mut self, participant_id: &str) -> Result<Value> {
+        let request = GetParticipantAccountsRequest {
+            participant_id: participant_id.to_string(),
+        };
+        let response = self.participant.get_participant_accounts(request).await?;
+        let accounts = response.into_inner().accounts;
+        let summary = accounts_summary(&accounts);
+        let accounts: Vec<Value> = accounts
             .into_iter()
             .map(|a| {
                 json!({
@@ -204,7 +596,92 @@ impl ScalegraphClient {
             .collect();
         Ok(json!({
             "participant_id": participant_id,
-            "accounts": accounts
+            "accounts": accounts,
+            "summary": summary,
+        }))
+    }
+
+    /// Apply `changes` to a participant's services. The ledger only has
+    /// per-service RPCs, so every addition and removal is its own call; a
+    /// failure part way leaves the earlier ones applied.
+    async fn update_participant_services(
+        &mut self,
+        participant_id: &str,
+        changes: &ServiceChanges,
+    ) -> Result<Value> {
+        let request = GetParticipantRequest {
+            participant_id: participant_id.to_string(),
+        };
+        let participant = match self.participant.get_participant(request).await {
+            Ok(response) => response.into_inner(),
+            Err(status) if status.code() == tonic::Code::NotFound => {
+                anyhow::bail!("Participant {} not found", participant_id)
+            }
+            Err(status) => return Err(status.into()),
+        };
+
+        let (to_add, to_remove) = changes.plan(&participant.services);
+        let mut services = participant.services;
+        for service_id in &to_remove {
+            let request = RemoveServiceRequest {
+                participant_id: participant_id.to_string(),
+                service_id: service_id.clone(),
+            };
+            services = self.participant.remove_service(request).await?.into_inner().services;
+        }
+        for service_id in &to_add {
+            let request = AddServiceRequest {
+                participant_id: participant_id.to_string(),
+                service_id: service_id.clone(),
+            };
+            services = self.participant.add_service(request).await?.into_inner().services;
+        }
+
+        Ok(json!({
+            "participant_id": participant_id,
+            "services": services,
+            "added": to_add,
+            "removed": to_remove,
+        }))
+    }
+
+    /// Set the contact fields given in `args`, keeping the others. The RPC
+    /// replaces the whole contact block, so the current one is read first.
+    async fn update_participant_contact(&mut self, participant_id: &str, args: &Value) -> Result<Value> {
+        let request = GetParticipantRequest {
+            participant_id: participant_id.to_string(),
+        };
+        let participant = match self.participant.get_participant(request).await {
+            Ok(response) => response.into_inner(),
+            Err(status) if status.code() == tonic::Code::NotFound => {
+                anyhow::bail!("Participant {} not found", participant_id)
+            }
+            Err(status) => return Err(status.into()),
+        };
+        let current = participant.contact.unwrap_or_default();
+        let contact = contact_from_args(&current, args)?;
+        let changed: Vec<&str> = CONTACT_FIELDS
+            .iter()
+            .filter(|field| contact_field(&contact, field) != contact_field(&current, field))
+            .copied()
+            .collect();
+        if changed.is_empty() {
+            return Ok(json!({
+                "participant_id": participant_id,
+                "contact": contact_to_json(&contact),
+                "updated": changed,
+            }));
+        }
+
+        let request = UpdateParticipantContactRequest {
+            participant_id: participant_id.to_string(),
+            contact: Some(contact),
+        };
+        let updated = self.participant.update_participant_contact(request).await?.into_inner();
+        Ok(json!({
+            "participant_id": participant_id,
+            "contact": contact_to_json(&updated.contact.unwrap_or_default()),
+            "updated": changed,
         }))
     }
 
@@ -221,6 +698,47 @@ impl ScalegraphClient {
         }))
     }
 
+    /// Balance of `account_id` at `timestamp` (ms): the current balance with
+    /// every later entry among the `limit` most recent transactions undone.
+    /// Exact as long as those transactions reach back to `timestamp`.
+    async fn get_balance_as_of(
+        &mut self,
+        account_id: &str,
+        timestamp: i64,
+        limit: ListLimit,
+    ) -> Result<Value> {
+        let current = self.get_account(account_id).await?.balance;
+        let request = ListTransactionsRequest {
+            limit: limit.value,
+            account_id: account_id.to_string(),
+        };
+        let fetched = self.ledger.list_transactions(request).await?.into_inner().transactions;
+        let (balance, undone) = balance_as_of(account_id, current, &fetched, timestamp);
+
+        // A full page whose oldest transaction is still after `timestamp` may
+        // have missed older later-than-timestamp ones
+        let oldest = fetched.iter().map(|tx| tx.timestamp).min();
+        let truncated = fetched.len() >= limit.value as usize && oldest.is_some_and(|t| t > timestamp);
+        let mut result = json!({
+            "account_id": account_id,
+            "timestamp": timestamp,
+            "balance": format_balance(balance),
+            "balance_cents": balance,
+            "current_balance_cents": current,
+            "transactions_after": undone,
+            "transactions_scanned": fetched.len(),
+            "complete": !truncated,
+            "_meta": list_meta(fetched.len(), limit, truncated),
+        });
+        if truncated {
+            result["warning"] = json!(format!(
+                "All {} scanned transactions are after the requested time, so older ones may be missing and the balance may be wrong. Retry with a larger limit.",
+                fetched.len()
+            ));
+        }
+        Ok(result)
+    }
+
     async fn transfer(&mut self, entries: Vec<(String, i64)>, reference: &str) -> Result<Value> {
         let request = TransferRequest {
             entries: entries
@@ -235,43 +753,211 @@ impl ScalegraphClient {
             "transaction_id": tx.id,
             "type": tx.r#type,
             "reference": tx.reference,
-            "entries": tx.entries.iter().map(|e| json!({
-                "account_id": e.account_id,
-                "amount": format_balance(e.amount),
-                "amount_cents": e.amount,
-            })).collect::<Vec<_>>(),
+            "entries": entries_with_memos(&tx.entries, &tx.reference),
+            "net_change": net_change(&tx.entries),
         }))
     }
 
+    /// Dry run of a transfer: look up every account and replay the entries
+    /// against the current balances without posting anything
+    async fn simulate_transfer(&mut self, entries: &[(String, i64)], allow_overdraft: bool) -> Result<Value> {
+        let mut accounts = BTreeMap::new();
+        for (account_id, _) in entries {
+            if accounts.contains_key(account_id) {
+                continue;
+            }
+            let request = GetAccountRequest {
+                account_id: account_id.clone(),
+            };
+            let account = match self.ledger.get_account(request).await {
+                Ok(response) => Some(response.into_inner()),
+                Err(status) if status.code() == tonic::Code::NotFound => None,
+                Err(status) => return Err(status.into()),
+            };
+            accounts.insert(account_id.clone(), account);
+        }
+        Ok(simulate_transfer_report(entries, &accounts, allow_overdraft))
+    }
+
+    /// Post `transfers` one after another. The ledger has no multi-transfer
+    /// RPC, so each transfer is atomic but the batch is not: completed
+    /// transfers stay posted when a later one fails.
+    async fn batch_transfer(
+        &mut self,
+        transfers: Vec<BatchItem>,
+        stop_on_error: bool,
+        progress: &mut Progress,
+    ) -> Result<Value> {
+        let total = transfers.len();
+        let mut results = Vec::with_capacity(total);
+        let mut completed = Vec::new();
+        let mut first_failure = None;
+        for (index, (entries, reference)) in transfers.into_iter().enumerate() {
+            if stop_on_error && first_failure.is_some() {
+                results.push(json!({ "index": index, "reference": reference, "status": "skipped" }));
+                continue;
+            }
+            match self.transfer(entries, &reference).await {
+                Ok(tx) => {
+                    completed.push(index);
+                    results.push(json!({
+                        "index": index,
+                        "reference": reference,
+                        "status": "ok",
+                        "transaction_id": tx["transaction_id"],
+                    }));
+                }
+                Err(e) => {
+                    first_failure.get_or_insert(index);
+                    results.push(json!({
+                        "index": index,
+                        "reference": reference,
+                        "status": "failed",
+                        "error": e.to_string(),
+                    }));
+                }
+            }
+            progress.report(index + 1, total, &format!("Transfer {} ({})", index, reference))?;
+        }
+
+        let failed = results.iter().filter(|r| r["status"] == "failed").count();
+        let skipped = results.iter().filter(|r| r["status"] == "skipped").count();
+        let mut response = json!({
+            "total": total,
+            "succeeded": completed.len(),
+            "failed": failed,
+            "skipped": skipped,
+            "results": results,
+        });
+        if let Some(index) = first_failure {
+            response["summary"] = json!(format!(
+                "Partial batch: transfer {} failed; {} of {} transfers were posted (indexes {:?}) and are NOT rolled back; {} skipped.",
+                index,
+                completed.len(),
+                total,
+                completed,
+                skipped
+            ));
+        }
+        Ok(response)
+    }
+
+    /// The RPC only supports limit/account_id, so `filter` is applied to the
+    /// (at most `limit`) fetched rows afterwards.
     async fn list_transactions(
         &mut self,
-        limit: Option<i32>,
+        limit: ListLimit,
         account_id: Option<&str>,
+        filter: &TransactionFilter,
     ) -> Result<Value> {
         let request = ListTransactionsRequest {
-            limit: limit.unwrap_or(50),
+            limit: limit.value,
             account_id: account_id.unwrap_or("").to_string(),
         };
         let response = self.ledger.list_transactions(request).await?;
-        let transactions: Vec<Value> = response
-            .into_inner()
-            .transactions
-            .into_iter()
-            .map(|tx| {
-                json!({
-                    "transaction_id": tx.id,
-                    "type": tx.r#type,
-                    "reference": tx.reference,
-                    "timestamp": tx.timestamp,
-                    "entries": tx.entries.iter().map(|e| json!({
-                        "account_id": e.account_id,
-                        "amount": format_balance(e.amount),
-                        "amount_cents": e.amount,
-                    })).collect::<Vec<_>>(),
-                })
-            })
+        let fetched = response.into_inner().transactions;
+        let truncated = fetched.len() >= limit.value as usize;
+        if filter.is_empty() {
+            let transactions: Vec<Value> = fetched.iter().map(transaction_to_json).collect();
+            return Ok(json!({
+                "transactions": transactions,
+                "_meta": list_meta(transactions.len(), limit, truncated),
+            }));
+        }
+        let transactions: Vec<Value> = fetched
+            .iter()
+            .filter(|tx| filter.matches(tx))
+            .map(transaction_to_json)
+            .collect();
+        Ok(json!({
+            "transactions": transactions,
+            "fetched": fetched.len(),
+            "matched": transactions.len(),
+            "_meta": list_meta(transactions.len(), limit, truncated),
+        }))
+    }
+
+    /// The ledger has no GetTransaction RPC, so this scans the most recent
+    /// `GET_TRANSACTION_SCAN_LIMIT` transactions (optionally only those touching
+    /// `account_id`) for the id. A unique id prefix is also accepted.
+    async fn get_transaction(&mut self, transaction_id: &str, account_id: Option<&str>) -> Result<Value> {
+        let tx = self.find_transaction(transaction_id, account_id).await?;
+        Ok(json!({ "transaction": transaction_to_json(&tx) }))
+    }
+
+    /// Transactions whose reference contains `reference`, case-insensitively,
+    /// among the `limit` most recent (optionally only those touching `account_id`)
+    async fn find_transactions_by_reference(
+        &mut self,
+        reference: &str,
+        limit: ListLimit,
+        account_id: Option<&str>,
+    ) -> Result<Value> {
+        if reference.trim().is_empty() {
+            return Err(InvalidParams("reference must not be empty".to_string()).into());
+        }
+        let request = ListTransactionsRequest {
+            limit: limit.value,
+            account_id: account_id.unwrap_or("").to_string(),
+        };
+        let fetched = self.ledger.list_transactions(request).await?.into_inner().transactions;
+        let transactions: Vec<Value> = fetched
+            .iter()
+            .filter(|tx| reference_matches(&tx.reference, reference))
+            .map(transaction_to_json)
+            .collect();
+        Ok(json!({
+            "reference": reference,
+            "count": transactions.len(),
+            "searched": fetched.len(),
+            "transactions": transactions,
+            "_meta": list_meta(transactions.len(), limit, fetched.len() >= limit.value as usize),
+        }))
+    }
+
+    async fn find_transaction(
+        &mut self,
+        transaction_id: &str,
+        account_id: Option<&str>,
+    ) -> Result<Transaction> {
+        let request = ListTransactionsRequest {
+            limit: GET_TRANSACTION_SCAN_LIMIT,
+            account_id: account_id.unwrap_or("").to_string(),
+        };
+        let mut transactions = self.ledger.list_transactions(request).await?.into_inner().transactions;
+
+        if let Some(i) = transactions.iter().position(|tx| tx.id == transaction_id) {
+            return Ok(transactions.swap_remove(i));
+        }
+        let matches: Vec<&Transaction> = transactions
+            .iter()
+            .filter(|tx| !transaction_id.is_empty() && tx.id.starts_with(transaction_id))
             .collect();
-        Ok(json!({ "transactions": transactions }))
+        match matches.as_slice() {
+            [tx] => Ok((*tx).clone()),
+            [] => anyhow::bail!(
+                "Transaction {} not found in the {} most recent transactions{}",
+                transaction_id,
+                GET_TRANSACTION_SCAN_LIMIT,
+                account_id.map(|a| format!(" for account {}", a)).unwrap_or_default()
+            ),
+            _ => anyhow::bail!(
+                "Transaction id prefix {} is ambiguous ({} matches); use the full id",
+                transaction_id,
+                matches.len()
+            ),
+        }
+    }
+
+    /// Post the inverse of a transaction, found the same way as get_transaction.
+    async fn reverse_transaction(&mut self, transaction_id: &str) -> Result<Value> {
+        let original = self.find_transaction(transaction_id, None).await?;
+        let entries = original.entries.iter().map(|e| (e.account_id.as_str(), e.amount));
+        let entries = reversal_entries(&original.id, &original.reference, entries).map_err(anyhow::Error::msg)?;
+        let reference = self.references.make_reference(ReferenceKind::Reversal, &original.id);
+        let mut result = self.transfer(entries, &reference).await?;
+        result["reversed_transaction_id"] = json!(original.id);
+        Ok(result)
     }
 
     async fn purchase_invoice(
@@ -343,6 +1029,10 @@ impl ScalegraphClient {
         };
         let response = self.business.access_payment(request).await?;
         let result = response.into_inner();
+        let platform = platform_id
+            .filter(|_| result.platform_fee > 0)
+            .map(|id| (id, result.platform_fee));
+        let entries = access_payment_entries(payer_id, access_provider_id, result.amount, platform);
         Ok(json!({
             "transaction_id": result.transaction_id,
             "reference": result.reference,
@@ -350,33 +1040,185 @@ impl ScalegraphClient {
             "amount_cents": result.amount,
             "platform_fee": format_balance(result.platform_fee),
             "platform_fee_cents": result.platform_fee,
+            "split": access_payment_split(&entries, result.platform_fee),
             "status": result.status,
             "message": result.message,
         }))
     }
 
+    /// Move `amount` from the payer's operating account into an escrow account
+    async fn fund_escrow(
+        &mut self,
+        payer_id: &str,
+        escrow_account_id: &str,
+        amount: i64,
+        reference: &str,
+    ) -> Result<Value> {
+        let escrow = self.get_account(escrow_account_id).await?;
+        let account_type = account_type_to_string(escrow.account_type);
+        check_escrow_account(&escrow.id, account_type, escrow.balance, EscrowMove::Fund, amount).map_err(anyhow::Error::msg)?;
+        let entries = vec![
+            (format!("{}:operating", payer_id), -amount),
+            (escrow_account_id.to_string(), amount),
+        ];
+        let mut result = self.transfer(entries, reference).await?;
+        result["escrow_account_id"] = json!(escrow_account_id);
+        result["escrow_balance"] = json!(format_balance(escrow.balance + amount));
+        Ok(result)
+    }
+
+    /// Move `amount` out of an escrow account to the beneficiary's operating account
+    async fn release_escrow(
+        &mut self,
+        escrow_account_id: &str,
+        beneficiary_id: &str,
+        amount: i64,
+        reference: &str,
+    ) -> Result<Value> {
+        let escrow = self.get_account(escrow_account_id).await?;
+        let account_type = account_type_to_string(escrow.account_type);
+        check_escrow_account(&escrow.id, account_type, escrow.balance, EscrowMove::Release, amount).map_err(anyhow::Error::msg)?;
+        let entries = vec![
+            (escrow_account_id.to_string(), -amount),
+            (format!("{}:operating", beneficiary_id), amount),
+        ];
+        let mut result = self.transfer(entries, reference).await?;
+        result["escrow_account_id"] = json!(escrow_account_id);
+        result["escrow_balance"] = json!(format_balance(escrow.balance - amount));
+        Ok(result)
+    }
+
+    /// Everything collected in the participant's fees account so far
+    async fn get_fees_collected(&mut self, participant_id: &str) -> Result<Value> {
+        let fees = self.fees_account(participant_id).await?;
+        Ok(json!({
+            "participant_id": participant_id,
+            "account_id": fees.id,
+            "fees_collected": format_balance(fees.balance),
+            "fees_collected_cents": fees.balance,
+        }))
+    }
+
+    /// Move the whole fees balance to the participant's operating account
+    async fn sweep_fees(&mut self, participant_id: &str) -> Result<Value> {
+        let fees = self.fees_account(participant_id).await?;
+        if fees.balance <= 0 {
+            return Ok(json!({
+                "participant_id": participant_id,
+                "swept": format_balance(0),
+                "swept_cents": 0,
+                "message": format!("Nothing to sweep: {} holds {}", fees.id, format_balance(fees.balance)),
+            }));
+        }
+        let entries = vec![
+            (fees.id.clone(), -fees.balance),
+            (format!("{}:operating", participant_id), fees.balance),
+        ];
+        let reference = self.references.make_reference(ReferenceKind::FeeSweep, participant_id);
+        let mut result = self.transfer(entries, &reference).await?;
+        result["participant_id"] = json!(participant_id);
+        result["swept"] = json!(format_balance(fees.balance));
+        result["swept_cents"] = json!(fees.balance);
+        Ok(result)
+    }
+
+    async fn fees_account(&mut self, participant_id: &str) -> Result<Account> {
+        if participant_id.is_empty() {
+            anyhow::bail!("participant_id is required");
+        }
+        let account = self.get_account(&format!("{}:fees", participant_id)).await?;
+        if account.account_type != AccountType::Fees as i32 {
+            anyhow::bail!(
+                "{} is a {} account, not a fees account",
+                account.id,
+                account_type_to_string(account.account_type)
+            );
+        }
+        Ok(account)
+    }
+
+    /// Charge the payer for `units` of metered usage, crediting the provider's
+    /// usage account. The unit count and price are recorded in the reference.
+    async fn record_usage(
+        &mut self,
+        payer_id: &str,
+        provider_id: &str,
+        units: i64,
+        unit_price_cents: i64,
+        reference: &str,
+    ) -> Result<Value> {
+        let amount = usage_amount(units, unit_price_cents)?;
+        let usage_account_id = format!("{}:usage", provider_id);
+        let usage = self.get_account(&usage_account_id).await?;
+        if usage.account_type != AccountType::Usage as i32 {
+            anyhow::bail!(
+                "{} is a {} account, not a usage account",
+                usage.id,
+                account_type_to_string(usage.account_type)
+            );
+        }
+        let entries = vec![
+            (format!("{}:operating", payer_id), -amount),
+            (usage_account_id, amount),
+        ];
+        let mut result = self.transfer(entries, reference).await?;
+        result["units"] = json!(units);
+        result["unit_price"] = json!(format_balance(unit_price_cents));
+        result["unit_price_cents"] = json!(unit_price_cents);
+        result["amount"] = json!(format_balance(amount));
+        result["amount_cents"] = json!(amount);
+        Ok(result)
+    }
+
+    async fn get_account(&mut self, account_id: &str) -> Result<Account> {
+        let request = GetAccountRequest {
+            account_id: account_id.to_string(),
+        };
+        match self.ledger.get_account(request).await {
+            Ok(response) => Ok(response.into_inner()),
+            Err(status) if status.code() == tonic::Code::NotFound => {
+                anyhow::bail!("Account {} not found", account_id)
+            }
+            Err(status) => Err(status.into()),
+        }
+    }
+
     async fn create_loan(
         &mut self,
         lender_id: &str,
         borrower_id: &str,
         amount: i64,
+        annual_interest_rate: f64,
+        term_months: i32,
         reference: &str,
     ) -> Result<Value> {
         let request = CreateLoanRequest {
             lender_id: lender_id.to_string(),
             borrower_id: borrower_id.to_string(),
             principal_cents: amount,
-            annual_interest_rate: 0.05, // Default 5% annual interest
-            term_months: 60, // Default 60 months (5 years)
+            annual_interest_rate,
+            term_months,
             reference: reference.to_string(),
         };
         let response = self.business.create_loan(request).await?;
         let result = response.into_inner();
+        // The server records the rate, term and monthly payment on the loan
+        // contract but only returns them in its message, so the payment
+        // figures are estimates worked out here with the same formula
+        let monthly_payment = loans::monthly_payment(amount, annual_interest_rate, term_months).unwrap_or(amount);
+        let total_repayment = monthly_payment * term_months as i64;
         Ok(json!({
             "transaction_id": result.transaction_id,
             "reference": result.reference,
             "amount": format_balance(result.amount),
             "amount_cents": result.amount,
+            "annual_interest_rate": annual_interest_rate,
+            "term_months": term_months,
+            "estimated_monthly_payment": format_balance(monthly_payment),
+            "estimated_monthly_payment_cents": monthly_payment,
+            "estimated_total_repayment": format_balance(total_repayment),
+            "estimated_total_repayment_cents": total_repayment,
+            "estimated_total_interest": format_balance(total_repayment - amount),
             "status": result.status,
             "message": result.message,
         }))
@@ -420,6 +1262,67 @@ impl ScalegraphClient {
         }))
     }
 
+    /// Outstanding loans of `lender_id` per borrower. There is no ListLoans
+    /// RPC, so the book is rebuilt from the loan and repayment transactions
+    /// on the lender's receivables account (invoices share that account and
+    /// are left out). `unattributed_cents` is whatever part of the receivables
+    /// balance the scanned transactions don't explain: invoices, or history
+    /// older than the scan. When the balance can't be read it and
+    /// `unattributed_cents` are null and `warning` says why.
+    async fn get_loan_book(&mut self, lender_id: &str) -> Result<Value> {
+        let receivables = format!("{}:receivables", lender_id);
+        let request = ListTransactionsRequest {
+            limit: GET_TRANSACTION_SCAN_LIMIT,
+            account_id: receivables.clone(),
+        };
+        let fetched = self.ledger.list_transactions(request).await?.into_inner().transactions;
+        let book = loan_book(&receivables, &fetched);
+
+        let total: i64 = book.values().map(|loans| loans.outstanding).sum();
+        let mut borrowers: Vec<(&String, &BorrowerLoans)> =
+            book.iter().filter(|(_, loans)| loans.outstanding != 0).collect();
+        borrowers.sort_by(|a, b| b.1.outstanding.cmp(&a.1.outstanding).then(a.0.cmp(b.0)));
+        let borrowers: Vec<Value> = borrowers
+            .into_iter()
+            .map(|(borrower_id, loans)| {
+                json!({
+                    "borrower_id": borrower_id,
+                    "outstanding": format_balance(loans.outstanding),
+                    "outstanding_cents": loans.outstanding,
+                    "lent": format_balance(loans.lent),
+                    "lent_cents": loans.lent,
+                    "repaid": format_balance(loans.repaid),
+                    "repaid_cents": loans.repaid,
+                    "loans": loans.loans,
+                })
+            })
+            .collect();
+
+        let balance = self.get_account(&receivables).await.map(|account| account.balance);
+        let truncated = fetched.len() >= GET_TRANSACTION_SCAN_LIMIT as usize;
+        let mut result = json!({
+            "lender_id": lender_id,
+            "borrower_count": borrowers.len(),
+            "borrowers": borrowers,
+            "total_outstanding": format_balance(total),
+            "total_outstanding_cents": total,
+            "receivables_balance_cents": balance.as_ref().ok(),
+            "unattributed_cents": balance.as_ref().ok().map(|balance| balance - total),
+            "transactions_scanned": fetched.len(),
+            "truncated": truncated,
+        });
+        if let Err(e) = balance {
+            result["warning"] = json!(format!("Could not read the balance of {}: {}", receivables, e));
+        }
+        if truncated {
+            result["note"] = json!(format!(
+                "Only the {} most recent transactions were scanned; older loans and repayments are left out of the book",
+                GET_TRANSACTION_SCAN_LIMIT
+            ));
+        }
+        Ok(result)
+    }
+
     async fn get_total_debt(&mut self, borrower_id: &str) -> Result<Value> {
         let request = GetTotalDebtRequest {
             borrower_id: borrower_id.to_string(),
@@ -433,14 +1336,88 @@ impl ScalegraphClient {
         }))
     }
 
-    // Smart Contract operations
+    // Reporting
 
-    async fn create_invoice_contract(
-        &mut self,
-        supplier_id: &str,
-        buyer_id: &str,
-        amount_cents: i64,
-        issue_date: i64,
+    /// Aggregate balances across every participant's accounts.
+    /// Payables are stored as negative balances, so system-wide receivables
+    /// and payables must sum to zero; any remainder is reported as an imbalance.
+    async fn get_ledger_summary(&mut self) -> Result<Value> {
+        let participants = self
+            .participant
+            .list_participants(ListParticipantsRequest { role: 0 })
+            .await?
+            .into_inner()
+            .participants;
+
+        let mut by_role: BTreeMap<&'static str, u64> = BTreeMap::new();
+        let mut by_account_type: BTreeMap<&'static str, (i64, u64)> = BTreeMap::new();
+        for p in &participants {
+            *by_role.entry(role_to_string(p.role)).or_default() += 1;
+
+            let request = GetParticipantAccountsRequest {
+                participant_id: p.id.clone(),
+            };
+            let accounts = self
+                .participant
+                .get_participant_accounts(request)
+                .await?
+                .into_inner()
+                .accounts;
+            for a in accounts {
+                let entry = by_account_type
+                    .entry(account_type_to_string(a.account_type))
+                    .or_default();
+                entry.0 += a.balance;
+                entry.1 += 1;
+            }
+        }
+
+        let total = |account_type: &str| by_account_type.get(account_type).map_or(0, |t| t.0);
+        let receivables = total("Receivables");
+        let payables = total("Payables");
+        let imbalance = receivables + payables;
+
+        let account_types: Vec<Value> = by_account_type
+            .iter()
+            .map(|(account_type, (balance, count))| {
+                json!({
+                    "type": account_type,
+                    "accounts": count,
+                    "total": format_balance(*balance),
+                    "total_cents": balance,
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            "participant_count": participants.len(),
+            "participants_by_role": by_role,
+            "account_types": account_types,
+            "total_operating_cash": format_balance(total("Operating")),
+            "total_operating_cash_cents": total("Operating"),
+            "total_receivables": format_balance(receivables),
+            "total_receivables_cents": receivables,
+            "total_payables": format_balance(payables),
+            "total_payables_cents": payables,
+            "escrow_held": format_balance(total("Escrow")),
+            "escrow_held_cents": total("Escrow"),
+            "consistency": {
+                "receivables_match_payables": imbalance == 0,
+                "imbalance": format_balance(imbalance),
+                "imbalance_cents": imbalance,
+            },
+        }))
+    }
+
+    // Smart Contract operations
+
+    #[allow(clippy::too_many_arguments)]
+    async fn create_invoice_contract(
+        &mut self,
+        supplier_id: &str,
+        buyer_id: &str,
+        amount_cents: i64,
+        issue_date: i64,
         due_date: i64,
         payment_terms: &str,
         auto_debit: bool,
@@ -468,7 +1445,9 @@ impl ScalegraphClient {
             "amount_cents": contract.amount_cents,
             "amount": format_balance(contract.amount_cents),
             "issue_date": contract.issue_date,
+            "issue_date_utc": format_date(contract.issue_date),
             "due_date": contract.due_date,
+            "due_date_utc": format_date(contract.due_date),
             "payment_terms": contract.payment_terms,
             "auto_debit": contract.auto_debit,
             "late_fee_cents": contract.late_fee_cents,
@@ -483,25 +1462,10 @@ impl ScalegraphClient {
             contract_type: ContractType::Invoice as i32,
         };
         let response = self.contracts.get_invoice_contract(request).await?;
-        let contract = response.into_inner();
-        Ok(json!({
-            "id": contract.id,
-            "supplier_id": contract.supplier_id,
-            "buyer_id": contract.buyer_id,
-            "amount_cents": contract.amount_cents,
-            "amount": format_balance(contract.amount_cents),
-            "issue_date": contract.issue_date,
-            "due_date": contract.due_date,
-            "payment_terms": contract.payment_terms,
-            "auto_debit": contract.auto_debit,
-            "late_fee_cents": contract.late_fee_cents,
-            "status": contract.status,
-            "reference": contract.reference,
-            "created_at": contract.created_at,
-            "paid_at": contract.paid_at,
-        }))
+        Ok(invoice_contract_to_json(&response.into_inner()))
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn create_subscription_contract(
         &mut self,
         provider_id: &str,
@@ -536,7 +1500,9 @@ impl ScalegraphClient {
             "auto_debit": contract.auto_debit,
             "cancellation_notice_days": contract.cancellation_notice_days,
             "start_date": contract.start_date,
+            "start_date_utc": format_date(contract.start_date),
             "end_date": contract.end_date,
+            "end_date_utc": format_date(contract.end_date),
             "status": contract.status,
             "next_billing_date": contract.next_billing_date,
         }))
@@ -548,21 +1514,7 @@ impl ScalegraphClient {
             contract_type: ContractType::Subscription as i32,
         };
         let response = self.contracts.get_subscription_contract(request).await?;
-        let contract = response.into_inner();
-        Ok(json!({
-            "id": contract.id,
-            "provider_id": contract.provider_id,
-            "subscriber_id": contract.subscriber_id,
-            "monthly_fee_cents": contract.monthly_fee_cents,
-            "monthly_fee": format_balance(contract.monthly_fee_cents),
-            "billing_date": contract.billing_date,
-            "auto_debit": contract.auto_debit,
-            "cancellation_notice_days": contract.cancellation_notice_days,
-            "start_date": contract.start_date,
-            "end_date": contract.end_date,
-            "status": contract.status,
-            "next_billing_date": contract.next_billing_date,
-        }))
+        Ok(subscription_contract_to_json(&response.into_inner()))
     }
 
     async fn create_conditional_payment(
@@ -603,19 +1555,7 @@ impl ScalegraphClient {
             contract_type: ContractType::ConditionalPayment as i32,
         };
         let response = self.contracts.get_conditional_payment(request).await?;
-        let contract = response.into_inner();
-        Ok(json!({
-            "id": contract.id,
-            "payer_id": contract.payer_id,
-            "receiver_id": contract.receiver_id,
-            "amount_cents": contract.amount_cents,
-            "amount": format_balance(contract.amount_cents),
-            "condition_type": contract.condition_type,
-            "trigger": contract.trigger,
-            "status": contract.status,
-            "created_at": contract.created_at,
-            "executed_at": contract.executed_at,
-        }))
+        Ok(conditional_payment_to_json(&response.into_inner()))
     }
 
     async fn create_revenue_share_contract(
@@ -624,6 +1564,7 @@ impl ScalegraphClient {
         parties: Vec<(String, f64)>,
         auto_split: bool,
     ) -> Result<Value> {
+        validate_revenue_shares(&parties).map_err(anyhow::Error::msg)?;
         let revenue_parties: Vec<RevenueShareParty> = parties
             .into_iter()
             .map(|(participant_id, share)| RevenueShareParty {
@@ -666,26 +1607,156 @@ impl ScalegraphClient {
             contract_type: ContractType::RevenueShare as i32,
         };
         let response = self.contracts.get_revenue_share_contract(request).await?;
-        let contract = response.into_inner();
-        let parties_json: Vec<Value> = contract
-            .parties
-            .iter()
-            .map(|p| {
-                json!({
-                    "participant_id": p.participant_id,
-                    "share": p.share,
-                    "share_percent": (p.share * 100.0) as i32,
-                })
-            })
-            .collect();
+        Ok(revenue_share_contract_to_json(&response.into_inner()))
+    }
+
+    /// Create a contract from a YAML definition (inline or a path on the
+    /// server), substituting `variables` into it.
+    async fn create_generic_contract(
+        &mut self,
+        yaml_content: Option<&str>,
+        yaml_file_path: Option<&str>,
+        variables: std::collections::HashMap<String, String>,
+    ) -> Result<Value> {
+        if yaml_content.is_some() == yaml_file_path.is_some() {
+            anyhow::bail!("give exactly one of yaml_content or yaml_file_path");
+        }
+        let request = CreateGenericContractRequest {
+            yaml_content: yaml_content.unwrap_or_default().to_string(),
+            yaml_file_path: yaml_file_path.unwrap_or_default().to_string(),
+            variables,
+        };
+        let response = self.contracts.create_generic_contract(request).await?;
+        Ok(generic_contract_to_json(&response.into_inner()))
+    }
+
+    async fn get_generic_contract(&mut self, contract_id: &str) -> Result<Value> {
+        use smartcontracts::contract_response::Contract;
+        let request = GetContractRequest {
+            contract_id: contract_id.to_string(),
+            contract_type: 0,
+        };
+        match self.contracts.get_contract(request).await?.into_inner().contract {
+            Some(Contract::Generic(contract)) => Ok(generic_contract_to_json(&contract)),
+            Some(_) => anyhow::bail!(
+                "contract {} is not a generic contract; use get_contract",
+                contract_id
+            ),
+            None => anyhow::bail!("Contract not found: {}", contract_id),
+        }
+    }
+
+    /// Fetch a contract without knowing its type. The generic GetContract RPC
+    /// is tried first; if it doesn't know the id, each typed getter is tried in
+    /// turn. The result carries the detected `type`.
+    async fn get_contract(&mut self, contract_id: &str) -> Result<Value> {
+        use smartcontracts::contract_response::Contract;
+        let request = GetContractRequest {
+            contract_id: contract_id.to_string(),
+            contract_type: 0,
+        };
+        match self.contracts.get_contract(request).await {
+            Ok(response) => {
+                let (contract_type, contract) = match response.into_inner().contract {
+                    Some(Contract::Invoice(c)) => ("invoice", invoice_contract_to_json(&c)),
+                    Some(Contract::Subscription(c)) => ("subscription", subscription_contract_to_json(&c)),
+                    Some(Contract::ConditionalPayment(c)) => {
+                        ("conditional_payment", conditional_payment_to_json(&c))
+                    }
+                    Some(Contract::RevenueShare(c)) => ("revenue_share", revenue_share_contract_to_json(&c)),
+                    Some(Contract::Generic(c)) => ("generic", generic_contract_to_json(&c)),
+                    None => anyhow::bail!("contract {} has no body", contract_id),
+                };
+                return Ok(json!({ "type": contract_type, "contract": contract }));
+            }
+            Err(status)
+                if matches!(status.code(), tonic::Code::NotFound | tonic::Code::Unimplemented) => {}
+            Err(status) => return Err(status.into()),
+        }
+
+        let found = if let Ok(c) = self.get_invoice_contract(contract_id).await {
+            Some(("invoice", c))
+        } else if let Ok(c) = self.get_subscription_contract(contract_id).await {
+            Some(("subscription", c))
+        } else if let Ok(c) = self.get_conditional_payment(contract_id).await {
+            Some(("conditional_payment", c))
+        } else if let Ok(c) = self.get_revenue_share_contract(contract_id).await {
+            Some(("revenue_share", c))
+        } else {
+            None
+        };
+        match found {
+            Some((contract_type, contract)) => Ok(json!({ "type": contract_type, "contract": contract })),
+            None => anyhow::bail!("Contract not found: {}", contract_id),
+        }
+    }
+
+    async fn revenue_share_allocation(
+        &mut self,
+        contract_id: &str,
+        amount_cents: i64,
+    ) -> Result<(smartcontracts::RevenueShareContract, Vec<i64>, i64)> {
+        if amount_cents <= 0 {
+            anyhow::bail!("amount_cents must be positive");
+        }
+        let request = GetContractRequest {
+            contract_id: contract_id.to_string(),
+            contract_type: ContractType::RevenueShare as i32,
+        };
+        let contract = self.contracts.get_revenue_share_contract(request).await?.into_inner();
+        let shares: Vec<f64> = contract.parties.iter().map(|p| p.share).collect();
+        let (amounts, residue) = split_revenue(amount_cents, &shares)?;
+        Ok((contract, amounts, residue))
+    }
+
+    async fn preview_revenue_share(&mut self, contract_id: &str, amount_cents: i64) -> Result<Value> {
+        let (contract, amounts, residue) =
+            self.revenue_share_allocation(contract_id, amount_cents).await?;
         Ok(json!({
-            "id": contract.id,
-            "transaction_type": contract.transaction_type,
-            "parties": parties_json,
-            "auto_split": contract.auto_split,
-            "status": contract.status,
-            "created_at": contract.created_at,
-            "last_distributed_at": contract.last_distributed_at,
+            "contract_id": contract.id,
+            "amount": format_balance(amount_cents),
+            "amount_cents": amount_cents,
+            "distributions": revenue_share_distributions(&contract, &amounts),
+            "rounding_remainder_cents": residue,
+        }))
+    }
+
+    /// Move `amount_cents` from `source_account_id` to each party's operating
+    /// account, split exactly as `preview_revenue_share` shows.
+    async fn execute_revenue_share(
+        &mut self,
+        contract_id: &str,
+        amount_cents: i64,
+        source_account_id: &str,
+        reference: &str,
+    ) -> Result<Value> {
+        let (contract, amounts, residue) =
+            self.revenue_share_allocation(contract_id, amount_cents).await?;
+        if contract.status != "active" {
+            anyhow::bail!("Revenue share contract {} is {}, not active", contract.id, contract.status);
+        }
+        let mut entries = vec![(source_account_id.to_string(), -amount_cents)];
+        entries.extend(
+            contract
+                .parties
+                .iter()
+                .zip(&amounts)
+                .filter(|(_, amount)| **amount != 0)
+                .map(|(party, amount)| (format!("{}:operating", party.participant_id), *amount)),
+        );
+        let reference = if reference.is_empty() {
+            self.references.make_reference(ReferenceKind::RevenueShare, &contract.id)
+        } else {
+            reference.to_string()
+        };
+        let transaction = self.transfer(entries, &reference).await?;
+        Ok(json!({
+            "contract_id": contract.id,
+            "amount": format_balance(amount_cents),
+            "amount_cents": amount_cents,
+            "distributions": revenue_share_distributions(&contract, &amounts),
+            "rounding_remainder_cents": residue,
+            "transaction": transaction,
         }))
     }
 
@@ -694,65 +1765,58 @@ impl ScalegraphClient {
         contract_type: Option<i32>,
         status: Option<&str>,
         participant_id: Option<&str>,
-        limit: Option<i32>,
+        list_limit: ListLimit,
     ) -> Result<Value> {
+        let limit = list_limit.value;
+        // The server ignores participant_id, so the filter is applied here,
+        // over a wider scan, and the limit afterwards
+        let scan_limit = if participant_id.is_some() { CONTRACT_SCAN_LIMIT.max(limit) } else { limit };
         let request = ListContractsRequest {
             contract_type: contract_type.unwrap_or(0),
             status: status.unwrap_or("").to_string(),
             participant_id: participant_id.unwrap_or("").to_string(),
-            limit: limit.unwrap_or(100),
+            limit: scan_limit,
         };
         let response = self.contracts.list_contracts(request).await?;
         let contracts = response.into_inner().contracts;
-        use smartcontracts::contract_response::Contract;
-        let contracts_json: Vec<Value> = contracts
+        let matching: Vec<&smartcontracts::ContractResponse> = contracts
             .iter()
-            .filter_map(|c| {
-                match c.contract.as_ref() {
-                    Some(Contract::Invoice(inv)) => Some(json!({
-                        "type": "invoice",
-                        "contract": {
-                            "id": inv.id,
-                            "supplier_id": inv.supplier_id,
-                            "buyer_id": inv.buyer_id,
-                            "amount_cents": inv.amount_cents,
-                            "status": inv.status,
-                            "reference": inv.reference,
-                        }
-                    })),
-                    Some(Contract::Subscription(sub)) => Some(json!({
-                        "type": "subscription",
-                        "contract": {
-                            "id": sub.id,
-                            "provider_id": sub.provider_id,
-                            "subscriber_id": sub.subscriber_id,
-                            "monthly_fee_cents": sub.monthly_fee_cents,
-                            "status": sub.status,
-                        }
-                    })),
-                    Some(Contract::ConditionalPayment(cp)) => Some(json!({
-                        "type": "conditional_payment",
-                        "contract": {
-                            "id": cp.id,
-                            "payer_id": cp.payer_id,
-                            "receiver_id": cp.receiver_id,
-                            "amount_cents": cp.amount_cents,
-                            "status": cp.status,
-                        }
-                    })),
-                    Some(Contract::RevenueShare(rs)) => Some(json!({
-                        "type": "revenue_share",
-                        "contract": {
-                            "id": rs.id,
-                            "transaction_type": rs.transaction_type,
-                            "status": rs.status,
-                        }
-                    })),
-                    None => None,
-                }
-            })
+            .filter(|c| participant_id.is_none_or(|id| contract_parties(c).contains(&id)))
+            .collect();
+        let truncated = matching.len() >= limit as usize || contracts.len() >= scan_limit as usize;
+        let contracts_json: Vec<Value> = matching
+            .into_iter()
+            .take(limit as usize)
+            .filter_map(contract_summary_json)
             .collect();
-        Ok(json!({ "contracts": contracts_json }))
+        Ok(json!({
+            "_meta": list_meta(contracts_json.len(), list_limit, truncated),
+            "contracts": contracts_json,
+        }))
+    }
+
+    /// Every contract `participant_id` is a party to, by status, see
+    /// `participant_contracts_report`
+    async fn get_participant_contracts(&mut self, participant_id: &str) -> Result<Value> {
+        if participant_id.trim().is_empty() {
+            return Err(InvalidParams("participant_id must not be empty".to_string()).into());
+        }
+        // The server ignores participant_id, so the parties are matched here
+        let request = ListContractsRequest {
+            limit: CONTRACT_SCAN_LIMIT,
+            ..Default::default()
+        };
+        let contracts = self.contracts.list_contracts(request).await?.into_inner().contracts;
+        let mut report = participant_contracts_report(participant_id.trim(), &contracts);
+        report["scanned"] = json!(contracts.len());
+        if contracts.len() >= CONTRACT_SCAN_LIMIT as usize {
+            report["truncated"] = json!(true);
+            report["note"] = json!(format!(
+                "Only the first {} contracts were scanned; the participant may have more",
+                CONTRACT_SCAN_LIMIT
+            ));
+        }
+        Ok(report)
     }
 
     async fn execute_contract(
@@ -774,16 +1838,85 @@ impl ScalegraphClient {
         }))
     }
 
+    /// Execute every active contract whose scheduled time has passed:
+    /// generic contracts by next_execution_at, subscriptions by next_billing_date,
+    /// and auto-debit invoices by due_date.
+    async fn execute_due_contracts(&mut self, progress: &mut Progress) -> Result<Value> {
+        let request = ListContractsRequest {
+            contract_type: 0,
+            status: String::new(),
+            participant_id: String::new(),
+            limit: 1000,
+        };
+        let contracts = self.contracts.list_contracts(request).await?.into_inner().contracts;
+        let now = now_millis();
+        let due: Vec<(String, i32)> = contracts.iter().filter_map(|c| due_contract(c, now)).collect();
+
+        let client = self.contracts.clone();
+        let results = run_with_progress(
+            due,
+            progress,
+            |(contract_id, _)| format!("Executed contract {}", contract_id),
+            |(contract_id, contract_type)| {
+                let mut client = client.clone();
+                async move {
+                    let request = ExecuteContractRequest {
+                        contract_id: contract_id.clone(),
+                        contract_type,
+                    };
+                    match client.execute_contract(request).await {
+                        Ok(response) => {
+                            let result = response.into_inner();
+                            json!({
+                                "contract_id": result.contract_id,
+                                "executed": result.executed,
+                                "message": result.message,
+                                "transaction_ids": result.transaction_ids,
+                            })
+                        }
+                        Err(e) => json!({
+                            "contract_id": contract_id,
+                            "executed": false,
+                            "message": e.message(),
+                        }),
+                    }
+                }
+            },
+        )
+        .await?;
+
+        let executed = results
+            .iter()
+            .filter(|r| r["executed"].as_bool().unwrap_or(false))
+            .count();
+        Ok(json!({
+            "due": results.len(),
+            "executed": executed,
+            "failed": results.len() - executed,
+            "results": results,
+        }))
+    }
+
     async fn update_contract_status(
         &mut self,
         contract_id: &str,
         contract_type: i32,
-        status: i32,
+        status: &str,
     ) -> Result<Value> {
+        let status_code = contract_status_string_to_int(status);
+        let request = GetContractRequest {
+            contract_id: contract_id.to_string(),
+            contract_type,
+        };
+        let current = self.contracts.get_contract(request).await?.into_inner();
+        let old_status = contract_status(&current)
+            .ok_or_else(|| anyhow::anyhow!("contract {} not found", contract_id))?;
+        check_status_transition(&old_status, &status.to_lowercase())?;
+
         let request = UpdateContractStatusRequest {
             contract_id: contract_id.to_string(),
             contract_type,
-            status,
+            status: status_code,
         };
         let response = self.contracts.update_contract_status(request).await?;
         let contract_response = response.into_inner();
@@ -809,9 +1942,18 @@ impl ScalegraphClient {
                 "id": rs.id,
                 "status": rs.status,
             }),
+            Some(Contract::Generic(gen)) => json!({
+                "type": "generic",
+                "id": gen.id,
+                "status": gen.status,
+            }),
             None => json!({"type": "unknown"}),
         };
-        Ok(json!({ "contract": contract_json }))
+        Ok(json!({
+            "contract": contract_json,
+            "old_status": old_status,
+            "new_status": contract_status(&contract_response),
+        }))
     }
 }
 
@@ -820,12 +1962,13 @@ impl ScalegraphClient {
 // ============================================================================
 
 fn role_to_string(role: i32) -> &'static str {
-    match role {
-        1 => "Access Provider",
-        2 => "Banking Partner",
-        3 => "Ecosystem Partner",
-        4 => "Supplier",
-        5 => "Equipment Provider",
+    match ParticipantRole::try_from(role) {
+        Ok(ParticipantRole::AccessProvider) => "Access Provider",
+        Ok(ParticipantRole::BankingPartner) => "Banking Partner",
+        Ok(ParticipantRole::EcosystemPartner) => "Ecosystem Partner",
+        Ok(ParticipantRole::Supplier) => "Supplier",
+        Ok(ParticipantRole::EquipmentProvider) => "Equipment Provider",
+        Ok(ParticipantRole::EcosystemOrchestrator) => "Ecosystem Orchestrator",
         _ => "Unknown",
     }
 }
@@ -843,984 +1986,5004 @@ fn account_type_to_string(account_type: i32) -> &'static str {
     }
 }
 
-fn format_balance(balance: i64) -> String {
-    let whole = balance / 100;
-    let cents = (balance % 100).abs();
-    if balance < 0 {
-        format!("-{}.{:02}", whole.abs(), cents)
-    } else {
-        format!("{}.{:02}", whole, cents)
+/// Conventional account types for a participant role, as used by setup_participant_accounts
+fn default_account_types(role: i32) -> &'static [&'static str] {
+    match role {
+        1 => &["operating", "fees", "usage"],             // Access Provider
+        2 => &["operating", "receivables", "escrow"],     // Banking Partner: lends and holds collateral
+        3 => &["operating", "payables"],                  // Ecosystem Partner: buys from suppliers
+        4 => &["operating", "receivables"],               // Supplier
+        5 => &["operating", "usage", "receivables"],      // Equipment Provider: pay-per-use
+        6 => &["operating", "fees"],                      // Ecosystem Orchestrator: platform fees
+        _ => &[],
     }
 }
 
-// ============================================================================
-// MCP Protocol Handlers
-// ============================================================================
+fn contract_type_to_string(contract_type: i32) -> &'static str {
+    match ContractType::try_from(contract_type) {
+        Ok(ContractType::Loan) => "Loan",
+        Ok(ContractType::Invoice) => "Invoice",
+        Ok(ContractType::Subscription) => "Subscription",
+        Ok(ContractType::ConditionalPayment) => "Conditional Payment",
+        Ok(ContractType::RevenueShare) => "Revenue Share",
+        Ok(ContractType::SupplierRegistration) => "Supplier Registration",
+        Ok(ContractType::EcosystemPartnerMembership) => "Ecosystem Partner Membership",
+        _ => "Unknown",
+    }
+}
+
+fn contract_status_to_string(status: i32) -> &'static str {
+    match ContractStatus::try_from(status) {
+        Ok(ContractStatus::Active) => "Active",
+        Ok(ContractStatus::Paused) => "Paused",
+        Ok(ContractStatus::Completed) => "Completed",
+        Ok(ContractStatus::Cancelled) => "Cancelled",
+        _ => "Unknown",
+    }
+}
 
-fn get_server_info() -> Value {
+/// Canonical values of every enum argument the tools accept: the string key
+/// to pass, the proto value it maps to, and the label used in results.
+/// Read from the proto enums, so a value added there shows up here.
+fn describe_enums() -> Value {
+    let describe = |name: fn(i32) -> Option<&'static str>, label: fn(i32) -> &'static str| {
+        enum_keys(name)
+            .into_iter()
+            .map(|(key, value)| json!({ "key": key, "value": value, "label": label(value) }))
+            .collect::<Vec<_>>()
+    };
     json!({
-        "protocolVersion": "2024-11-05",
-        "capabilities": {
-            "tools": {}
-        },
-        "serverInfo": {
-            "name": "scalegraph-ledger",
-            "version": "1.0.0"
-        }
+        "roles": describe(
+            |v| ParticipantRole::try_from(v).ok().map(|e| e.as_str_name()),
+            role_to_string
+        ),
+        "account_types": describe(
+            |v| AccountType::try_from(v).ok().map(|e| e.as_str_name()),
+            account_type_to_string
+        ),
+        "contract_types": describe(
+            |v| ContractType::try_from(v).ok().map(|e| e.as_str_name()),
+            contract_type_to_string
+        ),
+        "contract_statuses": describe(
+            |v| ContractStatus::try_from(v).ok().map(|e| e.as_str_name()),
+            contract_status_to_string
+        ),
     })
 }
 
-fn get_tools_list() -> Value {
-    json!({
-        "tools": [
-            {
-                "name": "list_participants",
-                "description": "List all participants in the Scalegraph ecosystem. Returns participant IDs, names, and roles (Access Provider, Banking Partner, Ecosystem Partner, Supplier, Equipment Provider).",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {},
-                    "required": []
-                }
-            },
-            {
-                "name": "create_participant",
-                "description": "Create a new participant in the ecosystem. Participants can be suppliers, access providers, banking partners, etc.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "id": {
-                            "type": "string",
-                            "description": "Unique participant ID (e.g., 'acme_supplies', 'new_salon')"
-                        },
-                        "name": {
-                            "type": "string",
-                            "description": "Display name (e.g., 'Acme Supplies AB', 'New Salon')"
-                        },
-                        "role": {
-                            "type": "string",
-                            "enum": ["access_provider", "banking_partner", "ecosystem_partner", "supplier", "equipment_provider"],
-                            "description": "Participant role in the ecosystem"
-                        }
-                    },
-                    "required": ["id", "name", "role"]
+/// Argument keys of a proto enum and their values, from 1 up to the last
+/// value `name` knows (0 is always the unspecified value). The key is the
+/// proto name in lowercase, as the `*_string_to_int` functions accept it.
+fn enum_keys(name: fn(i32) -> Option<&'static str>) -> Vec<(String, i32)> {
+    (1..)
+        .map_while(|value| name(value).map(|n| (n.to_ascii_lowercase(), value)))
+        .collect()
+}
+
+/// Business tools check participant ids first unless `validate_participants` is false
+fn validate_participants(args: &Value) -> bool {
+    args.get("validate_participants")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+/// Levenshtein distance between two strings, by character
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
+/// The candidate closest to `input`, if it's close enough to be a plausible typo
+fn closest_match<'a>(input: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let max_distance = (input.chars().count() / 3).max(2);
+    candidates
+        .iter()
+        .map(|c| (edit_distance(input, c), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c.as_str())
+}
+
+/// Lowercase letters and digits only, so "Salon Glamour", "salon-glamour"
+/// and "salon_glamour" compare equal
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Whether two participant names probably belong to the same company: equal
+/// once normalized, or a small edit apart when both are long enough for a
+/// typo to be told from a different name
+fn names_look_alike(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize_name(a), normalize_name(b));
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if a == b {
+        return true;
+    }
+    let shorter = a.chars().count().min(b.chars().count());
+    shorter >= 5 && edit_distance(&a, &b) <= (shorter / 6).max(1)
+}
+
+/// Indices of participants that look like duplicates of each other, grouped
+/// transitively, in list order. Participants without a look-alike are left out.
+fn duplicate_groups(participants: &[Participant]) -> Vec<Vec<usize>> {
+    let mut group_of: Vec<usize> = (0..participants.len()).collect();
+    for i in 0..participants.len() {
+        for j in i + 1..participants.len() {
+            if group_of[i] != group_of[j] && names_look_alike(&participants[i].name, &participants[j].name) {
+                let (keep, merged) = (group_of[i].min(group_of[j]), group_of[i].max(group_of[j]));
+                for group in group_of.iter_mut().filter(|g| **g == merged) {
+                    *group = keep;
                 }
-            },
-            {
-                "name": "create_participant_account",
-                "description": "Create a ledger account for a participant. Each participant needs accounts to transact (operating for cash, receivables for money owed to them, payables for money they owe, fees for service charges).",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "participant_id": {
-                            "type": "string",
-                            "description": "Participant ID to create account for"
-                        },
-                        "account_type": {
-                            "type": "string",
-                            "enum": ["operating", "receivables", "payables", "escrow", "fees", "usage"],
-                            "description": "Type of account: operating (main cash), receivables (A/R), payables (A/P), fees (service charges), escrow (held funds), usage (pay-per-use)"
-                        },
-                        "initial_balance_cents": {
-                            "type": "integer",
-                            "description": "Initial balance in cents (e.g., 100000 for $1,000.00). Default: 0"
-                        }
-                    },
-                    "required": ["participant_id", "account_type"]
+            }
+        }
+    }
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (index, &group) in group_of.iter().enumerate() {
+        match groups.iter_mut().find(|g| group_of[g[0]] == group) {
+            Some(members) => members.push(index),
+            None => groups.push(vec![index]),
+        }
+    }
+    groups.retain(|g| g.len() > 1);
+    groups
+}
+
+/// Participants matching `query`: an exact name or id wins outright;
+/// otherwise every participant whose name or id contains the query. With no
+/// match at all, the closest name is suggested.
+fn resolve_participant_report(query: &str, participants: &[Participant]) -> Value {
+    let wanted = normalize_name(query);
+    let exact: Vec<&Participant> = participants
+        .iter()
+        .filter(|p| normalize_name(&p.name) == wanted || normalize_name(&p.id) == wanted)
+        .collect();
+    let matches = if exact.is_empty() {
+        participants
+            .iter()
+            .filter(|p| normalize_name(&p.name).contains(&wanted) || normalize_name(&p.id).contains(&wanted))
+            .collect()
+    } else {
+        exact
+    };
+    let to_json = |p: &&Participant| json!({ "id": p.id, "name": p.name, "role": role_to_string(p.role) });
+    match matches.as_slice() {
+        [] => {
+            let names: Vec<String> = participants.iter().map(|p| p.name.clone()).collect();
+            let suggestion = closest_match(query, &names)
+                .and_then(|name| participants.iter().find(|p| p.name == name));
+            let mut report = json!({
+                "query": query,
+                "status": "not_found",
+                "message": format!("No participant name or id matches '{}'", query),
+            });
+            if let Some(p) = suggestion {
+                report["did_you_mean"] = to_json(&p);
+            }
+            report
+        }
+        [only] => json!({
+            "query": query,
+            "status": "resolved",
+            "participant": to_json(only),
+        }),
+        several => json!({
+            "query": query,
+            "status": "ambiguous",
+            "message": format!("{} participants match '{}'; use one of their ids", several.len(), query),
+            "matches": several.iter().map(to_json).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+/// Read a required, strictly positive cent amount. Business operations give
+/// the sign meaning, so zero and negative amounts are rejected before the RPC.
+fn positive_amount_arg(args: &Value, key: &str) -> Result<i64> {
+    let amount = match args.get(key) {
+        None | Some(Value::Null) => anyhow::bail!("{} is required", key),
+        Some(v) => v
+            .as_i64()
+            .ok_or_else(|| anyhow::anyhow!("{} must be an integer number of cents", key))?,
+    };
+    if amount <= 0 {
+        anyhow::bail!("{} must be positive, got {}", key, amount);
+    }
+    Ok(amount)
+}
+
+/// `annual_interest_rate` (default 0.05) and `term_months` (default 60) of
+/// `create_loan`: a rate of zero or more, and a term of 1 to `MAX_TERM_MONTHS`
+fn loan_terms_arg(args: &Value) -> Result<(f64, i32)> {
+    let rate = match args.get("annual_interest_rate") {
+        None | Some(Value::Null) => 0.05,
+        Some(value) => value
+            .as_f64()
+            .filter(|rate| rate.is_finite() && *rate >= 0.0)
+            .ok_or_else(|| InvalidParams(format!("annual_interest_rate must be a number of 0 or more, got {}", value)))?,
+    };
+    let term = match args.get("term_months") {
+        None | Some(Value::Null) => 60,
+        Some(value) => value
+            .as_i64()
+            .and_then(|term| i32::try_from(term).ok())
+            .filter(|term| (1..=MAX_TERM_MONTHS).contains(term))
+            .ok_or_else(|| {
+                InvalidParams(format!("term_months must be a whole number from 1 to {}, got {}", MAX_TERM_MONTHS, value))
+            })?,
+    };
+    Ok((rate, term))
+}
+
+/// Total charge for metered usage: `units` must be positive and the unit price
+/// non-negative
+fn usage_amount(units: i64, unit_price_cents: i64) -> Result<i64> {
+    if units <= 0 {
+        anyhow::bail!("units must be positive, got {}", units);
+    }
+    if unit_price_cents < 0 {
+        anyhow::bail!("unit_price_cents must not be negative, got {}", unit_price_cents);
+    }
+    units
+        .checked_mul(unit_price_cents)
+        .ok_or_else(|| anyhow::anyhow!("units * unit_price_cents overflows"))
+}
+
+/// Transactions only carry a reference string, so the metering details go
+/// there; see `ReferenceFormat::usage_reference`
+fn usage_metering(units: i64, unit_price_cents: i64) -> String {
+    format!("usage: {} units x {}", units, format_balance(unit_price_cents))
+}
+
+/// The platform's cut comes out of the payment and has to go somewhere: a
+/// fee needs a platform to receive it, a platform needs a fee, and the
+/// provider must keep something.
+fn validate_platform_fee(platform_id: Option<&str>, platform_fee: Option<i64>, amount: i64) -> Result<()> {
+    let platform_id = platform_id.filter(|id| !id.is_empty());
+    let message = match (platform_id, platform_fee) {
+        (_, Some(fee)) if fee < 0 => format!("platform_fee_cents must not be negative, got {}", fee),
+        (None, Some(fee)) if fee > 0 => {
+            format!("platform_fee_cents ({}) needs a platform_id to receive it", fee)
+        }
+        (Some(id), None | Some(0)) => format!(
+            "platform_id '{}' given without a platform_fee_cents; pass a positive fee or leave out platform_id",
+            id
+        ),
+        (_, Some(fee)) if fee >= amount => format!(
+            "platform_fee_cents ({}) must be less than amount_cents ({}) so the access provider receives something",
+            fee, amount
+        ),
+        _ => return Ok(()),
+    };
+    Err(InvalidParams(message).into())
+}
+
+/// The entries the ledger posts for an access payment: the payer's operating
+/// account pays the full amount, split between the provider's and the
+/// platform's fee accounts. Mirrors `Transactions.access_payment`.
+fn access_payment_entries(
+    payer_id: &str,
+    access_provider_id: &str,
+    amount: i64,
+    platform: Option<(&str, i64)>,
+) -> Vec<TransferEntry> {
+    let fee = platform.map_or(0, |(_, fee)| fee);
+    let mut entries = vec![
+        TransferEntry {
+            account_id: format!("{}:operating", payer_id),
+            amount: -amount,
+        },
+        TransferEntry {
+            account_id: format!("{}:fees", access_provider_id),
+            amount: amount - fee,
+        },
+    ];
+    if let Some((platform_id, fee)) = platform {
+        entries.push(TransferEntry {
+            account_id: format!("{}:fees", platform_id),
+            amount: fee,
+        });
+    }
+    entries
+}
+
+/// Who got what out of an access payment
+fn access_payment_split(entries: &[TransferEntry], platform_fee: i64) -> Value {
+    let provider_net = entries.get(1).map_or(0, |e| e.amount);
+    json!({
+        "provider_net": format_balance(provider_net),
+        "provider_net_cents": provider_net,
+        "platform_fee": format_balance(platform_fee),
+        "platform_fee_cents": platform_fee,
+        "entries": entries_to_json(entries),
+    })
+}
+
+/// Split `total_cents` in proportion to `shares` by largest remainder
+/// (Hamilton): each party first gets the floor of its exact share, then the
+/// leftover cents go one each to the parties with the largest fractional
+/// parts, ties broken by party order. The parts always sum to `total_cents`.
+/// Shares are weights and needn't sum to 1; without a positive finite sum
+/// they count as equal.
+fn split_amount(total_cents: i64, shares: &[f64]) -> Vec<i64> {
+    if shares.is_empty() {
+        return Vec::new();
+    }
+    let sum: f64 = shares.iter().sum();
+    let valid = sum.is_finite() && sum > 0.0 && shares.iter().all(|s| *s >= 0.0);
+    let weight = |s: f64| if valid { s / sum } else { 1.0 / shares.len() as f64 };
+    let exact: Vec<f64> = shares.iter().map(|s| total_cents as f64 * weight(*s)).collect();
+    let mut amounts: Vec<i64> = exact.iter().map(|e| e.floor() as i64).collect();
+
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by(|&a, &b| {
+        let frac = |i: usize| exact[i] - exact[i].floor();
+        frac(b).total_cmp(&frac(a)).then(a.cmp(&b))
+    });
+    // Float error can leave the floors a cent over as well as under the total;
+    // an excess is taken back from the smallest fractional parts
+    let residue = total_cents - amounts.iter().sum::<i64>();
+    if residue >= 0 {
+        for &i in order.iter().cycle().take(residue as usize) {
+            amounts[i] += 1;
+        }
+    } else {
+        for &i in order.iter().rev().cycle().take(residue.unsigned_abs() as usize) {
+            amounts[i] -= 1;
+        }
+    }
+    amounts
+}
+
+/// `split_amount` for a revenue share contract, plus the residue: how many
+/// cents were placed by remainder rather than by the floors of the shares
+fn split_revenue(amount_cents: i64, shares: &[f64]) -> Result<(Vec<i64>, i64)> {
+    let total: f64 = shares.iter().sum();
+    if shares.is_empty() || !total.is_finite() || total <= 0.0 {
+        anyhow::bail!("Revenue share contract has no positive shares");
+    }
+    let floors: i64 = shares
+        .iter()
+        .map(|s| (amount_cents as f64 * s / total).floor() as i64)
+        .sum();
+    Ok((split_amount(amount_cents, shares), amount_cents - floors))
+}
+
+fn revenue_share_distributions(
+    contract: &smartcontracts::RevenueShareContract,
+    amounts: &[i64],
+) -> Vec<Value> {
+    contract
+        .parties
+        .iter()
+        .zip(amounts)
+        .map(|(party, amount)| {
+            json!({
+                "participant_id": party.participant_id,
+                "account_id": format!("{}:operating", party.participant_id),
+                "share": party.share,
+                "amount": format_balance(*amount),
+                "amount_cents": amount,
+            })
+        })
+        .collect()
+}
+
+/// Entries and reference of one transfer in a batch
+type BatchItem = (Vec<(String, i64)>, String);
+
+/// Whether the ledger lets an account of this type go below zero
+fn may_go_negative(account_type: i32) -> bool {
+    account_type == AccountType::Receivables as i32 || account_type == AccountType::Payables as i32
+}
+
+/// Replay `entries` in order against `accounts` (None for an account that
+/// does not exist), the way the ledger applies them: each entry must leave a
+/// non-negative balance unless the account is receivables or payables.
+/// With `allow_overdraft` an overdraft is reported on the entry but does not
+/// fail the simulation.
+fn simulate_transfer_report(
+    entries: &[(String, i64)],
+    accounts: &BTreeMap<String, Option<Account>>,
+    allow_overdraft: bool,
+) -> Value {
+    let mut balances: BTreeMap<&str, i64> = BTreeMap::new();
+    let mut problems = Vec::new();
+    let report: Vec<Value> = entries
+        .iter()
+        .map(|(account_id, amount)| {
+            let Some(Some(account)) = accounts.get(account_id) else {
+                let problem = format!("account {} not found", account_id);
+                problems.push(problem.clone());
+                return json!({
+                    "account_id": account_id,
+                    "amount_cents": amount,
+                    "exists": false,
+                    "ok": false,
+                    "problem": problem,
+                });
+            };
+            let balance = balances.entry(account_id).or_insert(account.balance);
+            let before = *balance;
+            let after = before.saturating_add(*amount);
+            *balance = after;
+            let overdraws = after < 0 && !may_go_negative(account.account_type);
+            let mut entry = json!({
+                "account_id": account_id,
+                "amount_cents": amount,
+                "exists": true,
+                "account_type": account_type_to_string(account.account_type),
+                "balance_before": format_balance(before),
+                "balance_before_cents": before,
+                "balance_after": format_balance(after),
+                "balance_after_cents": after,
+                "ok": !overdraws || allow_overdraft,
+            });
+            if overdraws {
+                let problem = format!(
+                    "{} would go to {}; it has {} and the entry moves {}",
+                    account_id,
+                    format_balance(after),
+                    format_balance(before),
+                    format_balance(*amount)
+                );
+                entry["overdraft"] = json!(true);
+                entry["problem"] = json!(problem);
+                if !allow_overdraft {
+                    problems.push(problem);
                 }
-            },
-            {
-                "name": "get_participant_accounts",
-                "description": "Get all ledger accounts for a participant. Returns account IDs, types (Operating, Receivables, Payables, Fees, etc.), and balances.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "participant_id": {
-                            "type": "string",
-                            "description": "Participant ID (e.g., 'salon_glamour', 'schampo_etc', 'assa_abloy')"
-                        }
-                    },
-                    "required": ["participant_id"]
+            }
+            entry
+        })
+        .collect();
+
+    let net: i64 = entries.iter().map(|(_, amount)| amount).sum();
+    if entries.is_empty() {
+        problems.push("no entries".to_string());
+    }
+    if net != 0 {
+        problems.push(format!("entries sum to {}, must be zero", format_balance(net)));
+    }
+    json!({
+        "would_succeed": problems.is_empty(),
+        "nets_to_zero": net == 0,
+        "net_cents": net,
+        "allow_overdraft": allow_overdraft,
+        "entries": report,
+        "problems": problems,
+    })
+}
+
+/// Parse and check the `transfers` argument of batch_transfer. Every transfer
+/// is validated before any is posted, so a malformed item can't leave a
+/// partially applied batch behind.
+fn batch_transfers_arg(args: &Value) -> Result<Vec<BatchItem>> {
+    let items = args
+        .get("transfers")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("transfers must be an array"))?;
+    if items.is_empty() {
+        anyhow::bail!("transfers is empty");
+    }
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let reference = item
+                .get("reference")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("transfer {} has no reference", index))?
+                .to_string();
+            let entries = item
+                .get("entries")
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| anyhow::anyhow!("transfer {} has no entries array", index))?
+                .iter()
+                .map(|e| {
+                    let account_id = e.get("account_id").and_then(|v| v.as_str());
+                    let amount = e.get("amount_cents").and_then(|v| v.as_i64());
+                    match (account_id, amount) {
+                        (Some(account_id), Some(amount)) => Ok((account_id.to_string(), amount)),
+                        _ => anyhow::bail!(
+                            "transfer {} has an entry without account_id and integer amount_cents",
+                            index
+                        ),
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if entries.is_empty() {
+                anyhow::bail!("transfer {} has no entries", index);
+            }
+            let net: i64 = entries.iter().map(|(_, amount)| amount).sum();
+            if net != 0 {
+                anyhow::bail!("transfer {} entries sum to {}, must be zero", index, format_balance(net));
+            }
+            Ok((entries, reference))
+        })
+        .collect()
+}
+
+fn invoice_contract_to_json(contract: &smartcontracts::InvoiceContract) -> Value {
+    json!({
+        "id": contract.id,
+        "supplier_id": contract.supplier_id,
+        "buyer_id": contract.buyer_id,
+        "amount_cents": contract.amount_cents,
+        "amount": format_balance(contract.amount_cents),
+        "issue_date": contract.issue_date,
+        "issue_date_utc": format_date(contract.issue_date),
+        "due_date": contract.due_date,
+        "due_date_utc": format_date(contract.due_date),
+        "payment_terms": contract.payment_terms,
+        "auto_debit": contract.auto_debit,
+        "late_fee_cents": contract.late_fee_cents,
+        "status": contract.status,
+        "reference": contract.reference,
+        "created_at": contract.created_at,
+        "paid_at": contract.paid_at,
+    })
+}
+
+fn subscription_contract_to_json(contract: &smartcontracts::SubscriptionContract) -> Value {
+    json!({
+        "id": contract.id,
+        "provider_id": contract.provider_id,
+        "subscriber_id": contract.subscriber_id,
+        "monthly_fee_cents": contract.monthly_fee_cents,
+        "monthly_fee": format_balance(contract.monthly_fee_cents),
+        "billing_date": contract.billing_date,
+        "auto_debit": contract.auto_debit,
+        "cancellation_notice_days": contract.cancellation_notice_days,
+        "start_date": contract.start_date,
+        "start_date_utc": format_date(contract.start_date),
+        "end_date": contract.end_date,
+        "end_date_utc": format_date(contract.end_date),
+        "status": contract.status,
+        "next_billing_date": contract.next_billing_date,
+    })
+}
+
+fn conditional_payment_to_json(contract: &smartcontracts::ConditionalPaymentContract) -> Value {
+    json!({
+        "id": contract.id,
+        "payer_id": contract.payer_id,
+        "receiver_id": contract.receiver_id,
+        "amount_cents": contract.amount_cents,
+        "amount": format_balance(contract.amount_cents),
+        "condition_type": contract.condition_type,
+        "trigger": contract.trigger,
+        "status": contract.status,
+        "created_at": contract.created_at,
+        "executed_at": contract.executed_at,
+    })
+}
+
+fn revenue_share_contract_to_json(contract: &smartcontracts::RevenueShareContract) -> Value {
+    let parties_json: Vec<Value> = contract
+        .parties
+        .iter()
+        .map(|p| {
+            json!({
+                "participant_id": p.participant_id,
+                "share": p.share,
+                "share_percent": (p.share * 100.0) as i32,
+            })
+        })
+        .collect();
+    json!({
+        "id": contract.id,
+        "transaction_type": contract.transaction_type,
+        "parties": parties_json,
+        "auto_split": contract.auto_split,
+        "status": contract.status,
+        "created_at": contract.created_at,
+        "last_distributed_at": contract.last_distributed_at,
+    })
+}
+
+fn generic_contract_to_json(contract: &smartcontracts::GenericContract) -> Value {
+    json!({
+        "id": contract.id,
+        "name": contract.name,
+        "description": contract.description,
+        "contract_type": contract_type_to_string(contract.contract_type),
+        "status": contract_status_to_string(contract.status).to_lowercase(),
+        "created_at": contract.created_at,
+        "last_executed_at": contract.last_executed_at,
+        "next_execution_at": contract.next_execution_at,
+        "conditions": contract.conditions.iter().map(|c| json!({
+            "type": c.r#type,
+            "parameters": c.parameters,
+        })).collect::<Vec<_>>(),
+        "actions": contract.actions.iter().map(|a| json!({
+            "type": a.r#type,
+            "parameters": a.parameters,
+        })).collect::<Vec<_>>(),
+        "metadata": contract.metadata,
+        "yaml_source": contract.yaml_source,
+    })
+}
+
+/// Case-insensitive substring match of a business reference
+fn reference_matches(reference: &str, query: &str) -> bool {
+    reference.to_lowercase().contains(&query.trim().to_lowercase())
+}
+
+/// Largest `limit` the list tools pass on to the server
+const MAX_LIST_LIMIT: i64 = 1000;
+
+/// A list tool's `limit` argument, brought into 1..=MAX_LIST_LIMIT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ListLimit {
+    value: i32,
+    /// The limit asked for, when it was out of range and replaced
+    requested: Option<i64>,
+}
+
+impl ListLimit {
+    /// `default` for a missing or non-positive limit, MAX_LIST_LIMIT for one
+    /// above it
+    fn from_arg(requested: Option<i64>, default: i32) -> Self {
+        let value = match requested {
+            None => default,
+            Some(l) if l < 1 => default,
+            Some(l) => l.min(MAX_LIST_LIMIT) as i32,
+        };
+        Self {
+            value,
+            requested: requested.filter(|l| *l != i64::from(value)),
+        }
+    }
+
+    /// The `limit` argument of a list tool; see `from_arg`
+    fn from_args(args: &Value, default: i32) -> Self {
+        Self::from_arg(args.get("limit").and_then(|v| v.as_i64()), default)
+    }
+}
+
+/// Size note for list results. `truncated` means the limit was reached, so
+/// there may be more rows than were returned; raise `limit` to see them.
+fn list_meta(row_count: usize, limit: ListLimit, truncated: bool) -> Value {
+    let mut meta = json!({
+        "row_count": row_count,
+        "limit": limit.value,
+        "truncated": truncated,
+    });
+    if let Some(requested) = limit.requested {
+        meta["limit_clamped_from"] = json!(requested);
+        meta["note"] = json!(format!(
+            "limit must be between 1 and {}; {} was used instead of {}",
+            MAX_LIST_LIMIT, limit.value, requested
+        ));
+    }
+    meta
+}
+
+/// Client-side filters for `list_transactions`; all bounds are inclusive
+#[derive(Debug, Default)]
+struct TransactionFilter {
+    min_amount_cents: Option<i64>,
+    max_amount_cents: Option<i64>,
+    /// Unix timestamp in milliseconds
+    start_time: Option<i64>,
+    /// Unix timestamp in milliseconds
+    end_time: Option<i64>,
+    /// One of `TRANSACTION_TYPES`
+    tx_type: Option<String>,
+}
+
+impl TransactionFilter {
+    fn from_args(args: &Value) -> Result<Self> {
+        let get = |key: &str| args.get(key).and_then(|v| v.as_i64());
+        let tx_type = args.get("type").and_then(|v| v.as_str()).map(|t| t.trim().to_lowercase());
+        if let Some(t) = tx_type.as_deref().filter(|t| !TRANSACTION_TYPES.contains(t)) {
+            return Err(InvalidParams(format!(
+                "unknown transaction type '{}', expected one of: {}",
+                t,
+                TRANSACTION_TYPES.join(", ")
+            ))
+            .into());
+        }
+        let filter = Self {
+            min_amount_cents: get("min_amount_cents"),
+            max_amount_cents: get("max_amount_cents"),
+            start_time: get("start_time"),
+            end_time: get("end_time"),
+            tx_type,
+        };
+        if let (Some(min), Some(max)) = (filter.min_amount_cents, filter.max_amount_cents) {
+            if min > max {
+                return Err(InvalidParams(format!(
+                    "min_amount_cents ({}) is greater than max_amount_cents ({})",
+                    min, max
+                ))
+                .into());
+            }
+        }
+        if let (Some(start), Some(end)) = (filter.start_time, filter.end_time) {
+            if start > end {
+                return Err(InvalidParams(format!(
+                    "start_time ({}) is after end_time ({})",
+                    start, end
+                ))
+                .into());
+            }
+        }
+        Ok(filter)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min_amount_cents.is_none()
+            && self.max_amount_cents.is_none()
+            && self.start_time.is_none()
+            && self.end_time.is_none()
+            && self.tx_type.is_none()
+    }
+
+    /// The type must match, the timestamp must be in range, and so must the
+    /// absolute amount of at least one entry
+    fn matches(&self, tx: &Transaction) -> bool {
+        let in_range = |value: i64, min: Option<i64>, max: Option<i64>| {
+            min.is_none_or(|m| value >= m) && max.is_none_or(|m| value <= m)
+        };
+        self.tx_type.as_deref().is_none_or(|t| transaction_type(&tx.r#type, &tx.reference) == t)
+            && in_range(tx.timestamp, self.start_time, self.end_time)
+            && tx.entries.iter().any(|e| {
+                in_range(e.amount.abs(), self.min_amount_cents, self.max_amount_cents)
+            })
+    }
+}
+
+fn transaction_to_json(tx: &Transaction) -> Value {
+    json!({
+        "transaction_id": tx.id,
+        "type": transaction_type(&tx.r#type, &tx.reference),
+        "reference": tx.reference,
+        "timestamp": tx.timestamp,
+        "entries": entries_with_memos(&tx.entries, &tx.reference),
+        "net_change": net_change(&tx.entries),
+    })
+}
+
+/// `current` balance of `account_id` with the entries of every transaction
+/// after `timestamp` undone, and how many transactions that was
+fn balance_as_of(account_id: &str, current: i64, transactions: &[Transaction], timestamp: i64) -> (i64, usize) {
+    let later: Vec<&Transaction> = transactions.iter().filter(|tx| tx.timestamp > timestamp).collect();
+    let change: i64 = later
+        .iter()
+        .flat_map(|tx| &tx.entries)
+        .filter(|e| e.account_id == account_id)
+        .map(|e| e.amount)
+        .sum();
+    (current - change, later.len())
+}
+
+/// One borrower's position in a lender's loan book, in cents
+#[derive(Debug, Default, PartialEq)]
+struct BorrowerLoans {
+    lent: i64,
+    repaid: i64,
+    outstanding: i64,
+    /// Number of loans disbursed
+    loans: usize,
+}
+
+/// Group the loan transactions among `transactions` that touch `receivables`
+/// by borrower. The server posts loans as "LOAN: ..." and repayments as
+/// "LOAN_REPAYMENT: ..."; the borrower is the owner of the `:payables`
+/// account in the same transaction.
+fn loan_book(receivables: &str, transactions: &[Transaction]) -> BTreeMap<String, BorrowerLoans> {
+    let mut book: BTreeMap<String, BorrowerLoans> = BTreeMap::new();
+    for tx in transactions {
+        let is_loan = tx.reference.starts_with("LOAN: ");
+        if !is_loan && !tx.reference.starts_with("LOAN_REPAYMENT: ") {
+            continue;
+        }
+        let amount: i64 = tx
+            .entries
+            .iter()
+            .filter(|e| e.account_id == receivables)
+            .map(|e| e.amount)
+            .sum();
+        let Some(borrower) = tx
+            .entries
+            .iter()
+            .find_map(|e| e.account_id.strip_suffix(":payables"))
+        else {
+            continue;
+        };
+        let loans = book.entry(borrower.to_string()).or_default();
+        if is_loan {
+            loans.lent += amount;
+            loans.loans += 1;
+        } else {
+            loans.repaid -= amount;
+        }
+        loans.outstanding += amount;
+    }
+    book
+}
+
+/// The `type` and main fields of a contract, as list_contracts shows it
+fn contract_summary_json(c: &smartcontracts::ContractResponse) -> Option<Value> {
+    use smartcontracts::contract_response::Contract;
+    match c.contract.as_ref() {
+        Some(Contract::Invoice(inv)) => Some(json!({
+            "type": "invoice",
+            "contract": {
+                "id": inv.id,
+                "supplier_id": inv.supplier_id,
+                "buyer_id": inv.buyer_id,
+                "amount_cents": inv.amount_cents,
+                "status": inv.status,
+                "reference": inv.reference,
+            }
+        })),
+        Some(Contract::Subscription(sub)) => Some(json!({
+            "type": "subscription",
+            "contract": {
+                "id": sub.id,
+                "provider_id": sub.provider_id,
+                "subscriber_id": sub.subscriber_id,
+                "monthly_fee_cents": sub.monthly_fee_cents,
+                "status": sub.status,
+            }
+        })),
+        Some(Contract::ConditionalPayment(cp)) => Some(json!({
+            "type": "conditional_payment",
+            "contract": {
+                "id": cp.id,
+                "payer_id": cp.payer_id,
+                "receiver_id": cp.receiver_id,
+                "amount_cents": cp.amount_cents,
+                "status": cp.status,
+            }
+        })),
+        Some(Contract::RevenueShare(rs)) => Some(json!({
+            "type": "revenue_share",
+            "contract": {
+                "id": rs.id,
+                "transaction_type": rs.transaction_type,
+                "status": rs.status,
+            }
+        })),
+        Some(Contract::Generic(gen)) => Some(json!({
+            "type": "generic",
+            "contract": {
+                "id": gen.id,
+                "name": gen.name,
+                "contract_type": gen.contract_type,
+                "status": gen.status,
+                "next_execution_at": gen.next_execution_at,
+                "participants": contract_parties(c),
+            }
+        })),
+        None => None,
+    }
+}
+
+/// Buckets of get_participant_contracts; statuses that fit none go under "other"
+const CONTRACT_BUCKETS: [&str; 5] = ["active", "paused", "completed", "cancelled", "other"];
+
+/// Which of `CONTRACT_BUCKETS` a contract's status falls in. Each contract
+/// type names its statuses differently: a pending or overdue invoice is
+/// still active, a paid one completed.
+fn contract_bucket(contract: &smartcontracts::ContractResponse) -> &'static str {
+    use smartcontracts::contract_response::Contract;
+    let status = match contract.contract.as_ref() {
+        Some(Contract::Invoice(inv)) => inv.status.as_str(),
+        Some(Contract::Subscription(sub)) => sub.status.as_str(),
+        Some(Contract::ConditionalPayment(cp)) => cp.status.as_str(),
+        Some(Contract::RevenueShare(rs)) => rs.status.as_str(),
+        Some(Contract::Generic(gen)) => match ContractStatus::try_from(gen.status) {
+            Ok(ContractStatus::Active) => "active",
+            Ok(ContractStatus::Paused) => "paused",
+            Ok(ContractStatus::Completed) => "completed",
+            Ok(ContractStatus::Cancelled) => "cancelled",
+            _ => "",
+        },
+        None => "",
+    };
+    match status.to_lowercase().as_str() {
+        "active" | "pending" | "overdue" | "conditions_met" => "active",
+        "paused" => "paused",
+        "completed" | "paid" | "executed" | "expired" => "completed",
+        "cancelled" => "cancelled",
+        _ => "other",
+    }
+}
+
+/// `contracts` that `participant_id` is a party to, grouped by
+/// `contract_bucket`, with a count per bucket
+fn participant_contracts_report(participant_id: &str, contracts: &[smartcontracts::ContractResponse]) -> Value {
+    let mut groups: BTreeMap<&str, Vec<Value>> = CONTRACT_BUCKETS.iter().map(|b| (*b, Vec::new())).collect();
+    for contract in contracts.iter().filter(|c| contract_parties(c).contains(&participant_id)) {
+        if let (Some(group), Some(summary)) = (groups.get_mut(contract_bucket(contract)), contract_summary_json(contract)) {
+            group.push(summary);
+        }
+    }
+    let counts: serde_json::Map<String, Value> =
+        CONTRACT_BUCKETS.iter().map(|b| (b.to_string(), json!(groups[b].len()))).collect();
+    let total: usize = groups.values().map(Vec::len).sum();
+    let mut buckets = serde_json::Map::new();
+    for bucket in CONTRACT_BUCKETS {
+        if let Some(group) = groups.remove(bucket) {
+            buckets.insert(bucket.to_string(), json!(group));
+        }
+    }
+    json!({
+        "participant_id": participant_id,
+        "total": total,
+        "counts": counts,
+        "contracts": buckets,
+    })
+}
+
+/// Participant ids that are a party to `contract`. Generic contracts name
+/// their parties in metadata (see `PARTICIPANT_METADATA_FIELDS`).
+fn contract_parties(contract: &smartcontracts::ContractResponse) -> Vec<&str> {
+    use smartcontracts::contract_response::Contract;
+    let mut parties: Vec<&str> = match contract.contract.as_ref() {
+        Some(Contract::Invoice(inv)) => vec![&inv.supplier_id, &inv.buyer_id],
+        Some(Contract::Subscription(sub)) => vec![&sub.provider_id, &sub.subscriber_id],
+        Some(Contract::ConditionalPayment(cp)) => vec![&cp.payer_id, &cp.receiver_id],
+        Some(Contract::RevenueShare(rs)) => rs.parties.iter().map(|p| p.participant_id.as_str()).collect(),
+        Some(Contract::Generic(gen)) => PARTICIPANT_METADATA_FIELDS
+            .iter()
+            .filter_map(|field| gen.metadata.get(*field).map(String::as_str))
+            .collect(),
+        None => Vec::new(),
+    };
+    let mut seen = HashSet::new();
+    parties.retain(|id| !id.is_empty() && seen.insert(*id));
+    parties
+}
+
+fn entries_to_json(entries: &[TransferEntry]) -> Vec<Value> {
+    entries
+        .iter()
+        .map(|e| {
+            json!({
+                "account_id": e.account_id,
+                "amount": format_balance(e.amount),
+                "amount_cents": e.amount,
+                "direction": direction(e.amount),
+            })
+        })
+        .collect()
+}
+
+/// `entries_to_json` with each entry's memo, when `reference` carries memos
+/// (see `ReferenceFormat::with_memos`)
+fn entries_with_memos(entries: &[TransferEntry], reference: &str) -> Vec<Value> {
+    let (_, memos) = split_memos(reference);
+    let mut json = entries_to_json(entries);
+    for (position, memo) in memos {
+        if let Some(Value::Object(entry)) = position.checked_sub(1).and_then(|i| json.get_mut(i)) {
+            entry.insert("memo".to_string(), json!(memo));
+        }
+    }
+    json
+}
+
+/// Positive amounts credit an account, negative amounts debit it
+fn direction(amount: i64) -> &'static str {
+    match amount.signum() {
+        1 => "credit",
+        -1 => "debit",
+        _ => "none",
+    }
+}
+
+/// What each account gained or lost overall, keyed by account id. An account
+/// can appear in several entries of one transaction, so this is the sum.
+fn net_change(entries: &[TransferEntry]) -> Value {
+    let mut totals: BTreeMap<&str, i64> = BTreeMap::new();
+    for e in entries {
+        *totals.entry(&e.account_id).or_default() += e.amount;
+    }
+    let totals: serde_json::Map<String, Value> = totals
+        .into_iter()
+        .map(|(account_id, amount)| {
+            (
+                account_id.to_string(),
+                json!({
+                    "amount": format_balance(amount),
+                    "amount_cents": amount,
+                    "direction": direction(amount),
+                }),
+            )
+        })
+        .collect();
+    Value::Object(totals)
+}
+
+/// Fields of `Contact` that update_participant_contact can set
+const CONTACT_FIELDS: [&str; 7] = ["email", "phone", "website", "address", "postal_code", "city", "country"];
+
+fn contact_field<'a>(contact: &'a Contact, field: &str) -> &'a str {
+    match field {
+        "email" => &contact.email,
+        "phone" => &contact.phone,
+        "website" => &contact.website,
+        "address" => &contact.address,
+        "postal_code" => &contact.postal_code,
+        "city" => &contact.city,
+        "country" => &contact.country,
+        _ => "",
+    }
+}
+
+fn contact_to_json(contact: &Contact) -> Value {
+    CONTACT_FIELDS
+        .iter()
+        .map(|field| (field.to_string(), json!(contact_field(contact, field))))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// `current` with the contact fields given in `args` applied: a field left
+/// out is kept, an empty string clears it. The result must pass
+/// `validate_contact`.
+fn contact_from_args(current: &Contact, args: &Value) -> Result<Contact> {
+    if !CONTACT_FIELDS.iter().any(|field| args.get(*field).is_some_and(Value::is_string)) {
+        return Err(InvalidParams(format!("give at least one of: {}", CONTACT_FIELDS.join(", "))).into());
+    }
+    let get = |field: &str, current: &str| {
+        args.get(field)
+            .and_then(|v| v.as_str())
+            .map_or_else(|| current.to_string(), |v| v.trim().to_string())
+    };
+    let contact = Contact {
+        email: get("email", &current.email),
+        phone: get("phone", &current.phone),
+        website: get("website", &current.website),
+        address: get("address", &current.address),
+        postal_code: get("postal_code", &current.postal_code),
+        city: get("city", &current.city),
+        country: get("country", &current.country),
+    };
+    validate_contact(&contact.email, &contact.phone, &contact.website).map_err(InvalidParams)?;
+    Ok(contact)
+}
+
+/// Requested edits to a participant's service tags: replace the whole list
+/// with `set`, then add and remove individual services
+#[derive(Debug, Default, PartialEq)]
+struct ServiceChanges {
+    set: Option<Vec<String>>,
+    add: Vec<String>,
+    remove: Vec<String>,
+}
+
+impl ServiceChanges {
+    fn from_args(args: &Value) -> Result<Self> {
+        let list = |field: &str| -> Result<Option<Vec<String>>> {
+            let Some(values) = args.get(field).and_then(|v| v.as_array()) else {
+                return Ok(None);
+            };
+            let mut services: Vec<String> = Vec::new();
+            for value in values {
+                let service = value.as_str().map(str::trim).unwrap_or_default();
+                if service.is_empty() {
+                    return Err(InvalidParams(format!(
+                        "{} must contain non-empty service ids, got {}",
+                        field, value
+                    ))
+                    .into());
                 }
-            },
-            {
-                "name": "get_account_balance",
-                "description": "Get the current balance of a specific account.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "account_id": {
-                            "type": "string",
-                            "description": "Account ID in format 'participant_id:account_type' (e.g., 'salon_glamour:operating', 'schampo_etc:receivables')"
-                        }
-                    },
-                    "required": ["account_id"]
+                if !services.iter().any(|s| s == service) {
+                    services.push(service.to_string());
                 }
-            },
-            {
-                "name": "transfer",
-                "description": "Execute an atomic multi-party transfer. All entries must sum to zero. Use positive amounts for credits and negative for debits.",
+            }
+            Ok(Some(services))
+        };
+        let changes = Self {
+            set: list("services")?,
+            add: list("add")?.unwrap_or_default(),
+            remove: list("remove")?.unwrap_or_default(),
+        };
+        if changes.set.is_none() && changes.add.is_empty() && changes.remove.is_empty() {
+            return Err(InvalidParams("give services, add or remove".to_string()).into());
+        }
+        if let Some(both) = changes.add.iter().find(|s| changes.remove.contains(s)) {
+            return Err(InvalidParams(format!("service '{}' is in both add and remove", both)).into());
+        }
+        Ok(changes)
+    }
+
+    /// Services to add and to remove to get from `current` to the requested
+    /// list. Services already in the wanted state are left alone, so
+    /// repeating a request changes nothing.
+    fn plan(&self, current: &[String]) -> (Vec<String>, Vec<String>) {
+        let mut target: Vec<String> = self.set.clone().unwrap_or_else(|| current.to_vec());
+        for service in &self.add {
+            if !target.contains(service) {
+                target.push(service.clone());
+            }
+        }
+        target.retain(|s| !self.remove.contains(s));
+
+        let to_add = target.iter().filter(|s| !current.contains(s)).cloned().collect();
+        let to_remove = current.iter().filter(|s| !target.contains(s)).cloned().collect();
+        (to_add, to_remove)
+    }
+}
+
+/// Net position of a set of accounts, in total and per account type, so
+/// callers don't have to add up balances themselves.
+fn accounts_summary(accounts: &[Account]) -> Value {
+    let mut by_type: BTreeMap<&'static str, (i64, u64)> = BTreeMap::new();
+    for a in accounts {
+        let entry = by_type.entry(account_type_to_string(a.account_type)).or_default();
+        entry.0 += a.balance;
+        entry.1 += 1;
+    }
+    let total: i64 = accounts.iter().map(|a| a.balance).sum();
+    let by_type: serde_json::Map<String, Value> = by_type
+        .into_iter()
+        .map(|(account_type, (balance, count))| {
+            (
+                account_type.to_string(),
+                json!({
+                    "accounts": count,
+                    "total": format_balance(balance),
+                    "total_cents": balance,
+                }),
+            )
+        })
+        .collect();
+    json!({
+        "account_count": accounts.len(),
+        "total_balance": format_balance(total),
+        "total_balance_cents": total,
+        "by_type": by_type,
+    })
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// The (id, contract type) of a contract that is due for execution at `now`, if any.
+fn due_contract(contract: &smartcontracts::ContractResponse, now: i64) -> Option<(String, i32)> {
+    use smartcontracts::contract_response::Contract;
+    let is_due = |at: i64| at > 0 && at <= now;
+    match contract.contract.as_ref()? {
+        Contract::Generic(g) if g.status == ContractStatus::Active as i32 && is_due(g.next_execution_at) => {
+            Some((g.id.clone(), g.contract_type))
+        }
+        Contract::Subscription(sub) if sub.status == "active" && is_due(sub.next_billing_date) => {
+            Some((sub.id.clone(), ContractType::Subscription as i32))
+        }
+        Contract::Invoice(inv) if inv.status == "pending" && inv.auto_debit && is_due(inv.due_date) => {
+            Some((inv.id.clone(), ContractType::Invoice as i32))
+        }
+        _ => None,
+    }
+}
+
+// ============================================================================
+// MCP Protocol Handlers
+// ============================================================================
+
+/// Protocol revision that added `structuredContent` to tool results
+const STRUCTURED_CONTENT_PROTOCOL: &str = "2025-06-18";
+/// Oldest revision spoken, and the one assumed when a client doesn't say
+const BASELINE_PROTOCOL: &str = "2024-11-05";
+/// Every revision this server implements, newest first. 2025-03-26 is left
+/// out because it requires accepting JSON-RPC batches, which this server doesn't.
+const SUPPORTED_PROTOCOLS: [&str; 2] = [STRUCTURED_CONTENT_PROTOCOL, BASELINE_PROTOCOL];
+
+/// Pick the protocol version to answer `initialize` with: the client's own
+/// when supported, otherwise the newest one supported, as the spec asks. The
+/// client then decides whether it can continue.
+fn negotiate_protocol(requested: Option<&str>) -> &'static str {
+    match requested {
+        Some(version) => SUPPORTED_PROTOCOLS
+            .into_iter()
+            .find(|supported| *supported == version)
+            .unwrap_or(SUPPORTED_PROTOCOLS[0]),
+        None => BASELINE_PROTOCOL,
+    }
+}
+
+/// Per-connection state set up by `initialize`
+#[derive(Debug, Default)]
+struct Session {
+    /// Also return tool results as `structuredContent`, not only as text
+    structured_content: bool,
+    /// Tool result text as single-line JSON unless a call asks otherwise
+    compact_json: bool,
+}
+
+/// Whether `SCALEGRAPH_COMPACT_JSON` is set to anything but "", "0" or "false"
+fn compact_json_from_env() -> bool {
+    std::env::var("SCALEGRAPH_COMPACT_JSON")
+        .is_ok_and(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "" | "0" | "false"))
+}
+
+fn get_server_info(protocol_version: &str) -> Value {
+    json!({
+        "protocolVersion": protocol_version,
+        // Only tools are implemented; the tool list is fixed for the session
+        "capabilities": {
+            "tools": {
+                "listChanged": false
+            }
+        },
+        "serverInfo": {
+            "name": "scalegraph-ledger",
+            "version": "1.0.0"
+        }
+    })
+}
+
+fn get_tools_list() -> Value {
+    let validate_participants = json!({
+        "type": "boolean",
+        "description": "Check the participant ids exist first and suggest the closest match for typos (default: true)"
+    });
+    let transfer_entries = json!({
+        "type": "array",
+        "description": "Array of transfer entries, each with account_id and amount_cents",
+        "items": {
+            "type": "object",
+            "properties": {
+                "account_id": {
+                    "type": "string",
+                    "description": "Account ID"
+                },
+                "amount_cents": {
+                    "type": "integer",
+                    "description": "Amount in cents (positive=credit, negative=debit)"
+                }
+            },
+            "required": ["account_id", "amount_cents"]
+        }
+    });
+    let mut memo_entries = transfer_entries.clone();
+    memo_entries["items"]["properties"]["memo"] = json!({
+        "type": "string",
+        "description": "Optional note on this entry, e.g. 'platform fee'. Stored in the transaction reference after ' [memos] ' and returned on the entry by transfer, get_transaction and list_transactions"
+    });
+    json!({
+        "tools": [
+            {
+                "name": "list_participants",
+                "description": "List participants in the Scalegraph ecosystem, optionally filtered by role. Returns participant IDs, names, and roles (Access Provider, Banking Partner, Ecosystem Partner, Supplier, Equipment Provider, Ecosystem Orchestrator).",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "entries": {
-                            "type": "array",
-                            "description": "Array of transfer entries, each with account_id and amount_cents",
-                            "items": {
-                                "type": "object",
-                                "properties": {
-                                    "account_id": {
-                                        "type": "string",
-                                        "description": "Account ID"
-                                    },
-                                    "amount_cents": {
-                                        "type": "integer",
-                                        "description": "Amount in cents (positive=credit, negative=debit)"
-                                    }
-                                },
-                                "required": ["account_id", "amount_cents"]
-                            }
-                        },
-                        "reference": {
+                        "role": {
                             "type": "string",
-                            "description": "Transaction reference/description"
+                            "enum": ["access_provider", "banking_partner", "ecosystem_partner", "supplier", "equipment_provider", "ecosystem_orchestrator"],
+                            "description": "Only return participants with this role. Omit to list all participants."
                         }
                     },
-                    "required": ["entries", "reference"]
+                    "required": []
                 }
             },
             {
-                "name": "list_transactions",
-                "description": "List recent transactions from the ledger. Returns transaction history showing all transfers, invoices, and payments.",
+                "name": "resolve_participant",
+                "description": "Find a participant's ID from its name, e.g. 'Salon Glamour' -> 'salon_glamour'. Matching ignores case, spaces and punctuation. An exact name or ID gives status 'resolved' with the participant; otherwise every participant whose name or ID contains the query is returned, as 'resolved' if there is one and 'ambiguous' if there are several. With no match, status is 'not_found' with the closest name under did_you_mean. Use this before other tools when you only know a name.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "limit": {
-                            "type": "integer",
-                            "description": "Maximum number of transactions to return (default: 50)"
+                        "query": {
+                            "type": "string",
+                            "description": "Participant name, or part of a name or ID (e.g., 'glamour')"
                         },
-                        "account_id": {
+                        "role": {
                             "type": "string",
-                            "description": "Optional: Filter by account ID to see only transactions involving this account"
+                            "enum": ["access_provider", "banking_partner", "ecosystem_partner", "supplier", "equipment_provider", "ecosystem_orchestrator"],
+                            "description": "Only consider participants with this role"
                         }
                     },
-                    "required": []
+                    "required": ["query"]
                 }
             },
             {
-                "name": "purchase_invoice",
-                "description": "Create a B2B purchase invoice. Records debt: increases supplier's receivables and buyer's payables. Use pay_invoice later to settle.",
+                "name": "find_duplicate_participants",
+                "description": "Find participants that are probably the same company registered under different IDs. Names are compared ignoring case, spaces and punctuation, and names a typo or two apart also count. Returns groups of look-alikes with each participant's role, account count and total balance, so an operator can decide which ID to keep. Read-only: nothing is merged.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "supplier_id": {
-                            "type": "string",
-                            "description": "Supplier participant ID (e.g., 'schampo_etc')"
-                        },
-                        "buyer_id": {
-                            "type": "string",
-                            "description": "Buyer participant ID (e.g., 'salon_glamour')"
-                        },
-                        "amount_cents": {
-                            "type": "integer",
-                            "description": "Invoice amount in cents (e.g., 455000 for $4,550.00)"
-                        },
-                        "reference": {
+                        "role": {
                             "type": "string",
-                            "description": "Invoice reference (e.g., 'INV-2024-001 ABC Shine 300x')"
+                            "enum": ["access_provider", "banking_partner", "ecosystem_partner", "supplier", "equipment_provider", "ecosystem_orchestrator"],
+                            "description": "Only compare participants with this role"
                         }
                     },
-                    "required": ["supplier_id", "buyer_id", "amount_cents", "reference"]
+                    "required": []
                 }
             },
             {
-                "name": "pay_invoice",
-                "description": "Pay/settle a B2B invoice. Transfers money from buyer to supplier and clears receivables/payables. All 4 entries are atomic.",
+                "name": "create_participant",
+                "description": "Create a new participant in the ecosystem. Participants can be suppliers, access providers, banking partners, etc.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "supplier_id": {
+                        "id": {
                             "type": "string",
-                            "description": "Supplier participant ID"
+                            "description": "Unique participant ID (e.g., 'acme_supplies', 'new_salon')"
                         },
-                        "buyer_id": {
+                        "name": {
                             "type": "string",
-                            "description": "Buyer participant ID"
-                        },
-                        "amount_cents": {
-                            "type": "integer",
-                            "description": "Payment amount in cents"
+                            "description": "Display name (e.g., 'Acme Supplies AB', 'New Salon')"
                         },
-                        "reference": {
+                        "role": {
                             "type": "string",
-                            "description": "Payment reference (e.g., 'PAY-INV-2024-001')"
+                            "enum": ["access_provider", "banking_partner", "ecosystem_partner", "supplier", "equipment_provider", "ecosystem_orchestrator"],
+                            "description": "Participant role in the ecosystem"
                         }
                     },
-                    "required": ["supplier_id", "buyer_id", "amount_cents", "reference"]
+                    "required": ["id", "name", "role"]
                 }
             },
             {
-                "name": "access_payment",
-                "description": "Process real-time micro-payment for access control (e.g., door unlock). Debits payer and credits access provider. Optional platform fee.",
+                "name": "create_participant_account",
+                "description": "Create a ledger account for a participant. Each participant needs accounts to transact (operating for cash, receivables for money owed to them, payables for money they owe, fees for service charges).",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "payer_id": {
-                            "type": "string",
-                            "description": "Payer participant ID (e.g., 'salon_glamour')"
-                        },
-                        "access_provider_id": {
-                            "type": "string",
-                            "description": "Access provider participant ID (e.g., 'assa_abloy')"
-                        },
-                        "amount_cents": {
-                            "type": "integer",
-                            "description": "Access fee in cents (e.g., 800 for $8.00)"
-                        },
-                        "reference": {
+                        "participant_id": {
                             "type": "string",
-                            "description": "Access reference (e.g., 'DOOR-MAIN-20241215')"
+                            "description": "Participant ID to create account for"
                         },
-                        "platform_id": {
+                        "account_type": {
                             "type": "string",
-                            "description": "Optional: Platform to receive fee (e.g., 'beauty_hosting')"
+                            "enum": ["operating", "receivables", "payables", "escrow", "fees", "usage"],
+                            "description": "Type of account: operating (main cash), receivables (A/R), payables (A/P), fees (service charges), escrow (held funds), usage (pay-per-use)"
                         },
-                        "platform_fee_cents": {
+                        "initial_balance_cents": {
                             "type": "integer",
-                            "description": "Optional: Platform fee in cents (e.g., 50 for $0.50)"
+                            "description": "Initial balance in cents (e.g., 100000 for $1,000.00). Default: 0"
                         }
                     },
-                    "required": ["payer_id", "access_provider_id", "amount_cents", "reference"]
+                    "required": ["participant_id", "account_type"]
                 }
             },
             {
-                "name": "create_loan",
-                "description": "Create a loan with formal obligation tracking. Lender provides funds and records receivables/payables. All 4 entries (lender operating, borrower operating, lender receivables, borrower payables) are atomic.",
+                "name": "setup_participant_accounts",
+                "description": "Create the conventional set of accounts for a participant's role in one call: access_provider gets operating, fees, usage; banking_partner gets operating, receivables, escrow; ecosystem_partner gets operating, payables; supplier gets operating, receivables; equipment_provider gets operating, usage, receivables; ecosystem_orchestrator gets operating, fees. Account types the participant already has are skipped. Returns the created accounts.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "lender_id": {
-                            "type": "string",
-                            "description": "Lender participant ID (e.g., 'seb')"
-                        },
-                        "borrower_id": {
+                        "participant_id": {
                             "type": "string",
-                            "description": "Borrower participant ID (e.g., 'salon_glamour')"
-                        },
-                        "amount_cents": {
-                            "type": "integer",
-                            "description": "Loan amount in cents (e.g., 150023 for $1,500.23)"
+                            "description": "Participant ID to set up accounts for"
                         },
-                        "reference": {
+                        "role": {
                             "type": "string",
-                            "description": "Loan reference (e.g., 'LOAN-2024-001')"
+                            "enum": ["access_provider", "banking_partner", "ecosystem_partner", "supplier", "equipment_provider", "ecosystem_orchestrator"],
+                            "description": "Role whose defaults to use. Omit to use the participant's own role."
                         }
                     },
-                    "required": ["lender_id", "borrower_id", "amount_cents", "reference"]
+                    "required": ["participant_id"]
                 }
             },
             {
-                "name": "repay_loan",
-                "description": "Repay a loan and clear obligations. Reverses receivables/payables entries atomically.",
+                "name": "create_participant_accounts",
+                "description": "Create several accounts for a participant in one call, e.g. when onboarding. Accounts are created in order and a failure doesn't stop the rest; every account_type is checked before any account is created. Returns per-item status (created / skipped / failed) with the new account IDs or the error.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "lender_id": {
-                            "type": "string",
-                            "description": "Lender participant ID"
-                        },
-                        "borrower_id": {
+                        "participant_id": {
                             "type": "string",
-                            "description": "Borrower participant ID"
+                            "description": "Participant ID to create the accounts for"
                         },
-                        "amount_cents": {
-                            "type": "integer",
-                            "description": "Repayment amount in cents"
+                        "accounts": {
+                            "type": "array",
+                            "description": "Accounts to create, in order",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "account_type": {
+                                        "type": "string",
+                                        "enum": ["operating", "receivables", "payables", "escrow", "fees", "usage"],
+                                        "description": "Type of account to create"
+                                    },
+                                    "initial_balance_cents": {
+                                        "type": "integer",
+                                        "description": "Initial balance in cents (default: 0)"
+                                    }
+                                },
+                                "required": ["account_type"]
+                            }
                         },
-                        "reference": {
-                            "type": "string",
-                            "description": "Repayment reference (e.g., 'REPAY-LOAN-2024-001')"
+                        "skip_existing": {
+                            "type": "boolean",
+                            "description": "Skip account types the participant already has, or that appear earlier in the list (default: true). When false they are reported as failed."
                         }
                     },
-                    "required": ["lender_id", "borrower_id", "amount_cents", "reference"]
+                    "required": ["participant_id", "accounts"]
                 }
             },
             {
-                "name": "get_outstanding_loans",
-                "description": "Get total outstanding loans for a lender. Returns the positive balance in lender's receivables account.",
+                "name": "get_participant_accounts",
+                "description": "Get all ledger accounts for a participant. Returns account IDs, types (Operating, Receivables, Payables, Fees, etc.), and balances, plus a summary with the total balance and subtotals per account type.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "lender_id": {
+                        "participant_id": {
                             "type": "string",
-                            "description": "Lender participant ID (e.g., 'seb')"
+                            "description": "Participant ID (e.g., 'salon_glamour', 'schampo_etc', 'assa_abloy')"
                         }
                     },
-                    "required": ["lender_id"]
+                    "required": ["participant_id"]
                 }
             },
             {
-                "name": "get_total_debt",
-                "description": "Get total debt for a borrower. Returns the absolute value of negative balance in borrower's payables account.",
+                "name": "update_participant_services",
+                "description": "Edit the service tags a participant provides (used for matchmaking). Give 'services' to replace the whole list, and/or 'add' and 'remove' for individual tags. Tags already in the wanted state are skipped. Returns the resulting services and what was added and removed.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "borrower_id": {
+                        "participant_id": {
                             "type": "string",
-                            "description": "Borrower participant ID (e.g., 'salon_glamour')"
+                            "description": "Participant ID (e.g., 'schampo_etc')"
+                        },
+                        "services": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "The complete new list of service ids; an empty array clears them"
+                        },
+                        "add": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Service ids to add"
+                        },
+                        "remove": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Service ids to remove"
                         }
                     },
-                    "required": ["borrower_id"]
+                    "required": ["participant_id"]
                 }
             },
             {
-                "name": "create_invoice_contract",
-                "description": "Create a smart invoice contract with automation (auto-debit on due date, late fees). Higher-level than purchase_invoice - includes contract management.",
+                "name": "update_participant_contact",
+                "description": "Edit a participant's contact information. Only the fields given change; an empty string clears a field. The email, phone and website are checked for a plausible shape. Returns the resulting contact and which fields were updated.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "supplier_id": {"type": "string", "description": "Supplier participant ID"},
-                        "buyer_id": {"type": "string", "description": "Buyer participant ID"},
-                        "amount_cents": {"type": "integer", "description": "Invoice amount in cents"},
-                        "issue_date": {"type": "integer", "description": "Issue date (Unix timestamp in milliseconds)"},
-                        "due_date": {"type": "integer", "description": "Due date (Unix timestamp in milliseconds)"},
-                        "payment_terms": {"type": "string", "description": "Payment terms (e.g., 'Net 30')"},
-                        "auto_debit": {"type": "boolean", "description": "Enable automatic debit on due date"},
-                        "late_fee_cents": {"type": "integer", "description": "Late fee in cents if not paid by due date"},
-                        "reference": {"type": "string", "description": "Invoice reference"}
+                        "participant_id": {
+                            "type": "string",
+                            "description": "Participant ID (e.g., 'salon_glamour')"
+                        },
+                        "email": {"type": "string", "description": "Primary email address (e.g., 'info@salonglamour.se')"},
+                        "phone": {"type": "string", "description": "Primary phone number (e.g., '+46 8 123 456')"},
+                        "website": {"type": "string", "description": "Website URL (e.g., 'https://salonglamour.se')"},
+                        "address": {"type": "string", "description": "Street address"},
+                        "postal_code": {"type": "string", "description": "Postal/ZIP code"},
+                        "city": {"type": "string", "description": "City"},
+                        "country": {"type": "string", "description": "Country code (e.g., 'SE')"}
                     },
-                    "required": ["supplier_id", "buyer_id", "amount_cents", "issue_date", "due_date", "reference"]
+                    "required": ["participant_id"]
                 }
             },
             {
-                "name": "get_invoice_contract",
-                "description": "Get details of an invoice contract by ID.",
+                "name": "get_account_balance",
+                "description": "Get the current balance of a specific account.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "contract_id": {"type": "string", "description": "Invoice contract ID"}
+                        "account_id": {
+                            "type": "string",
+                            "description": "Account ID in format 'participant_id:account_type' (e.g., 'salon_glamour:operating', 'schampo_etc:receivables')"
+                        }
                     },
-                    "required": ["contract_id"]
+                    "required": ["account_id"]
                 }
             },
             {
-                "name": "create_subscription_contract",
-                "description": "Create a subscription contract with recurring billing (e.g., monthly SaaS fee). Supports auto-debit and cancellation notice periods.",
+                "name": "get_balance_as_of",
+                "description": "Get the balance an account had at a point in time, reconstructed by undoing the entries of later transactions from the current balance. Accuracy depends on the scanned history reaching back to that time; the result warns when it may not.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "provider_id": {"type": "string", "description": "Service provider participant ID"},
-                        "subscriber_id": {"type": "string", "description": "Subscriber participant ID"},
-                        "monthly_fee_cents": {"type": "integer", "description": "Monthly subscription fee in cents"},
-                        "billing_date": {"type": "string", "description": "Billing date pattern (e.g., 'every 1st', 'every 15th')"},
-                        "auto_debit": {"type": "boolean", "description": "Enable automatic monthly debit"},
-                        "cancellation_notice_days": {"type": "integer", "description": "Days notice required for cancellation"},
-                        "start_date": {"type": "integer", "description": "Start date (Unix timestamp in milliseconds)"},
-                        "end_date": {"type": "integer", "description": "Optional end date (Unix timestamp in milliseconds)"}
-                    },
-                    "required": ["provider_id", "subscriber_id", "monthly_fee_cents", "billing_date", "start_date"]
-                }
-            },
+                        "account_id": {
+                            "type": "string",
+                            "description": "Account ID in format 'participant_id:account_type' (e.g., 'salon_glamour:operating')"
+                        },
+                        "timestamp": {
+                            "type": "integer",
+                            "description": "Point in time (Unix timestamp in milliseconds). Transactions at exactly this time count as already applied."
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Optional: How many of the account's most recent transactions to scan, 1-1000 (default: 1000)"
+                        }
+                    },
+                    "required": ["account_id", "timestamp"]
+                }
+            },
             {
-                "name": "get_subscription_contract",
-                "description": "Get details of a subscription contract by ID.",
+                "name": "transfer",
+                "description": "Execute an atomic multi-party transfer. All entries must sum to zero. Use positive amounts for credits and negative for debits. Each entry may carry a memo explaining that leg.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "contract_id": {"type": "string", "description": "Subscription contract ID"}
+                        "entries": memo_entries,
+                        "reference": {
+                            "type": "string",
+                            "description": "Transaction reference/description"
+                        }
                     },
-                    "required": ["contract_id"]
+                    "required": ["entries", "reference"]
                 }
             },
             {
-                "name": "create_conditional_payment",
-                "description": "Create a conditional payment contract that executes when conditions are met (e.g., 'if_service_completed'). Payment is held until trigger condition is satisfied.",
+                "name": "simulate_transfer",
+                "description": "Check whether a transfer would succeed without posting it. Replays the entries in order against current balances, the way the ledger does: reports per entry whether the account exists and its balance before and after, flags any account other than receivables/payables that would go negative, and checks the entries net to zero. Returns would_succeed and the list of problems.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "payer_id": {"type": "string", "description": "Payer participant ID"},
-                        "receiver_id": {"type": "string", "description": "Receiver participant ID"},
-                        "amount_cents": {"type": "integer", "description": "Payment amount in cents"},
-                        "condition_type": {"type": "string", "description": "Condition type (e.g., 'if_service_completed')"},
-                        "trigger": {"type": "string", "description": "Trigger condition (e.g., \"status = 'completed'\")"}
+                        "entries": transfer_entries.clone(),
+                        "allow_overdraft": {
+                            "type": "boolean",
+                            "description": "Report overdrafts on the entries without failing the simulation, e.g. when the account will be funded first (default: false). The ledger itself still rejects them."
+                        }
                     },
-                    "required": ["payer_id", "receiver_id", "amount_cents", "condition_type", "trigger"]
+                    "required": ["entries"]
+                }
+            },
+            {
+                "name": "batch_transfer",
+                "description": "Post several balanced transfers in one call, e.g. paying a set of invoices. Transfers run sequentially, each atomic on its own; the batch as a whole is NOT atomic. Every transfer is checked (non-empty, nets to zero) before any is posted. Returns per-item status (ok / failed / skipped) and, if anything failed, a summary of which transfers completed so the caller can reconcile.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "transfers": {
+                            "type": "array",
+                            "description": "Transfers to post in order, each with entries and reference",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "entries": transfer_entries,
+                                    "reference": {
+                                        "type": "string",
+                                        "description": "Transaction reference/description"
+                                    }
+                                },
+                                "required": ["entries", "reference"]
+                            }
+                        },
+                        "stop_on_error": {
+                            "type": "boolean",
+                            "description": "Skip the remaining transfers after the first failure (default: true)"
+                        }
+                    },
+                    "required": ["transfers"]
+                }
+            },
+            {
+                "name": "list_transactions",
+                "description": "List recent transactions from the ledger. Returns transaction history showing all transfers, invoices, and payments. The type, amount and time filters are applied after fetching up to `limit` transactions, so raise `limit` when filtering to search further back. `_meta.truncated` is true when `limit` transactions were fetched, so older ones may exist.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of transactions to fetch before filtering, 1-1000 (default: 50)"
+                        },
+                        "account_id": {
+                            "type": "string",
+                            "description": "Optional: Filter by account ID to see only transactions involving this account"
+                        },
+                        "min_amount_cents": {
+                            "type": "integer",
+                            "description": "Optional: Only transactions with an entry whose absolute amount is at least this"
+                        },
+                        "max_amount_cents": {
+                            "type": "integer",
+                            "description": "Optional: Only transactions with an entry whose absolute amount is at most this"
+                        },
+                        "start_time": {
+                            "type": "integer",
+                            "description": "Optional: Only transactions at or after this time (Unix timestamp in milliseconds)"
+                        },
+                        "end_time": {
+                            "type": "integer",
+                            "description": "Optional: Only transactions at or before this time (Unix timestamp in milliseconds)"
+                        },
+                        "type": {
+                            "type": "string",
+                            "enum": TRANSACTION_TYPES,
+                            "description": "Optional: Only transactions of this type. The type comes from the reference prefix (INVOICE:, PAYMENT:, ACCESS:, LOAN:, LOAN_REPAYMENT:, REVENUE_SHARE:, REVERSAL of); anything else is a plain transfer."
+                        }
+                    },
+                    "required": []
+                }
+            },
+            {
+                "name": "get_transaction",
+                "description": "Get a single transaction by ID (or unique ID prefix) with its type, reference, timestamp and all entries. Searches the 1000 most recent transactions; pass account_id to search only that account's history.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "transaction_id": {
+                            "type": "string",
+                            "description": "Transaction ID, or a unique prefix of it"
+                        },
+                        "account_id": {
+                            "type": "string",
+                            "description": "Optional: an account involved in the transaction, to search further back in its history"
+                        }
+                    },
+                    "required": ["transaction_id"]
+                }
+            },
+            {
+                "name": "find_transactions_by_reference",
+                "description": "Find transactions by business reference (e.g. \"INV-2024-001\"): lists every transaction whose reference contains the given text, case-insensitively, with its id, type, timestamp and entries. Searches the 1000 most recent transactions by default.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "reference": {
+                            "type": "string",
+                            "description": "Text to look for in transaction references"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Optional: how many recent transactions to search, 1-1000 (default: 1000)"
+                        },
+                        "account_id": {
+                            "type": "string",
+                            "description": "Optional: only search transactions involving this account"
+                        }
+                    },
+                    "required": ["reference"]
+                }
+            },
+            {
+                "name": "reverse_transaction",
+                "description": "Undo a mistaken transaction by posting its inverse: every entry of the original is negated and posted as a new balanced transfer with a generated reference \"REVERSAL of <id> <stamp>\". Refuses transactions that don't net to zero and transactions that are already reversals. Returns the new transaction id.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "transaction_id": {
+                            "type": "string",
+                            "description": "ID (or unique ID prefix) of the transaction to reverse, among the 1000 most recent"
+                        }
+                    },
+                    "required": ["transaction_id"]
+                }
+            },
+            {
+                "name": "describe_enums",
+                "description": "List the valid values of every enum argument: participant roles, account types, contract types and contract statuses. Each entry has the string key to pass to other tools, its numeric proto value, and the human label used in results.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "get_ledger_summary",
+                "description": "Get an ecosystem-wide summary of the ledger: participant counts by role, balance totals by account type, total operating cash, receivables, payables and escrow held. Includes a consistency check that system-wide receivables and payables net to zero.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "purchase_invoice",
+                "description": "Create a B2B purchase invoice. Records debt: increases supplier's receivables and buyer's payables. Use pay_invoice later to settle.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "supplier_id": {
+                            "type": "string",
+                            "description": "Supplier participant ID (e.g., 'schampo_etc')"
+                        },
+                        "buyer_id": {
+                            "type": "string",
+                            "description": "Buyer participant ID (e.g., 'salon_glamour')"
+                        },
+                        "amount_cents": {
+                            "type": "integer",
+                            "description": "Invoice amount in cents (e.g., 455000 for $4,550.00)"
+                        },
+                        "reference": {
+                            "type": "string",
+                            "description": "Invoice reference (e.g., 'INV-2024-001 ABC Shine 300x')"
+                        },
+                        "validate_participants": validate_participants.clone()
+                    },
+                    "required": ["supplier_id", "buyer_id", "amount_cents", "reference"]
+                }
+            },
+            {
+                "name": "pay_invoice",
+                "description": "Pay/settle a B2B invoice. Transfers money from buyer to supplier and clears receivables/payables. All 4 entries are atomic.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "supplier_id": {
+                            "type": "string",
+                            "description": "Supplier participant ID"
+                        },
+                        "buyer_id": {
+                            "type": "string",
+                            "description": "Buyer participant ID"
+                        },
+                        "amount_cents": {
+                            "type": "integer",
+                            "description": "Payment amount in cents"
+                        },
+                        "reference": {
+                            "type": "string",
+                            "description": "Payment reference (e.g., 'PAY-INV-2024-001')"
+                        },
+                        "validate_participants": validate_participants.clone()
+                    },
+                    "required": ["supplier_id", "buyer_id", "amount_cents", "reference"]
+                }
+            },
+            {
+                "name": "access_payment",
+                "description": "Process real-time micro-payment for access control (e.g., door unlock). Debits payer and credits access provider. Optional platform fee, which needs a platform_id and must be less than the amount. The result includes the split: provider net, platform fee and the posted entries.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "payer_id": {
+                            "type": "string",
+                            "description": "Payer participant ID (e.g., 'salon_glamour')"
+                        },
+                        "access_provider_id": {
+                            "type": "string",
+                            "description": "Access provider participant ID (e.g., 'assa_abloy')"
+                        },
+                        "amount_cents": {
+                            "type": "integer",
+                            "description": "Access fee in cents (e.g., 800 for $8.00)"
+                        },
+                        "reference": {
+                            "type": "string",
+                            "description": "Access reference (e.g., 'DOOR-MAIN-20241215')"
+                        },
+                        "platform_id": {
+                            "type": "string",
+                            "description": "Optional: Platform to receive the fee (e.g., 'beauty_hosting'); required with platform_fee_cents"
+                        },
+                        "platform_fee_cents": {
+                            "type": "integer",
+                            "description": "Optional: Platform fee in cents (e.g., 50 for $0.50); positive, less than amount_cents, and only with platform_id"
+                        },
+                        "validate_participants": validate_participants.clone()
+                    },
+                    "required": ["payer_id", "access_provider_id", "amount_cents", "reference"]
+                }
+            },
+            {
+                "name": "fund_escrow",
+                "description": "Move funds from a payer's operating account into an escrow account (frozen capital, e.g. a deposit or security) as one balanced transfer. The escrow account must exist and be of type escrow.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "payer_id": {
+                            "type": "string",
+                            "description": "Participant whose operating account is debited (e.g., 'salon_glamour')"
+                        },
+                        "escrow_account_id": {
+                            "type": "string",
+                            "description": "Escrow account to credit (e.g., 'seb:escrow')"
+                        },
+                        "amount_cents": {
+                            "type": "integer",
+                            "description": "Amount to put in escrow, in cents"
+                        },
+                        "reference": {
+                            "type": "string",
+                            "description": "Reference (e.g., 'DEPOSIT-LEASE-2024-07')"
+                        },
+                        "validate_participants": validate_participants.clone()
+                    },
+                    "required": ["payer_id", "escrow_account_id", "amount_cents", "reference"]
+                }
+            },
+            {
+                "name": "release_escrow",
+                "description": "Release funds from an escrow account to a beneficiary's operating account as one balanced transfer. Fails if the account is not an escrow account or holds less than the amount.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "escrow_account_id": {
+                            "type": "string",
+                            "description": "Escrow account to debit (e.g., 'seb:escrow')"
+                        },
+                        "beneficiary_id": {
+                            "type": "string",
+                            "description": "Participant whose operating account is credited (e.g., 'schampo_etc')"
+                        },
+                        "amount_cents": {
+                            "type": "integer",
+                            "description": "Amount to release, in cents"
+                        },
+                        "reference": {
+                            "type": "string",
+                            "description": "Reference (e.g., 'DEPOSIT-RELEASE-2024-07')"
+                        },
+                        "validate_participants": validate_participants.clone()
+                    },
+                    "required": ["escrow_account_id", "beneficiary_id", "amount_cents", "reference"]
+                }
+            },
+            {
+                "name": "get_fees_collected",
+                "description": "Report the fees a participant has collected: the all-time balance of its fees account (service charges and platform fees credited there).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "participant_id": {
+                            "type": "string",
+                            "description": "Participant whose fees account to report (e.g., 'beauty_hosting')"
+                        }
+                    },
+                    "required": ["participant_id"]
+                }
+            },
+            {
+                "name": "sweep_fees",
+                "description": "Move a participant's whole fees balance to its operating account as one balanced transfer with a generated reference \"FEE-SWEEP: <participant_id> <stamp>\". Does nothing when the fees account is empty.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "participant_id": {
+                            "type": "string",
+                            "description": "Participant whose fees to sweep (e.g., 'beauty_hosting')"
+                        }
+                    },
+                    "required": ["participant_id"]
+                }
+            },
+            {
+                "name": "record_usage",
+                "description": "Charge for metered pay-per-use (API calls, equipment hours, ...): debits the payer's operating account and credits the provider's usage account with units * unit_price_cents, as one balanced transfer. The unit count and price are recorded at the end of the transaction reference, which is shortened to make room for them if it would exceed max_reference_length.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "payer_id": {
+                            "type": "string",
+                            "description": "Participant being charged (e.g., 'salon_glamour')"
+                        },
+                        "provider_id": {
+                            "type": "string",
+                            "description": "Provider whose usage account is credited (e.g., 'hairgrowers_united')"
+                        },
+                        "units": {
+                            "type": "integer",
+                            "description": "Units consumed; must be positive"
+                        },
+                        "unit_price_cents": {
+                            "type": "integer",
+                            "description": "Price per unit in cents; 0 or more"
+                        },
+                        "reference": {
+                            "type": "string",
+                            "description": "Optional: usage reference (e.g., 'DRYER-2024-07'; default: generated as 'USAGE: <provider_id> <stamp>')"
+                        },
+                        "validate_participants": validate_participants.clone()
+                    },
+                    "required": ["payer_id", "provider_id", "units", "unit_price_cents"]
+                }
+            },
+            {
+                "name": "create_loan",
+                "description": "Create a loan with formal obligation tracking. Lender provides funds and records receivables/payables. All 4 entries (lender operating, borrower operating, lender receivables, borrower payables) are atomic. Returns the loan terms with the monthly payment and total repayment on an amortizing schedule.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "lender_id": {
+                            "type": "string",
+                            "description": "Lender participant ID (e.g., 'seb')"
+                        },
+                        "borrower_id": {
+                            "type": "string",
+                            "description": "Borrower participant ID (e.g., 'salon_glamour')"
+                        },
+                        "amount_cents": {
+                            "type": "integer",
+                            "description": "Loan amount in cents (e.g., 150023 for $1,500.23)"
+                        },
+                        "annual_interest_rate": {
+                            "type": "number",
+                            "description": "Annual interest rate as decimal (e.g., 0.05 for 5%). Default: 0.05",
+                            "minimum": 0
+                        },
+                        "term_months": {
+                            "type": "integer",
+                            "description": "Loan term in months (e.g., 60 for 5 years), at most 600. Default: 60",
+                            "minimum": 1,
+                            "maximum": 600
+                        },
+                        "reference": {
+                            "type": "string",
+                            "description": "Loan reference (e.g., 'LOAN-2024-001')"
+                        },
+                        "validate_participants": validate_participants.clone()
+                    },
+                    "required": ["lender_id", "borrower_id", "amount_cents", "reference"]
+                }
+            },
+            {
+                "name": "repay_loan",
+                "description": "Repay a loan and clear obligations. Reverses receivables/payables entries atomically.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "lender_id": {
+                            "type": "string",
+                            "description": "Lender participant ID"
+                        },
+                        "borrower_id": {
+                            "type": "string",
+                            "description": "Borrower participant ID"
+                        },
+                        "amount_cents": {
+                            "type": "integer",
+                            "description": "Repayment amount in cents"
+                        },
+                        "reference": {
+                            "type": "string",
+                            "description": "Repayment reference (e.g., 'REPAY-LOAN-2024-001')"
+                        },
+                        "validate_participants": validate_participants.clone()
+                    },
+                    "required": ["lender_id", "borrower_id", "amount_cents", "reference"]
+                }
+            },
+            {
+                "name": "get_outstanding_loans",
+                "description": "Get total outstanding loans for a lender. Returns the positive balance in lender's receivables account.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "lender_id": {
+                            "type": "string",
+                            "description": "Lender participant ID (e.g., 'seb')"
+                        }
+                    },
+                    "required": ["lender_id"]
+                }
+            },
+            {
+                "name": "get_loan_book",
+                "description": "Get a lender's loan book: each borrower with the amount lent, repaid and still outstanding, plus the total. Built from the loan and repayment transactions on the lender's receivables account (most recent 1000; `truncated` is true when that limit was reached).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "lender_id": {
+                            "type": "string",
+                            "description": "Lender participant ID (e.g., 'seb')"
+                        }
+                    },
+                    "required": ["lender_id"]
+                }
+            },
+            {
+                "name": "get_total_debt",
+                "description": "Get total debt for a borrower. Returns the absolute value of negative balance in borrower's payables account.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "borrower_id": {
+                            "type": "string",
+                            "description": "Borrower participant ID (e.g., 'salon_glamour')"
+                        }
+                    },
+                    "required": ["borrower_id"]
+                }
+            },
+            {
+                "name": "create_invoice_contract",
+                "description": "Create a smart invoice contract with automation (auto-debit on due date, late fees). Higher-level than purchase_invoice - includes contract management.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "supplier_id": {"type": "string", "description": "Supplier participant ID"},
+                        "buyer_id": {"type": "string", "description": "Buyer participant ID"},
+                        "amount_cents": {"type": "integer", "description": "Invoice amount in cents"},
+                        "issue_date": {"type": ["integer", "string"], "description": "Issue date: Unix timestamp in milliseconds, or a date string like '2024-06-01' (midnight UTC) or RFC 3339 '2024-06-01T09:30:00Z'"},
+                        "due_date": {"type": ["integer", "string"], "description": "Due date, in the same forms as issue_date"},
+                        "payment_terms": {"type": "string", "description": "Payment terms (e.g., 'Net 30')"},
+                        "auto_debit": {"type": "boolean", "description": "Enable automatic debit on due date"},
+                        "late_fee_cents": {"type": "integer", "description": "Late fee in cents if not paid by due date"},
+                        "reference": {"type": "string", "description": "Invoice reference"}
+                    },
+                    "required": ["supplier_id", "buyer_id", "amount_cents", "issue_date", "due_date", "reference"]
+                }
+            },
+            {
+                "name": "get_invoice_contract",
+                "description": "Get details of an invoice contract by ID.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Invoice contract ID"}
+                    },
+                    "required": ["contract_id"]
+                }
+            },
+            {
+                "name": "create_subscription_contract",
+                "description": "Create a subscription contract with recurring billing (e.g., monthly SaaS fee). Supports auto-debit and cancellation notice periods.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "provider_id": {"type": "string", "description": "Service provider participant ID"},
+                        "subscriber_id": {"type": "string", "description": "Subscriber participant ID"},
+                        "monthly_fee_cents": {"type": "integer", "description": "Monthly subscription fee in cents"},
+                        "billing_date": {"type": "string", "description": "Billing date pattern (e.g., 'every 1st', 'every 15th')"},
+                        "auto_debit": {"type": "boolean", "description": "Enable automatic monthly debit"},
+                        "cancellation_notice_days": {"type": "integer", "description": "Days notice required for cancellation"},
+                        "start_date": {"type": ["integer", "string"], "description": "Start date: Unix timestamp in milliseconds, or a date string like '2024-06-01' (midnight UTC) or RFC 3339 '2024-06-01T09:30:00Z'"},
+                        "end_date": {"type": ["integer", "string"], "description": "Optional end date, in the same forms as start_date"}
+                    },
+                    "required": ["provider_id", "subscriber_id", "monthly_fee_cents", "billing_date", "start_date"]
+                }
+            },
+            {
+                "name": "get_subscription_contract",
+                "description": "Get details of a subscription contract by ID.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Subscription contract ID"}
+                    },
+                    "required": ["contract_id"]
+                }
+            },
+            {
+                "name": "create_conditional_payment",
+                "description": "Create a conditional payment contract that executes when conditions are met (e.g., 'if_service_completed'). Payment is held until trigger condition is satisfied.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "payer_id": {"type": "string", "description": "Payer participant ID"},
+                        "receiver_id": {"type": "string", "description": "Receiver participant ID"},
+                        "amount_cents": {"type": "integer", "description": "Payment amount in cents"},
+                        "condition_type": {"type": "string", "description": "Condition type (e.g., 'if_service_completed')"},
+                        "trigger": {"type": "string", "description": "Trigger condition (e.g., \"status = 'completed'\")"}
+                    },
+                    "required": ["payer_id", "receiver_id", "amount_cents", "condition_type", "trigger"]
+                }
+            },
+            {
+                "name": "get_conditional_payment",
+                "description": "Get details of a conditional payment contract by ID.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Conditional payment contract ID"}
+                    },
+                    "required": ["contract_id"]
+                }
+            },
+            {
+                "name": "create_revenue_share_contract",
+                "description": "Create a revenue share contract that automatically splits revenue from transactions among multiple parties (e.g., 70% salon, 20% supplier, 10% platform). Shares must each be between 0 and 1, sum to 1.0, and name each participant once.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "transaction_type": {"type": "string", "description": "Transaction type to apply revenue share to (e.g., 'service_sale')"},
+                        "parties": {
+                            "type": "array",
+                            "description": "Array of parties with their share percentages (must sum to 1.0)",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "participant_id": {"type": "string"},
+                                    "share": {"type": "number", "description": "Share as decimal between 0 and 1 (e.g., 0.70 for 70%)"}
+                                },
+                                "required": ["participant_id", "share"]
+                            }
+                        },
+                        "auto_split": {"type": "boolean", "description": "Enable automatic revenue splitting on each transaction"}
+                    },
+                    "required": ["transaction_type", "parties"]
+                }
+            },
+            {
+                "name": "get_revenue_share_contract",
+                "description": "Get details of a revenue share contract by ID.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Revenue share contract ID"}
+                    },
+                    "required": ["contract_id"]
+                }
+            },
+            {
+                "name": "preview_revenue_share",
+                "description": "Preview how an amount would be distributed by a revenue share contract. Returns each party's share in cents; rounding cents are allocated deterministically so the parts always sum exactly to the amount.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Revenue share contract ID"},
+                        "amount_cents": {"type": "integer", "description": "Amount to distribute in cents (e.g., 10001 for $100.01)"}
+                    },
+                    "required": ["contract_id", "amount_cents"]
+                }
+            },
+            {
+                "name": "execute_revenue_share",
+                "description": "Distribute an amount according to a revenue share contract: debits the source account and credits each party's operating account with exactly the amounts preview_revenue_share returns, in one atomic transfer.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Revenue share contract ID"},
+                        "amount_cents": {"type": "integer", "description": "Amount to distribute in cents"},
+                        "source_account_id": {"type": "string", "description": "Account the revenue is paid from (e.g., 'beauty_hosting:escrow')"},
+                        "reference": {"type": "string", "description": "Optional transaction reference (default: generated as 'REVENUE_SHARE: <contract_id> <stamp>')"}
+                    },
+                    "required": ["contract_id", "amount_cents", "source_account_id"]
+                }
+            },
+            {
+                "name": "create_generic_contract",
+                "description": "Create a contract from a YAML definition with conditions and actions, e.g. supplier registration or ecosystem partner membership. Give either the YAML text or a path to a YAML file on the server. Returns the contract with its conditions, actions, metadata and next_execution_at.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "yaml_content": {"type": "string", "description": "YAML contract definition"},
+                        "yaml_file_path": {"type": "string", "description": "Alternative to yaml_content: path to a YAML file readable by the server"},
+                        "variables": {"type": "object", "description": "Optional values substituted for ${name} placeholders in the YAML"}
+                    },
+                    "required": []
+                }
+            },
+            {
+                "name": "get_generic_contract",
+                "description": "Get a YAML-defined (generic) contract by ID with its name, description, contract type, status, conditions, actions, metadata, next_execution_at and YAML source.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Contract ID"}
+                    },
+                    "required": ["contract_id"]
+                }
+            },
+            {
+                "name": "get_contract",
+                "description": "Get any contract by ID without knowing its type. Detects whether it is an invoice, subscription, conditional payment, revenue share or generic contract and returns {type, contract} with the same shape as the typed getter. Prefer this over the typed get_* tools when the type is unknown.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Contract ID"}
+                    },
+                    "required": ["contract_id"]
+                }
+            },
+            {
+                "name": "list_contracts",
+                "description": "List all contracts with optional filters by type, status, or participant. `_meta` gives the row count and whether `limit` was reached, in which case more contracts may exist.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_type": {
+                            "type": "string",
+                            "enum": ["loan", "invoice", "subscription", "conditional_payment", "revenue_share", "supplier_registration", "ecosystem_partner_membership"],
+                            "description": "Optional: Filter by contract type"
+                        },
+                        "status": {"type": "string", "description": "Optional: Filter by status (e.g., 'active', 'completed')"},
+                        "participant_id": {"type": "string", "description": "Optional: Only contracts this participant is a party to, in any role. Generic contracts match on the participant ids in their metadata (supplier_id, buyer_id, payer_id, ...)."},
+                        "limit": {"type": "integer", "description": "Maximum results, 1-1000 (default: 100)"}
+                    },
+                    "required": []
+                }
+            },
+            {
+                "name": "get_participant_contracts",
+                "description": "All contracts a participant is a party to, in any role, grouped by status: active (including pending and overdue invoices), paused, completed (paid, executed, expired), cancelled, and other. Returns a count per group. Use this for questions like 'which contracts does Salon Glamour have, and which are active?'.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "participant_id": {"type": "string", "description": "Participant ID (use resolve_participant to find it from a name)"}
+                    },
+                    "required": ["participant_id"]
+                }
+            },
+            {
+                "name": "execute_contract",
+                "description": "Manually execute a contract (e.g., trigger conditional payment when conditions are met, process subscription billing).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Contract ID"},
+                        "contract_type": {
+                            "type": "string",
+                            "enum": ["loan", "invoice", "subscription", "conditional_payment", "revenue_share", "supplier_registration", "ecosystem_partner_membership"],
+                            "description": "Contract type"
+                        }
+                    },
+                    "required": ["contract_id", "contract_type"]
+                }
+            },
+            {
+                "name": "execute_due_contracts",
+                "description": "Execute all active contracts whose scheduled time has passed (generic contracts, subscription billing, auto-debit invoices). Sends progress notifications per contract when the call includes a progressToken.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "update_contract_status",
+                "description": "Update the status of a contract (e.g., pause, cancel, complete). Only valid lifecycle transitions are applied: active -> paused/completed/cancelled, paused -> active/cancelled, pending -> active/cancelled. Completed and cancelled contracts are final. Returns old_status and new_status.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "contract_id": {"type": "string", "description": "Contract ID"},
+                        "contract_type": {
+                            "type": "string",
+                            "enum": ["loan", "invoice", "subscription", "conditional_payment", "revenue_share", "supplier_registration", "ecosystem_partner_membership"],
+                            "description": "Contract type"
+                        },
+                        "status": {
+                            "type": "string",
+                            "enum": ["active", "paused", "completed", "cancelled"],
+                            "description": "New status"
+                        }
+                    },
+                    "required": ["contract_id", "contract_type", "status"]
+                }
+            }
+        ]
+    })
+}
+
+/// Status of whichever contract variant `response` holds, as a lowercase string
+fn contract_status(response: &smartcontracts::ContractResponse) -> Option<String> {
+    use smartcontracts::contract_response::Contract;
+    let status = match response.contract.as_ref()? {
+        Contract::Invoice(c) => c.status.clone(),
+        Contract::Subscription(c) => c.status.clone(),
+        Contract::ConditionalPayment(c) => c.status.clone(),
+        Contract::RevenueShare(c) => c.status.clone(),
+        // Generic contracts carry the ContractStatus enum rather than a string
+        Contract::Generic(c) => contract_status_to_string(c.status).to_lowercase(),
+    };
+    Some(status)
+}
+
+/// Contract lifecycle: active contracts can be paused, completed or cancelled;
+/// paused ones resumed or cancelled; pending ones activated or cancelled.
+/// Completed, cancelled and otherwise settled contracts are final.
+fn check_status_transition(from: &str, to: &str) -> Result<()> {
+    match (from, to) {
+        _ if from == to => anyhow::bail!("contract is already {}", from),
+        ("active", "paused" | "completed" | "cancelled")
+        | ("paused", "active" | "cancelled")
+        | ("pending", "active" | "cancelled") => Ok(()),
+        ("completed" | "cancelled" | "paid" | "executed" | "expired", _) => {
+            anyhow::bail!("contract is {}, which is final; it can't be made {}", from, to)
+        }
+        _ => anyhow::bail!("contract status can't change from {} to {}", from, to),
+    }
+}
+
+// The `*_string_to_int` functions take the proto name in any case and
+// return 0 (the unspecified value) for anything else
+
+fn role_string_to_int(role: &str) -> i32 {
+    ParticipantRole::from_str_name(&role.to_ascii_uppercase()).map_or(0, |r| r as i32)
+}
+
+fn account_type_string_to_int(account_type: &str) -> i32 {
+    AccountType::from_str_name(&account_type.to_ascii_uppercase()).map_or(0, |t| t as i32)
+}
+
+/// `account_type_string_to_int` restricted to `PARTICIPANT_ACCOUNT_TYPES`
+fn participant_account_type_to_int(account_type: &str) -> i32 {
+    match account_type_string_to_int(account_type) {
+        code if code == AccountType::Standalone as i32 => 0,
+        code => code,
+    }
+}
+
+fn contract_type_string_to_int(contract_type: &str) -> i32 {
+    ContractType::from_str_name(&contract_type.to_ascii_uppercase()).map_or(0, |t| t as i32)
+}
+
+fn contract_status_string_to_int(status: &str) -> i32 {
+    ContractStatus::from_str_name(&status.to_ascii_uppercase()).map_or(0, |s| s as i32)
+}
+
+const ROLES: &[&str] = &[
+    "access_provider",
+    "banking_partner",
+    "ecosystem_partner",
+    "supplier",
+    "equipment_provider",
+    "ecosystem_orchestrator",
+];
+/// Types a participant's own account can have; standalone accounts belong to no participant
+const PARTICIPANT_ACCOUNT_TYPES: &[&str] = &["operating", "receivables", "payables", "escrow", "fees", "usage"];
+const CONTRACT_TYPES: &[&str] = &[
+    "loan",
+    "invoice",
+    "subscription",
+    "conditional_payment",
+    "revenue_share",
+    "supplier_registration",
+    "ecosystem_partner_membership",
+];
+const CONTRACT_STATUSES: &[&str] = &["active", "paused", "completed", "cancelled"];
+
+/// Map an enum argument with one of the `*_string_to_int` functions, treating
+/// their 0 fallback as an InvalidParams error that lists the valid values.
+fn parse_enum_arg(arg: &str, value: &str, parse: fn(&str) -> i32, valid: &[&str]) -> Result<i32> {
+    match parse(value) {
+        0 => Err(InvalidParams(format!(
+            "invalid {} '{}'; expected one of: {}",
+            arg,
+            value,
+            valid.join(", ")
+        ))
+        .into()),
+        code => Ok(code),
+    }
+}
+
+/// The `reference` argument as it will be posted, see
+/// `ReferenceFormat::validate_reference`
+fn reference_arg(client: &ScalegraphClient, args: &Value) -> CheckedReference {
+    let reference = args.get("reference").and_then(|v| v.as_str()).unwrap_or("");
+    client.references.validate_reference(reference)
+}
+
+/// `result` with the reference that was posted and why, when it differs
+/// from the one given
+fn note_reference(mut result: Value, reference: &CheckedReference) -> Value {
+    if let (Some(warning), Some(fields)) = (&reference.warning, result.as_object_mut()) {
+        fields.insert("reference".to_string(), json!(reference.value));
+        fields.insert("reference_warning".to_string(), json!(warning));
+    }
+    result
+}
+
+/// A date argument in Unix milliseconds. Also accepted as a string: a plain
+/// date ("2024-06-01", read as midnight UTC) or an RFC 3339 timestamp
+/// ("2024-06-01T09:30:00+02:00").
+fn date_arg(args: &Value, key: &str) -> Result<Option<i64>> {
+    let invalid = |value: &Value| {
+        InvalidParams(format!(
+            "invalid {} {}; expected Unix milliseconds, a date like 2024-06-01 or an RFC 3339 timestamp",
+            key, value
+        ))
+    };
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value @ Value::Number(n)) => n.as_i64().map(Some).ok_or_else(|| invalid(value).into()),
+        Some(value @ Value::String(s)) => parse_date(s).map(Some).ok_or_else(|| invalid(value).into()),
+        Some(value) => Err(invalid(value).into()),
+    }
+}
+
+fn parse_date(input: &str) -> Option<i64> {
+    let input = input.trim();
+    if let Ok(at) = chrono::DateTime::parse_from_rfc3339(input) {
+        return Some(at.timestamp_millis());
+    }
+    let date = chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis())
+}
+
+/// RFC 3339 form of a Unix-millisecond date for results; null for 0, which
+/// the contract services use for "not set"
+fn format_date(millis: i64) -> Value {
+    match chrono::DateTime::from_timestamp_millis(millis) {
+        Some(at) if millis != 0 => json!(at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+        _ => Value::Null,
+    }
+}
+
+/// Parse the `accounts` argument of create_participant_accounts into
+/// (account type, initial balance) pairs. Every type is checked before any
+/// account is created.
+fn participant_accounts_arg(args: &Value) -> Result<Vec<(i32, i64)>> {
+    let items = args
+        .get("accounts")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| InvalidParams("accounts must be an array".to_string()))?;
+    if items.is_empty() {
+        return Err(InvalidParams("accounts is empty".to_string()).into());
+    }
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let account_type = item
+                .get("account_type")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| InvalidParams(format!("account {} has no account_type", index)))?;
+            let account_type = parse_enum_arg(
+                &format!("account_type for account {}", index),
+                account_type,
+                participant_account_type_to_int,
+                PARTICIPANT_ACCOUNT_TYPES,
+            )?;
+            let initial_balance = match item.get("initial_balance_cents") {
+                None | Some(Value::Null) => 0,
+                Some(v) => v.as_i64().ok_or_else(|| {
+                    InvalidParams(format!("account {} initial_balance_cents must be an integer", index))
+                })?,
+            };
+            Ok((account_type, initial_balance))
+        })
+        .collect()
+}
+
+/// Check `args` against the tool's declared inputSchema: every required
+/// property must be present (and not null), and every property given must have
+/// the declared JSON type. Unknown tools and undeclared properties pass.
+fn check_tool_args(name: &str, args: &Value) -> Result<()> {
+    static TOOLS: std::sync::OnceLock<Value> = std::sync::OnceLock::new();
+    let tools = TOOLS.get_or_init(get_tools_list);
+    let Some(schema) = tools["tools"]
+        .as_array()
+        .and_then(|tools| tools.iter().find(|t| t["name"] == name))
+        .map(|t| &t["inputSchema"])
+    else {
+        return Ok(());
+    };
+    if !args.is_object() {
+        return Err(InvalidParams("arguments must be an object".to_string()).into());
+    }
+
+    let required = schema["required"].as_array().map(Vec::as_slice).unwrap_or_default();
+    for field in required.iter().filter_map(|f| f.as_str()) {
+        if args.get(field).is_none_or(Value::is_null) {
+            return Err(InvalidParams(format!("missing required field '{}'", field)).into());
+        }
+    }
+
+    let Some(properties) = schema["properties"].as_object() else {
+        return Ok(());
+    };
+    for (field, value) in args.as_object().into_iter().flatten() {
+        let Some(expected) = properties.get(field).and_then(|p| p["type"].as_str()) else {
+            continue;
+        };
+        let matches = match expected {
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => true,
+        };
+        // Optional fields may be sent as null to mean "not given"
+        if !matches && !value.is_null() {
+            return Err(InvalidParams(format!(
+                "field '{}' must be of type {}, got {}",
+                field, expected, value
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Tools that only read from the ledger, so running one twice changes nothing
+const READ_ONLY_TOOLS: [&str; 25] = [
+    "list_participants",
+    "resolve_participant",
+    "find_duplicate_participants",
+    "get_participant_accounts",
+    "get_account_balance",
+    "get_balance_as_of",
+    "simulate_transfer",
+    "list_transactions",
+    "get_transaction",
+    "find_transactions_by_reference",
+    "describe_enums",
+    "get_ledger_summary",
+    "get_fees_collected",
+    "get_outstanding_loans",
+    "get_loan_book",
+    "get_total_debt",
+    "get_invoice_contract",
+    "get_subscription_contract",
+    "get_conditional_payment",
+    "get_revenue_share_contract",
+    "preview_revenue_share",
+    "list_contracts",
+    "get_participant_contracts",
+    "get_generic_contract",
+    "get_contract",
+];
+
+/// Run a tool call, reconnecting if the server was unreachable.
+///
+/// A dead channel (e.g. after the Elixir server restarted) fails every call
+/// with `Unavailable` until it is rebuilt. The server may still have applied
+/// a request that failed this way (the connection can drop before the reply
+/// arrives), so only read-only tools are retried. A tool that writes returns
+/// the error after the reconnect, and the caller can check the ledger before
+/// sending it again.
+async fn call_tool(
+    client: &mut ScalegraphClient,
+    name: &str,
+    args: &Value,
+    progress: &mut Progress,
+) -> Result<Value> {
+    client.use_next_channel();
+    match handle_tool_call(client, name, args, progress).await {
+        Err(e) if is_unavailable(&e) => {
+            tracing::warn!(tool = name, error = %e, "gRPC server unavailable, reconnecting");
+            if let Err(reconnect_error) = client.reconnect().await {
+                tracing::warn!(error = %reconnect_error, "reconnect failed");
+                return Err(e);
+            }
+            if !READ_ONLY_TOOLS.contains(&name) {
+                tracing::info!(tool = name, "reconnected, not retrying a tool that writes");
+                return Err(e);
+            }
+            tracing::info!(tool = name, "reconnected, retrying tool call");
+            handle_tool_call(client, name, args, progress).await
+        }
+        outcome => outcome,
+    }
+}
+
+/// Run `future`, turning a panic inside it into an error carrying the panic
+/// message. A bug in one tool then fails that call instead of the server.
+async fn catch_panic<T>(future: impl std::future::Future<Output = T>) -> Result<T, String> {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::task::Poll;
+
+    let mut future = std::pin::pin!(future);
+    std::future::poll_fn(|cx| match catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(cx))) {
+        Ok(Poll::Ready(value)) => Poll::Ready(Ok(value)),
+        Ok(Poll::Pending) => Poll::Pending,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Poll::Ready(Err(message))
+        }
+    })
+    .await
+}
+
+/// Whether any error in the chain is a gRPC `Unavailable` status
+fn is_unavailable(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<tonic::Status>())
+        .any(|status| status.code() == tonic::Code::Unavailable)
+}
+
+/// JSON-RPC error for a failed tool call: InvalidParams for bad arguments, and
+/// the mapped code for a gRPC status from the ledger. Other failures, such as
+/// local business checks, return None and become an `isError` tool result.
+fn tool_call_error(error: &anyhow::Error) -> Option<JsonRpcError> {
+    if let Some(invalid) = error.downcast_ref::<InvalidParams>() {
+        return Some(JsonRpcError {
+            code: INVALID_PARAMS,
+            message: invalid.to_string(),
+        });
+    }
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<tonic::Status>())
+        .map(map_status_to_jsonrpc)
+}
+
+async fn handle_tool_call(
+    client: &mut ScalegraphClient,
+    name: &str,
+    args: &Value,
+    progress: &mut Progress,
+) -> Result<Value> {
+    check_tool_args(name, args)?;
+    match name {
+        "list_participants" => {
+            // No role means "all participants"
+            let role = match args.get("role").and_then(|v| v.as_str()) {
+                Some(role) => parse_enum_arg("role", role, role_string_to_int, ROLES)?,
+                None => 0,
+            };
+            client.list_participants(role).await
+        }
+
+        "resolve_participant" => {
+            let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
+            let role = match args.get("role").and_then(|v| v.as_str()) {
+                Some(role) => parse_enum_arg("role", role, role_string_to_int, ROLES)?,
+                None => 0,
+            };
+            client.resolve_participant(query, role).await
+        }
+
+        "find_duplicate_participants" => {
+            let role = match args.get("role").and_then(|v| v.as_str()) {
+                Some(role) => parse_enum_arg("role", role, role_string_to_int, ROLES)?,
+                None => 0,
+            };
+            client.find_duplicate_participants(role).await
+        }
+
+        "create_participant" => {
+            let id = args.get("id").and_then(|v| v.as_str()).unwrap_or("");
+            let name_str = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            let role_str = args.get("role").and_then(|v| v.as_str()).unwrap_or("ecosystem_partner");
+            let role = parse_enum_arg("role", role_str, role_string_to_int, ROLES)?;
+            client.create_participant(id, name_str, role).await
+        }
+
+        "create_participant_account" => {
+            let participant_id = args
+                .get("participant_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let account_type_str = args
+                .get("account_type")
+                .and_then(|v| v.as_str())
+                .unwrap_or("operating");
+            let account_type = parse_enum_arg(
+                "account_type",
+                account_type_str,
+                participant_account_type_to_int,
+                PARTICIPANT_ACCOUNT_TYPES,
+            )?;
+            let initial_balance = args
+                .get("initial_balance_cents")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            client
+                .create_participant_account(participant_id, account_type, initial_balance)
+                .await
+        }
+
+        "setup_participant_accounts" => {
+            let participant_id = args
+                .get("participant_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let role = match args.get("role").and_then(|v| v.as_str()) {
+                Some(role) => Some(parse_enum_arg("role", role, role_string_to_int, ROLES)?),
+                None => None,
+            };
+            client.setup_participant_accounts(participant_id, role).await
+        }
+
+        "create_participant_accounts" => {
+            let participant_id = args
+                .get("participant_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let accounts = participant_accounts_arg(args)?;
+            let skip_existing = args
+                .get("skip_existing")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            client
+                .create_participant_accounts(participant_id, accounts, skip_existing, progress)
+                .await
+        }
+
+        "get_participant_accounts" => {
+            let participant_id = args
+                .get("participant_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            client.get_participant_accounts(participant_id).await
+        }
+
+        "update_participant_services" => {
+            let participant_id = args
+                .get("participant_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let changes = ServiceChanges::from_args(args)?;
+            client.update_participant_services(participant_id, &changes).await
+        }
+
+        "update_participant_contact" => {
+            let participant_id = args
+                .get("participant_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            client.update_participant_contact(participant_id, args).await
+        }
+
+        "get_account_balance" => {
+            let account_id = args.get("account_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_balance(account_id).await
+        }
+
+        "get_balance_as_of" => {
+            let account_id = args.get("account_id").and_then(|v| v.as_str()).unwrap_or("");
+            let timestamp = args.get("timestamp").and_then(|v| v.as_i64()).unwrap_or(0);
+            let limit = ListLimit::from_args(args, GET_TRANSACTION_SCAN_LIMIT);
+            client.get_balance_as_of(account_id, timestamp, limit).await
+        }
+
+        "transfer" => {
+            let legs: Vec<(String, i64, String)> = args
+                .get("entries")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|e| {
+                            let account_id = e.get("account_id")?.as_str()?.to_string();
+                            let amount = e.get("amount_cents")?.as_i64()?;
+                            let memo = e.get("memo").and_then(|v| v.as_str()).unwrap_or_default();
+                            Some((account_id, amount, memo.to_string()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            // Memos are numbered by entry position, the way they are read back
+            let memos: Vec<(usize, String)> = legs
+                .iter()
+                .enumerate()
+                .map(|(i, (_, _, memo))| (i + 1, memo.clone()))
+                .collect();
+            let entries = legs.into_iter().map(|(account_id, amount, _)| (account_id, amount)).collect();
+            let given = args.get("reference").and_then(|v| v.as_str()).unwrap_or("");
+            let reference = client.references.validate_reference(given);
+            let reference = client.references.with_memos(reference, &memos).map_err(InvalidParams)?;
+            client
+                .transfer(entries, &reference.value)
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "simulate_transfer" => {
+            let entries = args
+                .get("entries")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.as_slice())
+                .unwrap_or_default()
+                .iter()
+                .map(|e| {
+                    let account_id = e.get("account_id").and_then(|v| v.as_str());
+                    let amount = e.get("amount_cents").and_then(|v| v.as_i64());
+                    match (account_id, amount) {
+                        (Some(account_id), Some(amount)) => Ok((account_id.to_string(), amount)),
+                        _ => Err(InvalidParams(
+                            "every entry needs an account_id and an integer amount_cents".to_string(),
+                        )),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let allow_overdraft = args
+                .get("allow_overdraft")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            client.simulate_transfer(&entries, allow_overdraft).await
+        }
+
+        "batch_transfer" => {
+            let mut transfers = batch_transfers_arg(args)?;
+            let mut warnings = Vec::new();
+            for (index, (_, reference)) in transfers.iter_mut().enumerate() {
+                let checked = client.references.validate_reference(reference);
+                if let Some(warning) = checked.warning {
+                    warnings.push(json!({ "index": index, "reference": checked.value, "warning": warning }));
+                }
+                *reference = checked.value;
+            }
+            let stop_on_error = args
+                .get("stop_on_error")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let mut result = client.batch_transfer(transfers, stop_on_error, progress).await?;
+            if !warnings.is_empty() {
+                result["reference_warnings"] = json!(warnings);
+            }
+            Ok(result)
+        }
+
+        "list_transactions" => {
+            // The ledger's own default for a limit that isn't positive
+            let limit = ListLimit::from_args(args, 50);
+            let account_id = args.get("account_id").and_then(|v| v.as_str());
+            let filter = TransactionFilter::from_args(args)?;
+            client.list_transactions(limit, account_id, &filter).await
+        }
+
+        "get_transaction" => {
+            let transaction_id = args
+                .get("transaction_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let account_id = args.get("account_id").and_then(|v| v.as_str());
+            client.get_transaction(transaction_id, account_id).await
+        }
+
+        "find_transactions_by_reference" => {
+            let reference = args.get("reference").and_then(|v| v.as_str()).unwrap_or("");
+            let limit = ListLimit::from_args(args, GET_TRANSACTION_SCAN_LIMIT);
+            let account_id = args.get("account_id").and_then(|v| v.as_str());
+            client
+                .find_transactions_by_reference(reference, limit, account_id)
+                .await
+        }
+
+        "reverse_transaction" => {
+            let transaction_id = args
+                .get("transaction_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            client.reverse_transaction(transaction_id).await
+        }
+
+        "get_ledger_summary" => client.get_ledger_summary().await,
+
+        "describe_enums" => Ok(describe_enums()),
+
+        "purchase_invoice" => {
+            let supplier_id = args
+                .get("supplier_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let buyer_id = args.get("buyer_id").and_then(|v| v.as_str()).unwrap_or("");
+            let amount = positive_amount_arg(args, "amount_cents")?;
+            let reference = reference_arg(client, args);
+            if validate_participants(args) {
+                client
+                    .ensure_participants_exist(&[
+                        ("supplier_id", supplier_id),
+                        ("buyer_id", buyer_id),
+                    ])
+                    .await?;
+            }
+            client
+                .purchase_invoice(supplier_id, buyer_id, amount, &reference.value)
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "pay_invoice" => {
+            let supplier_id = args
+                .get("supplier_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let buyer_id = args.get("buyer_id").and_then(|v| v.as_str()).unwrap_or("");
+            let amount = positive_amount_arg(args, "amount_cents")?;
+            let reference = reference_arg(client, args);
+            if validate_participants(args) {
+                client
+                    .ensure_participants_exist(&[
+                        ("supplier_id", supplier_id),
+                        ("buyer_id", buyer_id),
+                    ])
+                    .await?;
+            }
+            client
+                .pay_invoice(supplier_id, buyer_id, amount, &reference.value)
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "access_payment" => {
+            let payer_id = args.get("payer_id").and_then(|v| v.as_str()).unwrap_or("");
+            let access_provider_id = args
+                .get("access_provider_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let amount = positive_amount_arg(args, "amount_cents")?;
+            let reference = reference_arg(client, args);
+            let platform_id = args.get("platform_id").and_then(|v| v.as_str());
+            let platform_fee = args.get("platform_fee_cents").and_then(|v| v.as_i64());
+            validate_platform_fee(platform_id, platform_fee, amount)?;
+            if validate_participants(args) {
+                let mut ids = vec![
+                    ("payer_id", payer_id),
+                    ("access_provider_id", access_provider_id),
+                ];
+                if let Some(platform_id) = platform_id.filter(|id| !id.is_empty()) {
+                    ids.push(("platform_id", platform_id));
+                }
+                client.ensure_participants_exist(&ids).await?;
+            }
+            client
+                .access_payment(
+                    payer_id,
+                    access_provider_id,
+                    amount,
+                    &reference.value,
+                    platform_id,
+                    platform_fee,
+                )
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "fund_escrow" => {
+            let payer_id = args.get("payer_id").and_then(|v| v.as_str()).unwrap_or("");
+            let escrow_account_id = args
+                .get("escrow_account_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let amount = positive_amount_arg(args, "amount_cents")?;
+            let reference = reference_arg(client, args);
+            if validate_participants(args) {
+                client.ensure_participants_exist(&[("payer_id", payer_id)]).await?;
+            }
+            client
+                .fund_escrow(payer_id, escrow_account_id, amount, &reference.value)
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "release_escrow" => {
+            let escrow_account_id = args
+                .get("escrow_account_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let beneficiary_id = args
+                .get("beneficiary_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let amount = positive_amount_arg(args, "amount_cents")?;
+            let reference = reference_arg(client, args);
+            if validate_participants(args) {
+                client
+                    .ensure_participants_exist(&[("beneficiary_id", beneficiary_id)])
+                    .await?;
+            }
+            client
+                .release_escrow(escrow_account_id, beneficiary_id, amount, &reference.value)
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "get_fees_collected" => {
+            let participant_id = args
+                .get("participant_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            client.get_fees_collected(participant_id).await
+        }
+
+        "sweep_fees" => {
+            let participant_id = args
+                .get("participant_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            client.sweep_fees(participant_id).await
+        }
+
+        "record_usage" => {
+            let payer_id = args.get("payer_id").and_then(|v| v.as_str()).unwrap_or("");
+            let provider_id = args.get("provider_id").and_then(|v| v.as_str()).unwrap_or("");
+            let units = args.get("units").and_then(|v| v.as_i64()).unwrap_or(0);
+            let unit_price_cents = args
+                .get("unit_price_cents")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            usage_amount(units, unit_price_cents)?;
+            let given = args.get("reference").and_then(|v| v.as_str()).unwrap_or("");
+            let metering = usage_metering(units, unit_price_cents);
+            let reference = client
+                .references
+                .usage_reference(given, provider_id, &metering)
+                .map_err(InvalidParams)?;
+            if validate_participants(args) {
+                client
+                    .ensure_participants_exist(&[("payer_id", payer_id), ("provider_id", provider_id)])
+                    .await?;
+            }
+            client
+                .record_usage(payer_id, provider_id, units, unit_price_cents, &reference.value)
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "create_loan" => {
+            let lender_id = args.get("lender_id").and_then(|v| v.as_str()).unwrap_or("");
+            let borrower_id = args.get("borrower_id").and_then(|v| v.as_str()).unwrap_or("");
+            let amount = positive_amount_arg(args, "amount_cents")?;
+            let (annual_interest_rate, term_months) = loan_terms_arg(args)?;
+            let reference = reference_arg(client, args);
+            if validate_participants(args) {
+                client
+                    .ensure_participants_exist(&[
+                        ("lender_id", lender_id),
+                        ("borrower_id", borrower_id),
+                    ])
+                    .await?;
+            }
+            client
+                .create_loan(
+                    lender_id,
+                    borrower_id,
+                    amount,
+                    annual_interest_rate,
+                    term_months,
+                    &reference.value,
+                )
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "repay_loan" => {
+            let lender_id = args.get("lender_id").and_then(|v| v.as_str()).unwrap_or("");
+            let borrower_id = args.get("borrower_id").and_then(|v| v.as_str()).unwrap_or("");
+            let amount = positive_amount_arg(args, "amount_cents")?;
+            let reference = reference_arg(client, args);
+            if validate_participants(args) {
+                client
+                    .ensure_participants_exist(&[
+                        ("lender_id", lender_id),
+                        ("borrower_id", borrower_id),
+                    ])
+                    .await?;
+            }
+            client
+                .repay_loan(lender_id, borrower_id, amount, &reference.value)
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "get_outstanding_loans" => {
+            let lender_id = args.get("lender_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_outstanding_loans(lender_id).await
+        }
+
+        "get_loan_book" => {
+            let lender_id = args.get("lender_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_loan_book(lender_id).await
+        }
+
+        "get_total_debt" => {
+            let borrower_id = args.get("borrower_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_total_debt(borrower_id).await
+        }
+
+        "create_invoice_contract" => {
+            let supplier_id = args.get("supplier_id").and_then(|v| v.as_str()).unwrap_or("");
+            let buyer_id = args.get("buyer_id").and_then(|v| v.as_str()).unwrap_or("");
+            let amount_cents = args.get("amount_cents").and_then(|v| v.as_i64()).unwrap_or(0);
+            let issue_date = date_arg(args, "issue_date")?.unwrap_or(0);
+            let due_date = date_arg(args, "due_date")?.unwrap_or(0);
+            let payment_terms = args.get("payment_terms").and_then(|v| v.as_str()).unwrap_or("Net 30");
+            let auto_debit = args.get("auto_debit").and_then(|v| v.as_bool()).unwrap_or(false);
+            let late_fee_cents = args.get("late_fee_cents").and_then(|v| v.as_i64()).unwrap_or(0);
+            let reference = reference_arg(client, args);
+            client
+                .create_invoice_contract(
+                    supplier_id,
+                    buyer_id,
+                    amount_cents,
+                    issue_date,
+                    due_date,
+                    payment_terms,
+                    auto_debit,
+                    late_fee_cents,
+                    &reference.value,
+                )
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "get_invoice_contract" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_invoice_contract(contract_id).await
+        }
+
+        "create_subscription_contract" => {
+            let provider_id = args.get("provider_id").and_then(|v| v.as_str()).unwrap_or("");
+            let subscriber_id = args.get("subscriber_id").and_then(|v| v.as_str()).unwrap_or("");
+            let monthly_fee_cents = args.get("monthly_fee_cents").and_then(|v| v.as_i64()).unwrap_or(0);
+            let billing_date = args.get("billing_date").and_then(|v| v.as_str()).unwrap_or("every 1st");
+            let auto_debit = args.get("auto_debit").and_then(|v| v.as_bool()).unwrap_or(true);
+            let cancellation_notice_days = args.get("cancellation_notice_days").and_then(|v| v.as_i64()).map(|v| v as i32).unwrap_or(30);
+            let start_date = date_arg(args, "start_date")?.unwrap_or(0);
+            let end_date = date_arg(args, "end_date")?;
+            client
+                .create_subscription_contract(
+                    provider_id,
+                    subscriber_id,
+                    monthly_fee_cents,
+                    billing_date,
+                    auto_debit,
+                    cancellation_notice_days,
+                    start_date,
+                    end_date,
+                )
+                .await
+        }
+
+        "get_subscription_contract" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_subscription_contract(contract_id).await
+        }
+
+        "create_conditional_payment" => {
+            let payer_id = args.get("payer_id").and_then(|v| v.as_str()).unwrap_or("");
+            let receiver_id = args.get("receiver_id").and_then(|v| v.as_str()).unwrap_or("");
+            let amount_cents = args.get("amount_cents").and_then(|v| v.as_i64()).unwrap_or(0);
+            let condition_type = args.get("condition_type").and_then(|v| v.as_str()).unwrap_or("");
+            let trigger = args.get("trigger").and_then(|v| v.as_str()).unwrap_or("");
+            client
+                .create_conditional_payment(payer_id, receiver_id, amount_cents, condition_type, trigger)
+                .await
+        }
+
+        "get_conditional_payment" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_conditional_payment(contract_id).await
+        }
+
+        "create_revenue_share_contract" => {
+            let transaction_type = args.get("transaction_type").and_then(|v| v.as_str()).unwrap_or("");
+            let parties: Vec<(String, f64)> = args
+                .get("parties")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .map(|p| {
+                            let participant_id = p.get("participant_id").and_then(|v| v.as_str());
+                            let share = p.get("share").and_then(|v| v.as_f64());
+                            match (participant_id, share) {
+                                (Some(id), Some(share)) => Ok((id.to_string(), share)),
+                                _ => Err(anyhow::anyhow!(
+                                    "each party needs a participant_id (string) and share (number)"
+                                )),
+                            }
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })
+                .transpose()?
+                .unwrap_or_default();
+            let auto_split = args.get("auto_split").and_then(|v| v.as_bool()).unwrap_or(true);
+            client
+                .create_revenue_share_contract(transaction_type, parties, auto_split)
+                .await
+        }
+
+        "get_revenue_share_contract" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_revenue_share_contract(contract_id).await
+        }
+
+        "preview_revenue_share" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            let amount = args
+                .get("amount_cents")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            client.preview_revenue_share(contract_id, amount).await
+        }
+
+        "execute_revenue_share" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            let amount = args
+                .get("amount_cents")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            let source_account_id = args
+                .get("source_account_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let reference = reference_arg(client, args);
+            client
+                .execute_revenue_share(contract_id, amount, source_account_id, &reference.value)
+                .await
+                .map(|result| note_reference(result, &reference))
+        }
+
+        "list_contracts" => {
+            let contract_type_str = args.get("contract_type").and_then(|v| v.as_str());
+            // No contract_type means "all types"
+            let contract_type = contract_type_str
+                .map(|t| parse_enum_arg("contract_type", t, contract_type_string_to_int, CONTRACT_TYPES))
+                .transpose()?;
+            let status = args.get("status").and_then(|v| v.as_str());
+            let participant_id = args.get("participant_id").and_then(|v| v.as_str());
+            let limit = ListLimit::from_args(args, 100);
+            client.list_contracts(contract_type, status, participant_id, limit).await
+        }
+
+        "get_participant_contracts" => {
+            let participant_id = args.get("participant_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_participant_contracts(participant_id).await
+        }
+
+        "create_generic_contract" => {
+            let yaml_content = args.get("yaml_content").and_then(|v| v.as_str());
+            let yaml_file_path = args.get("yaml_file_path").and_then(|v| v.as_str());
+            let variables = args
+                .get("variables")
+                .and_then(|v| v.as_object())
+                .map(|vars| {
+                    vars.iter()
+                        .map(|(k, v)| {
+                            let value = v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+                            (k.clone(), value)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            client
+                .create_generic_contract(yaml_content, yaml_file_path, variables)
+                .await
+        }
+
+        "get_generic_contract" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_generic_contract(contract_id).await
+        }
+
+        "get_contract" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            client.get_contract(contract_id).await
+        }
+
+        "execute_contract" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            let contract_type_str = args.get("contract_type").and_then(|v| v.as_str()).unwrap_or("");
+            let contract_type = parse_enum_arg(
+                "contract_type",
+                contract_type_str,
+                contract_type_string_to_int,
+                CONTRACT_TYPES,
+            )?;
+            client.execute_contract(contract_id, contract_type).await
+        }
+
+        "execute_due_contracts" => client.execute_due_contracts(progress).await,
+
+        "update_contract_status" => {
+            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
+            let contract_type_str = args.get("contract_type").and_then(|v| v.as_str()).unwrap_or("");
+            let contract_type = parse_enum_arg(
+                "contract_type",
+                contract_type_str,
+                contract_type_string_to_int,
+                CONTRACT_TYPES,
+            )?;
+            let status = args.get("status").and_then(|v| v.as_str()).unwrap_or("");
+            parse_enum_arg("status", status, contract_status_string_to_int, CONTRACT_STATUSES)?;
+            client.update_contract_status(contract_id, contract_type, status).await
+        }
+
+        // Stands in for a tool with a bug, to test that handle_request survives it
+        #[cfg(test)]
+        "panic_for_test" => panic!("index out of bounds"),
+
+        _ => Ok(json!({"error": format!("Unknown tool: {}", name)})),
+    }
+}
+
+/// Successful `tools/call` result: always the pretty-printed JSON as text, for
+/// older clients, plus the value itself as `structuredContent` when the
+/// session negotiated a protocol that has it. Structured content must be an
+/// object, so other values are only sent as text.
+fn tool_result(result: &Value, structured_content: bool, compact: bool) -> Value {
+    let text = if compact {
+        serde_json::to_string(result)
+    } else {
+        serde_json::to_string_pretty(result)
+    };
+    let mut response = json!({
+        "content": [{
+            "type": "text",
+            "text": text.unwrap_or_default()
+        }]
+    });
+    if structured_content && result.is_object() {
+        response["structuredContent"] = result.clone();
+    }
+    response
+}
+
+async fn handle_request(
+    client: &mut ScalegraphClient,
+    session: &mut Session,
+    request: JsonRpcRequest,
+) -> Option<JsonRpcResponse> {
+    // Notifications don't get responses
+    if request.method.starts_with("notifications/") {
+        tracing::debug!(method = %request.method, "notification received");
+        return None;
+    }
+
+    let id = request.id.unwrap_or(Value::Null);
+    let started = Instant::now();
+    let method = request.method.clone();
+
+    let result = match request.method.as_str() {
+        "initialize" => {
+            let params = request.params.as_ref();
+            let requested = params
+                .and_then(|p| p.get("protocolVersion"))
+                .and_then(|v| v.as_str());
+            let version = negotiate_protocol(requested);
+            session.structured_content = version == STRUCTURED_CONTENT_PROTOCOL;
+            // Client capabilities (sampling, roots, ...) go unused: the server never calls back
+            let client_info = |field: &str| {
+                params
+                    .and_then(|p| p.pointer(&format!("/clientInfo/{}", field)))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string()
+            };
+            tracing::info!(
+                client = %client_info("name"),
+                client_version = %client_info("version"),
+                requested_protocol = requested.unwrap_or("none"),
+                protocol = version,
+                "protocol negotiated"
+            );
+            Ok(get_server_info(version))
+        }
+        "tools/list" => Ok(get_tools_list()),
+        "tools/call" => {
+            if let Some(params) = request.params {
+                let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                let empty_args = json!({});
+                let args = params.get("arguments").unwrap_or(&empty_args);
+                let token = params.pointer("/_meta/progressToken").cloned();
+                let compact = params
+                    .pointer("/_meta/compact")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(session.compact_json);
+                let mut progress = Progress::new(token, Box::new(io::stdout()));
+                let outcome = match catch_panic(call_tool(client, name, args, &mut progress)).await {
+                    Ok(outcome) => outcome,
+                    Err(panic) => {
+                        tracing::error!(
+                            method = %method,
+                            tool = name,
+                            arguments = %args,
+                            outcome = "panic",
+                            error = %panic,
+                            "tool call panicked"
+                        );
+                        return Some(JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id,
+                            result: None,
+                            error: Some(JsonRpcError {
+                                code: INTERNAL_ERROR,
+                                message: format!("Internal error in tool {}: {}", name, panic),
+                            }),
+                        });
+                    }
+                };
+                let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+                match outcome {
+                    Ok(result) => {
+                        tracing::info!(
+                            method = %method,
+                            tool = name,
+                            arguments = %args,
+                            outcome = "ok",
+                            latency_ms,
+                            "tool call succeeded"
+                        );
+                        Ok(tool_result(&result, session.structured_content, compact))
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            method = %method,
+                            tool = name,
+                            arguments = %args,
+                            outcome = "error",
+                            error = %e,
+                            latency_ms,
+                            "tool call failed"
+                        );
+                        if let Some(error) = tool_call_error(&e) {
+                            return Some(JsonRpcResponse {
+                                jsonrpc: "2.0".to_string(),
+                                id,
+                                result: None,
+                                error: Some(error),
+                            });
+                        }
+                        Ok(json!({
+                            "content": [{
+                                "type": "text",
+                                "text": format!("Error: {}", e)
+                            }],
+                            "isError": true
+                        }))
+                    }
                 }
-            },
-            {
-                "name": "get_conditional_payment",
-                "description": "Get details of a conditional payment contract by ID.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "contract_id": {"type": "string", "description": "Conditional payment contract ID"}
-                    },
-                    "required": ["contract_id"]
+            } else {
+                Err("Missing params")
+            }
+        }
+        _ => Err("Method not found"),
+    };
+
+    // Tool calls are logged above with their tool name and arguments
+    if method != "tools/call" || result.is_err() {
+        let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+        match &result {
+            Ok(_) => tracing::info!(method = %method, outcome = "ok", latency_ms, "request handled"),
+            Err(msg) => tracing::warn!(
+                method = %method,
+                outcome = "error",
+                error = msg,
+                latency_ms,
+                "request failed"
+            ),
+        }
+    }
+
+    Some(match result {
+        Ok(r) => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(r),
+            error: None,
+        },
+        Err(msg) => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(JsonRpcError {
+                code: -32601,
+                message: msg.to_string(),
+            }),
+        },
+    })
+}
+
+/// Value of `--config <path>` (or `--config=<path>`) on the command line
+fn config_path_arg(mut args: impl Iterator<Item = String>) -> Option<std::path::PathBuf> {
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(Into::into);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(path.into());
+        }
+    }
+    None
+}
+
+/// Install a JSON-lines tracing subscriber that appends to `path`.
+fn init_file_logging(path: &str) -> Result<()> {
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| anyhow::anyhow!("cannot open log file {}: {}", path, e))?;
+    tracing_subscriber::fmt()
+        .json()
+        .with_ansi(false)
+        .with_current_span(false)
+        .with_writer(std::sync::Mutex::new(file))
+        .init();
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Environment, overridden by scalegraph.toml (--config or SCALEGRAPH_CONFIG)
+    let config_path = config_path_arg(std::env::args().skip(1));
+    let config = match Config::load(config_path.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            std::process::exit(1);
+        }
+    };
+    let references = match ReferenceFormat::from_config(&config) {
+        Ok(references) => references,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            std::process::exit(1);
+        }
+    };
+    let grpc_url = config.grpc_url().to_string();
+
+    // Structured logs go to a file, never stdout: stdout carries the JSON-RPC protocol
+    if let Ok(path) = std::env::var("SCALEGRAPH_LOG_FILE") {
+        init_file_logging(&path)?;
+        tracing::info!(grpc_url = %grpc_url, "starting scalegraph-mcp");
+    }
+
+    // Debug info only when SCALEGRAPH_DEBUG is set
+    if std::env::var("SCALEGRAPH_DEBUG").is_ok() {
+        eprintln!("Scalegraph Ledger MCP Server v1.0.0");
+        eprintln!("gRPC URL: {}", grpc_url);
+    }
+
+    let channel_count = match channel_count(std::env::var("SCALEGRAPH_CHANNELS").ok().as_deref()) {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Connect to gRPC server
+    let mut client = match ScalegraphClient::connect(&config, references, channel_count).await {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!(grpc_url = %grpc_url, error = %e, "failed to connect to gRPC server");
+            eprintln!("Failed to connect to gRPC server at {}: {}", grpc_url, e);
+            eprintln!("Make sure the Scalegraph Elixir server is running.");
+            std::process::exit(1);
+        }
+    };
+
+    let max_message_bytes = match max_message_bytes() {
+        Ok(max) => max,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut session = Session {
+        compact_json: compact_json_from_env(),
+        ..Session::default()
+    };
+    let mut stdout = io::stdout();
+    let mut messages = spawn_stdin_reader(max_message_bytes);
+    let shutdown = shutdown_signal()?;
+    tokio::pin!(shutdown);
+    let mut read_errors = 0;
+
+    loop {
+        let incoming = tokio::select! {
+            incoming = messages.recv() => incoming,
+            signal = &mut shutdown => {
+                tracing::info!(signal, "shutting down");
+                break;
+            }
+        };
+        // The reader thread only stops after passing on EOF, so None is unexpected
+        let Some(incoming) = incoming else { break };
+
+        let mut stopping = false;
+        let response = match incoming {
+            Ok(Incoming::Eof) => {
+                tracing::info!("stdin closed, shutting down");
+                break;
+            }
+            Ok(Incoming::TooLarge) => {
+                read_errors = 0;
+                tracing::warn!(outcome = "too_large", max_message_bytes, "oversized JSON-RPC message discarded");
+                Some(JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id: Value::Null,
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: INVALID_REQUEST,
+                        message: format!("Message exceeds the maximum size of {} bytes", max_message_bytes),
+                    }),
+                })
+            }
+            Ok(Incoming::Line(line)) => {
+                read_errors = 0;
+                if line.trim().is_empty() {
+                    continue;
                 }
-            },
-            {
-                "name": "create_revenue_share_contract",
-                "description": "Create a revenue share contract that automatically splits revenue from transactions among multiple parties (e.g., 70% salon, 20% supplier, 10% platform).",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "transaction_type": {"type": "string", "description": "Transaction type to apply revenue share to (e.g., 'service_sale')"},
-                        "parties": {
-                            "type": "array",
-                            "description": "Array of parties with their share percentages",
-                            "items": {
-                                "type": "object",
-                                "properties": {
-                                    "participant_id": {"type": "string"},
-                                    "share": {"type": "number", "description": "Share as decimal (e.g., 0.70 for 70%)"}
-                                },
-                                "required": ["participant_id", "share"]
+                match serde_json::from_str::<JsonRpcRequest>(&line) {
+                    // Notifications get no response
+                    Ok(request) => {
+                        let handling = handle_request(&mut client, &mut session, request);
+                        tokio::pin!(handling);
+                        tokio::select! {
+                            response = &mut handling => response,
+                            signal = &mut shutdown => {
+                                // Answer the request in flight, then stop
+                                tracing::info!(signal, "shutting down after the request in flight");
+                                stopping = true;
+                                match tokio::time::timeout(SHUTDOWN_GRACE, handling).await {
+                                    Ok(response) => response,
+                                    Err(_) => {
+                                        tracing::warn!("request in flight abandoned at shutdown");
+                                        break;
+                                    }
+                                }
                             }
-                        },
-                        "auto_split": {"type": "boolean", "description": "Enable automatic revenue splitting on each transaction"}
-                    },
-                    "required": ["transaction_type", "parties"]
-                }
-            },
-            {
-                "name": "get_revenue_share_contract",
-                "description": "Get details of a revenue share contract by ID.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "contract_id": {"type": "string", "description": "Revenue share contract ID"}
-                    },
-                    "required": ["contract_id"]
-                }
-            },
-            {
-                "name": "list_contracts",
-                "description": "List all contracts with optional filters by type, status, or participant.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "contract_type": {
-                            "type": "string",
-                            "enum": ["loan", "invoice", "subscription", "conditional_payment", "revenue_share"],
-                            "description": "Optional: Filter by contract type"
-                        },
-                        "status": {"type": "string", "description": "Optional: Filter by status (e.g., 'active', 'completed')"},
-                        "participant_id": {"type": "string", "description": "Optional: Filter by participant ID (any role)"},
-                        "limit": {"type": "integer", "description": "Maximum results (default: 100)"}
-                    },
-                    "required": []
-                }
-            },
-            {
-                "name": "execute_contract",
-                "description": "Manually execute a contract (e.g., trigger conditional payment when conditions are met, process subscription billing).",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "contract_id": {"type": "string", "description": "Contract ID"},
-                        "contract_type": {
-                            "type": "string",
-                            "enum": ["loan", "invoice", "subscription", "conditional_payment", "revenue_share"],
-                            "description": "Contract type"
                         }
-                    },
-                    "required": ["contract_id", "contract_type"]
+                    }
+                    Err(e) => {
+                        tracing::warn!(outcome = "parse_error", error = %e, "invalid JSON-RPC message");
+                        Some(parse_error(e))
+                    }
                 }
-            },
-            {
-                "name": "update_contract_status",
-                "description": "Update the status of a contract (e.g., pause, cancel, complete).",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "contract_id": {"type": "string", "description": "Contract ID"},
-                        "contract_type": {
-                            "type": "string",
-                            "enum": ["loan", "invoice", "subscription", "conditional_payment", "revenue_share"],
-                            "description": "Contract type"
-                        },
-                        "status": {
-                            "type": "string",
-                            "enum": ["active", "paused", "completed", "cancelled"],
-                            "description": "New status"
-                        }
-                    },
-                    "required": ["contract_id", "contract_type", "status"]
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            // The bad line has been consumed, so the next one can still be read
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                tracing::warn!(outcome = "parse_error", error = %e, "invalid JSON-RPC message");
+                Some(parse_error(e))
+            }
+            Err(e) => {
+                read_errors += 1;
+                tracing::warn!(error = %e, consecutive = read_errors, "failed to read from stdin");
+                if read_errors >= MAX_CONSECUTIVE_READ_ERRORS {
+                    tracing::error!("giving up after {} consecutive read errors", read_errors);
+                    break;
                 }
+                continue;
+            }
+        };
+
+        if let Some(response) = response {
+            // Usually a broken pipe: the client has gone away and nobody is left to answer
+            if let Err(e) = send(&mut stdout, &response) {
+                tracing::info!(error = %e, "stdout closed, shutting down");
+                break;
+            }
+        }
+        if stopping {
+            break;
+        }
+    }
+
+    // Every response is written whole before this point; make sure it's out
+    let _ = stdout.flush();
+    Ok(())
+}
+
+/// How long a request in flight may take to finish once a shutdown signal arrives
+const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Resolves with the signal's name on SIGINT or SIGTERM. The handlers are
+/// installed right away, so from here on neither signal kills the process
+/// in the middle of writing a response.
+fn shutdown_signal() -> io::Result<impl std::future::Future<Output = &'static str>> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        let mut terminate = signal(SignalKind::terminate())?;
+        Ok(async move {
+            tokio::select! {
+                _ = interrupt.recv() => "SIGINT",
+                _ = terminate.recv() => "SIGTERM",
+            }
+        })
+    }
+    #[cfg(not(unix))]
+    {
+        Ok(async {
+            if tokio::signal::ctrl_c().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+            "SIGINT"
+        })
+    }
+}
+
+/// Read stdin on a plain thread, since blocking reads can't be cancelled when a
+/// signal arrives. The channel holds a single message, so nothing is read far
+/// ahead of what has been handled; the thread ends after passing on EOF, or
+/// with the process.
+fn spawn_stdin_reader(max_bytes: usize) -> tokio::sync::mpsc::Receiver<io::Result<Incoming>> {
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    std::thread::spawn(move || {
+        let mut input = io::stdin().lock();
+        let mut buf = Vec::new();
+        loop {
+            let message = read_message(&mut input, &mut buf, max_bytes);
+            let eof = matches!(message, Ok(Incoming::Eof));
+            if tx.blocking_send(message).is_err() || eof {
+                break;
             }
-        ]
-    })
+        }
+    });
+    rx
 }
 
-fn role_string_to_int(role: &str) -> i32 {
-    match role.to_lowercase().as_str() {
-        "access_provider" => 1,
-        "banking_partner" => 2,
-        "ecosystem_partner" => 3,
-        "supplier" => 4,
-        "equipment_provider" => 5,
-        _ => 0,
+/// Stop reading after this many read errors in a row, rather than spin on a dead stdin
+const MAX_CONSECUTIVE_READ_ERRORS: u32 = 5;
+
+/// Largest accepted message unless `SCALEGRAPH_MAX_MESSAGE_BYTES` says otherwise
+const DEFAULT_MAX_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Most connections `SCALEGRAPH_CHANNELS` may ask for
+const MAX_CHANNELS: usize = 8;
+
+/// Number of gRPC connections to open, from `SCALEGRAPH_CHANNELS`. One
+/// channel already multiplexes calls over HTTP/2; more only spread the tool
+/// calls over separate connections.
+fn channel_count(value: Option<&str>) -> Result<usize> {
+    let Some(value) = value else {
+        return Ok(1);
+    };
+    match value.trim().parse::<usize>() {
+        Ok(count @ 1..=MAX_CHANNELS) => Ok(count),
+        _ => anyhow::bail!("SCALEGRAPH_CHANNELS must be a number from 1 to {}, got {:?}", MAX_CHANNELS, value),
     }
 }
 
-fn account_type_string_to_int(account_type: &str) -> i32 {
-    match account_type.to_lowercase().as_str() {
-        "standalone" => 1,
-        "operating" => 2,
-        "receivables" => 3,
-        "payables" => 4,
-        "escrow" => 5,
-        "fees" => 6,
-        "usage" => 7,
-        _ => 0,
+fn max_message_bytes() -> Result<usize> {
+    match std::env::var("SCALEGRAPH_MAX_MESSAGE_BYTES") {
+        Ok(value) => match value.trim().parse::<usize>() {
+            Ok(max) if max > 0 => Ok(max),
+            _ => anyhow::bail!(
+                "SCALEGRAPH_MAX_MESSAGE_BYTES must be a positive number of bytes, got {:?}",
+                value
+            ),
+        },
+        Err(_) => Ok(DEFAULT_MAX_MESSAGE_BYTES),
     }
 }
 
-fn contract_type_string_to_int(contract_type: &str) -> i32 {
-    match contract_type.to_lowercase().as_str() {
-        "loan" => ContractType::Loan as i32,
-        "invoice" => ContractType::Invoice as i32,
-        "subscription" => ContractType::Subscription as i32,
-        "conditional_payment" => ContractType::ConditionalPayment as i32,
-        "revenue_share" => ContractType::RevenueShare as i32,
-        _ => 0,
+enum Incoming {
+    Line(String),
+    /// Longer than the size limit; the whole line has been discarded
+    TooLarge,
+    Eof,
+}
+
+/// Read one newline-delimited message of at most `max_bytes` (not counting the
+/// newline) without buffering more than that. A longer line is skipped and
+/// reported as `TooLarge`; a line that isn't UTF-8 is consumed and reported as
+/// `InvalidData`. Either way the caller can answer it and carry on.
+fn read_message(input: &mut impl BufRead, buf: &mut Vec<u8>, max_bytes: usize) -> io::Result<Incoming> {
+    buf.clear();
+    let limit = max_bytes as u64 + 1;
+    if io::Read::take(&mut *input, limit).read_until(b'\n', buf)? == 0 {
+        return Ok(Incoming::Eof);
+    }
+    let content_len = buf.len() - usize::from(buf.ends_with(b"\n"));
+    if content_len > max_bytes {
+        if !buf.ends_with(b"\n") {
+            input.skip_until(b'\n')?;
+        }
+        buf.clear();
+        return Ok(Incoming::TooLarge);
     }
+    String::from_utf8(std::mem::take(buf))
+        .map(Incoming::Line)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
 }
 
-fn contract_status_string_to_int(status: &str) -> i32 {
-    match status.to_lowercase().as_str() {
-        "active" => ContractStatus::Active as i32,
-        "paused" => ContractStatus::Paused as i32,
-        "completed" => ContractStatus::Completed as i32,
-        "cancelled" => ContractStatus::Cancelled as i32,
-        _ => 0,
+fn parse_error(e: impl std::fmt::Display) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id: Value::Null,
+        result: None,
+        error: Some(JsonRpcError {
+            code: -32700,
+            message: format!("Parse error: {}", e),
+        }),
     }
 }
 
-async fn handle_tool_call(client: &mut ScalegraphClient, name: &str, args: &Value) -> Result<Value> {
-    match name {
-        "list_participants" => client.list_participants().await,
+fn send(out: &mut impl Write, response: &JsonRpcResponse) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, response)?;
+    writeln!(out)?;
+    out.flush()
+}
 
-        "create_participant" => {
-            let id = args.get("id").and_then(|v| v.as_str()).unwrap_or("");
-            let name_str = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
-            let role_str = args.get("role").and_then(|v| v.as_str()).unwrap_or("ecosystem_partner");
-            let role = role_string_to_int(role_str);
-            client.create_participant(id, name_str, role).await
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
 
-        "create_participant_account" => {
-            let participant_id = args
-                .get("participant_id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let account_type_str = args
-                .get("account_type")
-                .and_then(|v| v.as_str())
-                .unwrap_or("operating");
-            let account_type = account_type_string_to_int(account_type_str);
-            let initial_balance = args
-                .get("initial_balance_cents")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            client
-                .create_participant_account(participant_id, account_type, initial_balance)
-                .await
-        }
+    /// Writer that records everything written, shared with the test body.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
 
-        "get_participant_accounts" => {
-            let participant_id = args
-                .get("participant_id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            client.get_participant_accounts(participant_id).await
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<Value> {
+            let bytes = self.0.lock().unwrap();
+            String::from_utf8_lossy(&bytes)
+                .lines()
+                .map(|l| serde_json::from_str(l).unwrap())
+                .collect()
         }
+    }
 
-        "get_account_balance" => {
-            let account_id = args.get("account_id").and_then(|v| v.as_str()).unwrap_or("");
-            client.get_balance(account_id).await
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
         }
 
-        "transfer" => {
-            let entries: Vec<(String, i64)> = args
-                .get("entries")
-                .and_then(|v| v.as_array())
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|e| {
-                            let account_id = e.get("account_id")?.as_str()?.to_string();
-                            let amount = e.get("amount_cents")?.as_i64()?;
-                            Some((account_id, amount))
-                        })
-                        .collect()
-                })
-                .unwrap_or_default();
-            let reference = args
-                .get("reference")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            client.transfer(entries, reference).await
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
         }
+    }
 
-        "list_transactions" => {
-            let limit = args.get("limit").and_then(|v| v.as_i64()).map(|v| v as i32);
-            let account_id = args.get("account_id").and_then(|v| v.as_str());
-            client.list_transactions(limit, account_id).await
-        }
+    #[tokio::test]
+    async fn progress_notifications_precede_result() {
+        let out = SharedBuffer::default();
+        let mut progress = Progress::new(Some(json!("tok-1")), Box::new(out.clone()));
 
-        "purchase_invoice" => {
-            let supplier_id = args
-                .get("supplier_id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let buyer_id = args.get("buyer_id").and_then(|v| v.as_str()).unwrap_or("");
-            let amount = args
-                .get("amount_cents")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            let reference = args
-                .get("reference")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            client
-                .purchase_invoice(supplier_id, buyer_id, amount, reference)
-                .await
+        let observed = out.clone();
+        let results = run_with_progress(
+            vec!["a", "b", "c"],
+            &mut progress,
+            |id| format!("Executed contract {}", id),
+            |id| {
+                // Notifications for earlier items are already written while later ones run
+                let written = observed.lines().len();
+                async move { json!({ "contract_id": id, "seen": written }) }
+            },
+        )
+        .await
+        .unwrap();
+        let seen: Vec<u64> = results.iter().map(|r| r["seen"].as_u64().unwrap()).collect();
+        assert_eq!(seen, [0, 1, 2]);
+
+        // The final response goes to the same channel after the work is done
+        let response = JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id: json!(7),
+            result: Some(json!({ "results": results })),
+            error: None,
+        };
+        let mut writer = out.clone();
+        writeln!(writer, "{}", serde_json::to_string(&response).unwrap()).unwrap();
+
+        let lines = out.lines();
+        assert_eq!(lines.len(), 4);
+        for (i, line) in lines[..3].iter().enumerate() {
+            assert_eq!(line["method"], "notifications/progress");
+            assert_eq!(line["params"]["progressToken"], "tok-1");
+            assert_eq!(line["params"]["progress"], i + 1);
+            assert_eq!(line["params"]["total"], 3);
+            assert!(line.get("id").is_none());
         }
+        assert_eq!(lines[3]["id"], 7);
+        assert!(lines[3]["result"].is_object());
+    }
 
-        "pay_invoice" => {
-            let supplier_id = args
-                .get("supplier_id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let buyer_id = args.get("buyer_id").and_then(|v| v.as_str()).unwrap_or("");
-            let amount = args
-                .get("amount_cents")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            let reference = args
-                .get("reference")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            client
-                .pay_invoice(supplier_id, buyer_id, amount, reference)
-                .await
+    #[tokio::test]
+    async fn no_progress_without_token() {
+        let out = SharedBuffer::default();
+        let mut progress = Progress::new(None, Box::new(out.clone()));
+        let results = run_with_progress(
+            vec![1, 2],
+            &mut progress,
+            |n| n.to_string(),
+            |n| async move { json!(n) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(out.lines().is_empty());
+    }
+
+    #[test]
+    fn split_revenue_allocates_rounding_cents_exactly() {
+        // 70/20/10 of an odd amount: 700.7 / 200.2 / 100.1 → residue of 1 cent
+        let (amounts, residue) = split_revenue(1001, &[0.7, 0.2, 0.1]).unwrap();
+        assert_eq!(amounts.iter().sum::<i64>(), 1001);
+        assert_eq!(residue, 1);
+        assert_eq!(amounts, [701, 200, 100]);
+
+        // Equal thirds: ties go to the earlier parties
+        let (amounts, _) = split_revenue(100, &[1.0 / 3.0; 3]).unwrap();
+        assert_eq!(amounts, [34, 33, 33]);
+
+        assert!(split_revenue(100, &[]).is_err());
+    }
+
+    #[test]
+    fn split_amount_always_sums_to_the_total() {
+        // xorshift64, so the cases are random-looking but reproducible
+        let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..10_000 {
+            let parties = (next() % 12 + 1) as usize;
+            let shares: Vec<f64> = (0..parties).map(|_| (next() % 10_000) as f64 / 10_000.0).collect();
+            let total = match next() % 3 {
+                0 => (next() % 1_000) as i64,
+                1 => (next() % 1_000_000_000_000) as i64,
+                _ => -((next() % 1_000_000) as i64),
+            };
+            let amounts = split_amount(total, &shares);
+            assert_eq!(amounts.len(), parties);
+            assert_eq!(amounts.iter().sum::<i64>(), total, "split of {} by {:?}", total, shares);
+
+            // Each part is within a cent of its exact share
+            let sum: f64 = shares.iter().sum();
+            if sum > 0.0 {
+                for (amount, share) in amounts.iter().zip(&shares) {
+                    let exact = total as f64 * share / sum;
+                    assert!((*amount as f64 - exact).abs() < 1.0 + 1e-6, "{} vs {}", amount, exact);
+                }
+            }
         }
+        assert!(split_amount(100, &[]).is_empty());
+        assert_eq!(split_amount(100, &[0.0, 0.0, 0.0]), [34, 33, 33]);
+    }
 
-        "access_payment" => {
-            let payer_id = args.get("payer_id").and_then(|v| v.as_str()).unwrap_or("");
-            let access_provider_id = args
-                .get("access_provider_id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let amount = args
-                .get("amount_cents")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            let reference = args
-                .get("reference")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let platform_id = args.get("platform_id").and_then(|v| v.as_str());
-            let platform_fee = args.get("platform_fee_cents").and_then(|v| v.as_i64());
-            client
-                .access_payment(
-                    payer_id,
-                    access_provider_id,
-                    amount,
-                    reference,
-                    platform_id,
-                    platform_fee,
-                )
-                .await
+    #[test]
+    fn business_amounts_must_be_positive() {
+        let amount = |v: Value| positive_amount_arg(&json!({ "amount_cents": v }), "amount_cents");
+        assert_eq!(amount(json!(1500)).unwrap(), 1500);
+        assert!(amount(json!(0)).is_err());
+        assert!(amount(json!(-100)).is_err());
+        assert!(amount(json!(12.5)).is_err());
+        assert!(positive_amount_arg(&json!({}), "amount_cents").is_err());
+    }
+
+    #[test]
+    fn platform_fees_need_a_platform_and_leave_the_provider_something() {
+        let platform = Some("beauty_hosting");
+        assert!(validate_platform_fee(None, None, 250).is_ok());
+        assert!(validate_platform_fee(Some(""), Some(0), 250).is_ok());
+        assert!(validate_platform_fee(platform, Some(50), 250).is_ok());
+        assert!(validate_platform_fee(platform, Some(249), 250).is_ok());
+
+        let rejected = |platform_id, fee| {
+            let err = validate_platform_fee(platform_id, fee, 250).unwrap_err();
+            assert!(err.downcast_ref::<InvalidParams>().is_some());
+            err.to_string()
+        };
+        assert!(rejected(platform, Some(-1)).contains("must not be negative"));
+        assert!(rejected(None, Some(50)).contains("needs a platform_id"));
+        assert!(rejected(Some(""), Some(50)).contains("needs a platform_id"));
+        assert!(rejected(platform, None).contains("without a platform_fee_cents"));
+        assert!(rejected(platform, Some(0)).contains("without a platform_fee_cents"));
+        assert!(rejected(platform, Some(250)).contains("must be less than amount_cents"));
+        assert!(rejected(platform, Some(251)).contains("must be less than amount_cents"));
+    }
+
+    #[test]
+    fn access_payment_split_balances_the_three_way_payment() {
+        let entries = access_payment_entries("salon_glamour", "assa_abloy", 800, Some(("beauty_hosting", 50)));
+        let split = access_payment_split(&entries, 50);
+        assert_eq!(split["provider_net_cents"], 750);
+        assert_eq!(split["platform_fee_cents"], 50);
+        let entries = split["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0]["account_id"], "salon_glamour:operating");
+        assert_eq!(entries[0]["amount_cents"], -800);
+        assert_eq!(entries[1]["account_id"], "assa_abloy:fees");
+        assert_eq!(entries[2]["account_id"], "beauty_hosting:fees");
+        assert_eq!(entries.iter().map(|e| e["amount_cents"].as_i64().unwrap()).sum::<i64>(), 0);
+
+        let direct = access_payment_entries("salon_glamour", "assa_abloy", 800, None);
+        assert_eq!(access_payment_split(&direct, 0)["provider_net_cents"], 800);
+        assert_eq!(direct.len(), 2);
+    }
+
+    #[test]
+    fn closest_match_suggests_likely_typos_only() {
+        let ids: Vec<String> = ["salon_glamour", "schampo_etc", "seb"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(closest_match("salon_glamur", &ids), Some("salon_glamour"));
+        assert_eq!(closest_match("schampo-etc", &ids), Some("schampo_etc"));
+        assert_eq!(closest_match("beauty_hosting", &ids), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn due_contract_selects_only_elapsed_active_contracts() {
+        use smartcontracts::contract_response::Contract;
+        let subscription = |status: &str, next_billing_date: i64| smartcontracts::ContractResponse {
+            contract: Some(Contract::Subscription(smartcontracts::SubscriptionContract {
+                id: "sub-1".to_string(),
+                status: status.to_string(),
+                next_billing_date,
+                ..Default::default()
+            })),
+        };
+        assert_eq!(
+            due_contract(&subscription("active", 500), 1000),
+            Some(("sub-1".to_string(), ContractType::Subscription as i32))
+        );
+        assert_eq!(due_contract(&subscription("active", 1500), 1000), None);
+        assert_eq!(due_contract(&subscription("cancelled", 500), 1000), None);
+        assert_eq!(due_contract(&subscription("active", 0), 1000), None);
+    }
+
+    #[test]
+    fn batch_transfers_are_checked_before_posting() {
+        let transfers = batch_transfers_arg(&json!({
+            "transfers": [
+                {"reference": "INV-1", "entries": [
+                    {"account_id": "a:operating", "amount_cents": -100},
+                    {"account_id": "b:operating", "amount_cents": 100}
+                ]}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].1, "INV-1");
+
+        let unbalanced = json!({"transfers": [
+            {"reference": "ok", "entries": [
+                {"account_id": "a:operating", "amount_cents": -100},
+                {"account_id": "b:operating", "amount_cents": 100}
+            ]},
+            {"reference": "bad", "entries": [{"account_id": "a:operating", "amount_cents": -100}]}
+        ]});
+        let err = batch_transfers_arg(&unbalanced).unwrap_err().to_string();
+        assert!(err.contains("transfer 1"), "{}", err);
+        assert!(batch_transfers_arg(&json!({"transfers": []})).is_err());
+        assert!(batch_transfers_arg(&json!({"transfers": [{"reference": "x", "entries": [{"account_id": "a"}]}]})).is_err());
+    }
+
+    #[test]
+    fn contract_status_transitions_follow_the_lifecycle() {
+        assert!(check_status_transition("active", "paused").is_ok());
+        assert!(check_status_transition("paused", "active").is_ok());
+        assert!(check_status_transition("active", "cancelled").is_ok());
+        let err = check_status_transition("completed", "active").unwrap_err().to_string();
+        assert!(err.contains("final"), "{}", err);
+        assert!(check_status_transition("paused", "completed").is_err());
+        assert!(check_status_transition("active", "active").is_err());
+    }
+
+    #[test]
+    fn unknown_enum_strings_are_invalid_params() {
+        assert_eq!(
+            parse_enum_arg("contract_type", "Subscription", contract_type_string_to_int, CONTRACT_TYPES).unwrap(),
+            ContractType::Subscription as i32
+        );
+        let err = parse_enum_arg("contract_type", "subscrption", contract_type_string_to_int, CONTRACT_TYPES)
+            .unwrap_err();
+        let invalid = err.downcast_ref::<InvalidParams>().expect("InvalidParams");
+        assert!(invalid.0.contains("'subscrption'"), "{}", invalid);
+        assert!(invalid.0.contains("subscription, conditional_payment"), "{}", invalid);
+    }
+
+    #[test]
+    fn tool_args_are_checked_against_the_input_schema() {
+        let err = check_tool_args("create_participant", &json!({"id": "salon", "role": "supplier"}))
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InvalidParams>().expect("InvalidParams").0,
+            "missing required field 'name'"
+        );
+        let err = check_tool_args(
+            "purchase_invoice",
+            &json!({"supplier_id": "a", "buyer_id": "b", "amount_cents": "100", "reference": "INV-1"}),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("'amount_cents' must be of type integer"), "{}", err);
+        assert!(check_tool_args("create_participant", &json!({"id": "s", "name": "S", "role": "supplier"})).is_ok());
+        assert!(check_tool_args("get_ledger_summary", &json!({})).is_ok());
+    }
+
+    #[test]
+    fn describe_enums_covers_every_proto_value_and_the_tools_accept_them() {
+        let enums = describe_enums();
+        let account_types = [&["standalone"], PARTICIPANT_ACCOUNT_TYPES].concat();
+        let parse: fn(&str) -> i32 = role_string_to_int;
+        for (name, accepted, parse) in [
+            ("roles", ROLES, parse),
+            ("account_types", &account_types[..], account_type_string_to_int),
+            ("contract_types", CONTRACT_TYPES, contract_type_string_to_int),
+            ("contract_statuses", CONTRACT_STATUSES, contract_status_string_to_int),
+        ] {
+            let entries = enums[name].as_array().unwrap();
+            let keys: Vec<&str> = entries.iter().map(|e| e["key"].as_str().unwrap()).collect();
+            assert_eq!(keys, accepted, "{} differs from the keys the tools accept", name);
+            for entry in entries {
+                let key = entry["key"].as_str().unwrap();
+                assert_eq!(entry["value"], parse(key), "{} {}", name, key);
+                assert_ne!(entry["label"], "Unknown", "{} {} has no label", name, key);
+            }
         }
+        // The last proto value of each enum is listed
+        assert_eq!(enums["roles"][5]["value"], ParticipantRole::EcosystemOrchestrator as i32);
+        assert_eq!(enums["account_types"][6]["value"], AccountType::Usage as i32);
+        assert_eq!(enums["contract_types"][6]["key"], "ecosystem_partner_membership");
+        assert_eq!(enums["contract_statuses"][3]["value"], ContractStatus::Cancelled as i32);
+        assert_eq!(enums["contract_types"][3]["label"], "Conditional Payment");
+    }
 
-        "create_loan" => {
-            let lender_id = args.get("lender_id").and_then(|v| v.as_str()).unwrap_or("");
-            let borrower_id = args.get("borrower_id").and_then(|v| v.as_str()).unwrap_or("");
-            let amount = args
-                .get("amount_cents")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            let reference = args
-                .get("reference")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            client
-                .create_loan(lender_id, borrower_id, amount, reference)
-                .await
+    #[test]
+    fn transaction_filter_matches_amount_and_time_ranges() {
+        let tx = Transaction {
+            timestamp: 1_000,
+            entries: vec![
+                TransferEntry { account_id: "a".to_string(), amount: -150_000 },
+                TransferEntry { account_id: "b".to_string(), amount: 150_000 },
+            ],
+            ..Default::default()
+        };
+        let filter = |args: Value| TransactionFilter::from_args(&args).unwrap();
+        assert!(filter(json!({})).is_empty());
+        assert!(filter(json!({"min_amount_cents": 100_000})).matches(&tx));
+        assert!(filter(json!({"min_amount_cents": 150_000, "max_amount_cents": 150_000})).matches(&tx));
+        assert!(!filter(json!({"min_amount_cents": 200_000})).matches(&tx));
+        assert!(!filter(json!({"max_amount_cents": 100_000})).matches(&tx));
+        assert!(filter(json!({"start_time": 1_000, "end_time": 2_000})).matches(&tx));
+        assert!(!filter(json!({"start_time": 1_001})).matches(&tx));
+        assert!(!filter(json!({"end_time": 999})).matches(&tx));
+
+        let err = TransactionFilter::from_args(&json!({"min_amount_cents": 5, "max_amount_cents": 1})).unwrap_err();
+        assert!(err.downcast_ref::<InvalidParams>().is_some());
+
+        // Types come from the reference prefix the business layer writes
+        let access = Transaction { reference: "ACCESS: door-7".to_string(), ..tx.clone() };
+        assert_eq!(transaction_type(&access.r#type, &access.reference), "access_payment");
+        assert_eq!(transaction_type(&tx.r#type, &tx.reference), "transfer");
+        assert!(filter(json!({"type": "access_payment"})).matches(&access));
+        assert!(!filter(json!({"type": "Access_Payment"})).matches(&tx));
+        assert!(filter(json!({"type": "transfer"})).matches(&tx));
+        let err = TransactionFilter::from_args(&json!({"type": "refund"})).unwrap_err();
+        assert!(err.downcast_ref::<InvalidParams>().is_some());
+    }
+
+    #[test]
+    fn reference_matching_is_case_insensitive_substring() {
+        assert!(reference_matches("Payment INV-2024-001", "inv-2024-001"));
+        assert!(reference_matches("INV-2024-001", " INV-2024 "));
+        assert!(!reference_matches("INV-2024-002", "INV-2024-001"));
+    }
+
+    #[test]
+    fn unavailable_status_is_detected_through_context() {
+        let unavailable = anyhow::Error::new(tonic::Status::unavailable("connection refused"));
+        assert!(is_unavailable(&unavailable));
+        assert!(is_unavailable(&unavailable.context("list_participants")));
+        assert!(!is_unavailable(&anyhow::Error::new(tonic::Status::not_found("no such participant"))));
+        assert!(!is_unavailable(&anyhow::anyhow!("plain error")));
+    }
+
+    #[test]
+    fn structured_content_follows_negotiated_protocol() {
+        assert_eq!(negotiate_protocol(Some("2024-11-05")), BASELINE_PROTOCOL);
+        assert_eq!(negotiate_protocol(None), BASELINE_PROTOCOL);
+        assert_eq!(negotiate_protocol(Some("2025-06-18")), STRUCTURED_CONTENT_PROTOCOL);
+        // Unsupported revisions, older or newer, get the newest supported one
+        assert_eq!(negotiate_protocol(Some("2025-03-26")), STRUCTURED_CONTENT_PROTOCOL);
+        assert_eq!(negotiate_protocol(Some("2026-01-01")), STRUCTURED_CONTENT_PROTOCOL);
+        assert_eq!(negotiate_protocol(Some("1.0")), STRUCTURED_CONTENT_PROTOCOL);
+
+        let result = json!({"balance": "10.00"});
+        assert!(tool_result(&result, false, false).get("structuredContent").is_none());
+        let with_structured = tool_result(&result, true, false);
+        assert_eq!(with_structured["structuredContent"], result);
+        assert!(with_structured["content"][0]["text"].as_str().unwrap().contains("10.00"));
+        assert!(tool_result(&json!([1, 2]), true, false).get("structuredContent").is_none());
+    }
+
+    #[test]
+    fn compact_results_are_single_line() {
+        let result = json!({"transactions": [{"id": "tx-1"}, {"id": "tx-2"}]});
+        let text = |compact| tool_result(&result, false, compact)["content"][0]["text"].clone();
+        assert_eq!(text(true), json!(r#"{"transactions":[{"id":"tx-1"},{"id":"tx-2"}]}"#));
+        assert!(text(false).as_str().unwrap().contains('\n'));
+    }
+
+    #[test]
+    fn escrow_checks_recognise_escrow_accounts_by_type_name() {
+        assert_eq!(account_type_to_string(AccountType::Escrow as i32), scalegraph_rules::escrow::ESCROW_ACCOUNT_TYPE);
+        let operating = account_type_to_string(AccountType::Operating as i32);
+        assert!(check_escrow_account("seb:operating", operating, 1_000, EscrowMove::Fund, 500).is_err());
+    }
+
+    #[test]
+    fn usage_amount_validates_units_and_price() {
+        assert_eq!(usage_amount(3, 250).unwrap(), 750);
+        assert_eq!(usage_amount(3, 0).unwrap(), 0);
+        assert!(usage_amount(0, 250).is_err());
+        assert!(usage_amount(3, -1).is_err());
+        assert!(usage_amount(i64::MAX, 2).is_err());
+        assert_eq!(usage_metering(3, 250), "usage: 3 units x 2.50");
+    }
+
+    #[test]
+    fn read_message_survives_bad_lines_and_stops_at_eof() {
+        let mut input = io::Cursor::new(b"{\"a\":1}\n\xff\xfe\n\nlast".to_vec());
+        let mut buf = Vec::new();
+        assert!(matches!(read_message(&mut input, &mut buf, 64), Ok(Incoming::Line(l)) if l == "{\"a\":1}\n"));
+        let err = read_message(&mut input, &mut buf, 64).err().expect("invalid UTF-8");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(read_message(&mut input, &mut buf, 64), Ok(Incoming::Line(l)) if l == "\n"));
+        assert!(matches!(read_message(&mut input, &mut buf, 64), Ok(Incoming::Line(l)) if l == "last"));
+        assert!(matches!(read_message(&mut input, &mut buf, 64), Ok(Incoming::Eof)));
+
+        let mut out = Vec::new();
+        send(&mut out, &parse_error("bad")).unwrap();
+        assert!(String::from_utf8(out).unwrap().ends_with("}\n"));
+    }
+
+    #[test]
+    fn read_message_discards_oversized_lines() {
+        let mut input = io::Cursor::new(b"12345\n123456\n1234567890\nok".to_vec());
+        let mut buf = Vec::new();
+        assert!(matches!(read_message(&mut input, &mut buf, 5), Ok(Incoming::Line(l)) if l == "12345\n"));
+        assert!(matches!(read_message(&mut input, &mut buf, 5), Ok(Incoming::TooLarge)));
+        assert!(matches!(read_message(&mut input, &mut buf, 5), Ok(Incoming::TooLarge)));
+        assert!(matches!(read_message(&mut input, &mut buf, 5), Ok(Incoming::Line(l)) if l == "ok"));
+        assert!(matches!(read_message(&mut input, &mut buf, 5), Ok(Incoming::Eof)));
+    }
+
+    #[test]
+    fn accounts_summary_totals_by_type() {
+        let account = |id: &str, account_type: AccountType, balance: i64| Account {
+            id: id.to_string(),
+            account_type: account_type as i32,
+            balance,
+            ..Default::default()
+        };
+        let accounts = vec![
+            account("salon_glamour:operating", AccountType::Operating, 125_050),
+            account("salon_glamour:receivables", AccountType::Receivables, 20_000),
+            account("salon_glamour:payables", AccountType::Payables, -45_001),
+            account("salon_glamour:payables2", AccountType::Payables, -1),
+        ];
+
+        let summary = accounts_summary(&accounts);
+        assert_eq!(summary["account_count"], 4);
+        assert_eq!(summary["total_balance"], "1000.48");
+        assert_eq!(summary["total_balance_cents"], 100_048);
+        assert_eq!(summary["by_type"]["Payables"]["accounts"], 2);
+        assert_eq!(summary["by_type"]["Payables"]["total"], "-450.02");
+        assert_eq!(summary["by_type"]["Operating"]["total_cents"], 125_050);
+    }
+
+    #[test]
+    fn transaction_json_has_directions_and_net_change() {
+        let entry = |account_id: &str, amount: i64| TransferEntry {
+            account_id: account_id.to_string(),
+            amount,
+        };
+        let tx = Transaction {
+            id: "tx1".to_string(),
+            entries: vec![
+                entry("salon_glamour:operating", -50_000),
+                entry("schampo_etc:operating", 48_000),
+                entry("salon_glamour:operating", -1_000),
+                entry("beauty_hosting:fees", 3_000),
+            ],
+            ..Default::default()
+        };
+
+        let json = transaction_to_json(&tx);
+        assert_eq!(json["entries"][0]["direction"], "debit");
+        assert_eq!(json["entries"][1]["direction"], "credit");
+        let salon = &json["net_change"]["salon_glamour:operating"];
+        assert_eq!(salon["amount_cents"], -51_000);
+        assert_eq!(salon["amount"], "-510.00");
+        assert_eq!(salon["direction"], "debit");
+        assert_eq!(json["net_change"]["beauty_hosting:fees"]["direction"], "credit");
+        assert_eq!(json["net_change"].as_object().unwrap().len(), 3);
+        assert!(json["entries"][3].get("memo").is_none());
+
+        let memos = [(3, "processing fee".to_string()), (4, "platform cut".to_string())];
+        let format = ReferenceFormat::default();
+        let reference = format.with_memos(format.validate_reference("SETTLEMENT 2024-12"), &memos).unwrap();
+        let with_memos = Transaction {
+            reference: reference.value,
+            ..tx
+        };
+        let json = transaction_to_json(&with_memos);
+        assert_eq!(json["entries"][2]["memo"], "processing fee");
+        assert_eq!(json["entries"][3]["memo"], "platform cut");
+        assert!(json["entries"][0].get("memo").is_none());
+    }
+
+    #[tokio::test]
+    async fn panics_become_errors_and_later_calls_still_run() {
+        let panicking = catch_panic(async {
+            tokio::task::yield_now().await;
+            let amounts: Vec<i64> = Vec::new();
+            amounts[0]
+        })
+        .await;
+        assert!(panicking.unwrap_err().contains("index out of bounds"));
+
+        let message = catch_panic(async { panic!("boom") }).await;
+        assert_eq!(message.unwrap_err(), "boom");
+
+        assert_eq!(catch_panic(async { 42 }).await, Ok(42));
+    }
+
+    #[test]
+    fn service_changes_plan_only_what_differs() {
+        let current = vec!["haircare".to_string(), "styling".to_string()];
+        let changes = |args: Value| ServiceChanges::from_args(&args).unwrap();
+
+        let (add, remove) = changes(json!({"add": ["coloring", "haircare"]})).plan(&current);
+        assert_eq!((add, remove), (vec!["coloring".to_string()], vec![]));
+
+        let (add, remove) = changes(json!({"services": ["styling", " retail "], "remove": ["retail"]})).plan(&current);
+        assert_eq!((add, remove), (vec![], vec!["haircare".to_string()]));
+
+        let (add, remove) = changes(json!({"services": []})).plan(&current);
+        assert_eq!((add, remove), (vec![], current.clone()));
+
+        for bad in [json!({}), json!({"add": [""]}), json!({"add": ["x"], "remove": ["x"]})] {
+            let e = ServiceChanges::from_args(&bad).unwrap_err();
+            assert!(e.downcast_ref::<InvalidParams>().is_some(), "{}", bad);
         }
+    }
+
+    #[test]
+    fn contract_parties_cover_every_role() {
+        use smartcontracts::contract_response::Contract;
+        let revenue_share = smartcontracts::ContractResponse {
+            contract: Some(Contract::RevenueShare(smartcontracts::RevenueShareContract {
+                parties: ["salon_glamour", "beauty_hosting"]
+                    .iter()
+                    .map(|id| smartcontracts::RevenueShareParty {
+                        participant_id: id.to_string(),
+                        share: 0.5,
+                    })
+                    .collect(),
+                ..Default::default()
+            })),
+        };
+        assert_eq!(contract_parties(&revenue_share), vec!["salon_glamour", "beauty_hosting"]);
+
+        let generic = smartcontracts::ContractResponse {
+            contract: Some(Contract::Generic(smartcontracts::GenericContract {
+                metadata: [
+                    ("supplier_id", "schampo_etc"),
+                    ("buyer_id", "salon_glamour"),
+                    ("orchestrator_id", "schampo_etc"),
+                    ("note", "seb"),
+                ]
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+                ..Default::default()
+            })),
+        };
+        let mut parties = contract_parties(&generic);
+        parties.sort();
+        assert_eq!(parties, vec!["salon_glamour", "schampo_etc"]);
+        assert!(contract_parties(&smartcontracts::ContractResponse { contract: None }).is_empty());
+    }
+
+    #[test]
+    fn loan_book_groups_loans_by_borrower() {
+        let tx = |reference: &str, borrower: &str, amount: i64| Transaction {
+            reference: reference.to_string(),
+            entries: vec![
+                TransferEntry { account_id: "seb:operating".to_string(), amount: -amount },
+                TransferEntry { account_id: format!("{}:operating", borrower), amount },
+                TransferEntry { account_id: "seb:receivables".to_string(), amount },
+                TransferEntry { account_id: format!("{}:payables", borrower), amount: -amount },
+            ],
+            ..Default::default()
+        };
+        let transactions = [
+            tx("LOAN: L-1", "salon_glamour", 100_000),
+            tx("LOAN: L-2", "salon_glamour", 50_000),
+            tx("LOAN_REPAYMENT: L-1", "salon_glamour", -30_000),
+            tx("LOAN: L-3", "klipp_och_trim", 20_000),
+            tx("LOAN_REPAYMENT: L-3", "klipp_och_trim", -20_000),
+            tx("INVOICE: INV-9", "essity", 5_000),
+        ];
+        let book = loan_book("seb:receivables", &transactions);
+        assert_eq!(
+            book["salon_glamour"],
+            BorrowerLoans { lent: 150_000, repaid: 30_000, outstanding: 120_000, loans: 2 }
+        );
+        assert_eq!(book["klipp_och_trim"].outstanding, 0);
+        assert!(!book.contains_key("essity"));
+    }
+
+    #[test]
+    fn balance_as_of_undoes_later_entries() {
+        let tx = |timestamp: i64, amount: i64| Transaction {
+            timestamp,
+            entries: vec![
+                TransferEntry { account_id: "seb:operating".to_string(), amount },
+                TransferEntry { account_id: "essity:operating".to_string(), amount: -amount },
+            ],
+            ..Default::default()
+        };
+        let transactions = [tx(3_000, 500), tx(2_000, -200), tx(1_000, 1_000)];
+        assert_eq!(balance_as_of("seb:operating", 1_300, &transactions, 2_500), (800, 1));
+        assert_eq!(balance_as_of("seb:operating", 1_300, &transactions, 2_000), (800, 1));
+        assert_eq!(balance_as_of("seb:operating", 1_300, &transactions, 1_999), (1_000, 2));
+        assert_eq!(balance_as_of("seb:operating", 1_300, &transactions, 0), (0, 3));
+        assert_eq!(balance_as_of("essity:operating", -1_300, &transactions, 5_000), (-1_300, 0));
+    }
+
+    #[test]
+    fn contact_updates_keep_unset_fields_and_check_shapes() {
+        let current = Contact {
+            email: "info@salonglamour.se".to_string(),
+            city: "Stockholm".to_string(),
+            ..Default::default()
+        };
+        let updated = contact_from_args(
+            &current,
+            &json!({"participant_id": "salon_glamour", "phone": "+46 (8) 123-456", "city": ""}),
+        )
+        .unwrap();
+        assert_eq!(updated.email, "info@salonglamour.se");
+        assert_eq!(updated.phone, "+46 (8) 123-456");
+        assert_eq!(updated.city, "");
+
+        let invalid = |field: &str, value: &str| {
+            let err = contact_from_args(&current, &json!({ field: value })).unwrap_err();
+            assert!(err.downcast_ref::<InvalidParams>().is_some(), "{} = {}", field, value);
+        };
+        invalid("email", "info@salonglamour");
+        invalid("email", "info salon@glamour.se");
+        invalid("phone", "46+123456");
+        invalid("phone", "1234");
+        invalid("website", "salonglamour");
+        invalid("website", "ftp://salonglamour.se");
+        assert!(contact_from_args(&current, &json!({"website": "www.salonglamour.se/about"})).is_ok());
+        assert!(contact_from_args(&current, &json!({"participant_id": "salon_glamour"})).is_err());
+    }
+
+    #[test]
+    fn ledger_statuses_become_jsonrpc_errors_and_local_failures_tool_errors() {
+        let not_found = anyhow::Error::new(tonic::Status::not_found("account seb:escrow not found"))
+            .context("get_account_balance");
+        let error = tool_call_error(&not_found).expect("mapped status");
+        assert_eq!(error.code, errors::NOT_FOUND);
+        assert_eq!(error.message, "account seb:escrow not found");
+
+        let invalid = anyhow::Error::new(InvalidParams("limit must be positive".to_string()));
+        assert_eq!(tool_call_error(&invalid).expect("invalid params").code, INVALID_PARAMS);
+
+        assert!(tool_call_error(&anyhow::anyhow!("amount_cents must be a positive integer")).is_none());
+    }
 
-        "repay_loan" => {
-            let lender_id = args.get("lender_id").and_then(|v| v.as_str()).unwrap_or("");
-            let borrower_id = args.get("borrower_id").and_then(|v| v.as_str()).unwrap_or("");
-            let amount = args
-                .get("amount_cents")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            let reference = args
-                .get("reference")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            client
-                .repay_loan(lender_id, borrower_id, amount, reference)
-                .await
-        }
+    #[test]
+    fn simulated_transfers_replay_entries_in_order_like_the_ledger() {
+        let account = |id: &str, account_type: AccountType, balance: i64| {
+            let account = Account {
+                id: id.to_string(),
+                account_type: account_type as i32,
+                balance,
+                ..Default::default()
+            };
+            (id.to_string(), Some(account))
+        };
+        let mut accounts: BTreeMap<String, Option<Account>> = [
+            account("salon_glamour:operating", AccountType::Operating, 10_000),
+            account("salon_glamour:payables", AccountType::Payables, 0),
+            account("schampo_etc:operating", AccountType::Operating, 0),
+        ]
+        .into_iter()
+        .collect();
+        accounts.insert("seb:escrow".to_string(), None);
+        let entries = |list: &[(&str, i64)]| -> Vec<(String, i64)> {
+            list.iter().map(|(id, amount)| (id.to_string(), *amount)).collect()
+        };
 
-        "get_outstanding_loans" => {
-            let lender_id = args.get("lender_id").and_then(|v| v.as_str()).unwrap_or("");
-            client.get_outstanding_loans(lender_id).await
-        }
+        let ok = simulate_transfer_report(
+            &entries(&[("salon_glamour:operating", -10_000), ("schampo_etc:operating", 10_000)]),
+            &accounts,
+            false,
+        );
+        assert_eq!(ok["would_succeed"], true);
+        assert_eq!(ok["entries"][0]["balance_after_cents"], 0);
 
-        "get_total_debt" => {
-            let borrower_id = args.get("borrower_id").and_then(|v| v.as_str()).unwrap_or("");
-            client.get_total_debt(borrower_id).await
-        }
+        // The second debit overdraws even though a later credit would cover it
+        let overdraft = entries(&[
+            ("salon_glamour:operating", -6_000),
+            ("salon_glamour:operating", -6_000),
+            ("salon_glamour:operating", 2_000),
+            ("schampo_etc:operating", 10_000),
+        ]);
+        let report = simulate_transfer_report(&overdraft, &accounts, false);
+        assert_eq!(report["would_succeed"], false);
+        assert_eq!(report["entries"][0]["ok"], true);
+        assert_eq!(report["entries"][1]["ok"], false);
+        assert_eq!(report["entries"][1]["balance_after_cents"], -2_000);
+        assert_eq!(report["entries"][2]["balance_before_cents"], -2_000);
+        let allowed = simulate_transfer_report(&overdraft, &accounts, true);
+        assert_eq!(allowed["would_succeed"], true);
+        assert_eq!(allowed["entries"][1]["overdraft"], true);
 
-        "create_invoice_contract" => {
-            let supplier_id = args.get("supplier_id").and_then(|v| v.as_str()).unwrap_or("");
-            let buyer_id = args.get("buyer_id").and_then(|v| v.as_str()).unwrap_or("");
-            let amount_cents = args.get("amount_cents").and_then(|v| v.as_i64()).unwrap_or(0);
-            let issue_date = args.get("issue_date").and_then(|v| v.as_i64()).unwrap_or(0);
-            let due_date = args.get("due_date").and_then(|v| v.as_i64()).unwrap_or(0);
-            let payment_terms = args.get("payment_terms").and_then(|v| v.as_str()).unwrap_or("Net 30");
-            let auto_debit = args.get("auto_debit").and_then(|v| v.as_bool()).unwrap_or(false);
-            let late_fee_cents = args.get("late_fee_cents").and_then(|v| v.as_i64()).unwrap_or(0);
-            let reference = args.get("reference").and_then(|v| v.as_str()).unwrap_or("");
-            client
-                .create_invoice_contract(
-                    supplier_id,
-                    buyer_id,
-                    amount_cents,
-                    issue_date,
-                    due_date,
-                    payment_terms,
-                    auto_debit,
-                    late_fee_cents,
-                    reference,
-                )
-                .await
-        }
+        // Payables may go negative; a missing account and a non-zero net both fail
+        let report = simulate_transfer_report(
+            &entries(&[("salon_glamour:payables", -5_000), ("seb:escrow", 4_000)]),
+            &accounts,
+            true,
+        );
+        assert_eq!(report["would_succeed"], false);
+        assert_eq!(report["entries"][0]["ok"], true);
+        assert_eq!(report["entries"][1]["exists"], false);
+        assert_eq!(report["nets_to_zero"], false);
+        assert_eq!(report["problems"].as_array().unwrap().len(), 2);
+    }
 
-        "get_invoice_contract" => {
-            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
-            client.get_invoice_contract(contract_id).await
+    #[test]
+    fn participant_accounts_reject_unknown_and_standalone_types() {
+        let parse = |value: &str| {
+            parse_enum_arg("account_type", value, participant_account_type_to_int, PARTICIPANT_ACCOUNT_TYPES)
+        };
+        assert_eq!(parse("Receivables").unwrap(), AccountType::Receivables as i32);
+        for bad in ["standalone", "checking", ""] {
+            let err = parse(bad).unwrap_err();
+            let invalid = err.downcast_ref::<InvalidParams>().expect("InvalidParams");
+            assert!(invalid.0.contains("operating, receivables, payables, escrow, fees, usage"), "{}", invalid);
+            assert!(!invalid.0.contains("standalone,"), "{}", invalid);
         }
+    }
 
-        "create_subscription_contract" => {
-            let provider_id = args.get("provider_id").and_then(|v| v.as_str()).unwrap_or("");
-            let subscriber_id = args.get("subscriber_id").and_then(|v| v.as_str()).unwrap_or("");
-            let monthly_fee_cents = args.get("monthly_fee_cents").and_then(|v| v.as_i64()).unwrap_or(0);
-            let billing_date = args.get("billing_date").and_then(|v| v.as_str()).unwrap_or("every 1st");
-            let auto_debit = args.get("auto_debit").and_then(|v| v.as_bool()).unwrap_or(true);
-            let cancellation_notice_days = args.get("cancellation_notice_days").and_then(|v| v.as_i64()).map(|v| v as i32).unwrap_or(30);
-            let start_date = args.get("start_date").and_then(|v| v.as_i64()).unwrap_or(0);
-            let end_date = args.get("end_date").and_then(|v| v.as_i64());
-            client
-                .create_subscription_contract(
-                    provider_id,
-                    subscriber_id,
-                    monthly_fee_cents,
-                    billing_date,
-                    auto_debit,
-                    cancellation_notice_days,
-                    start_date,
-                    end_date,
-                )
-                .await
-        }
+    #[test]
+    fn bulk_account_items_are_all_checked_up_front() {
+        let accounts = participant_accounts_arg(&json!({
+            "accounts": [
+                { "account_type": "operating", "initial_balance_cents": 50_000 },
+                { "account_type": "Fees" },
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            accounts,
+            [(AccountType::Operating as i32, 50_000), (AccountType::Fees as i32, 0)]
+        );
 
-        "get_subscription_contract" => {
-            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
-            client.get_subscription_contract(contract_id).await
+        for (args, message) in [
+            (json!({}), "accounts must be an array"),
+            (json!({ "accounts": [] }), "accounts is empty"),
+            (json!({ "accounts": [{ "initial_balance_cents": 5 }] }), "account 0 has no account_type"),
+            (
+                json!({ "accounts": [{ "account_type": "fees" }, { "account_type": "standalone" }] }),
+                "invalid account_type for account 1 'standalone'",
+            ),
+            (
+                json!({ "accounts": [{ "account_type": "fees", "initial_balance_cents": 1.5 }] }),
+                "account 0 initial_balance_cents must be an integer",
+            ),
+        ] {
+            let err = participant_accounts_arg(&args).unwrap_err();
+            assert!(err.downcast_ref::<InvalidParams>().is_some(), "{}", err);
+            assert!(err.to_string().starts_with(message), "{}", err);
         }
+    }
 
-        "create_conditional_payment" => {
-            let payer_id = args.get("payer_id").and_then(|v| v.as_str()).unwrap_or("");
-            let receiver_id = args.get("receiver_id").and_then(|v| v.as_str()).unwrap_or("");
-            let amount_cents = args.get("amount_cents").and_then(|v| v.as_i64()).unwrap_or(0);
-            let condition_type = args.get("condition_type").and_then(|v| v.as_str()).unwrap_or("");
-            let trigger = args.get("trigger").and_then(|v| v.as_str()).unwrap_or("");
-            client
-                .create_conditional_payment(payer_id, receiver_id, amount_cents, condition_type, trigger)
-                .await
-        }
+    #[test]
+    fn generated_references_keep_the_prefix_their_type_is_read_from() {
+        let references = ReferenceFormat::default();
+        let tx = |kind, subject| Transaction {
+            reference: references.make_reference(kind, subject),
+            ..Default::default()
+        };
+        let reversal = tx(ReferenceKind::Reversal, "tx-1");
+        assert_eq!(transaction_type("transfer", &reversal.reference), "reversal");
+        let share = tx(ReferenceKind::RevenueShare, "rs-1");
+        assert_eq!(transaction_type("transfer", &share.reference), "revenue_share");
+        assert!(reversal_entries("tx-2", &reversal.reference, [("acct0", -500), ("acct1", 500)]).is_err());
+        let sweep = tx(ReferenceKind::FeeSweep, "salon_glamour");
+        assert!(reference_matches(&sweep.reference, "fee-sweep: salon_glamour"));
+    }
 
-        "get_conditional_payment" => {
-            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
-            client.get_conditional_payment(contract_id).await
-        }
+    #[test]
+    fn participants_resolve_by_name_fragment_or_id() {
+        let participant = |id: &str, name: &str| Participant {
+            id: id.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let participants = [
+            participant("salon_glamour", "Salon Glamour"),
+            participant("salon_bella", "Salon Bella"),
+            participant("glamour_supplies", "Glamour Supplies AB"),
+            participant("seb", "SEB"),
+        ];
+        let resolve = |query: &str| resolve_participant_report(query, &participants);
 
-        "create_revenue_share_contract" => {
-            let transaction_type = args.get("transaction_type").and_then(|v| v.as_str()).unwrap_or("");
-            let parties: Vec<(String, f64)> = args
-                .get("parties")
-                .and_then(|v| v.as_array())
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|p| {
-                            let participant_id = p.get("participant_id")?.as_str()?.to_string();
-                            let share = p.get("share")?.as_f64()?;
-                            Some((participant_id, share))
-                        })
-                        .collect()
-                })
-                .unwrap_or_default();
-            let auto_split = args.get("auto_split").and_then(|v| v.as_bool()).unwrap_or(true);
-            client
-                .create_revenue_share_contract(transaction_type, parties, auto_split)
-                .await
-        }
+        let exact = resolve("salon glamour");
+        assert_eq!(exact["status"], "resolved");
+        assert_eq!(exact["participant"]["id"], "salon_glamour");
+        assert_eq!(resolve("SEB")["participant"]["id"], "seb");
+        assert_eq!(resolve("bella")["participant"]["id"], "salon_bella");
 
-        "get_revenue_share_contract" => {
-            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
-            client.get_revenue_share_contract(contract_id).await
+        let ambiguous = resolve("Glamour");
+        assert_eq!(ambiguous["status"], "ambiguous");
+        assert_eq!(ambiguous["matches"].as_array().unwrap().len(), 2);
+
+        let missing = resolve("Salon Glamor");
+        assert_eq!(missing["status"], "not_found");
+        assert_eq!(missing["did_you_mean"]["id"], "salon_glamour");
+        assert!(resolve("Volvo").get("did_you_mean").is_none());
+    }
+
+    #[test]
+    fn duplicate_participants_group_by_similar_names() {
+        let participant = |id: &str, name: &str| Participant {
+            id: id.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let participants = [
+            participant("salon_glamour", "Salon Glamour"),
+            participant("seb", "SEB"),
+            participant("glamour_salon", "salon-glamour"),
+            participant("seb_bank", "SEK"),
+            participant("salon_glamor", "Salon Glamor"),
+            participant("salon_bella", "Salon Bella"),
+            participant("acme", "ACME Logistics"),
+            participant("acme2", "Acme  Logistics"),
+        ];
+        assert_eq!(duplicate_groups(&participants), vec![vec![0, 2, 4], vec![6, 7]]);
+        assert!(!names_look_alike("SEB", "SEK"));
+        assert!(!names_look_alike("Salon Bella", "Salon Glamour"));
+        assert!(duplicate_groups(&participants[..2]).is_empty());
+    }
+
+    #[test]
+    fn list_limits_are_clamped_and_noted() {
+        assert_eq!(ListLimit::from_arg(None, 50), ListLimit { value: 50, requested: None });
+        assert_eq!(ListLimit::from_arg(Some(200), 50), ListLimit { value: 200, requested: None });
+        assert_eq!(ListLimit::from_arg(Some(-1), 50), ListLimit { value: 50, requested: Some(-1) });
+        assert_eq!(ListLimit::from_arg(Some(0), 100).value, 100);
+        let huge = ListLimit::from_arg(Some(1_000_000_000_000), 100);
+        assert_eq!(huge.value, 1000);
+        let meta = list_meta(3, huge, false);
+        assert_eq!(meta["limit"], 1000);
+        assert_eq!(meta["limit_clamped_from"], 1_000_000_000_000i64);
+        assert!(list_meta(3, ListLimit::from_arg(Some(10), 100), false).get("note").is_none());
+    }
+
+    #[test]
+    fn find_transactions_by_reference_limits_are_clamped_not_truncated() {
+        let limit = |value: Value| ListLimit::from_args(&json!({"reference": "INV-1", "limit": value}), 1000);
+        // 2^32 + 1, which a cast to i32 would read as 1
+        assert_eq!(limit(json!(4_294_967_297i64)), ListLimit { value: 1000, requested: Some(4_294_967_297) });
+        assert_eq!(limit(json!(-5)), ListLimit { value: 1000, requested: Some(-5) });
+        assert_eq!(limit(json!(0)).value, 1000);
+        assert_eq!(limit(json!(25)), ListLimit { value: 25, requested: None });
+        assert_eq!(ListLimit::from_args(&json!({"reference": "INV-1"}), 1000).requested, None);
+    }
+
+    #[test]
+    fn contract_dates_accept_millis_or_strings() {
+        let args = json!({
+            "millis": 1_717_200_000_000i64,
+            "date": "2024-06-01",
+            "rfc3339": "2024-06-01T02:00:00+02:00",
+            "bad": "01/06/2024",
+            "float": 1.5,
+        });
+        assert_eq!(date_arg(&args, "millis").unwrap(), Some(1_717_200_000_000));
+        assert_eq!(date_arg(&args, "date").unwrap(), Some(1_717_200_000_000));
+        assert_eq!(date_arg(&args, "rfc3339").unwrap(), Some(1_717_200_000_000));
+        assert_eq!(date_arg(&args, "missing").unwrap(), None);
+        for key in ["bad", "float"] {
+            let err = date_arg(&args, key).unwrap_err();
+            assert!(err.downcast_ref::<InvalidParams>().is_some(), "{}", key);
         }
+        assert_eq!(format_date(1_717_200_000_000), "2024-06-01T00:00:00Z");
+        assert_eq!(format_date(0), Value::Null);
+    }
 
-        "list_contracts" => {
-            let contract_type_str = args.get("contract_type").and_then(|v| v.as_str());
-            let contract_type = contract_type_str.map(|s| contract_type_string_to_int(s));
-            let status = args.get("status").and_then(|v| v.as_str());
-            let participant_id = args.get("participant_id").and_then(|v| v.as_str());
-            let limit = args.get("limit").and_then(|v| v.as_i64()).map(|v| v as i32);
-            client.list_contracts(contract_type, status, participant_id, limit).await
+    #[test]
+    fn participant_contracts_are_grouped_by_status() {
+        use smartcontracts::contract_response::Contract;
+        use smartcontracts::{GenericContract, InvoiceContract, SubscriptionContract};
+
+        let invoice = |id: &str, supplier: &str, buyer: &str, status: &str| smartcontracts::ContractResponse {
+            contract: Some(Contract::Invoice(InvoiceContract {
+                id: id.to_string(),
+                supplier_id: supplier.to_string(),
+                buyer_id: buyer.to_string(),
+                status: status.to_string(),
+                ..Default::default()
+            })),
+        };
+        let contracts = [
+            invoice("inv-1", "salon_glamour", "seb", "pending"),
+            invoice("inv-2", "salon_glamour", "seb", "overdue"),
+            invoice("inv-3", "salon_glamour", "seb", "paid"),
+            smartcontracts::ContractResponse {
+                contract: Some(Contract::Subscription(SubscriptionContract {
+                    id: "sub-1".to_string(),
+                    provider_id: "beauty_hosting".to_string(),
+                    subscriber_id: "salon_glamour".to_string(),
+                    status: "cancelled".to_string(),
+                    ..Default::default()
+                })),
+            },
+            smartcontracts::ContractResponse {
+                contract: Some(Contract::Generic(GenericContract {
+                    id: "gen-1".to_string(),
+                    status: ContractStatus::Paused as i32,
+                    metadata: [("buyer_id".to_string(), "salon_glamour".to_string())].into(),
+                    ..Default::default()
+                })),
+            },
+            invoice("inv-other", "seb", "volvo", "pending"),
+        ];
+        let report = participant_contracts_report("salon_glamour", &contracts);
+        assert_eq!(report["total"], 5);
+        assert_eq!(
+            report["counts"],
+            json!({"active": 2, "paused": 1, "completed": 1, "cancelled": 1, "other": 0})
+        );
+        assert_eq!(report["contracts"]["active"][1]["contract"]["id"], "inv-2");
+        assert_eq!(report["contracts"]["paused"][0]["type"], "generic");
+
+        let report = participant_contracts_report("volvo", &contracts[5..]);
+        assert_eq!(report["counts"]["active"], 1);
+        assert_eq!(participant_contracts_report("nobody", &contracts)["total"], 0);
+    }
+
+    #[test]
+    fn changed_references_are_reported_with_the_result() {
+        let format = ReferenceFormat::default();
+        let result = json!({ "transaction_id": "tx-1" });
+        let unchanged = note_reference(result.clone(), &format.validate_reference("INV-1"));
+        assert_eq!(unchanged, result);
+
+        let noted = note_reference(result, &format.validate_reference("INV-1\r\n"));
+        assert_eq!(noted["reference"], "INV-1  ");
+        assert!(noted["reference_warning"].as_str().unwrap().contains("control characters"));
+    }
+
+    #[test]
+    fn channel_count_defaults_to_one_and_is_bounded() {
+        assert_eq!(channel_count(None).unwrap(), 1);
+        assert_eq!(channel_count(Some(" 4 ")).unwrap(), 4);
+        for bad in ["0", "9", "many", "-1"] {
+            assert!(channel_count(Some(bad)).is_err(), "{}", bad);
         }
+    }
 
-        "execute_contract" => {
-            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
-            let contract_type_str = args.get("contract_type").and_then(|v| v.as_str()).unwrap_or("");
-            let contract_type = contract_type_string_to_int(contract_type_str);
-            client.execute_contract(contract_id, contract_type).await
+    #[test]
+    fn loan_terms_default_and_reject_out_of_range_values() {
+        assert_eq!(loan_terms_arg(&json!({})).unwrap(), (0.05, 60));
+        assert_eq!(loan_terms_arg(&json!({"annual_interest_rate": 0, "term_months": 600})).unwrap(), (0.0, 600));
+        for bad in [
+            json!({"annual_interest_rate": -0.01}),
+            json!({"annual_interest_rate": "5%"}),
+            json!({"term_months": 0}),
+            json!({"term_months": 601}),
+            json!({"term_months": 4_294_967_356_i64}),
+            json!({"term_months": 12.5}),
+        ] {
+            let err = loan_terms_arg(&bad).unwrap_err();
+            assert!(err.downcast_ref::<InvalidParams>().is_some(), "{}", bad);
         }
+    }
 
-        "update_contract_status" => {
-            let contract_id = args.get("contract_id").and_then(|v| v.as_str()).unwrap_or("");
-            let contract_type_str = args.get("contract_type").and_then(|v| v.as_str()).unwrap_or("");
-            let contract_type = contract_type_string_to_int(contract_type_str);
-            let status_str = args.get("status").and_then(|v| v.as_str()).unwrap_or("");
-            let status = contract_status_string_to_int(status_str);
-            client.update_contract_status(contract_id, contract_type, status).await
+    #[test]
+    fn ecosystem_orchestrator_is_a_usable_role() {
+        let role = parse_enum_arg("role", "ecosystem_orchestrator", role_string_to_int, ROLES).unwrap();
+        assert_eq!(role, ParticipantRole::EcosystemOrchestrator as i32);
+        assert_eq!(role_to_string(role), "Ecosystem Orchestrator");
+        assert_eq!(default_account_types(role), ["operating", "fees"]);
+    }
+
+    /// Client whose channel connects to `config`'s server on the first call
+    fn lazy_client(config: Config) -> ScalegraphClient {
+        let channel = config.connect_lazy().unwrap();
+        ScalegraphClient {
+            ledger: LedgerServiceClient::new(channel.clone()),
+            participant: ParticipantServiceClient::new(channel.clone()),
+            business: BusinessServiceClient::new(channel.clone()),
+            contracts: SmartContractServiceClient::new(channel.clone()),
+            channels: vec![channel],
+            next_channel: 0,
+            config,
+            references: ReferenceFormat::default(),
         }
+    }
 
-        _ => Ok(json!({"error": format!("Unknown tool: {}", name)})),
+    #[tokio::test]
+    async fn a_panicking_tool_call_fails_alone_and_the_next_request_is_served() {
+        // A lazy channel never connects, which neither tool below needs
+        let mut client = lazy_client(Config::default());
+        let mut session = Session {
+            structured_content: false,
+            compact_json: true,
+        };
+        let call = |id: i64, tool: &str| JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(id)),
+            method: "tools/call".to_string(),
+            params: Some(json!({ "name": tool, "arguments": {} })),
+        };
+
+        let response = handle_request(&mut client, &mut session, call(1, "panic_for_test")).await.unwrap();
+        assert_eq!(response.id, 1);
+        assert!(response.result.is_none());
+        let error = response.error.expect("JSON-RPC error");
+        assert_eq!(error.code, -32603);
+        assert!(error.message.contains("panic_for_test"), "{}", error.message);
+        assert!(error.message.contains("index out of bounds"), "{}", error.message);
+
+        let response = handle_request(&mut client, &mut session, call(2, "describe_enums")).await.unwrap();
+        assert_eq!(response.id, 2);
+        assert!(response.error.is_none());
+        let text = response.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string();
+        assert!(text.contains("contract_types"), "{}", text);
     }
-}
 
-async fn handle_request(
-    client: &mut ScalegraphClient,
-    request: JsonRpcRequest,
-) -> Option<JsonRpcResponse> {
-    // Notifications don't get responses
-    if request.method.starts_with("notifications/") {
-        return None;
+    /// gRPC server answering every call with `Unavailable`, counting the calls
+    #[derive(Clone)]
+    struct UnavailableServer(Arc<std::sync::atomic::AtomicUsize>);
+
+    impl tonic::server::NamedService for UnavailableServer {
+        const NAME: &'static str = "scalegraph.ledger.LedgerService";
     }
 
-    let id = request.id.unwrap_or(Value::Null);
+    impl tonic::codegen::Service<tonic::codegen::http::Request<tonic::body::BoxBody>> for UnavailableServer {
+        type Response = tonic::codegen::http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = std::future::Ready<Result<Self::Response, Self::Error>>;
 
-    let result = match request.method.as_str() {
-        "initialize" => Ok(get_server_info()),
-        "tools/list" => Ok(get_tools_list()),
-        "tools/call" => {
-            if let Some(params) = request.params {
-                let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
-                let empty_args = json!({});
-                let args = params.get("arguments").unwrap_or(&empty_args);
-                match handle_tool_call(client, name, args).await {
-                    Ok(result) => Ok(json!({
-                        "content": [{
-                            "type": "text",
-                            "text": serde_json::to_string_pretty(&result).unwrap_or_default()
-                        }]
-                    })),
-                    Err(e) => Ok(json!({
-                        "content": [{
-                            "type": "text",
-                            "text": format!("Error: {}", e)
-                        }],
-                        "isError": true
-                    })),
-                }
-            } else {
-                Err("Missing params")
-            }
+        fn poll_ready(&mut self, _: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
         }
-        _ => Err("Method not found"),
-    };
 
-    Some(match result {
-        Ok(r) => JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id,
-            result: Some(r),
-            error: None,
-        },
-        Err(msg) => JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id,
-            result: None,
-            error: Some(JsonRpcError {
-                code: -32601,
-                message: msg.to_string(),
-            }),
-        },
-    })
-}
+        fn call(&mut self, _: tonic::codegen::http::Request<tonic::body::BoxBody>) -> Self::Future {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            std::future::ready(Ok(tonic::Status::unavailable("connection dropped").into_http()))
+        }
+    }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Use environment variable or default to localhost
-    let grpc_url =
-        std::env::var("SCALEGRAPH_GRPC_URL").unwrap_or_else(|_| "http://localhost:50051".to_string());
+    #[tokio::test]
+    async fn only_read_only_tools_are_retried_after_unavailable() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let server = tonic::transport::Server::builder()
+            .add_service(UnavailableServer(calls.clone()))
+            .serve_with_incoming(tonic::codegen::tokio_stream::wrappers::TcpListenerStream::new(listener));
+        tokio::spawn(server);
 
-    // Debug info only when SCALEGRAPH_DEBUG is set
-    if std::env::var("SCALEGRAPH_DEBUG").is_ok() {
-        eprintln!("Scalegraph Ledger MCP Server v1.0.0");
-        eprintln!("gRPC URL: {}", grpc_url);
+        let config = Config {
+            grpc_url: Some(url),
+            ..Config::default()
+        };
+        let mut client = lazy_client(config);
+        let mut progress = Progress::new(None, Box::new(io::sink()));
+        let count = || calls.load(std::sync::atomic::Ordering::SeqCst);
+
+        // The server may have posted the transfer before the connection
+        // dropped, so it is sent once and the error comes back
+        let args = json!({
+            "entries": [
+                {"account_id": "alice:operating", "amount_cents": -100},
+                {"account_id": "bob:operating", "amount_cents": 100},
+            ],
+            "reference": "INV-1",
+        });
+        let error = call_tool(&mut client, "transfer", &args, &mut progress).await.unwrap_err();
+        assert!(is_unavailable(&error), "{:#}", error);
+        assert_eq!(count(), 1);
+
+        // A read is sent again on the new connection
+        let args = json!({"account_id": "alice:operating"});
+        let error = call_tool(&mut client, "get_account_balance", &args, &mut progress).await.unwrap_err();
+        assert!(is_unavailable(&error), "{:#}", error);
+        assert_eq!(count(), 3);
     }
 
-    // Connect to gRPC server
-    let mut client = match ScalegraphClient::connect(&grpc_url).await {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("Failed to connect to gRPC server at {}: {}", grpc_url, e);
-            eprintln!("Make sure the Scalegraph Elixir server is running.");
-            std::process::exit(1);
-        }
-    };
+    /// Ledger server taking `SLOW_CALL` per call and answering `NotFound`
+    #[derive(Clone)]
+    struct SlowServer;
 
-    let stdin = io::stdin();
-    let mut stdout = io::stdout();
+    const SLOW_CALL: std::time::Duration = std::time::Duration::from_millis(20);
 
-    for line in stdin.lock().lines() {
-        let line = line?;
-        if line.is_empty() {
-            continue;
+    impl tonic::server::NamedService for SlowServer {
+        const NAME: &'static str = "scalegraph.ledger.LedgerService";
+    }
+
+    impl tonic::codegen::Service<tonic::codegen::http::Request<tonic::body::BoxBody>> for SlowServer {
+        type Response = tonic::codegen::http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = tonic::codegen::BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self, _: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
         }
 
-        match serde_json::from_str::<JsonRpcRequest>(&line) {
-            Ok(request) => {
-                // Only send response if not a notification
-                if let Some(response) = handle_request(&mut client, request).await {
-                    let response_json = serde_json::to_string(&response)?;
-                    writeln!(stdout, "{}", response_json)?;
-                    stdout.flush()?;
-                }
+        fn call(&mut self, _: tonic::codegen::http::Request<tonic::body::BoxBody>) -> Self::Future {
+            Box::pin(async {
+                tokio::time::sleep(SLOW_CALL).await;
+                Ok(tonic::Status::not_found("no such account").into_http())
+            })
+        }
+    }
+
+    /// Sequential vs pooled channels for a batch of 32 balance lookups against
+    /// a server that works on one call per connection at a time. Run with
+    /// `cargo test bench_channel_pool -- --ignored --nocapture`.
+    #[tokio::test]
+    #[ignore]
+    async fn bench_channel_pool() {
+        const BATCH: usize = 32;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tonic::transport::Server::builder()
+            .concurrency_limit_per_connection(1)
+            .add_service(SlowServer)
+            .serve_with_incoming(tonic::codegen::tokio_stream::wrappers::TcpListenerStream::new(listener));
+        tokio::spawn(server);
+        let config = Config {
+            grpc_url: Some(url),
+            ..Config::default()
+        };
+        let mut progress = Progress::new(None, Box::new(io::sink()));
+        let args = json!({"account_id": "alice:operating"});
+
+        for channels in [1, 4] {
+            let mut client = ScalegraphClient::connect(&config, ReferenceFormat::default(), channels)
+                .await
+                .unwrap();
+
+            // Tool calls as the server runs them: one request at a time
+            let started = std::time::Instant::now();
+            for _ in 0..BATCH {
+                let _ = call_tool(&mut client, "get_account_balance", &args, &mut progress).await;
             }
-            Err(e) => {
-                // Return JSON-RPC error
-                let error_response = JsonRpcResponse {
-                    jsonrpc: "2.0".to_string(),
-                    id: Value::Null,
-                    result: None,
-                    error: Some(JsonRpcError {
-                        code: -32700,
-                        message: format!("Parse error: {}", e),
-                    }),
-                };
-                let response_json = serde_json::to_string(&error_response)?;
-                writeln!(stdout, "{}", response_json)?;
-                stdout.flush()?;
+            let sequential = started.elapsed();
+
+            // The same calls in flight together, as concurrent dispatch would send them
+            let started = std::time::Instant::now();
+            let mut calls = tokio::task::JoinSet::new();
+            for _ in 0..BATCH {
+                client.use_next_channel();
+                let mut ledger = client.ledger.clone();
+                calls.spawn(async move {
+                    let request = GetBalanceRequest {
+                        account_id: "alice:operating".to_string(),
+                    };
+                    let _ = ledger.get_balance(request).await;
+                });
             }
+            while calls.join_next().await.is_some() {}
+            let concurrent = started.elapsed();
+
+            println!(
+                "{} channel(s): sequential {:?}, concurrent {:?}",
+                channels, sequential, concurrent
+            );
         }
     }
 
-    Ok(())
+    #[test]
+    fn read_only_tools_are_listed_tools_that_do_not_write() {
+        let tools = get_tools_list();
+        let names: Vec<&str> = tools["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        for tool in READ_ONLY_TOOLS {
+            assert!(names.contains(&tool), "{} is not a tool", tool);
+        }
+        for tool in [
+            "transfer",
+            "batch_transfer",
+            "reverse_transaction",
+            "record_usage",
+            "create_loan",
+            "fund_escrow",
+            "release_escrow",
+            "sweep_fees",
+        ] {
+            assert!(!READ_ONLY_TOOLS.contains(&tool), "{} writes", tool);
+        }
+    }
 }
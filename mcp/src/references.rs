@@ -0,0 +1,556 @@
+//! References for the transactions a tool posts without being given one.
+//!
+//! Reversals, fee sweeps, and revenue-share payouts or usage charges made
+//! without a reference all get one shaped the same way:
+//!
+//! ```text
+//! KIND [prefix] [subject] stamp      e.g. FEE-SWEEP: ACME salon_glamour 20241215-093000-1
+//! ```
+//!
+//! The kind comes first so `list_transactions` can still classify the
+//! transaction by its reference, and each part can be searched for with
+//! `find_transactions_by_reference`. The prefix and the stamp pattern are
+//! set with `reference_prefix` and `reference_pattern` in `scalegraph.toml`.
+//!
+//! References given by the caller are posted as they are, except that
+//! control characters are taken out and anything past
+//! `max_reference_length` (default `DEFAULT_MAX_LEN`) is cut off; see
+//! `ReferenceFormat::validate_reference`.
+//!
+//! The ledger keeps one reference per transaction and nothing per entry, so
+//! memos given for the entries of a `transfer` are appended to its reference
+//! after `MEMO_MARKER`, numbered by entry position:
+//!
+//! ```text
+//! SETTLEMENT 2024-12 [memos] 1: buyer pays; 3: platform fee
+//! ```
+//!
+//! The reference is checked before the memos go on, and a memo that would
+//! take it past `max_reference_length` is left out whole rather than cut, so
+//! `split_memos` only ever reads back complete memos.
+//!
+//! A usage charge records its units and price the same way, in brackets
+//! after the reference (see `ReferenceFormat::usage_reference`):
+//!
+//! ```text
+//! DRYER-2024-07 [usage: 3 units x 2.50]
+//! ```
+//!
+//! These details count toward `max_reference_length` too, but they are
+//! never dropped: the reference before them is cut to make room, and a
+//! charge whose details leave no room at all is refused.
+
+use anyhow::{bail, Result};
+use scalegraph_config::Config;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Stamp used when `reference_pattern` is not set
+pub const DEFAULT_PATTERN: &str = "{date}-{time}-{seq}";
+
+/// Longest generated reference, in characters. The ledger takes any length,
+/// but listings and exports are easier to read with a bound; the subject is
+/// shortened first so the kind and stamp survive.
+pub const MAX_REFERENCE_LEN: usize = 96;
+
+/// Longest reference passed on when `max_reference_length` is not set
+pub const DEFAULT_MAX_LEN: usize = 256;
+
+/// Separates a transfer's reference from the memos of its entries
+pub const MEMO_MARKER: &str = " [memos] ";
+
+const PLACEHOLDERS: [&str; 4] = ["date", "time", "timestamp", "seq"];
+
+/// Numbers the references generated by this process, for `{seq}`
+static SEQ: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Reversal,
+    FeeSweep,
+    RevenueShare,
+    Usage,
+}
+
+impl ReferenceKind {
+    pub const ALL: [ReferenceKind; 4] = [
+        ReferenceKind::Reversal,
+        ReferenceKind::FeeSweep,
+        ReferenceKind::RevenueShare,
+        ReferenceKind::Usage,
+    ];
+
+    /// Leading text that identifies the kind; list_transactions matches on it
+    pub fn label(self) -> &'static str {
+        match self {
+            ReferenceKind::Reversal => "REVERSAL of",
+            ReferenceKind::FeeSweep => "FEE-SWEEP:",
+            ReferenceKind::RevenueShare => "REVENUE_SHARE:",
+            ReferenceKind::Usage => "USAGE:",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceFormat {
+    prefix: String,
+    pattern: String,
+    max_len: usize,
+}
+
+impl Default for ReferenceFormat {
+    fn default() -> Self {
+        Self {
+            prefix: String::new(),
+            pattern: DEFAULT_PATTERN.to_string(),
+            max_len: DEFAULT_MAX_LEN,
+        }
+    }
+}
+
+/// A caller's reference as it will be posted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckedReference {
+    pub value: String,
+    /// What was changed, if anything
+    pub warning: Option<String>,
+}
+
+impl ReferenceFormat {
+    /// The configured format; a pattern with an unknown or unclosed
+    /// placeholder, or a `max_reference_length` too short for a generated
+    /// reference's kind and stamp, is rejected here rather than on first use
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut format = Self::default();
+        if let Some(prefix) = &config.reference_prefix {
+            format.prefix = prefix.trim().to_string();
+        }
+        if let Some(pattern) = &config.reference_pattern {
+            check_pattern(pattern)?;
+            format.pattern = pattern.trim().to_string();
+        }
+        if let Some(max_len) = config.max_reference_length {
+            let min_len = format.min_len();
+            if max_len < min_len {
+                bail!(
+                    "max_reference_length must be at least {} so generated references keep their kind, prefix and stamp",
+                    min_len
+                );
+            }
+            format.max_len = max_len;
+        }
+        Ok(format)
+    }
+
+    /// Shortest limit that fits the longest kind label, the prefix and the
+    /// stamp (with a 13-digit `{timestamp}`), leaving no room for a subject
+    fn min_len(&self) -> usize {
+        // 2100-01-01, so a {timestamp} has the width it will have for decades
+        let stamp = expand(&self.pattern, 4_102_444_800_000, 1);
+        let label = ReferenceKind::ALL.iter().map(|kind| kind.label().chars().count()).max().unwrap_or(0);
+        [label, self.prefix.chars().count(), stamp.chars().count()]
+            .iter()
+            .filter(|len| **len > 0)
+            .map(|len| len + 1)
+            .sum::<usize>()
+            .saturating_sub(1)
+    }
+
+    /// `reference` without control characters (line breaks and tabs become
+    /// spaces) and cut to the configured length, with a warning saying what
+    /// was changed
+    pub fn validate_reference(&self, reference: &str) -> CheckedReference {
+        self.fit(reference, self.max_len, "max_reference_length")
+    }
+
+    /// `validate_reference` with room for `max_len` characters, which the
+    /// warning explains as `limit`
+    fn fit(&self, reference: &str, max_len: usize, limit: &str) -> CheckedReference {
+        let cleaned = without_control_characters(reference);
+        let mut changes = Vec::new();
+        if cleaned != reference {
+            changes.push("control characters were removed".to_string());
+        }
+        let length = cleaned.chars().count();
+        let value = if length > max_len {
+            changes.push(format!("it was cut from {} to {} characters ({})", length, max_len, limit));
+            cleaned.chars().take(max_len).collect()
+        } else {
+            cleaned
+        };
+        CheckedReference {
+            value,
+            warning: (!changes.is_empty()).then(|| format!("reference was changed: {}", changes.join("; "))),
+        }
+    }
+
+    /// `reference`, as returned by `validate_reference`, with `memos`
+    /// (1-based entry position, text) appended after `MEMO_MARKER`.
+    /// Semicolons in a memo become commas, since they separate the memos;
+    /// empty memos are left out, and so is any memo that would take the
+    /// reference past the configured length, which the warning then names.
+    /// A reference that already contains the marker is refused, since it
+    /// would be read back as memos.
+    pub fn with_memos(
+        &self,
+        reference: CheckedReference,
+        memos: &[(usize, String)],
+    ) -> std::result::Result<CheckedReference, String> {
+        let marker = MEMO_MARKER.trim();
+        if reference.value.contains(marker) {
+            return Err(format!("reference must not contain '{}', which introduces entry memos", marker));
+        }
+        let base = reference.value.trim_end();
+        let mut value = base.to_string();
+        let mut length = value.chars().count();
+        let mut left_out = Vec::new();
+        for (position, memo) in memos {
+            let memo = without_control_characters(&memo.replace(marker, "")).replace(';', ",");
+            if memo.trim().is_empty() {
+                continue;
+            }
+            let separator = if value.len() == base.len() { MEMO_MARKER } else { "; " };
+            let part = format!("{}{}: {}", separator, position, memo.trim());
+            let part_length = part.chars().count();
+            if length + part_length > self.max_len {
+                left_out.push(position.to_string());
+                continue;
+            }
+            value.push_str(&part);
+            length += part_length;
+        }
+        let mut warning = reference.warning;
+        if !left_out.is_empty() {
+            let note = format!(
+                "the memos of entries {} did not fit in max_reference_length and were left out",
+                left_out.join(", ")
+            );
+            warning = Some(match warning {
+                Some(warning) => format!("{}; {}", warning, note),
+                None => format!("reference was changed: {}", note),
+            });
+        }
+        Ok(CheckedReference { value, warning })
+    }
+
+    /// The reference of a usage charge: `reference` as `validate_reference`
+    /// leaves it, or a generated `Usage` reference about `subject` if it is
+    /// empty, followed by `metering` in brackets. The metering counts toward
+    /// the configured length and is kept whole, so the reference before it
+    /// is shortened instead; if the metering leaves no room for it the
+    /// charge is refused.
+    pub fn usage_reference(
+        &self,
+        reference: &str,
+        subject: &str,
+        metering: &str,
+    ) -> std::result::Result<CheckedReference, String> {
+        let suffix = format!(" [{}]", without_control_characters(metering).trim());
+        let room = self.max_len.saturating_sub(suffix.chars().count());
+        let mut checked = if reference.is_empty() {
+            let max_len = MAX_REFERENCE_LEN.min(room);
+            let value = self.format_within(ReferenceKind::Usage, subject, now_ms(), next_seq(), max_len);
+            CheckedReference { value, warning: None }
+        } else {
+            self.fit(reference, room, "max_reference_length, less the usage details")
+        };
+        if checked.value.trim().is_empty() || checked.value.chars().count() > room {
+            return Err(format!(
+                "max_reference_length ({}) leaves no room for a reference before the usage details '{}'",
+                self.max_len,
+                suffix.trim()
+            ));
+        }
+        checked.value.push_str(&suffix);
+        Ok(checked)
+    }
+
+    /// A new reference of `kind` about `subject` (a transaction, participant
+    /// or contract id), stamped with the current time
+    pub fn make_reference(&self, kind: ReferenceKind, subject: &str) -> String {
+        self.format(kind, subject, now_ms(), next_seq())
+    }
+
+    fn format(&self, kind: ReferenceKind, subject: &str, now_ms: i64, seq: u64) -> String {
+        let max_len = MAX_REFERENCE_LEN.min(self.max_len);
+        let reference = self.format_within(kind, subject, now_ms, seq, max_len);
+        reference.chars().take(max_len).collect()
+    }
+
+    /// A reference of `kind` with `subject` shortened to fit `max_len`.
+    /// The kind, prefix and stamp are kept whole, so if they alone are
+    /// longer than `max_len` so is the result.
+    fn format_within(&self, kind: ReferenceKind, subject: &str, now_ms: i64, seq: u64, max_len: usize) -> String {
+        let stamp = expand(&self.pattern, now_ms, seq);
+        let fixed: usize = [kind.label(), self.prefix.as_str(), stamp.as_str()]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .map(|part| part.chars().count() + 1)
+            .sum();
+        let room = max_len.saturating_sub(fixed);
+        let subject: String = subject.trim().chars().take(room).collect();
+        [kind.label(), &self.prefix, &subject, &stamp]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// The current time in Unix milliseconds, for stamps
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// The next `{seq}` value of this process
+fn next_seq() -> u64 {
+    SEQ.fetch_add(1, Ordering::Relaxed)
+}
+
+/// `text` with line breaks and tabs turned into spaces and other control
+/// characters removed
+fn without_control_characters(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            '\n' | '\r' | '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+/// The reference as given, and the entry memos appended by `with_memos`,
+/// by entry position. References without memos come back unchanged.
+pub fn split_memos(reference: &str) -> (&str, BTreeMap<usize, String>) {
+    let Some((base, memos)) = reference.rsplit_once(MEMO_MARKER) else {
+        return (reference, BTreeMap::new());
+    };
+    let memos = memos
+        .split(';')
+        .filter_map(|memo| {
+            let (position, text) = memo.split_once(':')?;
+            Some((position.trim().parse().ok()?, text.trim().to_string()))
+        })
+        .collect();
+    (base, memos)
+}
+
+fn check_pattern(pattern: &str) -> Result<()> {
+    let mut rest = pattern;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            bail!("reference_pattern has an unclosed '{{' in '{}'", pattern);
+        };
+        let name = &rest[open + 1..open + close];
+        if !PLACEHOLDERS.contains(&name) {
+            bail!(
+                "unknown placeholder {{{}}} in reference_pattern '{}'; expected one of: {}",
+                name,
+                pattern,
+                PLACEHOLDERS.map(|p| format!("{{{}}}", p)).join(", ")
+            );
+        }
+        rest = &rest[open + close + 1..];
+    }
+    Ok(())
+}
+
+/// `pattern` with `{date}` (YYYYMMDD), `{time}` (HHMMSS), `{timestamp}`
+/// (Unix milliseconds) and `{seq}` filled in, all UTC
+fn expand(pattern: &str, now_ms: i64, seq: u64) -> String {
+    let now = chrono::DateTime::from_timestamp_millis(now_ms).unwrap_or_default();
+    pattern
+        .replace("{date}", &now.format("%Y%m%d").to_string())
+        .replace("{time}", &now.format("%H%M%S").to_string())
+        .replace("{timestamp}", &now_ms.to_string())
+        .replace("{seq}", &seq.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-12-15 09:30:00.250 UTC
+    const NOW: i64 = 1_734_255_000_250;
+
+    #[test]
+    fn references_lead_with_the_kind_and_end_with_the_stamp() {
+        let format = ReferenceFormat::default();
+        assert_eq!(
+            format.format(ReferenceKind::FeeSweep, "salon_glamour", NOW, 7),
+            "FEE-SWEEP: salon_glamour 20241215-093000-7"
+        );
+        let format = ReferenceFormat {
+            prefix: "ACME".to_string(),
+            pattern: "{timestamp}".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            format.format(ReferenceKind::Reversal, "tx-1", NOW, 1),
+            "REVERSAL of ACME tx-1 1734255000250"
+        );
+        assert_eq!(expand("{date}", 0, 1), "19700101");
+        assert_eq!(expand("{date}T{time}", 951_825_599_000, 1), "20000229T115959");
+    }
+
+    #[test]
+    fn long_subjects_are_shortened_the_same_way_every_time() {
+        let format = ReferenceFormat::default();
+        let subject = "x".repeat(200);
+        let reference = format.format(ReferenceKind::RevenueShare, &subject, NOW, 1);
+        assert_eq!(reference.chars().count(), MAX_REFERENCE_LEN);
+        assert!(reference.starts_with("REVENUE_SHARE: xxx"));
+        assert!(reference.ends_with(" 20241215-093000-1"));
+        assert_eq!(reference, format.format(ReferenceKind::RevenueShare, &subject, NOW, 1));
+    }
+
+    #[test]
+    fn caller_references_lose_control_characters_and_excess_length() {
+        let format = ReferenceFormat::default();
+        let plain = format.validate_reference("INV-2024-001 März");
+        assert_eq!(plain.value, "INV-2024-001 März");
+        assert_eq!(plain.warning, None);
+
+        let messy = format.validate_reference("line one\nline two\u{7}");
+        assert_eq!(messy.value, "line one line two");
+        assert!(messy.warning.unwrap().contains("control characters"));
+
+        let long = format.validate_reference(&"é".repeat(300));
+        assert_eq!(long.value.chars().count(), DEFAULT_MAX_LEN);
+        assert!(long.warning.unwrap().contains("from 300 to 256"));
+    }
+
+    #[test]
+    fn max_reference_length_must_fit_the_kind_and_stamp() {
+        let config = |max_len: usize| Config {
+            reference_prefix: Some("ACME".to_string()),
+            max_reference_length: Some(max_len),
+            ..Default::default()
+        };
+        // "REVENUE_SHARE: ACME 20241215-093000-1"
+        let min_len = 14 + 1 + 4 + 1 + 17;
+        let err = ReferenceFormat::from_config(&config(min_len - 1)).unwrap_err();
+        assert!(err.to_string().contains(&format!("at least {}", min_len)), "{}", err);
+
+        let short = ReferenceFormat::from_config(&config(min_len)).unwrap();
+        for kind in ReferenceKind::ALL {
+            let reference = short.format(kind, "salon_glamour", NOW, 7);
+            assert!(reference.chars().count() <= min_len, "{}", reference);
+            assert!(reference.starts_with(&format!("{} ACME ", kind.label())), "{}", reference);
+            assert!(reference.ends_with(" 20241215-093000-7"), "{}", reference);
+        }
+        assert_eq!(
+            short.format(ReferenceKind::FeeSweep, "salon_glamour", NOW, 7),
+            "FEE-SWEEP: ACME sal 20241215-093000-7"
+        );
+    }
+
+    #[test]
+    fn entry_memos_round_trip_through_the_reference() {
+        let memos = [
+            (1, "buyer pays".to_string()),
+            (2, " ".to_string()),
+            (3, "platform fee; 5%".to_string()),
+        ];
+        let format = ReferenceFormat::default();
+        let with_memos = |reference: &str, memos: &[(usize, String)]| {
+            format.with_memos(format.validate_reference(reference), memos).unwrap()
+        };
+        let reference = with_memos("SETTLEMENT 2024-12", &memos);
+        assert_eq!(reference.value, "SETTLEMENT 2024-12 [memos] 1: buyer pays; 3: platform fee, 5%");
+        assert_eq!(reference.warning, None);
+        let (base, parsed) = split_memos(&reference.value);
+        assert_eq!(base, "SETTLEMENT 2024-12");
+        assert_eq!(parsed, BTreeMap::from([(1, "buyer pays".to_string()), (3, "platform fee, 5%".to_string())]));
+
+        assert_eq!(with_memos("INV-1", &[]).value, "INV-1");
+        assert_eq!(split_memos("INV-1"), ("INV-1", BTreeMap::new()));
+    }
+
+    #[test]
+    fn memos_that_do_not_fit_are_left_out_whole() {
+        let format = ReferenceFormat {
+            max_len: 42,
+            ..Default::default()
+        };
+        let memos = [
+            (1, "buyer pays".to_string()),
+            (2, "a memo far too long to fit".to_string()),
+            (3, "fee".to_string()),
+        ];
+        // 12 + 22 + 8 = 42 without the second memo, which would add 31
+        let reference = format.with_memos(format.validate_reference("SETTLEMENT 1"), &memos).unwrap();
+        assert_eq!(reference.value, "SETTLEMENT 1 [memos] 1: buyer pays; 3: fee");
+        assert_eq!(reference.value.chars().count(), 42);
+        assert!(reference.warning.unwrap().contains("memos of entries 2 did not fit"));
+
+        // A reference cut to the limit leaves no room, and is never cut mid-memo
+        let long = format.with_memos(format.validate_reference(&"x".repeat(50)), &memos).unwrap();
+        assert_eq!(long.value, "x".repeat(42));
+        assert_eq!(split_memos(&long.value).1, BTreeMap::new());
+        let warning = long.warning.unwrap();
+        assert!(warning.contains("from 50 to 42") && warning.contains("entries 1, 2, 3"), "{}", warning);
+
+        let marked = format.with_memos(format.validate_reference("INV-1 [memos] 1: fake"), &[]);
+        assert!(marked.unwrap_err().contains("[memos]"));
+    }
+
+    #[test]
+    fn usage_details_are_kept_and_the_reference_shortened_for_them() {
+        let format = ReferenceFormat::default();
+        let metering = "usage: 3 units x 2.50";
+        let reference = format.usage_reference("DRYER-7", "hairgrowers", metering).unwrap();
+        assert_eq!(reference.value, "DRYER-7 [usage: 3 units x 2.50]");
+        assert_eq!(reference.warning, None);
+
+        let generated = format.usage_reference("", "hairgrowers", metering).unwrap();
+        assert!(generated.value.starts_with("USAGE: hairgrowers "), "{}", generated.value);
+        assert!(generated.value.ends_with(" [usage: 3 units x 2.50]"), "{}", generated.value);
+
+        // 256 less the 24 characters of " [usage: 3 units x 2.50]"
+        let long = format.usage_reference(&"x".repeat(300), "hairgrowers", metering).unwrap();
+        assert_eq!(long.value, format!("{} [{}]", "x".repeat(232), metering));
+        assert_eq!(long.value.chars().count(), DEFAULT_MAX_LEN);
+        assert!(long.warning.unwrap().contains("from 300 to 232"));
+    }
+
+    #[test]
+    fn usage_references_at_the_limit_are_posted_whole() {
+        let format = ReferenceFormat {
+            max_len: 40,
+            ..Default::default()
+        };
+        // " [usage: 3 units x 2.50]" is 24 characters, leaving 16
+        let metering = "usage: 3 units x 2.50";
+        let at_limit = format.usage_reference(&"x".repeat(16), "hairgrowers", metering).unwrap();
+        assert_eq!(at_limit.value.chars().count(), 40);
+        assert_eq!(at_limit.warning, None);
+
+        let over = format.usage_reference(&"x".repeat(17), "hairgrowers", metering).unwrap();
+        assert_eq!(over.value, at_limit.value);
+        assert!(over.warning.unwrap().contains("from 17 to 16"));
+
+        // Control characters go before the length is counted
+        let messy = format.usage_reference("DRYER\n7\u{7}", "hairgrowers", metering).unwrap();
+        assert_eq!(messy.value, "DRYER 7 [usage: 3 units x 2.50]");
+        assert!(messy.warning.unwrap().contains("control characters"));
+
+        // A generated reference needs at least 24 ("USAGE: " and a stamp)
+        let err = format.usage_reference("", "hairgrowers", metering).unwrap_err();
+        assert!(err.contains("max_reference_length (40)"), "{}", err);
+        let err = format.usage_reference("DRYER-7", "hairgrowers", &"9".repeat(40)).unwrap_err();
+        assert!(err.contains("leaves no room"), "{}", err);
+    }
+
+    #[test]
+    fn patterns_may_only_use_known_placeholders() {
+        assert!(check_pattern("{date}-{seq}").is_ok());
+        assert!(check_pattern("plain").is_ok());
+        assert!(check_pattern("{date}-{hour}").unwrap_err().to_string().contains("{hour}"));
+        assert!(check_pattern("{date").is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! Shared Configuration for the Scalegraph CLI and MCP Server
+//!
+//! Both binaries resolve their connection settings the same way, from lowest
+//! to highest precedence:
+//!
+//! 1. Environment variables (`SCALEGRAPH_GRPC_URL`, `SCALEGRAPH_AUTH_TOKEN`)
+//! 2. A `scalegraph.toml` file, from `--config`, `SCALEGRAPH_CONFIG`, or
+//!    `./scalegraph.toml` if it exists
+//! 3. Command-line flags, applied by each binary on top of the loaded `Config`
+//!
+//! The gRPC URL is checked up front (see `check_grpc_url`), so a value such as
+//! `localhost:50051` fails with a message naming where it came from instead of
+//! a low-level transport error.
+//!
+//! Example `scalegraph.toml`:
+//!
+//! ```toml
+//! grpc_url = "https://ledger.example.com:50051"
+//! auth_token = "s3cret"
+//! tls_ca = "/etc/scalegraph/ca.pem"
+//! rpc_timeout = 10        # seconds
+//! theme = "light"         # CLI only
+//! refresh_interval = 30   # seconds, CLI only
+//! idle_timeout = 15       # minutes, CLI only
+//! reference_prefix = "ACME"              # MCP only, see its `references` module
+//! reference_pattern = "{date}-{seq}"     # MCP only
+//! max_reference_length = 256             # characters, MCP only
+//!
+//! [keys]                  # CLI only, see the CLI's `keymap` module
+//! quit = "ctrl+q"
+//! ```
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
+use tonic::{Request, Status};
+
+pub const DEFAULT_GRPC_URL: &str = "http://localhost:50051";
+pub const DEFAULT_CONFIG_FILE: &str = "scalegraph.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// gRPC server URL
+    pub grpc_url: Option<String>,
+    /// Bearer token sent in the `authorization` header of every call
+    pub auth_token: Option<String>,
+    /// PEM file with the CA certificate used to verify the server
+    pub tls_ca: Option<PathBuf>,
+    /// Per-RPC timeout in seconds
+    pub rpc_timeout: Option<u64>,
+    /// TUI color theme (`dark`, `light`, `high-contrast`)
+    pub theme: Option<String>,
+    /// TUI auto-refresh interval in seconds
+    pub refresh_interval: Option<u64>,
+    /// Minutes without RPCs after which the TUI drops its connection
+    pub idle_timeout: Option<u64>,
+    /// Text put after the kind in references the MCP server generates
+    pub reference_prefix: Option<String>,
+    /// Stamp ending the references the MCP server generates
+    pub reference_pattern: Option<String>,
+    /// Longest reference, in characters, the MCP server passes on
+    pub max_reference_length: Option<usize>,
+    /// TUI key bindings: action name to space-separated keys
+    pub keys: Option<BTreeMap<String, String>>,
+}
+
+impl Config {
+    /// Resolve the config file (if any) and layer it over the environment.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let env = Self::from_env();
+        let path = match path {
+            Some(p) => Some(p.to_path_buf()),
+            None => match std::env::var_os("SCALEGRAPH_CONFIG") {
+                Some(p) => Some(PathBuf::from(p)),
+                None => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|p| p.exists()),
+            },
+        };
+        let file = match path {
+            Some(ref p) => Self::from_file(p)?,
+            None => Self::default(),
+        };
+        // Only the URL that takes effect is checked, named by its source
+        match (&file.grpc_url, &env.grpc_url, &path) {
+            (Some(url), _, Some(p)) => check_grpc_url(&format!("grpc_url in {}", p.display()), url)?,
+            (None, Some(url), _) => check_grpc_url("SCALEGRAPH_GRPC_URL", url)?,
+            _ => {}
+        }
+        Ok(env.merge(file))
+    }
+
+    pub fn from_env() -> Self {
+        Self {
+            grpc_url: std::env::var("SCALEGRAPH_GRPC_URL").ok(),
+            auth_token: std::env::var("SCALEGRAPH_AUTH_TOKEN").ok(),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read config file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    /// Values set in `other` take precedence over values in `self`.
+    pub fn merge(self, other: Config) -> Self {
+        Self {
+            grpc_url: other.grpc_url.or(self.grpc_url),
+            auth_token: other.auth_token.or(self.auth_token),
+            tls_ca: other.tls_ca.or(self.tls_ca),
+            rpc_timeout: other.rpc_timeout.or(self.rpc_timeout),
+            theme: other.theme.or(self.theme),
+            refresh_interval: other.refresh_interval.or(self.refresh_interval),
+            idle_timeout: other.idle_timeout.or(self.idle_timeout),
+            reference_prefix: other.reference_prefix.or(self.reference_prefix),
+            reference_pattern: other.reference_pattern.or(self.reference_pattern),
+            max_reference_length: other.max_reference_length.or(self.max_reference_length),
+            keys: other.keys.or(self.keys),
+        }
+    }
+
+    pub fn grpc_url(&self) -> &str {
+        self.grpc_url.as_deref().unwrap_or(DEFAULT_GRPC_URL)
+    }
+
+    pub fn rpc_timeout(&self) -> Option<Duration> {
+        self.rpc_timeout.map(Duration::from_secs)
+    }
+
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        self.refresh_interval
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| Duration::from_secs(minutes * 60))
+    }
+
+    /// Build the endpoint for `grpc_url` with TLS and timeout applied.
+    pub fn endpoint(&self) -> Result<Endpoint> {
+        check_grpc_url("grpc_url", self.grpc_url())?;
+        let mut endpoint = Channel::from_shared(self.grpc_url().to_string())?;
+        if let Some(ref ca_path) = self.tls_ca {
+            let pem = std::fs::read(ca_path)
+                .with_context(|| format!("cannot read TLS CA {}", ca_path.display()))?;
+            let tls = ClientTlsConfig::new().ca_certificate(Certificate::from_pem(pem));
+            endpoint = endpoint.tls_config(tls)?;
+        }
+        if let Some(timeout) = self.rpc_timeout() {
+            endpoint = endpoint.timeout(timeout);
+        }
+        Ok(endpoint)
+    }
+
+    /// Connect to the server, attaching the auth token (if any) to every call.
+    pub async fn connect(&self) -> Result<AuthChannel> {
+        let channel = self.endpoint()?.connect().await?;
+        Ok(InterceptedService::new(channel, self.auth_interceptor()?))
+    }
+
+    /// Like `connect`, but nothing is opened until the first call is made.
+    pub fn connect_lazy(&self) -> Result<AuthChannel> {
+        let channel = self.endpoint()?.connect_lazy();
+        Ok(InterceptedService::new(channel, self.auth_interceptor()?))
+    }
+
+    pub fn auth_interceptor(&self) -> Result<AuthInterceptor> {
+        let header = match self.auth_token {
+            Some(ref token) => Some(
+                format!("Bearer {}", token)
+                    .parse()
+                    .context("auth_token contains characters not allowed in a header")?,
+            ),
+            None => None,
+        };
+        Ok(AuthInterceptor { header })
+    }
+}
+
+/// Check that `url` can address a gRPC server: an `http://` or `https://`
+/// scheme, a host and a port. `source` names the setting in the error, e.g.
+/// `SCALEGRAPH_GRPC_URL` or `--server`.
+pub fn check_grpc_url(source: &str, url: &str) -> Result<()> {
+    const EXAMPLE: &str = "e.g. http://localhost:50051";
+    let Some((scheme, rest)) = url.split_once("://") else {
+        bail!("{} must include a scheme, {} (got '{}')", source, EXAMPLE, url);
+    };
+    if !matches!(scheme.to_ascii_lowercase().as_str(), "http" | "https") {
+        bail!("{} must use http:// or https://, {} (got '{}')", source, EXAMPLE, url);
+    }
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    // The port follows the last ':' outside an IPv6 literal such as [::1]
+    let (host, port) = match authority.rfind(':') {
+        Some(i) if !authority[i..].contains(']') => (&authority[..i], Some(&authority[i + 1..])),
+        _ => (authority, None),
+    };
+    if host.is_empty() || host == "[]" {
+        bail!("{} must include a host, {} (got '{}')", source, EXAMPLE, url);
+    }
+    match port.map(str::parse::<u16>) {
+        Some(Ok(port)) if port > 0 => Ok(()),
+        Some(_) => bail!("{} has an invalid port, {} (got '{}')", source, EXAMPLE, url),
+        None => bail!("{} must include a port, {} (got '{}')", source, EXAMPLE, url),
+    }
+}
+
+/// Channel type used by the generated service clients of both binaries
+pub type AuthChannel = InterceptedService<Channel, AuthInterceptor>;
+
+/// Adds `authorization: Bearer <token>` to outgoing requests when configured
+#[derive(Debug, Clone)]
+pub struct AuthInterceptor {
+    header: Option<MetadataValue<Ascii>>,
+}
+
+impl tonic::service::Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(ref header) = self.header {
+            request
+                .metadata_mut()
+                .insert("authorization", header.clone());
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grpc_urls_need_a_scheme_host_and_port() {
+        for url in ["http://localhost:50051", "https://ledger.example.com:443/", "http://[::1]:50051", "HTTP://10.0.0.5:1"] {
+            assert!(check_grpc_url("SCALEGRAPH_GRPC_URL", url).is_ok(), "{}", url);
+        }
+        for (url, problem) in [
+            ("localhost:50051", "must include a scheme"),
+            ("grpc://localhost:50051", "must use http:// or https://"),
+            ("http://:50051", "must include a host"),
+            ("https://ledger.example.com", "must include a port"),
+            ("http://[::1]", "must include a port"),
+            ("http://localhost:http", "invalid port"),
+            ("http://localhost:0", "invalid port"),
+        ] {
+            let message = check_grpc_url("--server", url).unwrap_err().to_string();
+            assert!(message.starts_with("--server "), "{}", message);
+            assert!(message.contains(problem), "{}: {}", url, message);
+            assert!(message.contains(&format!("(got '{}')", url)), "{}", message);
+        }
+    }
+}
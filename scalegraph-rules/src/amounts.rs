@@ -0,0 +1,25 @@
+//! Formatting of ledger amounts, kept in cents, as decimals
+
+/// `balance` in cents as a decimal amount, e.g. `-12.05`
+pub fn format_balance(balance: i64) -> String {
+    let whole = balance / 100;
+    let cents = (balance % 100).abs();
+    if balance < 0 {
+        format!("-{}.{:02}", whole.abs(), cents)
+    } else {
+        format!("{}.{:02}", whole, cents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cents_show_with_two_places() {
+        assert_eq!(format_balance(0), "0.00");
+        assert_eq!(format_balance(150_023), "1500.23");
+        assert_eq!(format_balance(-5), "-0.05");
+        assert_eq!(format_balance(-1_205), "-12.05");
+    }
+}
@@ -0,0 +1,67 @@
+//! Email, phone and website checks for participant contact blocks, as the server applies them
+
+/// Check the email, phone and website of a contact block: an address like
+/// name@example.com, 5-15 phone digits with an optional leading +, and a
+/// website with a dotted host and at most an http(s) scheme. The error names
+/// the first field that fails.
+pub fn validate_contact(email: &str, phone: &str, website: &str) -> Result<(), String> {
+    if !email.is_empty() {
+        let valid = email.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+        });
+        if !valid || email.contains(char::is_whitespace) {
+            return Err(format!("email '{}' is not an address like name@example.com", email));
+        }
+    }
+    if !phone.is_empty() {
+        let allowed = phone
+            .char_indices()
+            .all(|(i, c)| c.is_ascii_digit() || " -().".contains(c) || (c == '+' && i == 0));
+        let digits = phone.chars().filter(char::is_ascii_digit).count();
+        if !allowed || !(5..=15).contains(&digits) {
+            return Err(format!(
+                "phone '{}' must be 5-15 digits, optionally with a leading + and spaces, dashes, dots or parentheses",
+                phone
+            ));
+        }
+    }
+    if !website.is_empty() {
+        let rest = website
+            .strip_prefix("https://")
+            .or_else(|| website.strip_prefix("http://"))
+            .unwrap_or(website);
+        let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let valid = !website.contains(char::is_whitespace)
+            && !rest.contains("://")
+            && host.contains('.')
+            && !host.starts_with('.')
+            && !host.ends_with('.');
+        if !valid {
+            return Err(format!("website '{}' is not a URL like https://example.com", website));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_field_is_checked_and_empty_passes() {
+        assert!(validate_contact("", "", "").is_ok());
+        assert!(validate_contact("info@salonglamour.se", "+46 (8) 123-456", "www.salonglamour.se/about").is_ok());
+        assert!(validate_contact("", "", "https://salonglamour.se").is_ok());
+
+        assert!(validate_contact("info@salonglamour", "", "").unwrap_err().starts_with("email"));
+        assert!(validate_contact("a@b@c.se", "", "").is_err());
+        assert!(validate_contact("", "46+123456", "").unwrap_err().starts_with("phone"));
+        assert!(validate_contact("", "1234", "").is_err());
+        assert!(validate_contact("", "", "ftp://salonglamour.se").unwrap_err().starts_with("website"));
+        assert!(validate_contact("", "", "salonglamour").is_err());
+    }
+}
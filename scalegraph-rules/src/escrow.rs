@@ -0,0 +1,71 @@
+//! Checks on the escrow account and amount before funding or releasing escrow
+
+use crate::amounts::format_balance;
+
+/// Account type name, as both binaries show it, of an escrow account
+pub const ESCROW_ACCOUNT_TYPE: &str = "Escrow";
+
+/// Which way money moves through the escrow account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscrowMove {
+    Fund,
+    Release,
+}
+
+/// Account `account_id`, of type `account_type` (shown as e.g. "Escrow" or
+/// "Operating") and holding `balance`, must be an escrow account, and
+/// `amount` must be positive, since a negative one would move the money the
+/// other way. When releasing, the account must also hold at least `amount`.
+pub fn check_escrow_account(
+    account_id: &str,
+    account_type: &str,
+    balance: i64,
+    movement: EscrowMove,
+    amount: i64,
+) -> Result<(), String> {
+    if amount <= 0 {
+        return Err(format!("amount must be positive, got {}", amount));
+    }
+    if account_type != ESCROW_ACCOUNT_TYPE {
+        return Err(format!("{} is not an escrow account (type: {})", account_id, account_type));
+    }
+    if movement == EscrowMove::Release && balance < amount {
+        return Err(format!(
+            "Insufficient escrow balance in {}: holds {}, release needs {}",
+            account_id,
+            format_balance(balance),
+            format_balance(amount)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escrow_accounts_are_checked_for_type_and_balance() {
+        assert!(check_escrow_account("seb:escrow", "Escrow", 0, EscrowMove::Fund, 500).is_ok());
+        assert!(check_escrow_account("seb:escrow", "Escrow", 500, EscrowMove::Release, 500).is_ok());
+        assert_eq!(
+            check_escrow_account("seb:escrow", "Escrow", 499, EscrowMove::Release, 500).unwrap_err(),
+            "Insufficient escrow balance in seb:escrow: holds 4.99, release needs 5.00"
+        );
+        assert_eq!(
+            check_escrow_account("seb:operating", "Operating", 1_000, EscrowMove::Fund, 500).unwrap_err(),
+            "seb:operating is not an escrow account (type: Operating)"
+        );
+    }
+
+    #[test]
+    fn escrow_amounts_must_be_positive() {
+        for movement in [EscrowMove::Fund, EscrowMove::Release] {
+            assert_eq!(
+                check_escrow_account("seb:escrow", "Escrow", 1_000, movement, -500).unwrap_err(),
+                "amount must be positive, got -500"
+            );
+            assert!(check_escrow_account("seb:escrow", "Escrow", 1_000, movement, 0).is_err());
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! Shared Business Rules for the Scalegraph CLI and MCP Server
+//!
+//! Checks and calculations both binaries apply the same way before calling
+//! the ledger, so a request is refused, or a figure shown, identically
+//! whether it comes from the TUI or from a tool call.
+
+pub mod amounts;
+pub mod contact;
+pub mod escrow;
+pub mod loans;
+pub mod shares;
+pub mod transactions;
@@ -0,0 +1,37 @@
+//! Monthly payment and term limit of a loan, as the server records them
+
+/// Longest loan term accepted, in months (50 years)
+pub const MAX_TERM_MONTHS: i32 = 600;
+
+/// Monthly payment in cents of a fully amortizing loan, rounded to the
+/// nearest cent. With a zero rate the principal is split evenly. None for a
+/// principal or term that is not positive.
+pub fn monthly_payment(principal_cents: i64, annual_rate: f64, term_months: i32) -> Option<i64> {
+    if principal_cents <= 0 || term_months <= 0 {
+        return None;
+    }
+    let principal = principal_cents as f64;
+    let n = term_months as f64;
+    let r = annual_rate / 12.0;
+    let payment = if r == 0.0 {
+        principal / n
+    } else {
+        principal * r / (1.0 - (1.0 + r).powf(-n))
+    };
+    Some(payment.round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payments_amortize_the_principal_over_the_term() {
+        // Same figures as the server's ExUnit tests
+        assert_eq!(monthly_payment(12_000, 0.06, 12), Some(1_033));
+        assert_eq!(monthly_payment(12_000, 0.0, 12), Some(1_000));
+        assert_eq!(monthly_payment(1_000_000, 0.05, 12), Some(85_607));
+        assert_eq!(monthly_payment(12_000, 0.05, 0), None);
+        assert_eq!(monthly_payment(0, 0.05, 12), None);
+    }
+}
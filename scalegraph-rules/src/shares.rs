@@ -0,0 +1,51 @@
+//! Checks on the parties of a revenue-share contract
+
+/// Allowed deviation of the share total from 1.0
+const SHARE_SUM_EPSILON: f64 = 1e-6;
+
+/// Reject revenue-share party lists that can't be distributed sensibly:
+/// empty lists, duplicate participants, shares outside 0..=1, or a total
+/// other than 1.0.
+pub fn validate_revenue_shares(parties: &[(String, f64)]) -> Result<(), String> {
+    if parties.is_empty() {
+        return Err("revenue share needs at least one party".to_string());
+    }
+    let mut seen = std::collections::HashSet::new();
+    for (participant_id, share) in parties {
+        if !seen.insert(participant_id.as_str()) {
+            return Err(format!("duplicate participant_id {} in parties", participant_id));
+        }
+        if !share.is_finite() || *share < 0.0 || *share > 1.0 {
+            return Err(format!(
+                "share for {} is {}, must be between 0.0 and 1.0",
+                participant_id, share
+            ));
+        }
+    }
+    let total: f64 = parties.iter().map(|(_, share)| share).sum();
+    if (total - 1.0).abs() > SHARE_SUM_EPSILON {
+        return Err(format!("shares sum to {}, must be 1.0", (total * 1e6).round() / 1e6));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_splits_are_rejected() {
+        let parties = |shares: &[(&str, f64)]| -> Vec<(String, f64)> {
+            shares.iter().map(|(id, s)| (id.to_string(), *s)).collect()
+        };
+        assert!(validate_revenue_shares(&parties(&[("a", 0.7), ("b", 0.2), ("c", 0.1)])).is_ok());
+        assert_eq!(
+            validate_revenue_shares(&parties(&[("a", 0.75), ("b", 0.2)])).unwrap_err(),
+            "shares sum to 0.95, must be 1.0"
+        );
+        assert!(validate_revenue_shares(&[]).is_err());
+        assert!(validate_revenue_shares(&parties(&[("a", 1.5), ("b", -0.5)])).is_err());
+        assert!(validate_revenue_shares(&parties(&[("a", 0.5), ("a", 0.5)])).is_err());
+        assert!(validate_revenue_shares(&parties(&[("a", f64::NAN)])).is_err());
+    }
+}
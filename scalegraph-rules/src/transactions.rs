@@ -0,0 +1,115 @@
+//! Business types of transactions and the rules for reversing one
+
+use crate::amounts::format_balance;
+
+/// Reference prefix that marks a transaction as the reversal of another
+pub const REVERSAL_PREFIX: &str = "REVERSAL of ";
+
+/// Business types `transaction_type` returns, in the order the CLI's History
+/// filter cycles through them
+pub const TRANSACTION_TYPES: [&str; 8] = [
+    "transfer",
+    "invoice",
+    "payment",
+    "access_payment",
+    "loan",
+    "loan_repayment",
+    "revenue_share",
+    "reversal",
+];
+
+/// Business type of a transaction of type `tx_type` with `reference`. The
+/// ledger records every transaction as a "transfer"; the business layer
+/// marks its kind in the reference prefix ("INVOICE: ...", "ACCESS: ...",
+/// ...), so that is where the type comes from.
+pub fn transaction_type<'a>(tx_type: &'a str, reference: &str) -> &'a str {
+    const PREFIXES: [(&str, &str); 7] = [
+        (REVERSAL_PREFIX, "reversal"),
+        ("INVOICE: ", "invoice"),
+        ("PAYMENT: ", "payment"),
+        ("ACCESS: ", "access_payment"),
+        ("LOAN_REPAYMENT: ", "loan_repayment"),
+        ("LOAN: ", "loan"),
+        ("REVENUE_SHARE: ", "revenue_share"),
+    ];
+    if !tx_type.is_empty() && tx_type != "transfer" {
+        return tx_type;
+    }
+    PREFIXES
+        .iter()
+        .find(|(prefix, _)| reference.starts_with(prefix))
+        .map_or("transfer", |(_, kind)| kind)
+}
+
+/// Negated entries that undo transaction `id`, given its `reference` and its
+/// `(account_id, amount)` entries. Refuses transactions that don't net to
+/// zero and transactions that are themselves reversals.
+pub fn reversal_entries<'a>(
+    id: &str,
+    reference: &str,
+    entries: impl IntoIterator<Item = (&'a str, i64)>,
+) -> Result<Vec<(String, i64)>, String> {
+    if reference.starts_with(REVERSAL_PREFIX) {
+        return Err(format!("transaction {} is already a reversal ({})", id, reference));
+    }
+    let reversed: Vec<(String, i64)> = entries
+        .into_iter()
+        .map(|(account_id, amount)| (account_id.to_string(), -amount))
+        .collect();
+    if reversed.is_empty() {
+        return Err(format!("transaction {} has no entries to reverse", id));
+    }
+    let net: i64 = reversed.iter().map(|(_, amount)| amount).sum();
+    if net != 0 {
+        return Err(format!(
+            "transaction {} nets to {}, only balanced transactions can be reversed",
+            id,
+            format_balance(-net)
+        ));
+    }
+    Ok(reversed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn types_come_from_the_reference_prefix() {
+        let references = [
+            "rent",
+            "INVOICE: inv-1",
+            "PAYMENT: inv-1",
+            "ACCESS: door-7",
+            "LOAN: loan-1",
+            "LOAN_REPAYMENT: loan-1",
+            "REVENUE_SHARE: rs-1",
+            "REVERSAL of tx-1",
+        ];
+        for (reference, expected) in references.iter().zip(TRANSACTION_TYPES) {
+            assert_eq!(transaction_type("transfer", reference), expected);
+        }
+        assert_eq!(transaction_type("", "ACCESS: door-7"), "access_payment");
+        // A type the ledger recorded itself wins over the reference
+        assert_eq!(transaction_type("invoice", "ACCESS: door-7"), "invoice");
+    }
+
+    #[test]
+    fn only_balanced_transactions_are_reversed() {
+        let reverse = |reference: &str, amounts: &[i64]| {
+            let accounts: Vec<String> = (0..amounts.len()).map(|i| format!("acct{}", i)).collect();
+            let entries = accounts.iter().map(String::as_str).zip(amounts.iter().copied());
+            reversal_entries("tx-1", reference, entries)
+        };
+        assert_eq!(
+            reverse("rent", &[-500, 500]).unwrap(),
+            vec![("acct0".to_string(), 500), ("acct1".to_string(), -500)]
+        );
+        assert_eq!(
+            reverse("deposit", &[500]).unwrap_err(),
+            "transaction tx-1 nets to 5.00, only balanced transactions can be reversed"
+        );
+        assert!(reverse("REVERSAL of tx-0", &[500, -500]).unwrap_err().contains("already a reversal"));
+        assert!(reverse("empty", &[]).unwrap_err().contains("no entries"));
+    }
+}
@@ -179,6 +179,43 @@ defmodule Scalegraph.Business.TransactionsTest do
       assert {:error, {:insufficient_funds, _, _, _}} =
                Transactions.create_loan("lender1", "borrower1", 200_000, "LOAN-002")
     end
+
+    test "stores the interest rate and term on the loan contract" do
+      assert {:ok, result} =
+               Transactions.create_loan("lender1", "borrower1", 12_000, "LOAN-005",
+                 annual_interest_rate: 0.06,
+                 term_months: 12
+               )
+
+      assert result.annual_interest_rate == 0.06
+      assert result.term_months == 12
+      # 120.00 at 6% over a year: 10.33 a month
+      assert result.monthly_payment == 1_033
+
+      {:ok, loan} = Contracts.get_loan(result.loan_id)
+      assert loan.interest_rate == 0.06
+      assert loan.repayment_schedule == %{"term_months" => 12, "monthly_payment_cents" => 1_033}
+    end
+
+    test "an interest-free loan splits the principal evenly" do
+      assert Transactions.monthly_payment(12_000, 0, 12) == 1_000
+      assert Transactions.monthly_payment(12_000, 0.05, 0) == nil
+    end
+
+    test "rejects negative rates, terms and amounts without moving money" do
+      assert {:error, {:invalid_terms, _}} =
+               Transactions.create_loan("lender1", "borrower1", 1_000, "LOAN-006",
+                 annual_interest_rate: -0.01
+               )
+
+      assert {:error, {:invalid_terms, _}} =
+               Transactions.create_loan("lender1", "borrower1", 1_000, "LOAN-007", term_months: -3)
+
+      assert {:error, {:invalid_terms, _}} =
+               Transactions.create_loan("lender1", "borrower1", 0, "LOAN-008")
+
+      assert {:ok, 100_000} = Core.get_balance("lender1:operating")
+    end
   end
 
   describe "repay_loan/4" do
@@ -0,0 +1,63 @@
+defmodule Scalegraph.Participant.CoreTest do
+  use ExUnit.Case, async: false
+
+  alias Scalegraph.Participant.Core
+  alias Scalegraph.Storage.Schema
+
+  @contact %{
+    email: "info@salonglamour.se",
+    phone: "+46 (8) 123-456",
+    website: "www.salonglamour.se",
+    address: "Drottninggatan 1",
+    postal_code: "111 51",
+    city: "Stockholm",
+    country: "Sweden"
+  }
+
+  setup do
+    # Ensure Mnesia is running and clear tables for each test
+    Schema.init()
+    Schema.clear_all()
+
+    {:ok, _} = Core.create_participant("salon_glamour", "Salon Glamour", :ecosystem_partner, %{})
+    :ok
+  end
+
+  describe "update_contact/2" do
+    test "replaces the whole contact block" do
+      assert {:ok, participant} = Core.update_contact("salon_glamour", @contact)
+      assert participant.contact == @contact
+
+      cleared = %{@contact | phone: "", website: ""}
+      assert {:ok, _} = Core.update_contact("salon_glamour", cleared)
+      {:ok, participant} = Core.get_participant("salon_glamour")
+      assert participant.contact.phone == ""
+      assert participant.contact.city == "Stockholm"
+    end
+
+    test "returns not_found for an unknown participant" do
+      assert {:error, :not_found} = Core.update_contact("nonexistent", @contact)
+    end
+
+    test "rejects malformed fields and keeps the stored contact" do
+      {:ok, _} = Core.update_contact("salon_glamour", @contact)
+
+      for {field, value} <- [
+            email: "info@salonglamour",
+            email: "info salon@glamour.se",
+            phone: "46+123456",
+            phone: "1234",
+            website: "salonglamour",
+            website: "ftp://salonglamour.se"
+          ] do
+        assert {:error, {:invalid_contact, message}} =
+                 Core.update_contact("salon_glamour", Map.put(@contact, field, value))
+
+        assert message =~ value
+      end
+
+      {:ok, participant} = Core.get_participant("salon_glamour")
+      assert participant.contact == @contact
+    end
+  end
+end